@@ -34,6 +34,11 @@ pub(crate) fn imp(input: TokenStream) -> Result<TokenStream> {
                 None => quote!(#crate_name::entity::ealloc::ThreadRngShardAssigner),
             };
 
+        let key = match opts.find_one(|opt| option_match!(opt, Opt::Key(_, key) => key))? {
+            Some((_, key)) => key.clone(),
+            None => syn::LitStr::new(&ident.to_string(), ident.span()),
+        };
+
         let item = quote! {
             #(#meta)*
             #vis enum #ident {}
@@ -41,6 +46,9 @@ pub(crate) fn imp(input: TokenStream) -> Result<TokenStream> {
             impl #crate_name::Archetype for #ident {
                 type RawEntity = #raw_entity;
                 type Ealloc = #crate_name::entity::ealloc::Recycling<#raw_entity, #recycler, #shard_assigner>;
+
+                const SERIALIZE_KEY: ::std::option::Option<&'static str> =
+                    ::std::option::Option::Some(#key);
             }
         };
         output.extend(item);
@@ -100,6 +108,7 @@ enum Opt {
     RawEntity(syn::Token![=], syn::Type),
     Recycler(syn::Token![=], syn::Type),
     ShardAssigner(syn::Token![=], syn::Type),
+    Key(syn::Token![=], syn::LitStr),
 }
 
 impl Parse for Named<Opt> {
@@ -122,6 +131,11 @@ impl Parse for Named<Opt> {
                 let ty = input.parse::<syn::Type>()?;
                 Opt::ShardAssigner(eq, ty)
             }
+            "key" => {
+                let eq: syn::Token![=] = input.parse()?;
+                let key = input.parse::<syn::LitStr>()?;
+                Opt::Key(eq, key)
+            }
             _ => return Err(Error::new_spanned(&name, format!("Unknown argument `{}`", name))),
         };
 
@@ -5,7 +5,7 @@ use syn::parse::{Parse, ParseStream};
 use syn::Error;
 
 use crate::util::{Attr, Named, Result};
-use crate::{entity_ref, util};
+use crate::{entity_ref, serialize, soa, util};
 
 pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream> {
     let args: Attr<ItemOpt> = syn::parse2(args)?;
@@ -39,6 +39,11 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
                 .expect("Cannot parse storage::Vec as a path"),
         };
 
+    let soa = storage.is_ident("soa");
+    if let (true, Some((isotope_span, _))) = (soa, isotope) {
+        return Err(Error::new(isotope_span, "struct-of-arrays components cannot be isotopes"));
+    }
+
     let presence = args.find_one(|arg| option_match!(arg, ItemOpt::Required => &()))?;
     let presence_enum = match presence {
         Some(_) => quote!(#crate_name::comp::Presence::Required),
@@ -54,6 +59,21 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
     }
     let finalizer = finalizer.is_some();
 
+    let track_changes = args.find_one(|arg| option_match!(arg, ItemOpt::TrackChanges => &()))?;
+    let events = args.find_one(|arg| option_match!(arg, ItemOpt::Events => &()))?;
+    if let (Some((isotope_span, _)), Some((events_span, _))) = (isotope, events) {
+        return Err(Error::new(
+            isotope_span.join(events_span).unwrap_or(events_span),
+            "isotope components do not support add/remove events",
+        ));
+    }
+    if let (Some((isotope_span, _)), Some((track_span, _))) = (isotope, track_changes) {
+        return Err(Error::new(
+            isotope_span.join(track_span).unwrap_or(track_span),
+            "isotope components do not support change tracking",
+        ));
+    }
+
     let init = args.find_one(|arg| option_match!(arg, ItemOpt::Init(_, func) => func))?;
     if let (Some((isotope_span, _)), Some((presence_span, _)), None) = (isotope, presence, init) {
         return Err(Error::new(
@@ -66,13 +86,36 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
     let input: syn::DeriveInput = syn::parse2(input)?;
     let generics = util::parse_generics(&input);
 
+    let serialize = args.find_one(|arg| option_match!(arg, ItemOpt::Serialize(key) => key))?;
+    let serializer = match serialize {
+        Some((_, key)) => {
+            let key = serialize::key(&input.ident, key.as_ref().map(|(_, key)| key));
+            quote! {
+                const SERIALIZER: ::std::option::Option<#crate_name::serialize::Vtable<Self>> =
+                    ::std::option::Option::Some(#crate_name::serialize::Vtable::of(#key));
+            }
+        }
+        None => quote!(),
+    };
+
     let mut output = TokenStream::new();
     for archetype in archetypes {
-        let storage = if storage.segments.iter().all(|segment| segment.arguments.is_empty()) {
+        let storage = if soa {
+            let columns = soa::columns_ident(&input);
+            quote!(#crate_name::storage::Soa<<#archetype as #crate_name::Archetype>::RawEntity, #columns>)
+        } else if storage.segments.iter().all(|segment| segment.arguments.is_empty()) {
             quote!(#storage<<#archetype as #crate_name::Archetype>::RawEntity, Self>)
         } else {
             quote!(#storage)
         };
+        let storage = match events {
+            Some(_) => quote!(#crate_name::storage::Evented<#storage>),
+            None => storage,
+        };
+        let storage = match track_changes {
+            Some(_) => quote!(#crate_name::storage::Tracked<#storage>),
+            None => storage,
+        };
 
         let init_strategy = match init {
             None => quote!(#crate_name::comp::InitStrategy::None),
@@ -89,6 +132,7 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
             quote! {
                 const PRESENCE: #crate_name::comp::Presence = #presence_enum;
                 const INIT_STRATEGY: #crate_name::comp::InitStrategy<#archetype, Self> = #init_strategy;
+                #serializer
 
                 type Storage = #storage;
             },
@@ -117,14 +161,21 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
         }
     }
 
+    if serialize.is_some() {
+        output.extend(serialize::serialize(&input, crate_name.clone())?);
+    }
+
+    if soa {
+        output.extend(soa::soa(&input, crate_name.clone(), serialize.is_some())?);
+    }
+
+    // Fields of struct-of-arrays components are asserted to not reference entities.
+    let entity_ref_assertion = match soa {
+        true => quote!(struct_of_arrays_components_cannot_reference_entities),
+        false => quote!(this_field_references_an_entity_so_it_should_have_the_entity_attribute),
+    };
     let mut mut_input = input;
-    let entity_ref = entity_ref::entity_ref(
-        &mut mut_input,
-        crate_name,
-        quote! {
-            this_field_references_an_entity_so_it_should_have_the_entity_attribute
-        },
-    )?;
+    let entity_ref = entity_ref::entity_ref(&mut mut_input, crate_name, entity_ref_assertion)?;
 
     let output = quote! {
         #mut_input
@@ -145,6 +196,9 @@ enum ItemOpt {
     Storage(syn::Token![=], syn::Path),
     Required,
     Finalizer,
+    Serialize(Option<(syn::Token![=], syn::LitStr)>),
+    TrackChanges,
+    Events,
     Init(syn::Token![=], Box<FunctionRefWithArity>),
 }
 
@@ -177,6 +231,9 @@ impl Parse for Named<ItemOpt> {
             }
             "required" => ItemOpt::Required,
             "finalizer" => ItemOpt::Finalizer,
+            "serialize" => ItemOpt::Serialize(serialize::parse_key(input)?),
+            "track_changes" => ItemOpt::TrackChanges,
+            "events" => ItemOpt::Events,
             "init" => {
                 let eq: syn::Token![=] = input.parse()?;
                 let expr = input.parse::<FunctionRefWithArity>()?;
@@ -4,11 +4,12 @@ use quote::quote;
 use syn::parse::{Parse, ParseStream};
 use syn::Error;
 
-use crate::entity_ref;
 use crate::util::{Attr, Named, Result};
+use crate::{entity_ref, serialize};
 
 pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream> {
     let mut initial = None;
+    let mut serializer = None;
 
     let input: syn::DeriveInput = syn::parse2(input)?;
     let ident = &input.ident;
@@ -38,11 +39,28 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
                 }
             });
         }
+
+        if let Some((_, key)) =
+            args.find_one(|opt| option_match!(opt, ItemOpt::Serialize(key) => key))?
+        {
+            let key = serialize::key(ident, key.as_ref().map(|(_, key)| key));
+            let serialize_impl = serialize::serialize(&input, crate_name.clone())?;
+            serializer = Some((
+                quote! {
+                    const SERIALIZER: ::std::option::Option<#crate_name::serialize::Vtable<Self>> =
+                        ::std::option::Option::Some(#crate_name::serialize::Vtable::of(#key));
+                },
+                serialize_impl,
+            ));
+        }
     }
 
+    let (serializer, serialize_impl) = serializer.unzip();
+
     let global_impl = quote! {
         impl #crate_name::Global for #ident {
             #initial
+            #serializer
         }
     };
 
@@ -58,6 +76,7 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
     Ok(quote! {
         #mut_input
         #global_impl
+        #serialize_impl
         #entity_ref
     })
 }
@@ -65,6 +84,7 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
 enum ItemOpt {
     DynecAs(syn::token::Paren, TokenStream),
     Initial(Option<(syn::Token![=], Box<syn::Expr>)>),
+    Serialize(Option<(syn::Token![=], syn::LitStr)>),
 }
 
 impl Parse for Named<ItemOpt> {
@@ -88,6 +108,7 @@ impl Parse for Named<ItemOpt> {
                 };
                 ItemOpt::Initial(value)
             }
+            "serialize" => ItemOpt::Serialize(serialize::parse_key(input)?),
             _ => return Err(Error::new_spanned(&name, format!("Unknown argument `{}`", name))),
         };
 
@@ -11,6 +11,8 @@ mod comps;
 mod discrim;
 mod entity_ref;
 mod global;
+mod serialize;
+mod soa;
 mod system;
 mod tracer;
 mod tracer_def;
@@ -46,6 +48,11 @@ pub fn entity_ref(input: TokenStream) -> TokenStream {
     entity_ref::derive(input.into()).unwrap_or_else(|err| err.to_compile_error()).into()
 }
 
+#[proc_macro_derive(Serialize, attributes(dynec))]
+pub fn serialize(input: TokenStream) -> TokenStream {
+    serialize::derive(input.into()).unwrap_or_else(|err| err.to_compile_error()).into()
+}
+
 #[proc_macro_derive(Discrim, attributes(dynec))]
 pub fn discrim(input: TokenStream) -> TokenStream {
     discrim::derive(input.into()).unwrap_or_else(|err| err.to_compile_error()).into()
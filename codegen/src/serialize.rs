@@ -0,0 +1,188 @@
+use matches2::option_match;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::Error;
+
+use crate::util::{self, Attr, Named, Result};
+
+pub(crate) fn derive(input: TokenStream) -> Result<TokenStream> {
+    let input: syn::DeriveInput = syn::parse2(input)?;
+
+    let mut args: Attr<ItemOpt> = Attr::default();
+    for attr in &input.attrs {
+        if attr.path().is_ident("dynec") {
+            let this_args: Attr<ItemOpt> = attr.parse_args()?;
+            args.items.extend(this_args.items);
+        }
+    }
+
+    let crate_name = args
+        .find_one(|opt| option_match!(opt, ItemOpt::DynecAs(_, crate_name) => crate_name))?
+        .map_or_else(|| quote!(::dynec), |(_, crate_name)| crate_name.clone());
+
+    serialize(&input, crate_name)
+}
+
+/// Parses the optional `= "key"` after a `serialize` option.
+pub(crate) fn parse_key(input: ParseStream) -> Result<Option<(syn::Token![=], syn::LitStr)>> {
+    if input.peek(syn::Token![=]) {
+        let eq = input.parse()?;
+        let key = input.parse()?;
+        Ok(Some((eq, key)))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Returns the snapshot key of a type, which defaults to the type identifier.
+pub(crate) fn key(ident: &syn::Ident, key: Option<&syn::LitStr>) -> syn::LitStr {
+    match key {
+        Some(key) => key.clone(),
+        None => syn::LitStr::new(&ident.to_string(), ident.span()),
+    }
+}
+
+pub(crate) fn serialize(input: &syn::DeriveInput, crate_name: TokenStream) -> Result<TokenStream> {
+    let generics = util::parse_generics(input);
+
+    let (serialize_body, deserialize_body) = match &input.data {
+        syn::Data::Struct(s) => {
+            let (pattern, names) = fields_pattern(&s.fields);
+            let construct = fields_construct(&s.fields, &crate_name);
+            (
+                quote! {
+                    let Self #pattern = self;
+                    #(#crate_name::serialize::Serialize::serialize(#names, writer)?;)*
+                    ::std::result::Result::Ok(())
+                },
+                quote! {
+                    ::std::result::Result::Ok(Self #construct)
+                },
+            )
+        }
+        syn::Data::Enum(e) => {
+            let mut serialize_arms = Vec::new();
+            let mut deserialize_arms = Vec::new();
+
+            for (index, variant) in e.variants.iter().enumerate() {
+                let variant_ident = &variant.ident;
+                let index = u32::try_from(index)
+                    .map_err(|_| Error::new_spanned(variant, "too many variants"))?;
+
+                let (pattern, names) = fields_pattern(&variant.fields);
+                serialize_arms.push(quote! {
+                    Self::#variant_ident #pattern => {
+                        #crate_name::serialize::Serialize::serialize(&#index, writer)?;
+                        #(#crate_name::serialize::Serialize::serialize(#names, writer)?;)*
+                    }
+                });
+
+                let construct = fields_construct(&variant.fields, &crate_name);
+                deserialize_arms.push(quote! {
+                    #index => Self::#variant_ident #construct,
+                });
+            }
+
+            let ident_string = input.ident.to_string();
+
+            (
+                quote! {
+                    match self {
+                        #(#serialize_arms)*
+                    }
+                    ::std::result::Result::Ok(())
+                },
+                quote! {
+                    let variant = <u32 as #crate_name::serialize::Serialize>::deserialize(reader)?;
+                    ::std::result::Result::Ok(match variant {
+                        #(#deserialize_arms)*
+                        _ => return ::std::result::Result::Err(::std::io::Error::new(
+                            ::std::io::ErrorKind::InvalidData,
+                            ::std::format!("invalid variant index {} for {}", variant, #ident_string),
+                        )),
+                    })
+                },
+            )
+        }
+        syn::Data::Union(u) => {
+            return Err(Error::new_spanned(u.union_token, "only structs and enums are supported"))
+        }
+    };
+
+    Ok(generics.impl_trait(
+        quote!(#crate_name::serialize::Serialize),
+        quote! {
+            fn serialize(
+                &self,
+                writer: &mut #crate_name::serialize::Writer<'_>,
+            ) -> ::std::io::Result<()> {
+                #serialize_body
+            }
+
+            fn deserialize(
+                reader: &mut #crate_name::serialize::Reader<'_>,
+            ) -> ::std::io::Result<Self> {
+                #deserialize_body
+            }
+        },
+    ))
+}
+
+/// Returns a pattern that destructures the fields and the bindings of each field.
+fn fields_pattern(fields: &syn::Fields) -> (TokenStream, Vec<syn::Ident>) {
+    match fields {
+        syn::Fields::Unit => (quote!(), Vec::new()),
+        syn::Fields::Unnamed(fields) => {
+            let names: Vec<_> =
+                (0..fields.unnamed.len()).map(|i| format_ident!("field_{}", i)).collect();
+            (quote!((#(#names),*)), names)
+        }
+        syn::Fields::Named(fields) => {
+            let names: Vec<_> = fields
+                .named
+                .iter()
+                .map(|field| field.ident.clone().expect("named fields"))
+                .collect();
+            (quote!({ #(#names),* }), names)
+        }
+    }
+}
+
+/// Returns an expression suffix that constructs the fields by deserializing them in order.
+fn fields_construct(fields: &syn::Fields, crate_name: &TokenStream) -> TokenStream {
+    let value = quote!(#crate_name::serialize::Serialize::deserialize(reader)?);
+    match fields {
+        syn::Fields::Unit => quote!(),
+        syn::Fields::Unnamed(fields) => {
+            let values = fields.unnamed.iter().map(|_| &value);
+            quote!((#(#values),*))
+        }
+        syn::Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote!({ #(#names: #value),* })
+        }
+    }
+}
+
+enum ItemOpt {
+    DynecAs(syn::token::Paren, TokenStream),
+}
+
+impl Parse for Named<ItemOpt> {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let name = input.parse::<syn::Ident>()?;
+
+        let value = match name.to_string().as_str() {
+            "dynec_as" => {
+                let inner;
+                let paren = syn::parenthesized!(inner in input);
+                let args = inner.parse()?;
+                ItemOpt::DynecAs(paren, args)
+            }
+            _ => return Err(Error::new_spanned(&name, format!("Unknown argument `{}`", name))),
+        };
+
+        Ok(Named { name, value })
+    }
+}
@@ -0,0 +1,316 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Error;
+
+use crate::util::Result;
+
+/// Returns the identifier of the generated `Columns` type of a struct-of-arrays component.
+pub(crate) fn columns_ident(input: &syn::DeriveInput) -> syn::Ident {
+    format_ident!("{}Columns", input.ident)
+}
+
+/// Generates the struct-of-arrays layout items for a `#[comp(storage = soa)]` struct.
+pub(crate) fn soa(
+    input: &syn::DeriveInput,
+    crate_name: TokenStream,
+    serialize: bool,
+) -> Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "struct-of-arrays components cannot be generic",
+        ));
+    }
+
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(fields), .. }) => {
+            &fields.named
+        }
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "struct-of-arrays components must be structs with named fields",
+            ))
+        }
+    };
+    if fields.is_empty() {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "struct-of-arrays components must have at least one field",
+        ));
+    }
+    for field in fields {
+        if let Some(attr) = field.attrs.iter().find(|attr| attr.path().is_ident("entity")) {
+            return Err(Error::new_spanned(
+                attr,
+                "struct-of-arrays components cannot reference entities",
+            ));
+        }
+    }
+
+    let ident = &input.ident;
+    let vis = &input.vis;
+    let field_idents: Vec<_> =
+        fields.iter().map(|field| field.ident.clone().expect("named fields")).collect();
+    let first_field = &field_idents[0];
+    let field_indices: Vec<_> = (0..fields.len()).map(syn::Index::from).collect();
+    let field_vis: Vec<_> = fields.iter().map(|field| field.vis.clone()).collect();
+    let field_tys: Vec<_> = fields.iter().map(|field| field.ty.clone()).collect();
+    let field_docs: Vec<Vec<_>> = fields
+        .iter()
+        .map(|field| {
+            field.attrs.iter().filter(|attr| attr.path().is_ident("doc")).cloned().collect()
+        })
+        .collect();
+    let field_bindings: Vec<_> =
+        (0..fields.len()).map(|i| format_ident!("__dynec_field_{}", i)).collect();
+
+    let ref_ident = format_ident!("{}Ref", ident);
+    let mut_ident = format_ident!("{}Mut", ident);
+    let slices_ident = format_ident!("{}Slices", ident);
+    let slices_mut_ident = format_ident!("{}SlicesMut", ident);
+    let iter_ident = format_ident!("{}Iter", ident);
+    let iter_mut_ident = format_ident!("{}IterMut", ident);
+    let columns_ident = columns_ident(input);
+
+    let ref_doc = format!("Shared references to the fields of a [`{ident}`] component.");
+    let mut_doc = format!("Mutable references to the fields of a [`{ident}`] component.");
+    let slices_doc =
+        format!("Shared slices of each field of [`{ident}`] components over a chunk of entities.");
+    let slices_mut_doc =
+        format!("Mutable slices of each field of [`{ident}`] components over a chunk of entities.");
+    let iter_doc = format!("Iterates over the components in a [`{slices_ident}`].");
+    let iter_mut_doc = format!("Iterates over the components in a [`{slices_mut_ident}`].");
+    let columns_doc = format!("The arrays storing each field of [`{ident}`] components.");
+
+    // Only serializable components are written to snapshots.
+    let serialize_fn = if serialize {
+        quote! {
+            fn serialize(
+                comp: #ref_ident<'_>,
+                _vtable: &#crate_name::serialize::Vtable<#ident>,
+                writer: &mut #crate_name::serialize::Writer<'_>,
+            ) -> ::std::io::Result<()> {
+                // same format as the derived `Serialize` implementation of the component
+                #(#crate_name::serialize::Serialize::serialize(comp.#field_idents, writer)?;)*
+                ::std::result::Result::Ok(())
+            }
+        }
+    } else {
+        quote! {
+            fn serialize(
+                _comp: #ref_ident<'_>,
+                _vtable: &#crate_name::serialize::Vtable<#ident>,
+                _writer: &mut #crate_name::serialize::Writer<'_>,
+            ) -> ::std::io::Result<()> {
+                ::std::unreachable!("{} is not serializable", ::std::stringify!(#ident))
+            }
+        }
+    };
+
+    Ok(quote! {
+        #[doc = #ref_doc]
+        #[derive(Clone, Copy)]
+        #vis struct #ref_ident<'t> {
+            #(
+                #(#field_docs)*
+                #field_vis #field_idents: &'t #field_tys,
+            )*
+        }
+
+        #[doc = #mut_doc]
+        #vis struct #mut_ident<'t> {
+            #(
+                #(#field_docs)*
+                #field_vis #field_idents: &'t mut #field_tys,
+            )*
+        }
+
+        #[doc = #slices_doc]
+        #[derive(Clone, Copy)]
+        #vis struct #slices_ident<'t> {
+            #(
+                #(#field_docs)*
+                #field_vis #field_idents: &'t [#field_tys],
+            )*
+        }
+
+        #[doc = #slices_mut_doc]
+        #vis struct #slices_mut_ident<'t> {
+            #(
+                #(#field_docs)*
+                #field_vis #field_idents: &'t mut [#field_tys],
+            )*
+        }
+
+        #[doc = #iter_doc]
+        #vis struct #iter_ident<'t> {
+            #(#field_idents: ::std::slice::Iter<'t, #field_tys>,)*
+        }
+
+        #[doc = #iter_mut_doc]
+        #vis struct #iter_mut_ident<'t> {
+            #(#field_idents: ::std::slice::IterMut<'t, #field_tys>,)*
+        }
+
+        #[automatically_derived]
+        impl<'t> ::std::iter::Iterator for #iter_ident<'t> {
+            type Item = #ref_ident<'t>;
+
+            fn next(&mut self) -> ::std::option::Option<#ref_ident<'t>> {
+                ::std::option::Option::Some(#ref_ident {
+                    #(#field_idents: self.#field_idents.next()?,)*
+                })
+            }
+
+            fn size_hint(&self) -> (usize, ::std::option::Option<usize>) {
+                self.#first_field.size_hint()
+            }
+        }
+
+        #[automatically_derived]
+        impl<'t> ::std::iter::Iterator for #iter_mut_ident<'t> {
+            type Item = #mut_ident<'t>;
+
+            fn next(&mut self) -> ::std::option::Option<#mut_ident<'t>> {
+                ::std::option::Option::Some(#mut_ident {
+                    #(#field_idents: self.#field_idents.next()?,)*
+                })
+            }
+
+            fn size_hint(&self) -> (usize, ::std::option::Option<usize>) {
+                self.#first_field.size_hint()
+            }
+        }
+
+        #[automatically_derived]
+        impl<'t> ::std::iter::IntoIterator for #slices_ident<'t> {
+            type Item = #ref_ident<'t>;
+            type IntoIter = #iter_ident<'t>;
+
+            fn into_iter(self) -> #iter_ident<'t> {
+                #iter_ident { #(#field_idents: self.#field_idents.iter(),)* }
+            }
+        }
+
+        #[automatically_derived]
+        impl<'t> ::std::iter::IntoIterator for #slices_mut_ident<'t> {
+            type Item = #mut_ident<'t>;
+            type IntoIter = #iter_mut_ident<'t>;
+
+            fn into_iter(self) -> #iter_mut_ident<'t> {
+                #iter_mut_ident { #(#field_idents: self.#field_idents.iter_mut(),)* }
+            }
+        }
+
+        #[automatically_derived]
+        impl<'t> #crate_name::storage::Chunk for #slices_ident<'t> {
+            fn len(&self) -> usize { self.#first_field.len() }
+        }
+
+        #[automatically_derived]
+        impl<'t> #crate_name::storage::Chunk for #slices_mut_ident<'t> {
+            fn len(&self) -> usize { self.#first_field.len() }
+        }
+
+        #[doc = #columns_doc]
+        #[derive(Default)]
+        #vis struct #columns_ident {
+            #(#field_idents: #crate_name::comp::soa::Column<#field_tys>,)*
+        }
+
+        #[automatically_derived]
+        impl #crate_name::storage::Layout for #columns_ident {
+            type Comp = #ident;
+
+            type Ref<'t> = #ref_ident<'t>;
+            type Mut<'t> = #mut_ident<'t>;
+            type Slice<'t> = #slices_ident<'t>;
+            type SliceMut<'t> = #slices_mut_ident<'t>;
+
+            fn as_whole<'t>(_comp: #ref_ident<'t>) -> ::std::option::Option<&'t #ident>
+            where
+                #ident: 't,
+            {
+                ::std::option::Option::None
+            }
+
+            fn as_whole_mut<'t>(_comp: #mut_ident<'t>) -> ::std::option::Option<&'t mut #ident>
+            where
+                #ident: 't,
+            {
+                ::std::option::Option::None
+            }
+
+            #serialize_fn
+        }
+
+        #[automatically_derived]
+        #[allow(unused_unsafe)]
+        impl #crate_name::comp::soa::Columns for #columns_ident {
+            type Ptrs = (#(#crate_name::comp::soa::ColumnPtr<#field_tys>,)*);
+
+            fn grow_to(&mut self, len: usize) {
+                #(self.#field_idents.grow_to(len);)*
+            }
+
+            fn shrink_to(&mut self, len: usize) {
+                #(self.#field_idents.shrink_to(len);)*
+            }
+
+            fn capacity(&self) -> usize {
+                let capacity = usize::MAX;
+                #(let capacity = ::std::cmp::Ord::min(capacity, self.#field_idents.capacity());)*
+                capacity
+            }
+
+            fn data_bytes(&self) -> usize {
+                0 #(+ self.#field_idents.data_bytes())*
+            }
+
+            unsafe fn write(&mut self, index: usize, comp: #ident) {
+                let #ident { #(#field_idents: #field_bindings,)* } = comp;
+                #(unsafe { self.#field_idents.write(index, #field_bindings) };)*
+            }
+
+            unsafe fn read(&mut self, index: usize) -> #ident {
+                #ident { #(#field_idents: unsafe { self.#field_idents.read(index) },)* }
+            }
+
+            unsafe fn get(&self, index: usize) -> #ref_ident<'_> {
+                #ref_ident { #(#field_idents: unsafe { self.#field_idents.get(index) },)* }
+            }
+
+            unsafe fn slice(&self, range: ::std::ops::Range<usize>) -> #slices_ident<'_> {
+                #slices_ident {
+                    #(
+                        #field_idents: unsafe {
+                            self.#field_idents.slice(::std::clone::Clone::clone(&range))
+                        },
+                    )*
+                }
+            }
+
+            fn as_ptrs(&mut self) -> Self::Ptrs {
+                (#(self.#field_idents.as_ptr(),)*)
+            }
+
+            unsafe fn get_mut<'t>(ptrs: Self::Ptrs, index: usize) -> #mut_ident<'t> {
+                #mut_ident { #(#field_idents: unsafe { ptrs.#field_indices.get_mut(index) },)* }
+            }
+
+            unsafe fn slice_mut<'t>(
+                ptrs: Self::Ptrs,
+                range: ::std::ops::Range<usize>,
+            ) -> #slices_mut_ident<'t> {
+                #slices_mut_ident {
+                    #(
+                        #field_idents: unsafe {
+                            ptrs.#field_indices.slice_mut(::std::clone::Clone::clone(&range))
+                        },
+                    )*
+                }
+            }
+        }
+    })
+}
@@ -18,12 +18,9 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
     let vis = &input.vis;
     let other_attrs = &input.attrs;
 
-    if !matches!(input.sig.output, syn::ReturnType::Default) {
-        return Err(Error::new_spanned(&input.sig.output, "system functions must return unit"));
-    }
-
     let item = item::Agg::parse(ident, args)?;
-    let item::Agg { crate_name, name, state_maybe_uninit, deps, .. } = item;
+    let item::Agg { crate_name, name, state_maybe_uninit, deps, run_conditions, on_error, .. } =
+        item;
 
     // 2. Parse parameters.
 
@@ -42,12 +39,20 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
     let mut isotope_discrim_tys: Vec<Box<syn::Type>> = Vec::new();
     let mut isotope_discrim_values: Vec<Box<syn::Expr>> = Vec::new();
 
+    let mut dynamic_comp_idents: Vec<syn::Ident> = Vec::new();
+    let mut dynamic_comp_values: Vec<Box<syn::Expr>> = Vec::new();
+
+    let mut event_cursor_idents: Vec<syn::Ident> = Vec::new();
+
     let mut input_types: Vec<syn::Type> = Vec::new();
     let mut system_run_args: Vec<TokenStream> = Vec::new();
 
     let mut global_requests: Vec<TokenStream> = Vec::new();
     let mut simple_requests: Vec<TokenStream> = Vec::new();
     let mut isotope_requests: Vec<TokenStream> = Vec::new();
+    let mut dynamic_requests: Vec<TokenStream> = Vec::new();
+    let mut simple_event_requests: Vec<TokenStream> = Vec::new();
+    let mut event_requests: Vec<TokenStream> = Vec::new();
     let mut entity_creator_requests: Vec<TokenStream> = Vec::new();
 
     for (param_index, param) in input.sig.inputs.iter_mut().enumerate() {
@@ -209,6 +214,57 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
                     ealloc_shard_map.snapshot::<#arch>().clone(),
                 ))
             }
+            ArgType::Dynamic { mutable, arch, comp } => {
+                let comp_ident = quote::format_ident!("__dynec_dynamic_comp_{}", param_index);
+                dynamic_comp_idents.push(comp_ident.clone());
+                dynamic_comp_values.push(comp);
+
+                dynamic_requests.push(quote! {
+                    #crate_name::system::spec::DynamicRequest::new::<#arch>(
+                        self.__dynec_dynamic_comps.#comp_ident,
+                        #mutable,
+                    )
+                });
+
+                match mutable {
+                    true => quote!(components.write_dynamic_storage::<#arch>(
+                        self.__dynec_dynamic_comps.#comp_ident.id,
+                    )),
+                    false => quote!(components.read_dynamic_storage::<#arch>(
+                        self.__dynec_dynamic_comps.#comp_ident.id,
+                    )),
+                }
+            }
+            ArgType::SimpleEvents { arch, comp } => {
+                simple_event_requests.push(quote! {
+                    #crate_name::system::spec::SimpleEventRequest::new::<#arch, #comp>()
+                });
+
+                quote!(components.read_simple_events::<#arch, #comp>())
+            }
+            ArgType::Event { writer: true, event } => {
+                event_requests.push(quote! {
+                    #crate_name::system::spec::EventRequest::new_writer::<#event>()
+                });
+
+                quote!(#crate_name::system::EventWriter::<#event>::new(
+                    sync_globals.events::<#event>(),
+                    &offline_buffer,
+                ))
+            }
+            ArgType::Event { writer: false, event } => {
+                let cursor_ident = quote::format_ident!("__dynec_event_cursor_{}", param_index);
+                event_cursor_idents.push(cursor_ident.clone());
+
+                event_requests.push(quote! {
+                    #crate_name::system::spec::EventRequest::new_reader::<#event>()
+                });
+
+                quote!(#crate_name::system::EventReader::<#event>::new(
+                    sync_globals.events::<#event>(),
+                    &mut self.__dynec_event_cursors.#cursor_ident,
+                ))
+            }
             ArgType::EntityCreator { arch, no_partition } => {
                 let no_partition_call = no_partition.then(|| quote!(.no_partition()));
                 entity_creator_requests.push(quote! {
@@ -233,6 +289,24 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
     // 3. Generate code.
 
     let fn_body = &*input.block;
+    let output = &input.sig.output;
+
+    let call_original = match output {
+        syn::ReturnType::Default => quote! {
+            __dynec_original(#(#system_run_args),*);
+            ::std::result::Result::Ok(())
+        },
+        // Fallible systems must return `Result<(), E>` where `E: Error + Send + Sync`,
+        // which is checked by the compiler through this match.
+        syn::ReturnType::Type(..) => quote! {
+            match __dynec_original(#(#system_run_args),*) {
+                ::std::result::Result::Ok(()) => ::std::result::Result::Ok(()),
+                ::std::result::Result::Err(err) => ::std::result::Result::Err(
+                    ::std::boxed::Box::new(err) as #crate_name::system::BoxedError,
+                ),
+            }
+        },
+    };
 
     let input_args: Vec<_> = input.sig.inputs.iter().collect();
 
@@ -246,6 +320,8 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
             let Self {
                 #(#local_state_field_idents,)*
                 __dynec_isotope_discrim_idents: _,
+                __dynec_dynamic_comps: _,
+                __dynec_event_cursors: _,
             } = self;
             (#(#local_state_field_idents,)*)
         };
@@ -257,12 +333,28 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
             #(#isotope_discrim_idents: #isotope_discrim_tys,)*
         }
     };
+    let dynamic_comps_struct = quote! {
+        #[allow(non_camel_case_types)]
+        struct __dynec_dynamic_comps {
+            #(#dynamic_comp_idents: #crate_name::comp::dynamic::Descriptor,)*
+        }
+    };
+    let event_cursors_struct = quote! {
+        #[allow(non_camel_case_types)]
+        struct __dynec_event_cursors {
+            #(#event_cursor_idents: #crate_name::system::EventCursor,)*
+        }
+    };
     let mut local_state_struct = syn::parse2(quote! {
         #[allow(non_camel_case_types)]
         struct __dynec_local_state {
             #(#local_state_entity_attrs #local_state_field_idents: #local_state_field_tys,)*
             #[not_entity = "no entities can be assigned in discriminants because the world is not created yet."]
             __dynec_isotope_discrim_idents: __dynec_isotope_discrim_idents,
+            #[not_entity = "dynamic component descriptors do not contain entities."]
+            __dynec_dynamic_comps: __dynec_dynamic_comps,
+            #[not_entity = "event cursors do not contain entities."]
+            __dynec_event_cursors: __dynec_event_cursors,
         }
     }).expect("invalid struct expression");
     let impl_referrer_for_local_state = entity_ref::entity_ref(
@@ -301,7 +393,8 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
             &self,
             #(#param_state_field_idents: #param_state_field_tys,)*
         ) -> impl #crate_name::system::#system_trait {
-            // Parameters are first borrowed here to prepare the discrim set.
+            // Parameters are first borrowed here to prepare the discrim set
+            // and the dynamic component descriptors.
             // This block cannot move out any `#param_state_field_idents`
             // because they will be moved into the local state struct in the next
             // statement.
@@ -311,8 +404,16 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
                 }
             };
 
+            let __dynec_dynamic_comps = __dynec_dynamic_comps {
+                #(#dynamic_comp_idents: #dynamic_comp_values,)*
+            };
+
             __dynec_local_state {
                 __dynec_isotope_discrim_idents,
+                __dynec_dynamic_comps,
+                __dynec_event_cursors: __dynec_event_cursors {
+                    #(#event_cursor_idents: ::std::default::Default::default(),)*
+                },
                 #(#param_state_field_idents,)*
                 #(#initial_state_field_idents: #initial_state_field_defaults,)*
             }
@@ -322,7 +423,7 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
         /// Calls the underlying system function directly.
         ///
         /// This function should only be used in unit tests.
-        #vis fn call(#(#input_proxy_args: #input_types),*) {
+        #vis fn call(#(#input_proxy_args: #input_types),*) #output {
             __dynec_original(#(#input_proxy_args),*)
         }
     };
@@ -341,7 +442,12 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
                     global_requests: vec![#(#global_requests),*],
                     simple_requests: vec![#(#simple_requests),*],
                     isotope_requests: vec![#(#isotope_requests),*],
+                    dynamic_requests: vec![#(#dynamic_requests),*],
+                    simple_event_requests: vec![#(#simple_event_requests),*],
+                    event_requests: vec![#(#event_requests),*],
                     entity_creator_requests: vec![#(#entity_creator_requests),*],
+                    run_conditions: vec![#(#run_conditions),*],
+                    on_error: #on_error,
                 }
             }
 
@@ -361,10 +467,13 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
     let impl_system = quote! {
         #[automatically_derived]
         impl #crate_name::system::#system_trait for __dynec_local_state {
-            fn run(&mut self, #system_run_params) {
+            fn run(
+                &mut self,
+                #system_run_params
+            ) -> ::std::result::Result<(), #crate_name::system::BoxedError> {
                 let offline_buffer = ::std::cell::RefCell::new(offline_buffer);
 
-                __dynec_original(#(#system_run_args),*)
+                #call_original
             }
 
             fn as_descriptor_mut(&mut self) -> &mut dyn #crate_name::system::Descriptor { self }
@@ -388,13 +497,15 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
             }
 
             #isotope_discrim_idents_struct
+            #dynamic_comps_struct
+            #event_cursors_struct
 
             #local_state_struct
             #impl_referrer_for_local_state
 
             // The actual function is moved here.
             #(#other_attrs)*
-            fn __dynec_original(#(#input_args),*) {
+            fn __dynec_original(#(#input_args),*) #output {
                 #fn_body
             }
 
@@ -29,6 +29,19 @@ pub(super) enum ArgType {
         discrim_set:  Result<Box<syn::Type>, Span>,
         maybe_uninit: Vec<syn::Type>,
     },
+    Dynamic {
+        mutable: bool,
+        arch:    Box<syn::Type>,
+        comp:    Box<syn::Expr>,
+    },
+    SimpleEvents {
+        arch: Box<syn::Type>,
+        comp: Box<syn::Type>,
+    },
+    Event {
+        writer: bool,
+        event:  Box<syn::Type>,
+    },
     EntityCreator {
         arch:         Box<syn::Type>,
         no_partition: bool,
@@ -97,6 +110,57 @@ fn isotope_partial_builder(
     })
 }
 
+fn dynamic_partial_builder(comp: Box<syn::Expr>) -> PartialArgTypeBuilder {
+    Box::new(move |ident, args, args_span| {
+        let [arch]: [&syn::Type; 1] = args.try_into().map_err(|_| {
+            Error::new(
+                args_span,
+                "Cannot infer archetype for dynamic component access. Specify explicitly with \
+                 `#[dynec(dynamic(arch = X, comp = expr))]`, or use \
+                 `ReadDynamic<X>`/`WriteDynamic<X>`.",
+            )
+        })?;
+
+        Ok(ArgType::Dynamic {
+            mutable: ident == "WriteDynamic",
+            arch: Box::new(arch.clone()),
+            comp,
+        })
+    })
+}
+
+fn simple_events_partial_builder() -> PartialArgTypeBuilder {
+    Box::new(move |_, args, args_span| {
+        let [arch, comp]: [&syn::Type; 2] = args.try_into().map_err(|_| {
+            Error::new(
+                args_span,
+                "Cannot infer archetype and component for event subscription. Specify explicitly \
+                 with `#[dynec(simple_events(arch = X, comp = Y))]`, or use `SimpleEvents<X, Y>`.",
+            )
+        })?;
+
+        Ok(ArgType::SimpleEvents { arch: Box::new(arch.clone()), comp: Box::new(comp.clone()) })
+    })
+}
+
+fn event_partial_builder(writer: Option<bool>) -> PartialArgTypeBuilder {
+    Box::new(move |ident, args, args_span| {
+        let [event]: [&syn::Type; 1] = args.try_into().map_err(|_| {
+            Error::new(
+                args_span,
+                "Cannot infer event type. Specify explicitly with `#[dynec(event_writer(event = \
+                 T))]`/`#[dynec(event_reader(event = T))]`, or use \
+                 `EventWriter<T>`/`EventReader<T>`.",
+            )
+        })?;
+
+        Ok(ArgType::Event {
+            writer: writer.unwrap_or(ident == "EventWriter"),
+            event:  Box::new(event.clone()),
+        })
+    })
+}
+
 fn entity_creator_partial_builder(no_partition: bool) -> PartialArgTypeBuilder {
     Box::new(move |_, args, args_span| {
         let [arch]: [&syn::Type; 1] = args.try_into().map_err(|_| {
@@ -181,6 +245,15 @@ pub(super) fn infer_arg_type(param: &mut syn::PatType) -> Result<ArgType> {
                     | "WriteIsotopePartial"
                     | "ReadIsotopeFull"
                     | "WriteIsotopeFull" => isotope_partial_builder(false, None, Vec::new()),
+                    "ReadDynamic" | "WriteDynamic" => {
+                        return Err(Error::new_spanned(
+                            trait_name,
+                            "Dynamic components must be specified with `#[dynec(dynamic(comp = \
+                             expr))]`",
+                        ))
+                    }
+                    "SimpleEvents" => simple_events_partial_builder(),
+                    "EventWriter" | "EventReader" => event_partial_builder(None),
                     "EntityCreator" => entity_creator_partial_builder(false),
                     "EntityDeleter" => entity_deleter_partial_builder(),
                     "EntityIterator" => entity_iterator_partial_builder(),
@@ -319,6 +392,59 @@ fn try_attr_to_arg_type(arg: opt::Arg, attr_span: Span, param_span: Span) -> Res
                 }
             }
         }
+        opt::Arg::Dynamic(_, opts) => {
+            let mutable =
+                opts.find_one(|opt| option_match!(opt, opt::DynamicArg::Mutable => &()))?.is_some();
+            let arch =
+                opts.find_one(|opt| option_match!(opt, opt::DynamicArg::Arch(_, ty) => ty))?;
+            let comp = match opts
+                .find_one(|opt| option_match!(opt, opt::DynamicArg::Comp(_, expr) => expr))?
+            {
+                Some((_, comp)) => comp.clone(),
+                None => {
+                    return Err(Error::new(
+                        attr_span,
+                        "Missing required expression for #[dynec(dynamic(comp = expr))]",
+                    ))
+                }
+            };
+
+            match (arch, mutable) {
+                (Some((_, arch)), mutable) => {
+                    MaybePartial::Full(ArgType::Dynamic { mutable, arch: arch.clone(), comp })
+                }
+                (None, false) => MaybePartial::Partial(dynamic_partial_builder(comp)),
+                (None, true) => {
+                    return Err(Error::new(
+                        attr_span,
+                        "Invalid argument. `mut` has no effect unless `arch` is supplied.",
+                    ));
+                }
+            }
+        }
+        opt::Arg::SimpleEvents(_, opts) => {
+            let arch =
+                opts.find_one(|opt| option_match!(opt, opt::SimpleEventsArg::Arch(_, ty) => ty))?;
+            let comp =
+                opts.find_one(|opt| option_match!(opt, opt::SimpleEventsArg::Comp(_, ty) => ty))?;
+
+            match (arch, comp) {
+                (Some((_, arch)), Some((_, comp))) => MaybePartial::Full(ArgType::SimpleEvents {
+                    arch: arch.clone(),
+                    comp: comp.clone(),
+                }),
+                (None, None) => MaybePartial::Partial(simple_events_partial_builder()),
+                _ => {
+                    return Err(Error::new(
+                        attr_span,
+                        "Invalid argument. `arch` and `comp` have no effect unless both are \
+                         supplied.",
+                    ));
+                }
+            }
+        }
+        opt::Arg::EventWriter(_, opts) => event_arg_type(true, &opts)?,
+        opt::Arg::EventReader(_, opts) => event_arg_type(false, &opts)?,
         opt::Arg::EntityCreator(_, opts) => {
             let arch =
                 opts.find_one(|opt| option_match!(opt, opt::EntityCreatorArg::Arch(_, ty) => ty))?;
@@ -358,3 +484,12 @@ fn try_attr_to_arg_type(arg: opt::Arg, attr_span: Span, param_span: Span) -> Res
     };
     Ok(maybe)
 }
+
+fn event_arg_type(writer: bool, opts: &Attr<opt::EventArg>) -> Result<MaybePartial> {
+    let event = opts.find_one(|opt| option_match!(opt, opt::EventArg::Event(_, ty) => ty))?;
+
+    Ok(match event {
+        Some((_, event)) => MaybePartial::Full(ArgType::Event { writer, event: event.clone() }),
+        None => MaybePartial::Partial(event_partial_builder(Some(writer))),
+    })
+}
@@ -15,6 +15,8 @@ pub(super) enum Opt {
     Before(syn::token::Paren, Punctuated<syn::Expr, syn::Token![,]>),
     After(syn::token::Paren, Punctuated<syn::Expr, syn::Token![,]>),
     Name(syn::Token![=], Box<syn::Expr>),
+    RunIf(syn::Token![=], Box<syn::Expr>),
+    OnError(syn::Token![=], Box<syn::Expr>),
     MaybeUninit(syn::token::Paren, Punctuated<syn::Type, syn::Token![,]>),
 }
 
@@ -47,6 +49,16 @@ impl Parse for Named<Opt> {
                 let name = input.parse::<syn::Expr>()?;
                 Opt::Name(eq, Box::new(name))
             }
+            "run_if" => {
+                let eq = input.parse::<syn::Token![=]>()?;
+                let condition = input.parse::<syn::Expr>()?;
+                Opt::RunIf(eq, Box::new(condition))
+            }
+            "on_error" => {
+                let eq = input.parse::<syn::Token![=]>()?;
+                let on_error = input.parse::<syn::Expr>()?;
+                Opt::OnError(eq, Box::new(on_error))
+            }
             "maybe_uninit" => parse_maybe_uninit(input, Opt::MaybeUninit)?,
             _ => return Err(Error::new_spanned(&name, "Unknown attribute")),
         };
@@ -62,6 +74,8 @@ pub(super) struct Agg {
     pub(super) state_maybe_uninit:  Vec<syn::Type>,
     pub(super) name:                TokenStream,
     pub(super) deps:                Vec<TokenStream>,
+    pub(super) run_conditions:      Vec<TokenStream>,
+    pub(super) on_error:            TokenStream,
 }
 
 impl Agg {
@@ -73,6 +87,8 @@ impl Agg {
             state_maybe_uninit:  Vec::new(),
             name:                quote!(concat!(module_path!(), "::", stringify!(#ident))),
             deps:                Vec::new(),
+            run_conditions:      Vec::new(),
+            on_error:            quote!(::dynec::system::OnError::Continue),
         };
 
         if args.is_empty() {
@@ -84,6 +100,8 @@ impl Agg {
         if let Some((_, ts)) = args.find_one(|opt| option_match!(opt, Opt::DynecAs(_, ts) => ts))? {
             agg.crate_name = ts.clone();
         }
+        let crate_name = &agg.crate_name;
+        agg.on_error = quote!(#crate_name::system::OnError::Continue);
 
         agg.system_thread_local =
             args.find_one(|opt| option_match!(opt, Opt::ThreadLocal => &()))?.is_some();
@@ -93,7 +111,6 @@ impl Agg {
             |opt| option_match!(opt, Opt::MaybeUninit(_, archs) => archs.iter().cloned()),
         );
 
-        let crate_name = &agg.crate_name;
         for named in &args.items {
             match &named.value {
                 Opt::DynecAs(_, _) => {} // already handled
@@ -112,6 +129,18 @@ impl Agg {
                 Opt::Name(_, name_expr) => {
                     agg.name = quote!(#name_expr);
                 }
+                Opt::RunIf(_, condition) => {
+                    agg.run_conditions.push(quote!({
+                        let condition: #crate_name::system::RunCondition = #condition;
+                        condition
+                    }));
+                }
+                Opt::OnError(_, on_error) => {
+                    agg.on_error = quote!({
+                        let on_error: #crate_name::system::OnError = #on_error;
+                        on_error
+                    });
+                }
                 Opt::MaybeUninit(_, _) => {} // already handled
             }
         }
@@ -1,3 +1,4 @@
+use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::Error;
@@ -11,6 +12,10 @@ pub(super) enum Arg {
     Global(Option<syn::token::Paren>, Attr<GlobalArg>),
     Simple(Option<syn::token::Paren>, Attr<SimpleArg>),
     Isotope(Option<syn::token::Paren>, Attr<IsotopeArg>),
+    Dynamic(Option<syn::token::Paren>, Attr<DynamicArg>),
+    SimpleEvents(Option<syn::token::Paren>, Attr<SimpleEventsArg>),
+    EventWriter(Option<syn::token::Paren>, Attr<EventArg>),
+    EventReader(Option<syn::token::Paren>, Attr<EventArg>),
     EntityCreator(Option<syn::token::Paren>, Attr<EntityCreatorArg>),
     EntityDeleter(Option<syn::token::Paren>, Attr<EntityDeleterArg>),
     EntityIterator(Option<syn::token::Paren>, Attr<EntityIteratorArg>),
@@ -27,6 +32,10 @@ impl Parse for Named<Arg> {
             "global" => parse_opt_list(input, Arg::Global)?,
             "simple" => parse_opt_list(input, Arg::Simple)?,
             "isotope" => parse_opt_list(input, Arg::Isotope)?,
+            "dynamic" => parse_opt_list(input, Arg::Dynamic)?,
+            "simple_events" => parse_opt_list(input, Arg::SimpleEvents)?,
+            "event_writer" => parse_opt_list(input, Arg::EventWriter)?,
+            "event_reader" => parse_opt_list(input, Arg::EventReader)?,
             "entity_creator" => parse_opt_list(input, Arg::EntityCreator)?,
             "entity_deleter" => parse_opt_list(input, Arg::EntityDeleter)?,
             "entity_iterator" => parse_opt_list(input, Arg::EntityIterator)?,
@@ -192,6 +201,90 @@ impl Parse for Named<IsotopeArg> {
     }
 }
 
+pub(super) enum DynamicArg {
+    Mutable,
+    Arch(syn::Token![=], Box<syn::Type>),
+    Comp(syn::Token![=], Box<syn::Expr>),
+}
+
+impl Parse for Named<DynamicArg> {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.call(syn::Ident::parse_any)?;
+        let name_string = name.to_string();
+
+        let value = match name_string.as_str() {
+            "mut" => DynamicArg::Mutable,
+            "arch" => {
+                let eq = input.parse::<syn::Token![=]>()?;
+                let ty = input.parse::<syn::Type>()?;
+                DynamicArg::Arch(eq, Box::new(ty))
+            }
+            "comp" => {
+                let eq = input.parse::<syn::Token![=]>()?;
+                let expr = input.parse::<syn::Expr>()?;
+                DynamicArg::Comp(eq, Box::new(expr))
+            }
+            _ => return Err(Error::new_spanned(&name, "Unknown option for #[dynec(dynamic)]")),
+        };
+        Ok(Named { name, value })
+    }
+}
+
+pub(super) enum SimpleEventsArg {
+    Arch(syn::Token![=], Box<syn::Type>),
+    Comp(syn::Token![=], Box<syn::Type>),
+}
+
+impl Parse for Named<SimpleEventsArg> {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse::<syn::Ident>()?;
+        let name_string = name.to_string();
+
+        let value = match name_string.as_str() {
+            "arch" => {
+                let eq = input.parse::<syn::Token![=]>()?;
+                let ty = input.parse::<syn::Type>()?;
+                SimpleEventsArg::Arch(eq, Box::new(ty))
+            }
+            "comp" => {
+                let eq = input.parse::<syn::Token![=]>()?;
+                let ty = input.parse::<syn::Type>()?;
+                SimpleEventsArg::Comp(eq, Box::new(ty))
+            }
+            _ => {
+                return Err(Error::new_spanned(&name, "Unknown option for #[dynec(simple_events)]"))
+            }
+        };
+        Ok(Named { name, value })
+    }
+}
+
+pub(super) enum EventArg {
+    Event(syn::Token![=], Box<syn::Type>),
+}
+
+impl Parse for Named<EventArg> {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse::<syn::Ident>()?;
+        let name_string = name.to_string();
+
+        let value = match name_string.as_str() {
+            "event" => {
+                let eq = input.parse::<syn::Token![=]>()?;
+                let ty = input.parse::<syn::Type>()?;
+                EventArg::Event(eq, Box::new(ty))
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    &name,
+                    "Unknown option for #[dynec(event_writer)] or #[dynec(event_reader)]",
+                ))
+            }
+        };
+        Ok(Named { name, value })
+    }
+}
+
 pub(super) enum EntityCreatorArg {
     Arch(syn::Token![=], Box<syn::Type>),
     NoPartition,
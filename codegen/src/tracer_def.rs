@@ -191,9 +191,13 @@ pub(crate) fn imp(args: TokenStream, input: TokenStream) -> Result<TokenStream>
                     });
                 }
 
-                polyfill_macro_data.extend(quote! {
-                    fn #item_ident { #noop_item }
-                });
+                // Methods with a default body are not polyfilled,
+                // so that `#[tracer]` impls inherit the default instead of a no-op.
+                if item.default.is_none() {
+                    polyfill_macro_data.extend(quote! {
+                        fn #item_ident { #noop_item }
+                    });
+                }
             }
             _ => return Err(syn::Error::new_spanned(item, "unsupported trait item")),
         }
@@ -22,7 +22,8 @@ impl dynec::world::Bundle for Bundle {
     }
 
     /// Populates the world with entities.
-    /// In actual games, this function should load the world from a save file instead.
+    /// In actual games, this function should load the world from a save file
+    /// with [`dynec::World::load`] instead.
     fn populate(&mut self, _: &mut dynec::World) {}
 }
 
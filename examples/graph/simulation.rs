@@ -11,7 +11,8 @@ impl dynec::world::Bundle for Bundle {
     }
 
     /// Populates the world with entities.
-    /// In actual games, this function should load the world from a save file instead.
+    /// In actual games, this function should load the world from a save file
+    /// with [`dynec::World::load`] instead.
     fn populate(&mut self, world: &mut dynec::World) {
         // First, we populate the world with entities with archetype `Node`.
         // Note that no components from the `render` crate are specified here.
@@ -27,5 +27,22 @@ pub trait Archetype: Send + Sync + 'static {
     type RawEntity: entity::Raw;
 
     /// The entity ID allocator for entities of this archetype.
-    type Ealloc: entity::Ealloc<Raw = Self::RawEntity>;
+    ///
+    /// The allocator must be `Send`,
+    /// because the entities created during a cycle are initialized offline
+    /// with different archetypes on different worker threads,
+    /// each of which takes the allocator of its archetype.
+    ///
+    /// This bound is a breaking change from earlier versions,
+    /// where allocators were always used on the main thread.
+    /// Allocators holding thread-bound state such as `Rc` or `RefCell`
+    /// must switch to their thread-safe equivalents.
+    type Ealloc: entity::Ealloc<Raw = Self::RawEntity> + Send;
+
+    /// The key that identifies this archetype in [world snapshots](crate::serialize).
+    ///
+    /// [`archetype!`](macro@crate::archetype) sets this to the archetype identifier
+    /// unless overridden with the `key` option.
+    /// Entities of archetypes without a key cannot be saved.
+    const SERIALIZE_KEY: Option<&'static str> = None;
 }
@@ -44,14 +44,26 @@
 //! specified in [`SimpleOrIsotope::INIT_STRATEGY`] if it is absent in the creation args.
 //!
 //! Isotope components are never instantiated on entity creation.
+//!
+//! # Dynamic components
+//! Components whose schema is only known at runtime can be registered as
+//! [dynamic components](dynamic), which are stored as byte blobs identified by a runtime ID.
+//!
+//! # Struct-of-arrays components
+//! Structs declared with `#[comp(storage = soa)]` are [struct-of-arrays components](soa),
+//! which store each field in a separate array.
 
 use std::any::type_name;
 
-use crate::{entity, Archetype, Storage};
+use crate::{entity, serialize, storage, Archetype, Storage};
 
 pub mod discrim;
 pub use discrim::Discrim;
 
+pub mod dynamic;
+
+pub mod soa;
+
 pub(crate) mod any;
 pub use any::{DepList, InitFn, Initer, Map};
 use itertools::Itertools;
@@ -66,8 +78,33 @@ pub trait SimpleOrIsotope<A: Archetype>: entity::Referrer + Send + Sync + Sized
 
     /// The storage type used for storing this simple component.
     type Storage: Storage<RawEntity = A::RawEntity, Comp = Self>;
+
+    /// The serializer used to save this component in [world snapshots](crate::serialize).
+    ///
+    /// Components are not saved if this is `None`.
+    /// This is set to `Some` by `#[comp(serialize)]`.
+    const SERIALIZER: Option<serialize::Vtable<Self>> = None;
 }
 
+/// The [layout](storage::Layout) in which the component `C` is stored.
+pub type Layout<A, C> = <<C as SimpleOrIsotope<A>>::Storage as storage::Access>::Layout;
+/// A shared reference to the component `C`.
+///
+/// This is `&'t C` unless `C` is a [struct-of-arrays component](soa).
+pub type Ref<'t, A, C> = <Layout<A, C> as storage::Layout>::Ref<'t>;
+/// A mutable reference to the component `C`.
+///
+/// This is `&'t mut C` unless `C` is a [struct-of-arrays component](soa).
+pub type Mut<'t, A, C> = <Layout<A, C> as storage::Layout>::Mut<'t>;
+/// Shared references to the components `C` of a chunk of consecutive entities.
+///
+/// This is `&'t [C]` unless `C` is a [struct-of-arrays component](soa).
+pub type Slice<'t, A, C> = <Layout<A, C> as storage::Layout>::Slice<'t>;
+/// Mutable references to the components `C` of a chunk of consecutive entities.
+///
+/// This is `&'t mut [C]` unless `C` is a [struct-of-arrays component](soa).
+pub type SliceMut<'t, A, C> = <Layout<A, C> as storage::Layout>::SliceMut<'t>;
+
 /// A simple component has only one instance per entity.
 ///
 /// See the [module-level documentation](mod@crate::comp) for more information.
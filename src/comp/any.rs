@@ -112,7 +112,11 @@ macro_rules! impl_simple_init_fn {
             $($deps: comp::Simple<A>,)*
         > InitFn<A, C> for fn(
             $(&$deps,)*
-        ) -> C {
+        ) -> C
+        where
+            // struct-of-arrays components cannot be borrowed as a whole
+            $(<$deps as comp::SimpleOrIsotope<A>>::Storage: storage::Access<Layout = storage::Whole<$deps>>,)*
+        {
             fn init(
                 &self,
                 #[allow(unused_variables)] dep_getter: DepGetter<'_, A>,
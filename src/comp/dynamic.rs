@@ -0,0 +1,35 @@
+//! Dynamic components are components whose schema is only known at runtime.
+//!
+//! Unlike simple and isotope components, a dynamic component is not a Rust type.
+//! It is identified by a runtime [`Id`] (unique within its archetype)
+//! and stored as a fixed-size byte blob for each entity.
+//! Interpreting the bytes is up to the user, e.g. with a layout descriptor from a modding layer.
+//!
+//! Dynamic components are registered to an archetype when a system requesting them is scheduled,
+//! either through `#[dynec(dynamic(comp = expr))]` in [`#[system]`](macro@crate::system)
+//! or through [`DynamicRequest`](crate::system::spec::DynamicRequest).
+//! They are always optional and are never instantiated on entity creation.
+
+use std::fmt;
+
+/// Identifies a dynamic component within an archetype.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Id(pub u32);
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "dynamic#{}", self.0) }
+}
+
+/// Describes a dynamic component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Descriptor {
+    /// The runtime ID of the component.
+    pub id:   Id,
+    /// The number of bytes in each instance of the component.
+    pub size: usize,
+}
+
+impl Descriptor {
+    /// Creates a new descriptor.
+    pub const fn new(id: Id, size: usize) -> Self { Self { id, size } }
+}
@@ -0,0 +1,215 @@
+//! Struct-of-arrays components store each field in a separate array.
+//!
+//! A struct with named fields is declared as a struct-of-arrays component
+//! with `#[comp(of = A, storage = soa)]`.
+//! The component is stored in a [`storage::Soa`],
+//! and [`#[comp]`](macro@crate::comp) generates the following items
+//! with the same visibility as the component, taking `Position` as an example:
+//! - `PositionRef<'t>` and `PositionMut<'t>`,
+//!   which hold a shared/mutable reference to each field of a single component;
+//! - `PositionSlices<'t>` and `PositionSlicesMut<'t>`,
+//!   which hold a shared/mutable slice of each field over a chunk of consecutive entities;
+//! - `PositionIter<'t>` and `PositionIterMut<'t>`,
+//!   which iterate over the components in `PositionSlices<'t>` and `PositionSlicesMut<'t>`;
+//! - `PositionColumns`, which implements [`Columns`] with one [`Column`] per field.
+//!
+//! Apart from the storage layout, struct-of-arrays components are ordinary simple components.
+//! They are accessed through [`ReadSimple`](crate::system::ReadSimple)
+//! and [`WriteSimple`](crate::system::WriteSimple),
+//! except that single-entity accessors return `PositionRef`/`PositionMut`
+//! and chunk accessors return `PositionSlices`/`PositionSlicesMut` (see [`comp::Ref`](super::Ref)).
+//! Since each field is stored contiguously,
+//! systems can process a field over a chunk of entities as a plain slice (e.g. `&[f32]`),
+//! which the compiler can auto-vectorize.
+//!
+//! Struct-of-arrays components cannot be isotopes,
+//! cannot be passed to the initializers of other components,
+//! and their fields must not reference entities.
+
+use std::mem::{self, MaybeUninit};
+use std::{ops, slice};
+
+use crate::storage;
+
+/// The arrays storing each field of a struct-of-arrays component.
+///
+/// The arrays are indexed by entity IDs directly.
+/// Slots are uninitialized unless a component was written to them,
+/// and the storage keeps track of which slots are initialized.
+pub trait Columns: storage::Layout + Default + Send + Sync {
+    /// Raw pointers to the arrays, returned by [`as_ptrs`](Self::as_ptrs).
+    ///
+    /// Mutable references to different slots are derived from the same pointers,
+    /// so that they do not invalidate each other.
+    type Ptrs: Copy + Send + Sync + 'static;
+
+    /// Extends every array to at least `len` slots.
+    fn grow_to(&mut self, len: usize);
+
+    /// Truncates every array to `len` slots and releases the excess capacity.
+    ///
+    /// Initialized slots beyond `len` are leaked.
+    fn shrink_to(&mut self, len: usize);
+
+    /// The number of slots that can be stored without reallocation.
+    fn capacity(&self) -> usize;
+
+    /// The number of bytes allocated by all arrays.
+    fn data_bytes(&self) -> usize;
+
+    /// Moves a component into the slot `index`, overwriting the fields without dropping them.
+    ///
+    /// # Safety
+    /// `index` must be less than the length of the arrays.
+    unsafe fn write(&mut self, index: usize, comp: Self::Comp);
+
+    /// Moves the component out of the slot `index`, leaving the slot uninitialized.
+    ///
+    /// # Safety
+    /// The slot `index` must be initialized.
+    unsafe fn read(&mut self, index: usize) -> Self::Comp;
+
+    /// Returns shared references to the fields in the slot `index`.
+    ///
+    /// # Safety
+    /// The slot `index` must be initialized.
+    unsafe fn get(&self, index: usize) -> Self::Ref<'_>;
+
+    /// Returns shared slices of each field over the slots in `range`.
+    ///
+    /// # Safety
+    /// All slots in `range` must be initialized.
+    unsafe fn slice(&self, range: ops::Range<usize>) -> Self::Slice<'_>;
+
+    /// Returns raw pointers to the arrays.
+    ///
+    /// The pointers are valid until the arrays are accessed through `self` again.
+    fn as_ptrs(&mut self) -> Self::Ptrs;
+
+    /// Returns mutable references to the fields in the slot `index`.
+    ///
+    /// # Safety
+    /// `ptrs` must be valid for `'t`, the slot `index` must be initialized,
+    /// and no other reference to the slot may be alive during `'t`.
+    unsafe fn get_mut<'t>(ptrs: Self::Ptrs, index: usize) -> Self::Mut<'t>;
+
+    /// Returns mutable slices of each field over the slots in `range`.
+    ///
+    /// # Safety
+    /// `ptrs` must be valid for `'t`, all slots in `range` must be initialized,
+    /// and no other reference to the slots may be alive during `'t`.
+    unsafe fn slice_mut<'t>(ptrs: Self::Ptrs, range: ops::Range<usize>) -> Self::SliceMut<'t>;
+}
+
+/// The array storing a single field of a struct-of-arrays component.
+///
+/// This is used in the [`Columns`] implementations generated by
+/// [`#[comp(storage = soa)]`](macro@crate::comp).
+pub struct Column<T>(Vec<MaybeUninit<T>>);
+
+impl<T> Default for Column<T> {
+    fn default() -> Self { Self(Vec::new()) }
+}
+
+impl<T> Column<T> {
+    /// Extends the array to at least `len` slots.
+    pub fn grow_to(&mut self, len: usize) {
+        if self.0.len() < len {
+            self.0.resize_with(len, MaybeUninit::uninit);
+        }
+    }
+
+    /// Truncates the array to `len` slots and releases the excess capacity.
+    pub fn shrink_to(&mut self, len: usize) {
+        self.0.truncate(len);
+        self.0.shrink_to_fit();
+    }
+
+    /// The number of slots that can be stored without reallocation.
+    pub fn capacity(&self) -> usize { self.0.capacity() }
+
+    /// The number of bytes allocated by the array.
+    pub fn data_bytes(&self) -> usize { self.0.capacity() * mem::size_of::<T>() }
+
+    /// Moves a value into the slot `index` without dropping the previous value.
+    ///
+    /// # Safety
+    /// `index` must be less than the length of the array.
+    pub unsafe fn write(&mut self, index: usize, value: T) {
+        debug_assert!(index < self.0.len());
+        self.0.as_mut_ptr().add(index).write(MaybeUninit::new(value));
+    }
+
+    /// Moves the value out of the slot `index`.
+    ///
+    /// # Safety
+    /// The slot `index` must be initialized.
+    pub unsafe fn read(&mut self, index: usize) -> T {
+        debug_assert!(index < self.0.len());
+        self.0.as_ptr().add(index).read().assume_init()
+    }
+
+    /// Returns a shared reference to the slot `index`.
+    ///
+    /// # Safety
+    /// The slot `index` must be initialized.
+    pub unsafe fn get(&self, index: usize) -> &T {
+        debug_assert!(index < self.0.len());
+        &*self.0.as_ptr().add(index).cast::<T>()
+    }
+
+    /// Returns a shared slice over the slots in `range`.
+    ///
+    /// # Safety
+    /// All slots in `range` must be initialized.
+    pub unsafe fn slice(&self, range: ops::Range<usize>) -> &[T] {
+        debug_assert!(range.start <= range.end && range.end <= self.0.len());
+        slice::from_raw_parts(self.0.as_ptr().add(range.start).cast::<T>(), range.len())
+    }
+
+    /// Returns a raw pointer to the array.
+    ///
+    /// The pointer is valid until the array is accessed through `self` again.
+    pub fn as_ptr(&mut self) -> ColumnPtr<T> {
+        ColumnPtr { ptr: self.0.as_mut_ptr().cast::<T>(), len: self.0.len() }
+    }
+}
+
+/// A raw pointer to a [`Column`], returned by [`Column::as_ptr`].
+pub struct ColumnPtr<T> {
+    ptr: *mut T,
+    len: usize,
+}
+
+impl<T> Clone for ColumnPtr<T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T> Copy for ColumnPtr<T> {}
+
+// Safety: a `ColumnPtr` is only dereferenced to obtain references to disjoint slots,
+// which are sent or shared across threads like `&mut T`.
+unsafe impl<T: Send> Send for ColumnPtr<T> {}
+unsafe impl<T: Sync> Sync for ColumnPtr<T> {}
+
+impl<T> ColumnPtr<T> {
+    /// Returns a mutable reference to the slot `index`.
+    ///
+    /// # Safety
+    /// The pointer must be valid for `'t`, the slot `index` must be initialized,
+    /// and no other reference to the slot may be alive during `'t`.
+    pub unsafe fn get_mut<'t>(self, index: usize) -> &'t mut T {
+        debug_assert!(index < self.len);
+        &mut *self.ptr.add(index)
+    }
+
+    /// Returns a mutable slice over the slots in `range`.
+    ///
+    /// # Safety
+    /// The pointer must be valid for `'t`, all slots in `range` must be initialized,
+    /// and no other reference to the slots may be alive during `'t`.
+    pub unsafe fn slice_mut<'t>(self, range: ops::Range<usize>) -> &'t mut [T] {
+        debug_assert!(range.start <= range.end && range.end <= self.len);
+        slice::from_raw_parts_mut(self.ptr.add(range.start), range.len())
+    }
+}
@@ -13,7 +13,7 @@ use std::marker::PhantomData;
 
 use crate::Archetype;
 
-mod raw;
+pub(crate) mod raw;
 pub use raw::Raw;
 
 pub mod deletion;
@@ -24,11 +24,16 @@ pub use ealloc::Ealloc;
 pub mod generation;
 pub use generation::Generation;
 
+pub mod permutation;
+pub use permutation::Permutation;
+
 pub(crate) mod rctrack;
 
 pub mod referrer;
 pub use referrer::Referrer;
 
+mod serialize;
+
 /// Re-export of [`dynec::EntityRef`](crate::EntityRef).
 pub use crate::macros::EntityRef as Ref;
 
@@ -130,6 +135,8 @@ pub(crate) mod maybe {
     pub(crate) type MaybeWeak = Weak<()>;
 
     pub(crate) fn downgrade(arc: &MaybeArc) -> MaybeWeak { Arc::downgrade(arc) }
+
+    pub(crate) fn dangling() -> MaybeWeak { Weak::new() }
 }
 
 #[cfg(not(any(
@@ -144,6 +151,8 @@ mod maybe {
 
     #[allow(clippy::unused_unit)]
     pub(crate) fn downgrade(&MaybeArc: &MaybeArc) -> MaybeWeak { MaybeWeak }
+
+    pub(crate) fn dangling() -> MaybeWeak { MaybeWeak }
 }
 
 pub(crate) use maybe::{MaybeArc, MaybeWeak};
@@ -217,6 +226,13 @@ pub struct Weak<A: Archetype> {
     rc: maybe::MaybeWeak,
 }
 
+impl<A: Archetype> Weak<A> {
+    /// Returns the generation of the entity when this weak reference was created.
+    ///
+    /// The entity is still alive if this is equal to the generation in the [`generation::Store`].
+    pub fn generation(&self) -> Generation { self.generation }
+}
+
 impl<A: Archetype> sealed::Sealed for Weak<A> {}
 impl<A: Archetype> Ref for Weak<A> {
     type Archetype = A;
@@ -65,23 +65,38 @@ pub trait Ealloc: 'static {
     fn mark_need_flush(&mut self);
     /// Flush and reset the mark if `mark_need_flush` was called since the last flush.
     fn flush_if_marked(&mut self);
+
+    /// Resets the allocator after its entities have been [rearranged](crate::World::rearrange)
+    /// to occupy exactly the `count` smallest IDs, leaving no recyclable IDs.
+    ///
+    /// This method is only called in offline mode after [`flush`](Self::flush).
+    fn reset_compact(&mut self, count: usize);
+
+    /// Restores the allocator to the state of a [`snapshot`](Self::snapshot),
+    /// where all IDs below `gauge` except those in `recyclable` are allocated.
+    ///
+    /// This method is only called in offline mode when loading a [world snapshot](crate::serialize).
+    fn restore(&mut self, gauge: Self::Raw, recyclable: impl Iterator<Item = Self::Raw>);
 }
 
 // Object-safe version of [`Ealloc`].
-pub(crate) trait AnyEalloc {
+pub(crate) trait AnyEalloc: Send {
     fn as_any_mut(&mut self) -> &mut dyn Any;
 
     fn shards(&mut self, vec: &mut Vec<Box<dyn AnyShard>>);
 
     fn snapshot(&self) -> Box<dyn Any + Send + Sync>;
 
+    /// Returns true if no entities are allocated.
+    fn is_empty(&self) -> bool;
+
     fn flush(&mut self);
 
     fn mark_need_flush(&mut self);
     fn flush_if_marked(&mut self);
 }
 
-impl<T: Ealloc> AnyEalloc for T {
+impl<T: Ealloc + Send> AnyEalloc for T {
     fn as_any_mut(&mut self) -> &mut dyn Any { self }
 
     fn shards(&mut self, vec: &mut Vec<Box<dyn AnyShard>>) {
@@ -90,6 +105,8 @@ impl<T: Ealloc> AnyEalloc for T {
 
     fn snapshot(&self) -> Box<dyn Any + Send + Sync> { Box::new(Ealloc::snapshot(self)) }
 
+    fn is_empty(&self) -> bool { Ealloc::snapshot(self).iter_allocated_chunks().next().is_none() }
+
     fn flush(&mut self) { Ealloc::flush(self); }
 
     fn mark_need_flush(&mut self) { Ealloc::mark_need_flush(self) }
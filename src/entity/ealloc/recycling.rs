@@ -170,6 +170,37 @@ impl<RawT: Raw, T: Recycler<RawT>, S: ShardAssigner> Ealloc for Recycling<RawT,
             self.flush();
         }
     }
+
+    fn reset_compact(&mut self, count: usize) {
+        let gauge = Arc::get_mut(&mut self.global_gauge)
+            .expect("all exposed shards should be dropped before reset");
+        *gauge = RawT::new();
+        gauge.fetch_add(count);
+
+        Arc::get_mut(&mut self.recyclable)
+            .expect("all exposed shards should be dropped before reset")
+            .clear();
+
+        for recycler in &mut self.recycler_shards {
+            *Arc::get_mut(recycler)
+                .expect("all exposed shards should be dropped before reset")
+                .get_mut() = T::default();
+        }
+    }
+
+    fn restore(&mut self, gauge: RawT, recyclable: impl Iterator<Item = RawT>) {
+        self.reset_compact(gauge.sub(RawT::new().load_mut()));
+
+        for queue in &mut self.reuse_queue_shards {
+            Arc::get_mut(queue)
+                .expect("all exposed shards should be dropped before restore")
+                .get_mut()
+                .clear();
+        }
+        self.dealloc_queue.clear();
+        self.dealloc_queue.extend(recyclable);
+        self.flush();
+    }
 }
 
 fn distribute_sorted(sizes: &mut [usize], total: usize) {
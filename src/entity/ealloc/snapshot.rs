@@ -13,8 +13,8 @@ use crate::entity::Raw;
 /// A snapshot of the allocated entities during offline.
 #[derive(Debug, Clone)]
 pub struct Snapshot<E> {
-    pub(super) gauge:      E,
-    pub(super) recyclable: Arc<BTreeSet<E>>,
+    pub(crate) gauge:      E,
+    pub(crate) recyclable: Arc<BTreeSet<E>>,
 }
 
 impl<E: Raw> Snapshot<E> {
@@ -23,6 +23,11 @@ impl<E: Raw> Snapshot<E> {
         iter_gaps(self.gauge, self.recyclable.iter().copied())
     }
 
+    /// Returns whether `id` is allocated in this snapshot.
+    pub(crate) fn contains(&self, id: E) -> bool {
+        E::new().load_mut() <= id && id < self.gauge && !self.recyclable.contains(&id)
+    }
+
     pub(crate) fn as_slice(&self) -> Slice<'_, E> {
         Slice {
             start:      E::new().load_mut(),
@@ -3,7 +3,9 @@
 
 use std::any::TypeId;
 use std::collections::HashMap;
+use std::io;
 
+use crate::serialize::{self, Serialize};
 use crate::util::DbgTypeId;
 use crate::Archetype;
 
@@ -12,6 +14,16 @@ use crate::Archetype;
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Generation(u32);
 
+impl Serialize for Generation {
+    fn serialize(&self, writer: &mut serialize::Writer<'_>) -> io::Result<()> {
+        self.0.serialize(writer)
+    }
+
+    fn deserialize(reader: &mut serialize::Reader<'_>) -> io::Result<Self> {
+        u32::deserialize(reader).map(Self)
+    }
+}
+
 /// Stores generations of entities for a specific archetype.
 #[derive(Default)]
 pub struct Store {
@@ -32,6 +44,41 @@ impl Store {
 
     /// Gets the generation of the last created entity with the given `id`.
     pub fn get(&self, id: usize) -> Generation { self.vec.get(id).copied().unwrap_or_default() }
+
+    /// Overwrites the generation of the entity with the given `id`.
+    /// Used when loading a [world snapshot](crate::serialize).
+    pub(crate) fn set(&mut self, id: usize, generation: Generation) {
+        if self.vec.len() <= id {
+            self.vec.resize(id + 1, Generation::default());
+        }
+        *self.vec.get_mut(id).expect("just resized") = generation;
+    }
+
+    /// Moves entities from `old` to `new` for each `(old, new)` in `moves`,
+    /// returning the `(old, new)` generations of each moved entity.
+    ///
+    /// The new generation is newer than the previous generations of both slots,
+    /// so that weak references to deleted entities remain distinguishable.
+    pub(crate) fn rearrange(&mut self, moves: &[(usize, usize)]) -> Vec<(Generation, Generation)> {
+        let generations: Vec<_> = moves
+            .iter()
+            .map(|&(old, new)| {
+                let old_generation = self.get(old);
+                let new_generation =
+                    Generation(old_generation.max(self.get(new)).0.wrapping_add(1));
+                (old_generation, new_generation)
+            })
+            .collect();
+
+        for (&(_, new), &(_, generation)) in moves.iter().zip(&generations) {
+            if self.vec.len() <= new {
+                self.vec.resize(new + 1, Generation::default());
+            }
+            *self.vec.get_mut(new).expect("just resized") = generation;
+        }
+
+        generations
+    }
 }
 
 /// A map of generation stores for each archetype.
@@ -54,6 +101,25 @@ impl StoreMap {
             None => Generation::default(),
         }
     }
+
+    /// Returns the generation store for the given archetype.
+    pub(crate) fn get_mut<A: Archetype>(&mut self) -> &mut Store {
+        self.map.entry(DbgTypeId::of::<A>()).or_default()
+    }
+
+    /// Returns the generation store for the archetype identified by its type ID.
+    pub(crate) fn get_mut_by_id(&mut self, ty: DbgTypeId) -> &mut Store {
+        self.map.entry(ty).or_default()
+    }
+
+    /// Moves the generations of entities with the given archetype.
+    /// See [`Store::rearrange`].
+    pub(crate) fn rearrange<A: Archetype>(
+        &mut self,
+        moves: &[(usize, usize)],
+    ) -> Vec<(Generation, Generation)> {
+        self.map.entry(DbgTypeId::of::<A>()).or_default().rearrange(moves)
+    }
 }
 
 /// Parameter to [`super::Entity::weak`].
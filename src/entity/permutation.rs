@@ -0,0 +1,200 @@
+//! Describes the new order of entities in an archetype,
+//! used for [rearranging](crate::World::rearrange) entities.
+
+use crate::entity::{Ealloc, Raw, TempRef};
+use crate::{comp, Archetype, World};
+
+mod curve;
+pub use curve::{Curve, Point};
+
+#[cfg(test)]
+mod tests;
+
+/// A new order of all entities in an archetype.
+///
+/// After [rearrangement](crate::World::rearrange),
+/// the `i`-th entity in the permutation is moved to the `i`-th smallest entity ID,
+/// such that the allocated entity IDs become contiguous.
+pub struct Permutation<A: Archetype> {
+    /// `order[i]` is the original ID of the entity moved to the `i`-th ID.
+    order: Vec<A::RawEntity>,
+}
+
+impl<A: Archetype> Permutation<A> {
+    /// Creates a permutation from the desired order of entities.
+    ///
+    /// `order` must yield every allocated entity of the archetype exactly once.
+    /// This is validated when the permutation is applied.
+    pub fn from_order(order: impl IntoIterator<Item = A::RawEntity>) -> Self {
+        Self { order: order.into_iter().collect() }
+    }
+
+    /// Creates a permutation by sorting the entities with a key function.
+    ///
+    /// The sort is stable, so entities with equal keys retain their original relative order.
+    pub fn sort_by_key<K: Ord>(
+        entities: impl IntoIterator<Item = A::RawEntity>,
+        mut key: impl FnMut(A::RawEntity) -> K,
+    ) -> Self {
+        let mut order: Vec<_> = entities.into_iter().collect();
+        order.sort_by_cached_key(|&entity| key(entity));
+        Self { order }
+    }
+
+    /// Creates a permutation that orders entities along a space-filling [`Curve`].
+    ///
+    /// Entities mapped to `None` are placed after all other entities in their original order.
+    pub fn sort_by_curve<P: Point>(
+        entities: impl IntoIterator<Item = (A::RawEntity, Option<P>)>,
+        curve: Curve,
+    ) -> Self {
+        let (mut located, unlocated): (Vec<_>, Vec<_>) =
+            entities.into_iter().partition(|(_, point)| point.is_some());
+        let indices = curve::indices(
+            curve,
+            located.iter().map(|(_, point)| point.as_ref().expect("partitioned").coords()),
+        );
+
+        let mut order: Vec<_> = located.drain(..).map(|(entity, _)| entity).zip(indices).collect();
+        order.sort_by_key(|&(_, index)| index);
+
+        Self::from_order(
+            order
+                .into_iter()
+                .map(|(entity, _)| entity)
+                .chain(unlocated.into_iter().map(|(entity, _)| entity)),
+        )
+    }
+
+    /// Creates a permutation that clusters entities by the entities of archetype `B`
+    /// they reference.
+    ///
+    /// Entities are sorted by the ID of the referenced entity,
+    /// so that entities referencing nearby entities are also located nearby.
+    /// This is most effective if `B` has already been [rearranged](crate::World::rearrange),
+    /// e.g. with [`sort_by_curve`](Self::sort_by_curve).
+    ///
+    /// Entities mapped to `None` are placed after all other entities in their original order.
+    pub fn sort_by_reference<B: Archetype>(
+        entities: impl IntoIterator<Item = (A::RawEntity, Option<B::RawEntity>)>,
+    ) -> Self {
+        let mut order: Vec<_> = entities.into_iter().collect();
+        order.sort_by_key(|&(_, reference)| {
+            reference.map_or((true, 0), |reference| (false, reference.to_primitive()))
+        });
+        Self::from_order(order.into_iter().map(|(entity, _)| entity))
+    }
+
+    /// Computes a permutation that orders all entities of the archetype
+    /// along a space-filling [`Curve`] by the position stored in component `C`.
+    ///
+    /// Entities without the component `C` are placed after all other entities.
+    ///
+    /// # Example
+    /// ```
+    /// # use dynec::entity::permutation::{Curve, Permutation};
+    /// dynec::archetype!(Node);
+    ///
+    /// #[dynec::comp(of = Node)]
+    /// struct Position([f32; 2]);
+    ///
+    /// # #[dynec::system]
+    /// # fn use_position(_: dynec::system::ReadSimple<Node, Position>) {}
+    /// # let mut builder = dynec::world::Builder::new(0);
+    /// # builder.schedule(use_position.build());
+    /// # let mut world = builder.build();
+    /// # for i in 0..4 { world.create::<Node>(dynec::comps![Node => Position([(i % 2) as f32, (i / 2) as f32])]); }
+    /// let permutation =
+    ///     Permutation::<Node>::from_curve::<Position, _>(&mut world, Curve::Hilbert, |pos| pos.0);
+    /// world.rearrange(permutation);
+    /// ```
+    pub fn from_curve<C: comp::Simple<A>, P: Point>(
+        world: &mut World,
+        curve: Curve,
+        mut point: impl FnMut(comp::Ref<'_, A, C>) -> P,
+    ) -> Self {
+        let entities = allocated_entities::<A>(world);
+        let storage = world.components.get_simple_storage::<A, C>();
+        Self::sort_by_curve(
+            entities.into_iter().map(|entity| {
+                let value = storage.try_get(TempRef::<A>::new(entity));
+                (entity, value.map(&mut point))
+            }),
+            curve,
+        )
+    }
+
+    /// Computes a permutation that clusters all entities of the archetype
+    /// by the entity of archetype `B` referenced in component `C`.
+    /// See [`sort_by_reference`](Self::sort_by_reference) for details.
+    ///
+    /// Entities without the component `C` are placed after all other entities.
+    ///
+    /// # Example
+    /// ```
+    /// # use dynec::entity::permutation::{Curve, Permutation};
+    /// use dynec::entity::Ref as _;
+    ///
+    /// dynec::archetype!(Node; Edge);
+    ///
+    /// #[dynec::comp(of = Node, required)]
+    /// struct Position([f32; 2]);
+    ///
+    /// #[dynec::comp(of = Edge, required)]
+    /// struct Endpoints {
+    ///     #[entity]
+    ///     from: dynec::Entity<Node>,
+    ///     #[entity]
+    ///     to:   dynec::Entity<Node>,
+    /// }
+    ///
+    /// # #[dynec::system]
+    /// # fn use_endpoints(
+    /// #     _: dynec::system::ReadSimple<Node, Position>,
+    /// #     _: dynec::system::ReadSimple<Edge, Endpoints>,
+    /// # ) {}
+    /// # let mut builder = dynec::world::Builder::new(0);
+    /// # builder.schedule(use_endpoints.build());
+    /// # let mut world = builder.build();
+    /// # let from = world.create::<Node>(dynec::comps![Node => Position([0.0, 0.0])]);
+    /// # let to = world.create::<Node>(dynec::comps![Node => Position([1.0, 0.0])]);
+    /// # world.create::<Edge>(dynec::comps![Edge => Endpoints { from, to }]);
+    /// // Rearrange nodes spatially first, then cluster edges by their source nodes.
+    /// let permutation =
+    ///     Permutation::<Node>::from_curve::<Position, _>(&mut world, Curve::Hilbert, |pos| pos.0);
+    /// world.rearrange(permutation);
+    ///
+    /// let permutation =
+    ///     Permutation::<Edge>::follow_references::<Endpoints, Node>(&mut world, |endpoints| {
+    ///         Some(endpoints.from.id())
+    ///     });
+    /// world.rearrange(permutation);
+    /// ```
+    pub fn follow_references<C: comp::Simple<A>, B: Archetype>(
+        world: &mut World,
+        mut reference: impl FnMut(comp::Ref<'_, A, C>) -> Option<B::RawEntity>,
+    ) -> Self {
+        let entities = allocated_entities::<A>(world);
+        let storage = world.components.get_simple_storage::<A, C>();
+        Self::sort_by_reference::<B>(entities.into_iter().map(|entity| {
+            let value = storage.try_get(TempRef::<A>::new(entity));
+            (entity, value.and_then(&mut reference))
+        }))
+    }
+
+    /// Returns the number of entities in this permutation.
+    pub fn len(&self) -> usize { self.order.len() }
+
+    /// Returns whether the permutation contains no entities.
+    pub fn is_empty(&self) -> bool { self.order.is_empty() }
+
+    /// Iterates over the original entity IDs in the new order.
+    pub fn iter(&self) -> impl Iterator<Item = A::RawEntity> + '_ { self.order.iter().copied() }
+}
+
+/// Lists all allocated entities of an archetype in ascending order.
+fn allocated_entities<A: Archetype>(world: &mut World) -> Vec<A::RawEntity> {
+    let ealloc = world.ealloc_map.get::<A>();
+    ealloc.flush();
+    ealloc.snapshot().iter_allocated_chunks().flat_map(A::RawEntity::range).collect()
+}
@@ -0,0 +1,136 @@
+//! Space-filling curves for ordering entities spatially.
+
+/// A space-filling curve used to order points in multi-dimensional space.
+///
+/// Points that are close to each other on the curve are also close in space,
+/// so entities ordered along the curve are likely to be accessed together
+/// by systems that process spatially nearby entities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Curve {
+    /// The Z-order curve, computed by interleaving the bits of each coordinate.
+    ///
+    /// This is cheaper to compute than [`Hilbert`](Self::Hilbert),
+    /// but has long jumps between quadrants.
+    Morton,
+    /// The Hilbert curve, which has better locality than [`Morton`](Self::Morton)
+    /// because consecutive points on the curve are always adjacent in space.
+    Hilbert,
+}
+
+/// A point that can be ordered along a [`Curve`].
+pub trait Point {
+    /// Returns the coordinates of this point.
+    ///
+    /// All points ordered together must have the same number of coordinates.
+    fn coords(&self) -> &[f32];
+}
+
+impl Point for [f32; 2] {
+    fn coords(&self) -> &[f32] { self }
+}
+
+impl Point for [f32; 3] {
+    fn coords(&self) -> &[f32] { self }
+}
+
+/// Computes the curve index of each point.
+///
+/// The points are quantized against their common bounding box,
+/// so the returned indices are only comparable within the same call.
+pub(crate) fn indices<'t>(
+    curve: Curve,
+    points: impl Iterator<Item = &'t [f32]> + Clone,
+) -> Vec<u64> {
+    let Some(dims) = points.clone().next().map(<[f32]>::len) else { return Vec::new() };
+    assert!(
+        (1..=u64::BITS as usize).contains(&dims),
+        "Points must have between 1 and {} coordinates",
+        u64::BITS
+    );
+    let bits = u64::BITS as usize / dims;
+
+    let mut min = vec![f32::INFINITY; dims];
+    let mut max = vec![f32::NEG_INFINITY; dims];
+    for point in points.clone() {
+        assert_eq!(point.len(), dims, "All points must have the same number of coordinates");
+        for ((&coord, min), max) in point.iter().zip(&mut min).zip(&mut max) {
+            if coord.is_finite() {
+                *min = min.min(coord);
+                *max = max.max(coord);
+            }
+        }
+    }
+
+    let scale = (u64::MAX >> (u64::BITS as usize - bits)) as f64;
+    let mut axes = vec![0u64; dims];
+    points
+        .map(|point| {
+            for (((&coord, &min), &max), axis) in point.iter().zip(&min).zip(&max).zip(&mut axes) {
+                *axis = if coord.is_finite() && max > min {
+                    ((f64::from(coord) - f64::from(min)) / (f64::from(max) - f64::from(min))
+                        * scale)
+                        .round() as u64
+                } else {
+                    0
+                };
+            }
+
+            if curve == Curve::Hilbert {
+                axes_to_transpose(&mut axes, bits);
+            }
+            interleave(&axes, bits)
+        })
+        .collect()
+}
+
+/// Interleaves the lowest `bits` bits of each axis, with the first axis as the most significant.
+fn interleave(axes: &[u64], bits: usize) -> u64 {
+    let mut index = 0;
+    for bit in (0..bits).rev() {
+        for axis in axes {
+            index = (index << 1) | ((axis >> bit) & 1);
+        }
+    }
+    index
+}
+
+/// Converts coordinates into the transposed form of their Hilbert index in place.
+///
+/// This is the `AxesToTranspose` algorithm from
+/// John Skilling, "Programming the Hilbert curve", AIP Conference Proceedings 707, 381 (2004).
+fn axes_to_transpose(axes: &mut [u64], bits: usize) {
+    let Some(last) = axes.len().checked_sub(1) else { return };
+    let msb = 1u64 << (bits - 1);
+
+    // inverse undo
+    let mut q = msb;
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..axes.len() {
+            if axes[i] & q != 0 {
+                axes[0] ^= p;
+            } else {
+                let t = (axes[0] ^ axes[i]) & p;
+                axes[0] ^= t;
+                axes[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+
+    // gray encode
+    for i in 1..axes.len() {
+        axes[i] ^= axes[i - 1];
+    }
+    let mut t = 0;
+    let mut q = msb;
+    while q > 1 {
+        if axes[last] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for axis in axes {
+        *axis ^= t;
+    }
+}
@@ -0,0 +1,66 @@
+use std::num::NonZeroU32;
+
+use super::{Curve, Permutation};
+use crate::test_util::TestArch;
+
+fn raw(id: u32) -> NonZeroU32 { NonZeroU32::new(id).expect("id != 0") }
+
+fn order(permutation: &Permutation<TestArch>) -> Vec<u32> {
+    permutation.iter().map(NonZeroU32::get).collect()
+}
+
+const SQUARE: [[f32; 2]; 4] = [[1.0, 1.0], [0.0, 1.0], [1.0, 0.0], [0.0, 0.0]];
+
+#[test]
+fn test_sort_by_morton() {
+    let permutation = Permutation::<TestArch>::sort_by_curve(
+        SQUARE.into_iter().enumerate().map(|(i, point)| (raw(i as u32 + 1), Some(point))),
+        Curve::Morton,
+    );
+    assert_eq!(order(&permutation), vec![4, 2, 3, 1]);
+}
+
+#[test]
+fn test_sort_by_hilbert() {
+    let permutation = Permutation::<TestArch>::sort_by_curve(
+        SQUARE.into_iter().enumerate().map(|(i, point)| (raw(i as u32 + 1), Some(point))),
+        Curve::Hilbert,
+    );
+    assert_eq!(order(&permutation), vec![4, 2, 1, 3]);
+}
+
+#[test]
+fn test_sort_by_hilbert_3d_adjacent() {
+    let points: Vec<[f32; 3]> =
+        (0..64).map(|i| [(i % 4) as f32, (i / 4 % 4) as f32, (i / 16) as f32]).collect();
+    let permutation = Permutation::<TestArch>::sort_by_curve(
+        points.iter().enumerate().map(|(i, &point)| (raw(i as u32 + 1), Some(point))),
+        Curve::Hilbert,
+    );
+
+    let sorted: Vec<_> = permutation.iter().map(|id| points[id.get() as usize - 1]).collect();
+    for pair in sorted.windows(2) {
+        let distance: f32 = pair[0].iter().zip(pair[1]).map(|(a, b)| (a - b).abs()).sum();
+        assert_eq!(distance, 1.0, "consecutive points {pair:?} are not adjacent");
+    }
+}
+
+#[test]
+fn test_sort_by_curve_unlocated_last() {
+    let permutation = Permutation::<TestArch>::sort_by_curve(
+        [(raw(1), None), (raw(2), Some([1.0, 0.0])), (raw(3), None), (raw(4), Some([0.0, 0.0]))],
+        Curve::Morton,
+    );
+    assert_eq!(order(&permutation), vec![4, 2, 1, 3]);
+}
+
+#[test]
+fn test_sort_by_reference() {
+    let permutation = Permutation::<TestArch>::sort_by_reference::<TestArch>([
+        (raw(1), Some(raw(3))),
+        (raw(2), None),
+        (raw(3), Some(raw(1))),
+        (raw(4), Some(raw(3))),
+    ]);
+    assert_eq!(order(&permutation), vec![3, 1, 4, 2]);
+}
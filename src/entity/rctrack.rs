@@ -43,6 +43,14 @@ mod inner {
         pub fn get(&self, id: usize) -> Option<&sync::Arc<()>> {
             self.vec.get(id).and_then(Option::as_ref)
         }
+
+        /// Moves the reference counters from `old` to `new` for each `(old, new)` in `moves`.
+        pub(crate) fn rearrange(&mut self, moves: &[(usize, usize)]) {
+            let rcs: Vec<_> = moves.iter().map(|&(old, _)| self.remove(old)).collect();
+            for (&(_, new), rc) in moves.iter().zip(rcs) {
+                self.set(new, rc);
+            }
+        }
     }
 
     #[derive(Default)]
@@ -64,6 +72,21 @@ mod inner {
                 .remove(id)
         }
 
+        /// Returns the store for an archetype.
+        pub(crate) fn get_mut<A: Archetype>(&mut self) -> &mut Store {
+            self.map.entry(DbgTypeId::of::<A>()).or_default()
+        }
+
+        /// Returns the store for an archetype identified by its type ID.
+        pub(crate) fn get_mut_by_id(&mut self, ty: DbgTypeId) -> &mut Store {
+            self.map.entry(ty).or_default()
+        }
+
+        /// Returns a new reference counter of an entity if it is allocated.
+        pub(crate) fn get_rc<A: Archetype>(&self, id: usize) -> Option<entity::MaybeArc> {
+            self.map.get(&TypeId::of::<A>())?.get(id).cloned()
+        }
+
         pub(super) fn to_strong<A: Archetype>(&self, entity: entity::TempRef<'_, A>) -> Entity<A> {
             let archetype = self.map.get(&TypeId::of::<A>()).expect("entity archetype is unknown");
             let arc = archetype.get(entity.value.to_primitive()).expect("entity does not exist");
@@ -77,19 +100,39 @@ mod inner {
     all(not(debug_assertions), feature = "release-entity-rc"),
 )))]
 mod inner {
+    use crate::util::DbgTypeId;
     use crate::{entity, Archetype, Entity};
 
+    /// A dummy Store that does not track anything.
+    #[derive(Default)]
+    pub(crate) struct Store(());
+
+    impl Store {
+        pub(crate) fn set(&mut self, _id: usize, _rc: entity::MaybeArc) {}
+    }
+
     /// A dummy StoreMap that implements `to_string` without any lookup or arc clone.
     #[derive(Default)]
-    pub(crate) struct StoreMap(());
+    pub(crate) struct StoreMap(Store);
 
     impl StoreMap {
+        pub(crate) fn get_mut_by_id(&mut self, _ty: DbgTypeId) -> &mut Store { &mut self.0 }
+
+        /// Entity allocation is not tracked without refcounting,
+        /// so this method always returns a dummy reference counter.
+        #[allow(clippy::extra_unused_type_parameters)] // consistent with the refcounted version
+        pub(crate) fn get_rc<A: Archetype>(&self, _id: usize) -> Option<entity::MaybeArc> {
+            Some(entity::maybe::MaybeArc)
+        }
+
         pub(super) fn to_strong<A: Archetype>(&self, entity: entity::TempRef<'_, A>) -> Entity<A> {
             Entity { id: entity.value, rc: entity::maybe::MaybeArc }
         }
     }
 }
 
+pub(crate) use inner::Store as MaybeStore;
+
 /// A map of rctrack stores for each archetype.
 #[derive(Default)]
 pub struct MaybeStoreMap(pub(crate) inner::StoreMap);
@@ -6,11 +6,13 @@ use std::collections::HashSet;
 use std::marker::PhantomData;
 use std::{iter, ops};
 
-use self::search_single::SearchSingleStrong;
-use super::Raw;
+use self::rearrange::Rearrange;
+use self::search_single::{SearchSingleStrong, SearchStrong};
+use super::{Generation, Raw};
 use crate::util::DbgTypeId;
-use crate::Archetype;
+use crate::{serialize, Archetype, Global};
 
+pub(crate) mod rearrange;
 pub(crate) mod search_single;
 mod std_impl;
 
@@ -136,9 +138,10 @@ pub struct VisitStrongResult {
 
 #[doc(hidden)]
 pub struct VisitWeakArgs<'t> {
-    archetype: DbgTypeId,
-    raw:       usize,
-    rc:        &'t mut super::MaybeWeak,
+    archetype:  DbgTypeId,
+    raw:        usize,
+    generation: &'t mut Generation,
+    rc:         &'t mut super::MaybeWeak,
 }
 
 #[doc(hidden)]
@@ -173,9 +176,10 @@ impl<A: Archetype> Referrer for super::Weak<A> {
     #[inline]
     fn visit_mut<V: VisitMutArg>(&mut self, arg: &mut V) {
         let ret = arg._visit_weak(VisitWeakArgs {
-            archetype: DbgTypeId::of::<A>(),
-            raw:       self.id.to_primitive(),
-            rc:        &mut self.rc,
+            archetype:  DbgTypeId::of::<A>(),
+            raw:        self.id.to_primitive(),
+            generation: &mut self.generation,
+            rc:         &mut self.rc,
         });
         self.id = A::RawEntity::from_primitive(ret.new_raw);
     }
@@ -195,23 +199,38 @@ impl<'t> AsObject<'t> {
 /// with specific implementors of [`VisitMutArg`].
 pub(crate) trait Object {
     fn search_single_strong(&mut self, state: &mut SearchSingleStrong);
+
+    fn search_strong(&mut self, state: &mut SearchStrong);
+
+    fn rearrange(&mut self, state: &mut Rearrange);
 }
 
 /// Virtual dispatch table to operate referrer functions on single instances,
 /// used on global states.
 pub(crate) struct SingleVtable {
-    search_single_strong: fn(&mut dyn Any, &mut SearchSingleStrong),
+    search_single_strong:  fn(&mut dyn Any, &mut SearchSingleStrong),
+    rearrange:             fn(&mut dyn Any, &mut Rearrange),
+    /// Saves the global state in world snapshots if it is serializable.
+    pub(crate) serializer: Option<serialize::GlobalVtable>,
 }
 
 impl SingleVtable {
-    pub(crate) fn of<T: Referrer>() -> Self {
+    pub(crate) fn of<T: Global>() -> Self {
         Self {
+            serializer:           serialize::GlobalVtable::of::<T>(),
             search_single_strong: |object, state| {
                 object.downcast_mut::<T>().expect("TypeId mismatch").visit_mut(state)
             },
+            rearrange:            |object, state| {
+                object.downcast_mut::<T>().expect("TypeId mismatch").visit_mut(state)
+            },
         }
     }
 
+    pub(crate) fn rearrange(&mut self, value: &mut dyn Any, state: &mut Rearrange) {
+        (self.rearrange)(value, state)
+    }
+
     pub(crate) fn search_single_strong(
         &mut self,
         value: &mut dyn Any,
@@ -235,6 +254,20 @@ where
             item.visit_mut(state);
         }
     }
+
+    fn search_strong(&mut self, state: &mut SearchStrong) {
+        for mut item in self.0.by_ref() {
+            let item = &mut *item;
+            item.visit_mut(state);
+        }
+    }
+
+    fn rearrange(&mut self, state: &mut Rearrange) {
+        for mut item in self.0.by_ref() {
+            let item = &mut *item;
+            item.visit_mut(state);
+        }
+    }
 }
 
 /// An iterator over `T: Object` that delegates to each object.
@@ -249,6 +282,18 @@ impl<T: Object, I: Iterator<Item = (Option<String>, T)>> Object for NamedIter<I>
             item.search_single_strong(state);
         }
     }
+
+    fn search_strong(&mut self, state: &mut SearchStrong) {
+        for (_, mut item) in self.0.by_ref() {
+            item.search_strong(state);
+        }
+    }
+
+    fn rearrange(&mut self, state: &mut Rearrange) {
+        for (_, mut item) in self.0.by_ref() {
+            item.rearrange(state);
+        }
+    }
 }
 
 /// An iterator over `Box<dyn Object>` that delegates to each object.
@@ -263,4 +308,16 @@ impl<'t, I: Iterator<Item = (Option<String>, Box<dyn Object + 't>)>> Object for
             item.search_single_strong(state);
         }
     }
+
+    fn search_strong(&mut self, state: &mut SearchStrong) {
+        for (_, mut item) in self.0.by_ref() {
+            item.search_strong(state);
+        }
+    }
+
+    fn rearrange(&mut self, state: &mut Rearrange) {
+        for (_, mut item) in self.0.by_ref() {
+            item.rearrange(state);
+        }
+    }
 }
@@ -0,0 +1,88 @@
+use super::{VisitMutArg, VisitStrongArgs, VisitStrongResult, VisitWeakArgs, VisitWeakResult};
+use crate::entity::{Generation, Raw};
+use crate::util::DbgTypeId;
+use crate::Archetype;
+
+/// The destination of an entity moved during rearrangement.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Moved {
+    /// The new primitive ID of the entity.
+    pub(crate) new_raw:        usize,
+    /// The generation of the entity before rearrangement.
+    pub(crate) old_generation: Generation,
+    /// The generation of the entity after rearrangement.
+    pub(crate) new_generation: Generation,
+}
+
+/// Rewrites all references to entities of an archetype according to a permutation.
+#[derive(Debug)]
+pub(crate) struct Rearrange {
+    ty:                       DbgTypeId,
+    /// Maps the original primitive ID of each entity to its destination.
+    forward:                  Vec<Option<Moved>>,
+    /// The number of strong references visited for each new primitive ID.
+    pub(crate) strong_visits: Vec<usize>,
+}
+
+impl Rearrange {
+    pub(crate) fn new(ty: DbgTypeId, moves: impl Iterator<Item = (usize, Moved)>) -> Self {
+        let mut forward = Vec::new();
+        let mut max_new_raw = 0;
+        for (old_raw, moved) in moves {
+            if forward.len() <= old_raw {
+                forward.resize(old_raw + 1, None);
+            }
+            *forward.get_mut(old_raw).expect("just resized") = Some(moved);
+            max_new_raw = max_new_raw.max(moved.new_raw);
+        }
+
+        Self { ty, forward, strong_visits: vec![0; max_new_raw + 1] }
+    }
+
+    /// Maps a raw entity of archetype `A` to its new ID.
+    ///
+    /// Returns the original ID if the entity is not affected by this rearrangement.
+    pub(crate) fn map_raw<A: Archetype>(&self, raw: A::RawEntity) -> A::RawEntity {
+        if self.ty != DbgTypeId::of::<A>() {
+            return raw;
+        }
+
+        match self.forward.get(raw.to_primitive()) {
+            Some(Some(moved)) => A::RawEntity::from_primitive(moved.new_raw),
+            _ => raw,
+        }
+    }
+}
+
+impl super::sealed::Sealed for Rearrange {}
+impl VisitMutArg for Rearrange {
+    #[inline]
+    fn _visit_strong(&mut self, args: VisitStrongArgs) -> VisitStrongResult {
+        if args.archetype == self.ty {
+            if let Some(Some(moved)) = self.forward.get(args.raw) {
+                let visits =
+                    self.strong_visits.get_mut(moved.new_raw).expect("allocated for all moves");
+                *visits += 1;
+                return VisitStrongResult { new_raw: moved.new_raw };
+            }
+        }
+
+        VisitStrongResult { new_raw: args.raw }
+    }
+
+    #[inline]
+    fn _visit_weak(&mut self, args: VisitWeakArgs) -> VisitWeakResult {
+        if args.archetype == self.ty {
+            if let Some(Some(moved)) = self.forward.get(args.raw) {
+                // Weak references with an older generation point to a previously deleted entity,
+                // which must not be redirected to the moved entity.
+                if *args.generation == moved.old_generation {
+                    *args.generation = moved.new_generation;
+                    return VisitWeakResult { new_raw: moved.new_raw };
+                }
+            }
+        }
+
+        VisitWeakResult { new_raw: args.raw }
+    }
+}
@@ -35,3 +35,23 @@ impl VisitMutArg for SearchSingleStrong {
 
     fn _set_debug_name(&mut self, name: String) { self.current = name; }
 }
+
+/// Checks whether the visited objects contain any strong references.
+#[derive(Debug, Default)]
+pub(crate) struct SearchStrong {
+    pub(crate) found: bool,
+}
+
+impl super::sealed::Sealed for SearchStrong {}
+impl VisitMutArg for SearchStrong {
+    #[inline]
+    fn _visit_strong(&mut self, args: VisitStrongArgs) -> VisitStrongResult {
+        self.found = true;
+        VisitStrongResult { new_raw: args.raw }
+    }
+
+    #[inline]
+    fn _visit_weak(&mut self, args: VisitWeakArgs) -> VisitWeakResult {
+        VisitWeakResult { new_raw: args.raw }
+    }
+}
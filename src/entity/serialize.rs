@@ -0,0 +1,45 @@
+//! Implements [`Serialize`] for entity references.
+//!
+//! Entity references are written as raw entity IDs.
+//! They are resolved against the reference counters of the loaded entities,
+//! so they can only be deserialized after the entities of the snapshot have been loaded.
+
+use std::{any, io};
+
+use super::{maybe, Entity, Generation, Raw, Weak};
+use crate::serialize::{self, Reader, Serialize, Writer};
+use crate::Archetype;
+
+impl<A: Archetype> Serialize for Entity<A> {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+        serialize::write_raw(writer, self.id)
+    }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        let id: A::RawEntity = serialize::read_raw(reader)?;
+        let rc = reader.rctrack()?.0.get_rc::<A>(id.to_primitive()).ok_or_else(|| {
+            serialize::invalid_data(format!(
+                "strong reference to unallocated entity {}#{id:?}",
+                any::type_name::<A>()
+            ))
+        })?;
+        Ok(Self { id, rc })
+    }
+}
+
+impl<A: Archetype> Serialize for Weak<A> {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+        serialize::write_raw(writer, self.id)?;
+        self.generation.serialize(writer)
+    }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        let id: A::RawEntity = serialize::read_raw(reader)?;
+        let generation = Generation::deserialize(reader)?;
+        let rc = match reader.rctrack()?.0.get_rc::<A>(id.to_primitive()) {
+            Some(rc) => maybe::downgrade(&rc),
+            None => maybe::dangling(),
+        };
+        Ok(Self { id, generation, rc })
+    }
+}
@@ -1,6 +1,6 @@
 use std::any;
 
-use crate::entity;
+use crate::{entity, serialize};
 
 /// A global state that can be requested by all systems.
 ///
@@ -18,4 +18,10 @@ pub trait Global: entity::Referrer + Sized + 'static {
             any::type_name::<Self>()
         )
     }
+
+    /// The serializer used to save this global state in [world snapshots](crate::serialize).
+    ///
+    /// Global states are not saved if this is `None`.
+    /// This is set to `Some` by `#[global(serialize)]`.
+    const SERIALIZER: Option<serialize::Vtable<Self>> = None;
 }
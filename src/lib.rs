@@ -117,7 +117,7 @@
 //! This is not possible in ECS frameworks that only support type-based component key,
 //! which lack flexibility for dynamically defined logic.
 //!
-//! # Entities can be rearranged to optimize random access (not yet implemented)
+//! # Entities can be rearranged to optimize random access
 //! One of the reasons why ECS performs better than traditional OOP-based code style
 //! is that components are stored in a compact region instead of scattered around the heap,
 //! reducing the frequency of CPU cache penetration that causes slow memory access.
@@ -150,6 +150,8 @@
 //! this is a stop-the-world operation that must not be performed frequently,
 //! so the period for which the arrangement drifts away (such that rearrangement is necessary)
 //! should be negligibly long such that user experience is not affected.
+//! Entities are rearranged in offline mode with [`World::rearrange`]
+//! according to an [`entity::Permutation`].
 //!
 //! [k8s-finalizer]: https://kubernetes.io/docs/concepts/overview/working-with-objects/finalizers/
 
@@ -197,6 +199,8 @@ pub use global::Global;
 
 pub mod scheduler;
 
+pub mod serialize;
+
 pub mod storage;
 pub use storage::Storage;
 
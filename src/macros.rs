@@ -18,10 +18,15 @@
 ///
 ///     /// Options can be applied in parentheses.
 ///     pub Qux(raw_entity = NonZeroU16, recycler = BTreeSet<NonZeroU16>);
+///
+///     /// The `key` option overrides the identifier used in world snapshots.
+///     pub Renamed(key = "legacy_name");
 /// }
 ///
 /// static_assertions::assert_impl_all!(Foo: dynec::Archetype);
 /// static_assertions::assert_impl_all!(Bar: dynec::Archetype);
+/// assert_eq!(<Foo as dynec::Archetype>::SERIALIZE_KEY, Some("Foo"));
+/// assert_eq!(<Renamed as dynec::Archetype>::SERIALIZE_KEY, Some("legacy_name"));
 /// ```
 ///
 /// Since documentation, attributes, visibility and the trailing semicolon are optional,
@@ -49,6 +54,11 @@
 /// Selects the [strategy to assign](crate::entity::ealloc::ShardAssigner) available entity IDs
 /// to different hsards.
 /// The default value is [`ThreadRngShardAssigner`](crate::entity::ealloc::ThreadRngShardAssigner).
+///
+/// ## `key = "key"`
+/// Sets the [key](crate::Archetype::SERIALIZE_KEY) that identifies the archetype
+/// in [world snapshots](crate::serialize).
+/// The default value is the archetype identifier.
 #[doc(inline)]
 pub use dynec_codegen::archetype;
 
@@ -103,6 +113,70 @@ mod archetype_tests {}
 /// it is automatically filled with `<Arch::RawEntity, Self>`,
 /// which is the format automatically compatible with all default storage types.
 ///
+/// For example, `storage = dynec::storage::SparseSet` packs components densely,
+/// which saves memory for components present on few entities of a large archetype.
+/// `storage = dynec::storage::Paged` allocates components in fixed-size pages,
+/// which avoids large reallocations when a large archetype grows.
+/// `storage = dynec::storage::Tag` only stores a bitset for zero-sized marker components,
+/// which can be filtered efficiently with [`system::TagFilter`](crate::system::TagFilter).
+///
+/// ## `storage = soa`
+/// Stores the component as a [struct-of-arrays component](crate::comp::soa)
+/// in a [`storage::Soa`](crate::storage::Soa).
+/// The applied type must be a non-generic struct with named fields,
+/// each of which is stored in a separate array.
+/// Proxy types for field references and per-field chunk slices are generated next to the struct,
+/// which are returned by the accessors of [`ReadSimple`](crate::system::ReadSimple)
+/// and [`WriteSimple`](crate::system::WriteSimple) instead of `&C` and `&[C]`.
+///
+/// This argument cannot be used with `isotope`.
+///
+/// ```
+/// use dynec::comp;
+///
+/// dynec::archetype!(Bullet);
+///
+/// #[comp(of = Bullet, storage = soa, required)]
+/// pub struct Position {
+///     pub x: f32,
+///     pub y: f32,
+/// }
+///
+/// static_assertions::assert_type_eq_all!(
+///     comp::Slice<'static, Bullet, Position>,
+///     PositionSlices<'static>
+/// );
+/// fn sum_x(slices: PositionSlices<'_>) -> f32 { slices.x.iter().sum() }
+/// ```
+///
+/// ## `serialize`, `serialize = "key"`
+/// Saves the component in [world snapshots](crate::serialize).
+/// This option calls [`Serialize`](macro@Serialize) implicitly,
+/// so all fields must implement [`serialize::Serialize`](crate::serialize::Serialize).
+/// Components without this option are absent after a snapshot is loaded.
+///
+/// The component is identified in snapshots by the type identifier,
+/// or by `key` if specified.
+///
+/// ## `track_changes`
+/// Wraps the storage with [`storage::Tracked`](crate::storage::Tracked),
+/// which records the [tick](crate::storage::Tick) at which each component was last changed.
+/// A component is marked as changed when it is inserted or mutably accessed,
+/// including entity creation, snapshot loading, [rearrangement](crate::World::rearrange)
+/// and iteration through [`WriteSimple`](crate::system::WriteSimple) accessors.
+/// Changed components can be queried with
+/// [`iter_changed_since`](crate::system::access::Single::iter_changed_since)
+/// or the [`Changed`](crate::system::Changed) zip adaptor.
+///
+/// This argument is exclusive with `isotope`.
+///
+/// ## `events`
+/// Wraps the storage with [`storage::Evented`](crate::storage::Evented),
+/// which records an [event](crate::storage::Event) whenever the component is added or removed.
+/// Systems can subscribe to the events with [`SimpleEvents`](crate::system::SimpleEvents).
+///
+/// This argument is exclusive with `isotope`.
+///
 /// # Example
 /// ```
 /// use dynec::comp;
@@ -215,6 +289,12 @@ mod comps_tests {}
 /// The `initial` argument can be used to specify an initial value for the global.
 /// If `initial` is given without a value, the global will be initialized to `Default::default()`.
 ///
+/// The `serialize` argument saves the global state in [world snapshots](crate::serialize).
+/// It calls [`Serialize`](macro@Serialize) implicitly,
+/// so all fields must implement [`serialize::Serialize`](crate::serialize::Serialize).
+/// The global state is identified in snapshots by the type identifier,
+/// or by `key` if the argument is written as `serialize = "key"`.
+///
 /// This macro calls [`EntityRef`] implicitly.
 /// Fields that reference entities should be annotated with `#[entity]`.
 ///
@@ -279,6 +359,48 @@ mod global_tests {}
 /// However, only the expressions are only resolved once before the first run of the system,
 /// so mutating states has no effect on the system schedule.
 ///
+/// ## `run_if = $expr`
+/// Only runs the system in cycles where the [`RunCondition`](crate::system::RunCondition)
+/// returned by `$expr` holds.
+/// This option can be specified multiple times, in which case all conditions must hold.
+///
+/// Similar to `before`/`after`, the expression is only evaluated once when the system is scheduled.
+///
+/// ## `on_error = $expr`
+/// Specifies whether an error returned by the system blocks the systems that depend on it,
+/// where `$expr` evaluates to a [`system::OnError`](crate::system::OnError).
+/// Defaults to [`OnError::Continue`](crate::system::OnError::Continue).
+///
+/// # Return type
+/// A system function may return `Result<(), E>` instead of `()`,
+/// where `E` implements <code>[std::error::Error] + [Send] + [Sync] + 'static</code>.
+/// Returned errors are reported in the [`CycleReport`](crate::scheduler::CycleReport)
+/// returned by [`World::execute`](crate::World::execute)
+/// and through [`Tracer::system_error`](crate::tracer::Tracer::system_error).
+///
+/// ```
+/// use std::fmt;
+///
+/// #[derive(Debug)]
+/// struct NotReady;
+///
+/// impl fmt::Display for NotReady {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("not ready") }
+/// }
+///
+/// impl std::error::Error for NotReady {}
+///
+/// #[dynec::system(on_error = dynec::system::OnError::Block)]
+/// fn load_assets() -> Result<(), NotReady> { Err(NotReady) }
+///
+/// let mut builder = dynec::world::Builder::new(0);
+/// builder.schedule(load_assets.build());
+/// let mut world = builder.build();
+///
+/// let report = world.execute(&dynec::tracer::Noop);
+/// assert_eq!(report.errors.len(), 1);
+/// ```
+///
 /// # Parameters
 /// Each parameter of a system function has a special meaning:
 ///
@@ -477,6 +599,90 @@ mod global_tests {}
 /// # */
 /// ```
 ///
+/// ## Dynamic components
+/// Parameters of type [`ReadDynamic<A>`](crate::system::ReadDynamic) or
+/// [`WriteDynamic<A>`](crate::system::WriteDynamic)
+/// request access to a [dynamic component](crate::comp::dynamic)
+/// from entities of the [archetype](crate::Archetype) `A`.
+/// The latter provides mutable and exclusive access to the component storage.
+///
+/// Since dynamic components are identified at runtime,
+/// the component must always be specified with an attribute:
+///
+/// ```
+/// # /*
+/// #[dynec(dynamic(comp = descriptor))] param_name: system::ReadDynamic<A>,
+/// # */
+/// ```
+///
+/// The expression `descriptor` is a [`comp::dynamic::Descriptor`](crate::comp::dynamic::Descriptor).
+/// Similar to `discrim` in isotope components, the expression may reference param states directly,
+/// and it is only evaluated once when the system is built.
+///
+/// ### Syntax reference
+/// ```
+/// # /*
+/// #[dynec(dynamic(
+///     // Required, the descriptor of the dynamic component.
+///     comp = $expr,
+///     // Optional, specifies the archetype explicitly.
+///     // Only required when the parameter type is not `ReadDynamic`/`WriteDynamic`.
+///     arch = $ty,
+///     // Optional, indicates that the component access is exclusive explicitly.
+///     // Only allowed together with `arch`.
+///     mut,
+/// ))]
+/// # */
+/// ```
+///
+/// ## Simple component events
+/// Parameters of type [`SimpleEvents<A, C>`](crate::system::SimpleEvents)
+/// receive the add/remove events of the simple component `C` for the archetype `A`
+/// recorded since the start of the previous cycle.
+/// `C` must be declared with `#[comp(events)]`.
+///
+/// Subscribing does not request access to the component storage,
+/// so subscribers can run in parallel with systems that read or write the component.
+///
+/// ### Syntax reference
+/// ```
+/// # /*
+/// #[dynec(simple_events(
+///     // Optional, specifies the archetype and component explicitly.
+///     // Only required when the parameter type is not `SimpleEvents`.
+///     arch = $ty,
+///     comp = $ty,
+/// ))]
+/// # */
+/// ```
+///
+/// ## Event channels
+/// Parameters of type [`EventWriter<T>`](crate::system::EventWriter)
+/// send events of type `T`,
+/// and parameters of type [`EventReader<T>`](crate::system::EventReader)
+/// receive the events sent since the last time the system ran.
+/// The read position of each reader is kept in the system's local state.
+///
+/// All writers of `T` are scheduled before all readers of `T`,
+/// so events are received in the same cycle they are sent.
+/// See [`EventPartition`](crate::system::EventPartition) for more information.
+///
+/// ### Syntax reference
+/// ```
+/// # /*
+/// #[dynec(event_writer(
+///     // Optional, specifies the event type explicitly.
+///     // Only required when the parameter type is not `EventWriter`.
+///     event = $ty,
+/// ))]
+/// #[dynec(event_reader(
+///     // Optional, specifies the event type explicitly.
+///     // Only required when the parameter type is not `EventReader`.
+///     event = $ty,
+/// ))]
+/// # */
+/// ```
+///
 /// ## Entity creation
 /// Parameters that require an [`EntityCreator`](crate::system::EntityCreator)
 /// can be used to create entities.
@@ -799,6 +1005,37 @@ mod entity_ref_tests {}
 /// Only to be called from generated code in polyfill_tracer_decl.
 #[doc(hidden)]
 pub use dynec_codegen::polyfill_tracer_proc;
+/// Derives a [`serialize::Serialize`](crate::serialize::Serialize) implementation for the type.
+///
+/// All fields are serialized in declaration order.
+/// Enums are serialized as the variant index followed by the fields of the variant.
+/// Use `#[dynec(dynec_as(path))]` to rename the `dynec` crate in the generated code.
+///
+/// This derive macro is automatically called in [`comp`] and [`global`]
+/// with the `serialize` option.
+/// It should only be called explicitly if the type is not a component or global,
+/// e.g. if it is a type included in a component field.
+///
+/// # Example
+/// ```
+/// dynec::archetype!(Foo);
+///
+/// #[derive(dynec::Serialize)]
+/// enum Bar {
+///     Empty,
+///     Pair(u32, String),
+///     Named { value: Option<f32> },
+/// }
+///
+/// #[dynec::comp(of = Foo, serialize)]
+/// struct Qux {
+///     bar:    Bar,
+///     #[entity]
+///     target: Option<dynec::Entity<Foo>>,
+/// }
+/// ```
+#[doc(inline)]
+pub use dynec_codegen::Serialize;
 
 // The rest are macros for testing.
 
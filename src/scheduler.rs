@@ -1,20 +1,29 @@
 //! The scheduler manages the execution of systems,
 //! including resource negotiation and dependency constraints.
 
-use std::fmt;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroUsize;
+use std::sync::atomic::{self, AtomicU64};
+use std::{fmt, mem, panic};
 
 use crate::entity::{ealloc, rctrack};
-use crate::system;
 use crate::tracer::Tracer;
 use crate::util::DbgTypeId;
 use crate::world::{self, offline};
+use crate::{comp, system};
 
 mod builder;
 pub(crate) use builder::Builder;
 
+mod deadlock;
+pub use deadlock::{Deadlock, DeadlockBlocker, DeadlockNode};
+
 mod executor;
-use executor::Executor;
+use executor::{Executor, Failure};
+
+mod graph;
+pub use graph::{Graph, GraphExclusion, GraphNode, GraphOrder};
 
 mod planner;
 use parking_lot::Mutex;
@@ -35,9 +44,20 @@ pub(crate) struct Scheduler {
     sync_state:   SyncState,
     unsync_state: UnsyncState,
     executor:     Executor,
+    /// The inputs of `topology`, retained to rebuild it when systems are added or removed.
+    resources:    HashMap<ResourceType, HashMap<Node, Vec<ResourceAccess>>>,
+    orders:       Vec<Order>,
+    handles:      HashMap<Node, SystemHandle>,
+    /// The static weights of systems, used to compute planner priorities.
+    weights:      HashMap<Node, u64>,
+    weighting:    Weighting,
+    /// The moving averages of system run durations in nanoseconds,
+    /// only populated with [`Weighting::Measured`].
+    measured:     HashMap<Node, f64>,
 }
 
 impl Scheduler {
+    #[allow(clippy::too_many_arguments)] // FIXME
     pub(crate) fn execute(
         &mut self,
         tracer: &impl Tracer,
@@ -46,8 +66,10 @@ impl Scheduler {
         unsync_globals: &mut world::UnsyncGlobals,
         rctrack: &mut rctrack::MaybeStoreMap,
         ealloc_map: &mut ealloc::Map,
-    ) {
-        self.executor.execute_full_cycle(
+        offline_buffer: &mut offline::Buffer,
+        other_systems: Vec<(&str, &mut dyn system::Descriptor)>,
+    ) -> CycleReport {
+        let failures = self.executor.execute_full_cycle(
             tracer,
             &self.topology,
             &mut self.planner,
@@ -57,11 +79,148 @@ impl Scheduler {
             rctrack,
             UnsendArgs { state: &mut self.unsync_state, globals: unsync_globals },
             ealloc_map,
+            offline_buffer,
+            other_systems,
         );
+
+        if let Weighting::Measured = self.weighting {
+            for (node, duration) in self.planner.get_mut().durations.drain(..) {
+                let sample = duration.as_nanos() as f64;
+                self.measured
+                    .entry(node)
+                    .and_modify(|average| *average += (sample - *average) * MEASURED_SMOOTHING)
+                    .or_insert(sample);
+            }
+            self.update_priorities();
+        }
+
+        let mut report = CycleReport::default();
+        for (node, failure) in failures {
+            let handle = *self.handles.get(&node).expect("handles are assigned to all systems");
+            let debug_name = match node {
+                Node::SendSystem(index) => {
+                    &self.sync_state.send_systems.get(index.0).expect("invalid node index").0
+                }
+                Node::UnsendSystem(index) => {
+                    &self.unsync_state.unsend_systems.get(index.0).expect("invalid node index").0
+                }
+                Node::Partition(_) => unreachable!("partitions do not run"),
+            };
+            let debug_name = debug_name.clone();
+
+            match failure {
+                Failure::Panic(payload) => {
+                    let policy = self.topology.panic_policy_of(node);
+                    if let PanicPolicy::Disable = policy {
+                        self.topology.disabled.insert(node);
+                    }
+                    report.panics.push(SystemPanic { handle, debug_name, policy, payload });
+                }
+                Failure::Error(error) => {
+                    report.errors.push(SystemError { handle, debug_name, error });
+                }
+            }
+        }
+        report
     }
 
-    pub(crate) fn offline_buffer(&mut self) -> &mut offline::Buffer {
-        &mut self.executor.offline_buffer
+    /// Recomputes the planner priorities from the current weights.
+    fn update_priorities(&mut self) {
+        let (weights, weighting, measured) = (&self.weights, self.weighting, &self.measured);
+        self.topology.set_weights(|node| weight_of(node, weights, weighting, measured));
+    }
+
+    /// Sets the static weight of the system identified by `handle`.
+    ///
+    /// Returns `false` if the system is not scheduled in this scheduler.
+    pub(crate) fn set_weight(&mut self, handle: SystemHandle, weight: u64) -> bool {
+        let Some((&node, _)) = self.handles.iter().find(|&(_, &other)| other == handle) else {
+            return false;
+        };
+        self.weights.insert(node, weight);
+        self.update_priorities();
+        true
+    }
+
+    /// Sets the panic policy of the system identified by `handle`.
+    ///
+    /// Returns `false` if the system is not scheduled in this scheduler.
+    pub(crate) fn set_panic_policy(&mut self, handle: SystemHandle, policy: PanicPolicy) -> bool {
+        let Some((&node, _)) = self.handles.iter().find(|&(_, &other)| other == handle) else {
+            return false;
+        };
+        self.topology.panic_policies.insert(node, policy);
+        true
+    }
+
+    /// Modifies the scheduled systems with a builder and rebuilds the topology.
+    ///
+    /// The thread pool of the executor is reused.
+    /// If `f` or the topology check panics,
+    /// the systems added by `f` are removed and the previous topology is restored
+    /// before the panic is resumed.
+    pub(crate) fn edit<R>(&mut self, f: impl FnOnce(&mut Builder) -> R) -> R {
+        let executor = mem::replace(&mut self.executor, Executor::new(0));
+        let mut builder = Builder::from_scheduler(self, executor.concurrency);
+        let existing: HashSet<SystemHandle> = builder.handles().collect();
+
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            let ret = f(&mut builder);
+            (ret, builder.init_topology())
+        }));
+        match result {
+            Ok((ret, topology)) => {
+                *self = builder.build_with_topology(topology, executor);
+                ret
+            }
+            Err(payload) => {
+                let added: Vec<_> =
+                    builder.handles().filter(|handle| !existing.contains(handle)).collect();
+                for handle in added {
+                    builder.remove_system(handle);
+                }
+                *self = builder.build_with_executor(executor);
+                panic::resume_unwind(payload)
+            }
+        }
+    }
+
+    /// Describes the topology of this scheduler.
+    pub(crate) fn graph(&self) -> Graph { Graph::new(self) }
+
+    /// Enables or disables the system identified by `handle`.
+    ///
+    /// Returns `false` if the system is not scheduled in this scheduler.
+    pub(crate) fn set_enabled(&mut self, handle: SystemHandle, enabled: bool) -> bool {
+        let Some((&node, _)) = self.handles.iter().find(|&(_, &other)| other == handle) else {
+            return false;
+        };
+        if enabled {
+            self.topology.disabled.remove(&node);
+        } else {
+            self.topology.disabled.insert(node);
+        }
+        true
+    }
+
+    /// Returns the system identified by `handle` if it is scheduled in this scheduler.
+    pub(crate) fn get_system_mut(
+        &mut self,
+        handle: SystemHandle,
+    ) -> Option<(&str, &mut dyn system::Descriptor)> {
+        let (&node, _) = self.handles.iter().find(|&(_, &other)| other == handle)?;
+        Some(match node {
+            Node::SendSystem(index) => {
+                let (debug_name, system) =
+                    self.sync_state.send_systems.get_mut(index.0).expect("invalid node index");
+                (debug_name.as_str(), system.get_mut().as_descriptor_mut())
+            }
+            Node::UnsendSystem(index) => {
+                let (debug_name, system) = self.unsync_state.get_unsend_system_mut(index);
+                (debug_name, system.as_descriptor_mut())
+            }
+            Node::Partition(_) => unreachable!("handles are only assigned to systems"),
+        })
     }
 
     pub(crate) fn get_system_refs(&mut self) -> Vec<(&str, &mut dyn system::Descriptor)> {
@@ -79,10 +238,127 @@ impl Scheduler {
     }
 }
 
+/// The smoothing factor of the exponential moving average of measured durations.
+const MEASURED_SMOOTHING: f64 = 0.125;
+
+/// The default static weight of a system.
+const DEFAULT_WEIGHT: u64 = 1;
+
+/// Computes the weight of a node for planner priorities.
+fn weight_of(
+    node: Node,
+    weights: &HashMap<Node, u64>,
+    weighting: Weighting,
+    measured: &HashMap<Node, f64>,
+) -> u64 {
+    if let Node::Partition(_) = node {
+        return 0;
+    }
+
+    let measured = match weighting {
+        Weighting::Static => None,
+        Weighting::Measured => measured.get(&node).map(|&average| average as u64),
+    };
+    measured.unwrap_or_else(|| weights.get(&node).copied().unwrap_or(DEFAULT_WEIGHT))
+}
+
+/// Determines the weights of systems when the planner prioritizes runnable systems.
+///
+/// When multiple systems are runnable, the planner starts the system
+/// with the heaviest chain of systems that (transitively) depend on it,
+/// so that long chains start early and the cycle completes sooner.
+/// Systems with the same priority are started in the order they were scheduled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Weighting {
+    /// Each system is weighted by its static weight,
+    /// which is 1 unless [set explicitly](crate::World::set_weight).
+    #[default]
+    Static,
+    /// Each system is weighted by the moving average of its run durations in nanoseconds,
+    /// as measured by the executor at the end of each cycle.
+    ///
+    /// Systems that have not run yet are weighted by their static weight.
+    /// Measurements are discarded when systems are scheduled or unscheduled.
+    Measured,
+}
+
+/// Determines how the executor handles a panic in a system.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PanicPolicy {
+    /// The panic is propagated from [`World::execute`](crate::World::execute)
+    /// after all other running systems return.
+    ///
+    /// The world is left in an unspecified state and should not be used anymore.
+    #[default]
+    Abort,
+    /// The system is treated as completed for the rest of the cycle,
+    /// so systems that depend on it are woken as usual.
+    /// The system runs again in the next cycle.
+    Skip,
+    /// Same as [`Skip`](Self::Skip), but the system is also
+    /// [disabled](crate::World::set_enabled) after the cycle.
+    Disable,
+}
+
+/// Describes the abnormal events during a cycle.
+#[derive(Debug, Default)]
+pub struct CycleReport {
+    /// The systems that panicked and were recovered according to their [`PanicPolicy`],
+    /// in the order they panicked.
+    pub panics: Vec<SystemPanic>,
+    /// The errors returned by fallible systems, in the order they were returned.
+    pub errors: Vec<SystemError>,
+}
+
+impl CycleReport {
+    /// Returns whether no systems panicked or returned errors during the cycle.
+    pub fn is_ok(&self) -> bool { self.panics.is_empty() && self.errors.is_empty() }
+}
+
+/// A panic in a system that was recovered during a cycle.
+#[derive(Debug)]
+pub struct SystemPanic {
+    /// The system that panicked.
+    pub handle:     SystemHandle,
+    /// The debug name of the system.
+    pub debug_name: String,
+    /// The panic policy applied to the system.
+    pub policy:     PanicPolicy,
+    /// The panic payload, as returned by [`std::panic::catch_unwind`].
+    pub payload:    Box<dyn Any + Send>,
+}
+
+impl SystemPanic {
+    /// Returns the panic message if the payload is a string,
+    /// which is the case for panics raised by [`panic!`] with a message.
+    pub fn message(&self) -> Option<&str> {
+        if let Some(&message) = self.payload.downcast_ref::<&'static str>() {
+            Some(message)
+        } else {
+            self.payload.downcast_ref::<String>().map(String::as_str)
+        }
+    }
+}
+
+/// An error returned by a fallible system during a cycle.
+#[derive(Debug)]
+pub struct SystemError {
+    /// The system that returned the error.
+    pub handle:     SystemHandle,
+    /// The debug name of the system.
+    pub debug_name: String,
+    /// The returned error.
+    pub error:      system::BoxedError,
+}
+
+/// The scheduling state of a node during a cycle.
 #[derive(Debug, Clone, Copy)]
-pub(crate) enum WakeupState {
+pub enum WakeupState {
     /// The node is runnable after being awaken by `count` other nodes.
-    Blocked { count: NonZeroUsize },
+    Blocked {
+        /// The number of incomplete dependencies and started exclusive nodes.
+        count: NonZeroUsize,
+    },
     /// The node is in the planner queue.
     Pending,
     /// The node is scheduled on one of the threads.
@@ -102,6 +378,23 @@ pub enum Node {
     Partition(PartitionIndex),
 }
 
+/// Identifies a system scheduled in a [`World`](crate::World).
+///
+/// Handles are returned when a system is scheduled,
+/// and can be used to [enable or disable](crate::World::set_enabled)
+/// or [unschedule](crate::World::unschedule) the system.
+/// Unlike [`Node`], a handle remains valid when other systems are scheduled or unscheduled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SystemHandle(u64);
+
+impl SystemHandle {
+    /// Allocates a handle that is unique within the process.
+    fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, atomic::Ordering::Relaxed))
+    }
+}
+
 /// Uniquely identifies a [`system::Sendable`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SendSystemIndex(usize);
@@ -114,19 +407,41 @@ pub struct UnsendSystemIndex(usize);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct PartitionIndex(usize);
 
+/// A resource that systems request access to.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub(crate) enum ResourceType {
+pub enum ResourceType {
+    /// A global state.
     Global(DbgTypeId),
-    Simple { arch: DbgTypeId, comp: DbgTypeId },
-    Isotope { arch: DbgTypeId, comp: DbgTypeId },
+    /// The storage of a simple component.
+    Simple {
+        /// The archetype of the component.
+        arch: DbgTypeId,
+        /// The component type.
+        comp: DbgTypeId,
+    },
+    /// The storages of an isotope component.
+    Isotope {
+        /// The archetype of the component.
+        arch: DbgTypeId,
+        /// The component type.
+        comp: DbgTypeId,
+    },
+    /// The storage of a dynamic component.
+    Dynamic {
+        /// The archetype of the component.
+        arch: DbgTypeId,
+        /// The dynamic component ID.
+        comp: comp::dynamic::Id,
+    },
 }
 
 impl fmt::Display for ResourceType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::Global(ty) => writeln!(f, "global state {ty}"),
-            Self::Simple { arch, comp } => writeln!(f, "simple component {arch}/{comp}"),
-            Self::Isotope { arch, comp } => writeln!(f, "isotope component {arch}/{comp}"),
+            Self::Global(ty) => write!(f, "global state {ty}"),
+            Self::Simple { arch, comp } => write!(f, "simple component {arch}/{comp}"),
+            Self::Isotope { arch, comp } => write!(f, "isotope component {arch}/{comp}"),
+            Self::Dynamic { arch, comp } => write!(f, "dynamic component {arch}/{comp}"),
         }
     }
 }
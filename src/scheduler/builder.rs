@@ -1,12 +1,12 @@
-use std::collections::{hash_map, HashMap};
-use std::fmt;
+use std::collections::{hash_map, HashMap, HashSet};
+use std::{fmt, mem};
 
 use indexmap::IndexSet;
 use parking_lot::Mutex;
 
 use super::{
-    Executor, Node, Order, PartitionIndex, ResourceAccess, ResourceType, Scheduler,
-    SendSystemIndex, SyncState, Topology, UnsendSystemIndex, UnsyncState,
+    Executor, Node, Order, PanicPolicy, PartitionIndex, ResourceAccess, ResourceType, Scheduler,
+    SendSystemIndex, SyncState, SystemHandle, Topology, UnsendSystemIndex, UnsyncState, Weighting,
 };
 use crate::system::{self, spec};
 
@@ -17,6 +17,13 @@ pub(crate) struct Builder {
     partitions:             IndexSet<system::partition::Wrapper>,
     resources:              HashMap<ResourceType, HashMap<Node, Vec<ResourceAccess>>>,
     orders:                 Vec<Order>,
+    run_conditions:         HashMap<Node, Vec<system::RunCondition>>,
+    handles:                HashMap<Node, SystemHandle>,
+    disabled:               HashSet<Node>,
+    panic_policies:         HashMap<Node, PanicPolicy>,
+    blocking_on_error:      HashSet<Node>,
+    weights:                HashMap<Node, u64>,
+    pub(crate) weighting:   Weighting,
 }
 
 impl Builder {
@@ -28,6 +35,35 @@ impl Builder {
             partitions: IndexSet::new(),
             resources: HashMap::new(),
             orders: Vec::new(),
+            run_conditions: HashMap::new(),
+            handles: HashMap::new(),
+            disabled: HashSet::new(),
+            panic_policies: HashMap::new(),
+            blocking_on_error: HashSet::new(),
+            weights: HashMap::new(),
+            weighting: Weighting::default(),
+        }
+    }
+
+    /// Moves the systems and topology inputs out of a built scheduler.
+    pub(super) fn from_scheduler(scheduler: &mut Scheduler, concurrency: usize) -> Self {
+        Self {
+            concurrency,
+            send_systems: mem::take(&mut scheduler.sync_state.send_systems)
+                .into_iter()
+                .map(|(name, system)| (name, system.into_inner()))
+                .collect(),
+            unsend_systems: mem::take(&mut scheduler.unsync_state.unsend_systems),
+            partitions: mem::take(&mut scheduler.topology.partitions).into_iter().collect(),
+            resources: mem::take(&mut scheduler.resources),
+            orders: mem::take(&mut scheduler.orders),
+            run_conditions: mem::take(&mut scheduler.sync_state.run_conditions),
+            handles: mem::take(&mut scheduler.handles),
+            disabled: mem::take(&mut scheduler.topology.disabled),
+            panic_policies: mem::take(&mut scheduler.topology.panic_policies),
+            blocking_on_error: mem::take(&mut scheduler.topology.blocking_on_error),
+            weights: mem::take(&mut scheduler.weights),
+            weighting: scheduler.weighting,
         }
     }
 
@@ -38,6 +74,7 @@ impl Builder {
         let spec = sys.get_spec();
         let index = SendSystemIndex(self.send_systems.len());
         self.send_systems.push((spec.debug_name.clone(), sys));
+        self.handles.insert(Node::SendSystem(index), SystemHandle::next());
         (Node::SendSystem(index), spec)
     }
 
@@ -48,9 +85,108 @@ impl Builder {
         let spec = sys.get_spec();
         let index = UnsendSystemIndex(self.unsend_systems.len());
         self.unsend_systems.push((spec.debug_name.clone(), sys));
+        self.handles.insert(Node::UnsendSystem(index), SystemHandle::next());
         (Node::UnsendSystem(index), spec)
     }
 
+    /// Sets the static weight of a system pushed to this builder.
+    ///
+    /// Returns whether the system was found.
+    pub(crate) fn set_weight(&mut self, handle: SystemHandle, weight: u64) -> bool {
+        let Some((&node, _)) = self.handles.iter().find(|&(_, &other)| other == handle) else {
+            return false;
+        };
+        self.weights.insert(node, weight);
+        true
+    }
+
+    /// Sets the panic policy of a system pushed to this builder.
+    ///
+    /// Returns whether the system was found.
+    pub(crate) fn set_panic_policy(&mut self, handle: SystemHandle, policy: PanicPolicy) -> bool {
+        let Some((&node, _)) = self.handles.iter().find(|&(_, &other)| other == handle) else {
+            return false;
+        };
+        self.panic_policies.insert(node, policy);
+        true
+    }
+
+    /// Returns the handles of all systems pushed to this builder.
+    pub(super) fn handles(&self) -> impl Iterator<Item = SystemHandle> + '_ {
+        self.handles.values().copied()
+    }
+
+    /// Returns the handle of a system node pushed to this builder.
+    pub(crate) fn handle_of(&self, node: Node) -> SystemHandle {
+        *self.handles.get(&node).expect("handles are assigned to all system nodes")
+    }
+
+    /// Removes a system and all dependencies and resource accesses of the system.
+    ///
+    /// Indices of the systems pushed after the removed system are shifted.
+    /// Partitions are retained even if no systems depend on them anymore.
+    /// Returns whether the system was found.
+    pub(crate) fn remove_system(&mut self, handle: SystemHandle) -> bool {
+        let Some(node) =
+            self.handles.iter().find(|&(_, &other)| other == handle).map(|(&node, _)| node)
+        else {
+            return false;
+        };
+
+        match node {
+            Node::SendSystem(index) => drop(self.send_systems.remove(index.0)),
+            Node::UnsendSystem(index) => drop(self.unsend_systems.remove(index.0)),
+            Node::Partition(_) => unreachable!("handles are only assigned to systems"),
+        }
+
+        let remap = |other: Node| -> Option<Node> {
+            match (node, other) {
+                _ if other == node => None,
+                (Node::SendSystem(removed), Node::SendSystem(index)) if index > removed => {
+                    Some(Node::SendSystem(SendSystemIndex(index.0 - 1)))
+                }
+                (Node::UnsendSystem(removed), Node::UnsendSystem(index)) if index > removed => {
+                    Some(Node::UnsendSystem(UnsendSystemIndex(index.0 - 1)))
+                }
+                _ => Some(other),
+            }
+        };
+
+        self.orders = mem::take(&mut self.orders)
+            .into_iter()
+            .filter_map(|order| {
+                Some(Order { before: remap(order.before)?, after: remap(order.after)? })
+            })
+            .collect();
+        for accesses in self.resources.values_mut() {
+            *accesses = mem::take(accesses)
+                .into_iter()
+                .filter_map(|(other, access)| Some((remap(other)?, access)))
+                .collect();
+        }
+        self.run_conditions = mem::take(&mut self.run_conditions)
+            .into_iter()
+            .filter_map(|(other, conditions)| Some((remap(other)?, conditions)))
+            .collect();
+        self.handles = mem::take(&mut self.handles)
+            .into_iter()
+            .filter_map(|(other, handle)| Some((remap(other)?, handle)))
+            .collect();
+        self.disabled = mem::take(&mut self.disabled).into_iter().filter_map(remap).collect();
+        self.blocking_on_error =
+            mem::take(&mut self.blocking_on_error).into_iter().filter_map(remap).collect();
+        self.panic_policies = mem::take(&mut self.panic_policies)
+            .into_iter()
+            .filter_map(|(other, policy)| Some((remap(other)?, policy)))
+            .collect();
+        self.weights = mem::take(&mut self.weights)
+            .into_iter()
+            .filter_map(|(other, weight)| Some((remap(other)?, weight)))
+            .collect();
+
+        true
+    }
+
     pub(crate) fn push_partition(
         &mut self,
         par: system::partition::Wrapper,
@@ -127,38 +263,82 @@ impl Builder {
         Ret(self, node)
     }
 
+    pub(crate) fn add_run_conditions(&mut self, node: Node, conditions: Vec<system::RunCondition>) {
+        if !conditions.is_empty() {
+            self.run_conditions.entry(node).or_default().extend(conditions);
+        }
+    }
+
+    pub(crate) fn set_on_error(&mut self, node: Node, on_error: system::OnError) {
+        match on_error {
+            system::OnError::Continue => self.blocking_on_error.remove(&node),
+            system::OnError::Block => self.blocking_on_error.insert(node),
+        };
+    }
+
     pub(crate) fn add_dependency(&mut self, before: Node, after: Node) {
         self.orders.push(Order { before, after });
     }
 
     pub(crate) fn build(self) -> Scheduler {
+        let executor = Executor::new(self.concurrency);
+        self.build_with_executor(executor)
+    }
+
+    pub(super) fn build_with_executor(self, executor: Executor) -> Scheduler {
+        let topology = self.init_topology();
+        self.build_with_topology(topology, executor)
+    }
+
+    /// Computes the topology of the pushed systems.
+    ///
+    /// # Panics
+    /// Panics if the systems have a cyclic dependency.
+    pub(super) fn init_topology(&self) -> Topology {
         let partitions: Vec<_> = self.partitions.iter().collect();
-        let mut topology = Topology::init(
+        Topology::init(
             self.send_systems.len(),
             self.unsend_systems.len(),
             &partitions,
             &self.orders,
             &self.resources,
             |node| self.display_node(node).to_string(),
-        );
+            |node| super::weight_of(node, &self.weights, self.weighting, &HashMap::new()),
+        )
+    }
+
+    pub(super) fn build_with_topology(
+        self,
+        mut topology: Topology,
+        executor: Executor,
+    ) -> Scheduler {
         // late-initialized because display_node needs to read this field
         topology.partitions = self.partitions.into_iter().collect();
+        topology.disabled = self.disabled;
+        topology.panic_policies = self.panic_policies;
+        topology.blocking_on_error = self.blocking_on_error;
 
         let planner = Mutex::new(topology.initial_planner().clone());
-        let executor = Executor::new(self.concurrency);
 
         Scheduler {
             topology,
             planner,
             executor,
             sync_state: SyncState {
-                send_systems: self
+                send_systems:   self
                     .send_systems
                     .into_iter()
                     .map(|(ty, sys)| (ty, Mutex::new(sys)))
                     .collect(),
+                run_conditions: self.run_conditions,
             },
             unsync_state: UnsyncState { unsend_systems: self.unsend_systems },
+            resources: self.resources,
+            orders: self.orders,
+            handles: self.handles,
+            weights: self.weights,
+            weighting: self.weighting,
+            measured: HashMap::new(),
         }
     }
 }
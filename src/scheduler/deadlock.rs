@@ -0,0 +1,138 @@
+//! Diagnostics for executor deadlocks.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::{Node, Planner, ResourceType, Topology, WakeupState};
+
+/// A snapshot of the planner when all threads are waiting for tasks
+/// but the cycle has not completed.
+///
+/// The [`Display`](fmt::Display) implementation renders the snapshot as human-readable text,
+/// which is also used as the panic message of the deadlock.
+#[derive(Debug, Clone)]
+pub struct Deadlock {
+    /// The state of every node in the schedule, sorted by node.
+    pub nodes:   Vec<DeadlockNode>,
+    /// The systems that have started but not completed.
+    pub started: Vec<Node>,
+}
+
+/// The state of a node in a [`Deadlock`].
+#[derive(Debug, Clone)]
+pub struct DeadlockNode {
+    /// The node in the topology.
+    pub node:     Node,
+    /// The description of the node.
+    pub name:     String,
+    /// The wakeup state of the node.
+    pub state:    WakeupState,
+    /// The nodes that prevent this node from becoming runnable.
+    ///
+    /// This is only non-empty for [blocked](WakeupState::Blocked) nodes.
+    pub blockers: Vec<DeadlockBlocker>,
+}
+
+/// A node that blocks another node in a [`Deadlock`].
+#[derive(Debug, Clone)]
+pub enum DeadlockBlocker {
+    /// A dependency that has not completed yet.
+    Dependency(Node),
+    /// A started system that requests conflicting access to some resources.
+    Exclusion {
+        /// The started system.
+        node:      Node,
+        /// The resources that the two systems request conflicting access to.
+        resources: Vec<ResourceType>,
+    },
+}
+
+impl Deadlock {
+    pub(crate) fn new(planner: &Planner, topology: &Topology) -> Self {
+        let mut dependencies: HashMap<Node, Vec<Node>> = HashMap::new();
+        for node in topology.nodes() {
+            for &dependent in topology.dependents_of(node) {
+                dependencies.entry(dependent).or_default().push(node);
+            }
+        }
+
+        let state_of = |node: Node| *planner.wakeup_state.get(&node).expect("invalid node index");
+        let is_completed = |node: Node| matches!(state_of(node), WakeupState::Completed);
+        let is_started = |node: Node| matches!(state_of(node), WakeupState::Started);
+
+        let mut nodes: Vec<_> = topology.nodes().collect();
+        nodes.sort();
+
+        let nodes: Vec<_> = nodes
+            .into_iter()
+            .map(|node| {
+                let state = state_of(node);
+
+                let mut blockers = Vec::new();
+                if let WakeupState::Blocked { .. } = state {
+                    let mut node_dependencies =
+                        dependencies.get(&node).cloned().unwrap_or_default();
+                    node_dependencies.sort();
+                    blockers.extend(
+                        node_dependencies
+                            .into_iter()
+                            .filter(|&dependency| !is_completed(dependency))
+                            .map(DeadlockBlocker::Dependency),
+                    );
+
+                    let mut exclusions = topology.exclusions_of(node).to_vec();
+                    exclusions.sort();
+                    blockers.extend(exclusions.into_iter().filter(|&excl| is_started(excl)).map(
+                        |excl| DeadlockBlocker::Exclusion {
+                            node:      excl,
+                            resources: topology.exclusion_resources_of(node, excl).to_vec(),
+                        },
+                    ));
+                }
+
+                DeadlockNode { node, name: topology.describe(node).to_string(), state, blockers }
+            })
+            .collect();
+
+        let started = nodes
+            .iter()
+            .filter(|node| matches!(node.state, WakeupState::Started))
+            .map(|node| node.node)
+            .collect();
+
+        Self { nodes, started }
+    }
+}
+
+impl fmt::Display for Deadlock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: HashMap<Node, &str> =
+            self.nodes.iter().map(|node| (node.node, node.name.as_str())).collect();
+        let name_of = |node: Node| names.get(&node).copied().unwrap_or("<unknown>");
+
+        writeln!(f, "Started systems:")?;
+        for &node in &self.started {
+            writeln!(f, "  {}", name_of(node))?;
+        }
+
+        writeln!(f, "Nodes:")?;
+        for node in &self.nodes {
+            writeln!(f, "  {}: {:?}", node.name, node.state)?;
+            for blocker in &node.blockers {
+                match blocker {
+                    DeadlockBlocker::Dependency(dependency) => {
+                        writeln!(f, "    after {}", name_of(*dependency))?;
+                    }
+                    DeadlockBlocker::Exclusion { node: excl, resources } => {
+                        writeln!(f, "    excluded by {}", name_of(*excl))?;
+                        for resource in resources {
+                            writeln!(f, "      on {resource}")?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,19 +1,24 @@
+use std::any::Any;
+use std::collections::HashSet;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::atomic::{self, AtomicBool};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use parking_lot::{Condvar, Mutex, MutexGuard};
+use rayon::prelude::*;
 
 use super::planner::StealResult;
 use super::state::SyncState;
-use super::{Node, Planner, Topology, UnsendArgs};
+use super::{Deadlock, Node, PanicPolicy, Planner, Topology, UnsendArgs};
 use crate::entity::{ealloc, rctrack};
+use crate::system;
 use crate::tracer::{self, Tracer};
+use crate::util::DbgTypeId;
 use crate::world::{self, offline, WorldMut};
 
 pub(crate) struct Executor {
-    thread_pool:               Option<rayon::ThreadPool>,
-    concurrency:               usize,
-    pub(crate) offline_buffer: offline::Buffer,
+    thread_pool:            Option<rayon::ThreadPool>,
+    pub(super) concurrency: usize,
 }
 
 impl Executor {
@@ -33,10 +38,13 @@ impl Executor {
                     .expect("Failed to create thread pool")
             }),
             concurrency,
-            offline_buffer: offline::Buffer::new(concurrency + 1),
         }
     }
 
+    /// Executes a full cycle, including the offline phase.
+    ///
+    /// Returns the errors returned by systems
+    /// and the panics recovered according to the panic policies of systems.
     #[allow(clippy::too_many_arguments)] // FIXME
     pub(crate) fn execute_full_cycle(
         &mut self,
@@ -49,19 +57,25 @@ impl Executor {
         rctrack: &mut rctrack::MaybeStoreMap,
         mut unsend: UnsendArgs<'_>,
         ealloc_map: &mut ealloc::Map,
-    ) {
+        offline_buffer: &mut offline::Buffer,
+        other_systems: Vec<(&str, &mut dyn system::Descriptor)>,
+    ) -> Vec<(Node, Failure)> {
         let condvar = Condvar::new();
         let had_panic = AtomicBool::new(false);
+        let failures = Mutex::new(Vec::new());
+        let blocked = Mutex::new(HashSet::new());
 
         planner.get_mut().clone_from(topology.initial_planner());
 
         let cycle_context = tracer.start_cycle();
 
-        for &index in &planner.get_mut().send_runnable {
-            tracer.mark_runnable(Node::SendSystem(index));
+        planner.get_mut().complete_disabled(tracer, topology);
+
+        for runnable in &planner.get_mut().send_runnable {
+            tracer.mark_runnable(Node::SendSystem(runnable.index));
         }
-        for &index in &planner.get_mut().unsend_runnable {
-            tracer.mark_runnable(Node::UnsendSystem(index));
+        for runnable in &planner.get_mut().unsend_runnable {
+            tracer.mark_runnable(Node::UnsendSystem(runnable.index));
         }
 
         for &index in &topology.depless_pars {
@@ -70,7 +84,14 @@ impl Executor {
             tracer.partition(node, partition);
         }
 
-        let context = Context { topology, planner, condvar: &condvar, had_panic: &had_panic };
+        let context = Context {
+            topology,
+            planner,
+            condvar: &condvar,
+            had_panic: &had_panic,
+            failures: &failures,
+            blocked: &blocked,
+        };
 
         let prepare_ealloc_shards_context = tracer.start_prepare_ealloc_shards();
         let mut ealloc_shards = ealloc_map.shards(self.concurrency + 1);
@@ -86,8 +107,7 @@ impl Executor {
                     .split_last_mut()
                     .expect("ealloc_shards.len() == self.concurrency + 1");
                 debug_assert_eq!(worker_ealloc_shards.len(), self.concurrency);
-                let (main_offline_shard, worker_offline_shards) = self
-                    .offline_buffer
+                let (main_offline_shard, worker_offline_shards) = offline_buffer
                     .shards
                     .split_last_mut()
                     .expect("offline shards.len() == self.concurrency + 1");
@@ -131,7 +151,7 @@ impl Executor {
                 &mut unsend,
                 true,
                 ealloc_shards.get_mut(0).expect("concurrency = 0 in single-thread executor"),
-                self.offline_buffer.shards.get_mut(0).expect("incorrect shard count"),
+                offline_buffer.shards.get_mut(0).expect("incorrect shard count"),
                 &deadlock_counter,
             );
         }
@@ -161,9 +181,15 @@ impl Executor {
             .unsend_systems
             .iter_mut()
             .map(|(name, boxed)| (name.as_str(), boxed.as_mut().as_descriptor_mut()));
-        let all_system_refs: Vec<_> = sync_system_refs.chain(unsend_system_refs).collect();
+        let other_system_refs = other_systems
+            .into_iter()
+            .map(|(name, system)| (name, system as &mut dyn system::Descriptor));
+        let all_system_refs: Vec<_> =
+            sync_system_refs.chain(unsend_system_refs).chain(other_system_refs).collect();
+
+        sync_globals.swap_event_buffers();
 
-        self.offline_buffer.drain_cycle(
+        offline_buffer.drain_cycle(
             WorldMut {
                 ealloc_map,
                 components,
@@ -172,16 +198,22 @@ impl Executor {
                 rctrack,
             },
             all_system_refs,
+            self.thread_pool.as_ref(),
         );
 
-        // TODO parallelize this loop
-        for (&arch, ealloc) in &mut ealloc_map.map {
+        let flush = |(&arch, ealloc): (&DbgTypeId, &mut Box<dyn ealloc::AnyEalloc>)| {
             let flush_ealloc_context = tracer.start_flush_ealloc(arch);
             ealloc.flush();
             tracer.end_flush_ealloc(flush_ealloc_context, arch);
+        };
+        match &self.thread_pool {
+            Some(pool) => pool.install(|| ealloc_map.map.par_iter_mut().for_each(flush)),
+            None => ealloc_map.map.iter_mut().for_each(flush),
         }
 
         tracer.end_cycle(cycle_context);
+
+        failures.into_inner()
     }
 }
 
@@ -206,53 +238,67 @@ fn main_worker(
                 match planner_guard.steal_send(tracer, tracer::Thread::Main, context.topology) {
                     StealResult::CycleComplete => return,
                     StealResult::Pending => {
-                        match wait_for_task(
-                            deadlock_counter,
-                            &mut planner_guard,
-                            context.condvar,
-                            context.had_panic,
-                        ) {
+                        match wait_for_task(tracer, context, deadlock_counter, &mut planner_guard) {
                             TaskWait::HasTask => continue,
                             TaskWait::HadPanic => return,
                         }
                     }
                     StealResult::Ready(index) => {
-                        MutexGuard::unlocked(&mut planner_guard, || {
+                        let duration = MutexGuard::unlocked(&mut planner_guard, || {
                             let (debug_name, system) = send.state.get_send_system(index);
 
-                            {
-                                let mut panic_guard = context.panic_guard();
+                            let node = Node::SendSystem(index);
+                            let mut panic_guard = context.panic_guard();
 
+                            let duration = if !context.is_blocked(node)
+                                && send.state.should_run(node, send.globals)
+                            {
                                 let mut system = system
                                     .try_lock()
                                     .expect("system should only be scheduled to one worker");
                                 let run_context = tracer.start_run_sendable(
                                     tracer::Thread::Main,
-                                    Node::SendSystem(index),
+                                    node,
                                     debug_name,
                                     &mut **system,
                                 );
-                                system.run(
-                                    send.globals,
-                                    send.components,
-                                    ealloc_shard_map,
-                                    offline_buffer,
+                                let start = Instant::now();
+                                let completed = context.run_system(
+                                    tracer,
+                                    tracer::Thread::Main,
+                                    node,
+                                    debug_name,
+                                    || {
+                                        system.run(
+                                            send.globals,
+                                            send.components,
+                                            ealloc_shard_map,
+                                            offline_buffer,
+                                        )
+                                    },
                                 );
+                                let duration = start.elapsed();
                                 tracer.end_run_sendable(
                                     run_context,
                                     tracer::Thread::Main,
-                                    Node::SendSystem(index),
+                                    node,
                                     debug_name,
                                     &mut **system,
                                 );
-
-                                panic_guard.done = true;
-                            }
+                                completed.then_some(duration)
+                            } else {
+                                tracer.skip_system(tracer::Thread::Main, node, debug_name);
+                                None
+                            };
+
+                            panic_guard.done = true;
+                            duration
                         });
 
                         planner_guard.complete(
                             tracer,
                             Node::SendSystem(index),
+                            duration,
                             context.topology,
                             context.condvar,
                             deadlock_counter,
@@ -261,49 +307,64 @@ fn main_worker(
                 }
             }
             StealResult::Pending => {
-                match wait_for_task(
-                    deadlock_counter,
-                    &mut planner_guard,
-                    context.condvar,
-                    context.had_panic,
-                ) {
+                match wait_for_task(tracer, context, deadlock_counter, &mut planner_guard) {
                     TaskWait::HasTask => continue,
                     TaskWait::HadPanic => return,
                 }
             }
             StealResult::Ready(index) => {
-                MutexGuard::unlocked(&mut planner_guard, || {
+                let duration = MutexGuard::unlocked(&mut planner_guard, || {
                     let (debug_name, system) = unsend.state.get_unsend_system_mut(index);
 
+                    let node = Node::UnsendSystem(index);
                     let mut panic_guard = context.panic_guard();
 
-                    let run_context = tracer.start_run_unsendable(
-                        tracer::Thread::Main,
-                        Node::UnsendSystem(index),
-                        debug_name,
-                        &mut *system,
-                    );
-                    system.run(
-                        send.globals,
-                        unsend.globals,
-                        send.components,
-                        ealloc_shard_map,
-                        offline_buffer,
-                    );
-                    tracer.end_run_unsendable(
-                        run_context,
-                        tracer::Thread::Main,
-                        Node::UnsendSystem(index),
-                        debug_name,
-                        &mut *system,
-                    );
+                    let duration =
+                        if !context.is_blocked(node) && send.state.should_run(node, send.globals) {
+                            let run_context = tracer.start_run_unsendable(
+                                tracer::Thread::Main,
+                                node,
+                                debug_name,
+                                &mut *system,
+                            );
+                            let start = Instant::now();
+                            let completed = context.run_system(
+                                tracer,
+                                tracer::Thread::Main,
+                                node,
+                                debug_name,
+                                || {
+                                    system.run(
+                                        send.globals,
+                                        unsend.globals,
+                                        send.components,
+                                        ealloc_shard_map,
+                                        offline_buffer,
+                                    )
+                                },
+                            );
+                            let duration = start.elapsed();
+                            tracer.end_run_unsendable(
+                                run_context,
+                                tracer::Thread::Main,
+                                node,
+                                debug_name,
+                                &mut *system,
+                            );
+                            completed.then_some(duration)
+                        } else {
+                            tracer.skip_system(tracer::Thread::Main, node, debug_name);
+                            None
+                        };
 
                     panic_guard.done = true;
+                    duration
                 });
 
                 planner_guard.complete(
                     tracer,
                     Node::UnsendSystem(index),
+                    duration,
                     context.topology,
                     context.condvar,
                     deadlock_counter,
@@ -330,48 +391,57 @@ fn threaded_worker(
         match planner_guard.steal_send(tracer, thread, context.topology) {
             StealResult::CycleComplete => return,
             StealResult::Pending => {
-                match wait_for_task(
-                    deadlock_counter,
-                    &mut planner_guard,
-                    context.condvar,
-                    context.had_panic,
-                ) {
+                match wait_for_task(tracer, context, deadlock_counter, &mut planner_guard) {
                     TaskWait::HasTask => continue,
                     TaskWait::HadPanic => return,
                 }
             }
             StealResult::Ready(index) => {
-                MutexGuard::unlocked(&mut planner_guard, || {
+                let duration = MutexGuard::unlocked(&mut planner_guard, || {
                     let (debug_name, system) = send.state.get_send_system(index);
 
-                    {
-                        let mut panic_guard = context.panic_guard();
+                    let node = Node::SendSystem(index);
+                    let mut panic_guard = context.panic_guard();
 
-                        let mut system = system
-                            .try_lock()
-                            .expect("system should only be scheduled to one worker");
-                        let run_context = tracer.start_run_sendable(
-                            thread,
-                            Node::SendSystem(index),
-                            debug_name,
-                            &mut **system,
-                        );
-                        system.run(send.globals, send.components, ealloc_shard_map, offline_buffer);
-                        tracer.end_run_sendable(
-                            run_context,
-                            thread,
-                            Node::SendSystem(index),
-                            debug_name,
-                            &mut **system,
-                        );
+                    let duration =
+                        if !context.is_blocked(node) && send.state.should_run(node, send.globals) {
+                            let mut system = system
+                                .try_lock()
+                                .expect("system should only be scheduled to one worker");
+                            let run_context =
+                                tracer.start_run_sendable(thread, node, debug_name, &mut **system);
+                            let start = Instant::now();
+                            let completed =
+                                context.run_system(tracer, thread, node, debug_name, || {
+                                    system.run(
+                                        send.globals,
+                                        send.components,
+                                        ealloc_shard_map,
+                                        offline_buffer,
+                                    )
+                                });
+                            let duration = start.elapsed();
+                            tracer.end_run_sendable(
+                                run_context,
+                                thread,
+                                node,
+                                debug_name,
+                                &mut **system,
+                            );
+                            completed.then_some(duration)
+                        } else {
+                            tracer.skip_system(thread, node, debug_name);
+                            None
+                        };
 
-                        panic_guard.done = true;
-                    }
+                    panic_guard.done = true;
+                    duration
                 });
 
                 planner_guard.complete(
                     tracer,
                     Node::SendSystem(index),
+                    duration,
                     context.topology,
                     context.condvar,
                     deadlock_counter,
@@ -387,17 +457,24 @@ enum TaskWait {
 }
 
 fn wait_for_task(
+    tracer: &impl Tracer,
+    context: Context<'_>,
     deadlock_counter: &DeadlockCounter,
     planner_guard: &mut MutexGuard<'_, Planner>,
-    condvar: &Condvar,
-    had_panic: &AtomicBool,
 ) -> TaskWait {
-    deadlock_counter.start_wait();
+    if deadlock_counter.start_wait() {
+        // interrupt the other threads, which are waiting for tasks that never come
+        context.had_panic.store(true, atomic::Ordering::Release);
+
+        let deadlock = Deadlock::new(planner_guard, context.topology);
+        tracer.deadlock(&deadlock);
+        panic!("Deadlock detected, all workers and main are waiting for tasks\n{deadlock}");
+    }
 
     loop {
         // wait for condvar to be notified, or poll for panic interrupts every second
-        let result = condvar.wait_for(planner_guard, Duration::from_secs(1));
-        if had_panic.load(atomic::Ordering::Acquire) {
+        let result = context.condvar.wait_for(planner_guard, Duration::from_secs(1));
+        if context.had_panic.load(atomic::Ordering::Acquire) {
             return TaskWait::HadPanic;
         }
 
@@ -416,11 +493,10 @@ mod deadlock_counter {
     impl DeadlockCounter {
         pub(crate) fn new(concurrency: usize) -> Self { Self(AtomicUsize::new(concurrency)) }
 
-        pub(crate) fn start_wait(&self) {
+        /// Returns `true` if all threads are waiting, i.e. a deadlock is detected.
+        pub(crate) fn start_wait(&self) -> bool {
             let cnt = self.0.fetch_sub(1, atomic::Ordering::SeqCst);
-            if cnt == 1 {
-                panic!("Deadlock detected, all workers and main are waiting for tasks");
-            }
+            cnt == 1
         }
 
         pub(crate) fn end_wait(&self, count: usize) {
@@ -435,25 +511,88 @@ mod deadlock_counter {
 
     impl DeadlockCounter {
         pub(crate) fn new(_concurrency: usize) -> Self { Self }
-        pub(crate) fn start_wait(&self) {}
+        pub(crate) fn start_wait(&self) -> bool { false }
         pub(crate) fn end_wait(&self, _count: usize) {}
     }
 }
 
 pub(crate) use deadlock_counter::DeadlockCounter;
 
+/// An abnormal outcome of a system run.
+pub(crate) enum Failure {
+    /// The system panicked, and the panic was caught according to its panic policy.
+    Panic(Box<dyn Any + Send>),
+    /// The system returned an error.
+    Error(system::BoxedError),
+}
+
 #[derive(Clone, Copy)]
 struct Context<'t> {
     topology:  &'t Topology,
     planner:   &'t Mutex<Planner>,
     condvar:   &'t Condvar,
     had_panic: &'t AtomicBool,
+    failures:  &'t Mutex<Vec<(Node, Failure)>>,
+    /// The nodes skipped for the rest of the cycle because a system they depend on has failed.
+    blocked:   &'t Mutex<HashSet<Node>>,
 }
 
 impl<'t> Context<'t> {
     fn panic_guard(&self) -> PanicGuard<'_> {
         PanicGuard { done: false, had_panic: self.had_panic }
     }
+
+    /// Runs a system, catching its panic unless the panic policy of the system is to abort.
+    ///
+    /// Returns `false` if a panic was caught.
+    fn run_system(
+        &self,
+        tracer: &impl Tracer,
+        thread: tracer::Thread,
+        node: Node,
+        debug_name: &str,
+        run: impl FnOnce() -> Result<(), system::BoxedError>,
+    ) -> bool {
+        let result = if let PanicPolicy::Abort = self.topology.panic_policy_of(node) {
+            run()
+        } else {
+            match panic::catch_unwind(AssertUnwindSafe(run)) {
+                Ok(result) => result,
+                Err(payload) => {
+                    self.failures.lock().push((node, Failure::Panic(payload)));
+                    return false;
+                }
+            }
+        };
+
+        if let Err(error) = result {
+            tracer.system_error(thread, node, debug_name, &*error);
+            if self.topology.blocking_on_error.contains(&node) {
+                self.block_dependents(node);
+            }
+            self.failures.lock().push((node, Failure::Error(error)));
+        }
+
+        true
+    }
+
+    /// Marks all nodes that transitively depend on `node` as blocked.
+    ///
+    /// This must be called before `node` completes,
+    /// so that none of its dependents have started yet.
+    fn block_dependents(&self, node: Node) {
+        let mut blocked = self.blocked.lock();
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            for &dependent in self.topology.dependents_of(node) {
+                if blocked.insert(dependent) {
+                    stack.push(dependent);
+                }
+            }
+        }
+    }
+
+    fn is_blocked(&self, node: Node) -> bool { self.blocked.lock().contains(&node) }
 }
 
 struct PanicGuard<'t> {
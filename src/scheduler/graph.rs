@@ -0,0 +1,228 @@
+//! Structured descriptions of schedule topologies.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+
+use super::{Node, ResourceType, Scheduler};
+use crate::util::JsonString;
+
+/// A structured description of the topology of a schedule,
+/// returned by [`World::schedule_graph`](crate::World::schedule_graph).
+///
+/// All lists are sorted, so the rendered output is stable for the same schedule
+/// and can be diffed over time.
+#[derive(Debug, Clone)]
+pub struct Graph {
+    /// The systems and partitions in the schedule.
+    pub nodes:      Vec<GraphNode>,
+    /// The ordering edges between nodes.
+    pub orders:     Vec<GraphOrder>,
+    /// The pairs of systems that must not run concurrently.
+    pub exclusions: Vec<GraphExclusion>,
+}
+
+/// A system or partition in a [`Graph`].
+#[derive(Debug, Clone)]
+pub struct GraphNode {
+    /// The node in the topology.
+    pub node: Node,
+    /// The debug name of the system, or the debug representation of the partition.
+    pub name: String,
+}
+
+/// An ordering edge in a [`Graph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GraphOrder {
+    /// The node that must complete first.
+    pub before: Node,
+    /// The node that can only start after `before` completes.
+    pub after:  Node,
+}
+
+/// An exclusion edge in a [`Graph`].
+#[derive(Debug, Clone)]
+pub struct GraphExclusion {
+    /// The pair of conflicting systems, where `nodes.0 < nodes.1`.
+    pub nodes:     (Node, Node),
+    /// The resources that the two systems request conflicting access to,
+    /// sorted by their display names.
+    pub resources: Vec<ResourceType>,
+}
+
+impl Graph {
+    pub(super) fn new(scheduler: &Scheduler) -> Self {
+        let send_nodes =
+            scheduler.sync_state.send_systems.iter().enumerate().map(|(index, (name, _))| {
+                GraphNode {
+                    node: Node::SendSystem(super::SendSystemIndex(index)),
+                    name: name.clone(),
+                }
+            });
+        let unsend_nodes =
+            scheduler.unsync_state.unsend_systems.iter().enumerate().map(|(index, (name, _))| {
+                GraphNode {
+                    node: Node::UnsendSystem(super::UnsendSystemIndex(index)),
+                    name: name.clone(),
+                }
+            });
+        let partition_nodes =
+            scheduler.topology.partitions.iter().enumerate().map(|(index, partition)| GraphNode {
+                node: Node::Partition(super::PartitionIndex(index)),
+                name: format!("{partition:?}"),
+            });
+        let nodes = send_nodes.chain(unsend_nodes).chain(partition_nodes).collect();
+
+        let mut orders: Vec<_> = scheduler
+            .orders
+            .iter()
+            .map(|order| GraphOrder { before: order.before, after: order.after })
+            .collect();
+        orders.sort();
+        orders.dedup();
+
+        let exclusions: BTreeMap<(Node, Node), &[ResourceType]> =
+            scheduler.topology.exclusion_pairs().collect();
+        let exclusions = exclusions
+            .into_iter()
+            .map(|(nodes, resources)| {
+                let mut resources = resources.to_vec();
+                resources.sort_by_cached_key(ToString::to_string);
+                GraphExclusion { nodes, resources }
+            })
+            .collect();
+
+        Self { nodes, orders, exclusions }
+    }
+
+    /// Renders the graph in the Graphviz DOT format.
+    ///
+    /// Thread-safe systems are drawn as boxes, thread-unsafe systems as bold boxes
+    /// and partitions as diamonds.
+    /// Exclusions are drawn as dashed undirected edges labelled with the conflicting resources.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph schedule {\n");
+
+        for node in &self.nodes {
+            let attrs = match node.node {
+                Node::SendSystem(_) => "shape = box",
+                Node::UnsendSystem(_) => "shape = box, style = bold",
+                Node::Partition(_) => "shape = diamond",
+            };
+            writeln!(out, "\t{} [label = {}, {attrs}]", NodeId(node.node), DotString(&node.name))
+                .expect("String write is infallible");
+        }
+
+        if !self.orders.is_empty() {
+            out.push('\n');
+        }
+        for order in &self.orders {
+            writeln!(out, "\t{} -> {}", NodeId(order.before), NodeId(order.after))
+                .expect("String write is infallible");
+        }
+
+        if !self.exclusions.is_empty() {
+            out.push('\n');
+        }
+        for exclusion in &self.exclusions {
+            let label = exclusion.resources.iter().map(ToString::to_string).collect::<Vec<_>>();
+            writeln!(
+                out,
+                "\t{} -> {} [dir = none, style = dashed, color = red, label = {}]",
+                NodeId(exclusion.nodes.0),
+                NodeId(exclusion.nodes.1),
+                DotString(&label.join("\n")),
+            )
+            .expect("String write is infallible");
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Renders the graph as a JSON document.
+    ///
+    /// Nodes are identified by the same IDs as in [`to_dot`](Self::to_dot).
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\n  \"nodes\": [");
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            let kind = match node.node {
+                Node::SendSystem(_) => "send_system",
+                Node::UnsendSystem(_) => "unsend_system",
+                Node::Partition(_) => "partition",
+            };
+            write!(
+                out,
+                "{}\n    {{\"id\": \"{}\", \"kind\": \"{kind}\", \"name\": {}}}",
+                if i == 0 { "" } else { "," },
+                NodeId(node.node),
+                JsonString(&node.name),
+            )
+            .expect("String write is infallible");
+        }
+
+        out.push_str("\n  ],\n  \"orders\": [");
+        for (i, order) in self.orders.iter().enumerate() {
+            write!(
+                out,
+                "{}\n    {{\"before\": \"{}\", \"after\": \"{}\"}}",
+                if i == 0 { "" } else { "," },
+                NodeId(order.before),
+                NodeId(order.after),
+            )
+            .expect("String write is infallible");
+        }
+
+        out.push_str("\n  ],\n  \"exclusions\": [");
+        for (i, exclusion) in self.exclusions.iter().enumerate() {
+            let resources = exclusion
+                .resources
+                .iter()
+                .map(|resource| JsonString(&resource.to_string()).to_string())
+                .collect::<Vec<_>>();
+            write!(
+                out,
+                "{}\n    {{\"nodes\": [\"{}\", \"{}\"], \"resources\": [{}]}}",
+                if i == 0 { "" } else { "," },
+                NodeId(exclusion.nodes.0),
+                NodeId(exclusion.nodes.1),
+                resources.join(", "),
+            )
+            .expect("String write is infallible");
+        }
+
+        out.push_str("\n  ]\n}\n");
+        out
+    }
+}
+
+/// Formats the ID of a node in rendered graphs.
+struct NodeId(Node);
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Node::SendSystem(index) => write!(f, "send_{}", index.0),
+            Node::UnsendSystem(index) => write!(f, "unsend_{}", index.0),
+            Node::Partition(index) => write!(f, "partition_{}", index.0),
+        }
+    }
+}
+
+/// Formats a quoted DOT string.
+struct DotString<'t>(&'t str);
+
+impl<'t> fmt::Display for DotString<'t> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char('"')?;
+        for ch in self.0.chars() {
+            match ch {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                ch => f.write_char(ch)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
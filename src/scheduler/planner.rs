@@ -1,5 +1,7 @@
+use std::cmp::Reverse;
 use std::collections::{BTreeSet, HashMap};
 use std::num::NonZeroUsize;
+use std::time::Duration;
 
 use parking_lot::Condvar;
 
@@ -18,15 +20,38 @@ pub(crate) struct Planner {
     /// The queue of [`Node::SendSystem`] nodes that may be runnable.
     /// Due to exclusion, nodes in the queue may no longer be runnable.
     /// `wakeup_count` must always be re-checked.
-    pub(crate) send_runnable: BTreeSet<SendSystemIndex>,
+    pub(crate) send_runnable: BTreeSet<Runnable<SendSystemIndex>>,
 
     /// The queue of [`Node::UnsendSystem`] nodes that may be runnable.
     /// Due to exclusion, nodes in the queue may no longer be runnable.
     /// `wakeup_count` must always be re-checked.
-    pub(crate) unsend_runnable: BTreeSet<UnsendSystemIndex>,
+    pub(crate) unsend_runnable: BTreeSet<Runnable<UnsendSystemIndex>>,
 
     /// Number of remaining systems to run.
     pub(crate) remaining_systems: usize,
+
+    /// The run durations of the systems completed in this cycle.
+    pub(crate) durations: Vec<(Node, Duration)>,
+}
+
+/// An entry in a runnable pool.
+///
+/// Entries are ordered by descending priority, then by ascending index,
+/// so that the first entry in the pool is the one to steal next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Runnable<I> {
+    priority:         Reverse<u64>,
+    pub(crate) index: I,
+}
+
+impl<I: Copy> Runnable<I> {
+    pub(crate) fn new(topology: &Topology, index: I, to_node: fn(I) -> Node) -> Self {
+        Self::with_priority(topology.priority_of(to_node(index)), index)
+    }
+
+    pub(crate) fn with_priority(priority: u64, index: I) -> Self {
+        Self { priority: Reverse(priority), index }
+    }
 }
 
 impl Planner {
@@ -36,7 +61,7 @@ impl Planner {
         tracer: &impl Tracer,
         thread: tracer::Thread,
         topology: &Topology,
-        pool: fn(&mut Self) -> &mut BTreeSet<I>,
+        pool: fn(&mut Self) -> &mut BTreeSet<Runnable<I>>,
         to_node: fn(I) -> Node,
     ) -> StealResult<I> {
         if self.remaining_systems == 0 {
@@ -45,7 +70,7 @@ impl Planner {
         }
 
         let index = match pool(self).pop_first() {
-            Some(index) => index,
+            Some(runnable) => runnable.index,
             None => {
                 tracer.steal_return_pending(thread);
                 return StealResult::Pending;
@@ -75,19 +100,19 @@ impl Planner {
                     match excl {
                         Node::SendSystem(index) => {
                             self.send_runnable
-                                .take(&index)
+                                .take(&Runnable::new(topology, index, Node::SendSystem))
                                 .expect("Pending node should be in runnable pool");
                         }
                         Node::UnsendSystem(index) => {
                             self.unsend_runnable
-                                .take(&index)
+                                .take(&Runnable::new(topology, index, Node::UnsendSystem))
                                 .expect("Pending node should be in runnable pool");
                         }
                         Node::Partition(_) => {
                             panic!("partitions are not exclusive with other nodes")
                         }
                     }
-                    tracer.unmark_runnable(excl);
+                    tracer.unmark_runnable_by(excl, node);
                 }
                 WakeupState::Blocked { count } => {
                     *count = NonZeroUsize::new(count.get() + 1).expect("integer overflow");
@@ -120,14 +145,61 @@ impl Planner {
         self.steal(tracer, thread, topology, |this| &mut this.unsend_runnable, Node::UnsendSystem)
     }
 
+    /// Completes the disabled systems that are runnable at the start of a cycle.
+    pub(crate) fn complete_disabled(&mut self, tracer: &impl Tracer, topology: &Topology) {
+        let disabled: Vec<Node> = self
+            .send_runnable
+            .iter()
+            .map(|runnable| Node::SendSystem(runnable.index))
+            .chain(self.unsend_runnable.iter().map(|runnable| Node::UnsendSystem(runnable.index)))
+            .filter(|node| topology.disabled.contains(node))
+            .collect();
+
+        let mut queue = Vec::new();
+        for node in disabled {
+            match node {
+                Node::SendSystem(index) => {
+                    self.send_runnable.remove(&Runnable::new(topology, index, Node::SendSystem))
+                }
+                Node::UnsendSystem(index) => {
+                    self.unsend_runnable.remove(&Runnable::new(topology, index, Node::UnsendSystem))
+                }
+                Node::Partition(_) => unreachable!("partitions are not runnable"),
+            };
+            self.complete_instantly(tracer, node, topology, &mut queue);
+        }
+
+        while let Some(node) = queue.pop() {
+            self.remove_one_block_no_recursion(tracer, node, topology, &mut queue);
+        }
+    }
+
+    /// Marks a disabled system node as completed without starting it.
+    ///
+    /// Since the node never started, it holds no exclusions to release.
+    fn complete_instantly(
+        &mut self,
+        tracer: &impl Tracer,
+        node: Node,
+        topology: &Topology,
+        queue: &mut Vec<Node>,
+    ) {
+        *self.wakeup_state.get_mut(&node).expect("invalid node index") = WakeupState::Completed;
+        self.remaining_systems -= 1;
+        tracer.complete_system(node, self.remaining_systems);
+        queue.extend(topology.dependents_of(node).iter().copied());
+    }
+
     /// Mark a node as completed.
     ///
     /// This method is only called for system nodes.
     /// Partition nodes are completed in-place.
+    /// `duration` is the run duration of the system, or `None` if it was skipped.
     pub(crate) fn complete(
         &mut self,
         tracer: &impl Tracer,
         node: Node,
+        duration: Option<Duration>,
         topology: &Topology,
         condvar: &Condvar,
         deadlock_counter: &DeadlockCounter,
@@ -144,6 +216,7 @@ impl Planner {
         self.remove_one_block(tracer, topology, topology.exclusions_of(node).iter().copied());
 
         self.remaining_systems -= 1;
+        self.durations.extend(duration.map(|duration| (node, duration)));
 
         tracer.complete_system(node, self.remaining_systems);
 
@@ -179,16 +252,29 @@ impl Planner {
             }
             WakeupState::Blocked { count } if count.get() == 1 => {
                 *state = WakeupState::Pending;
+                if topology.disabled.contains(&node) {
+                    self.complete_instantly(tracer, node, topology, queue);
+                    return;
+                }
+
                 match node {
                     Node::SendSystem(index) => {
-                        let new = self.send_runnable.insert(index);
+                        let new = self.send_runnable.insert(Runnable::new(
+                            topology,
+                            index,
+                            Node::SendSystem,
+                        ));
                         if !new {
                             panic!("Blocked node {node:?} is already in runnable pool")
                         }
                         tracer.mark_runnable(node);
                     }
                     Node::UnsendSystem(index) => {
-                        let new = self.unsend_runnable.insert(index);
+                        let new = self.unsend_runnable.insert(Runnable::new(
+                            topology,
+                            index,
+                            Node::UnsendSystem,
+                        ));
                         if !new {
                             panic!("Blocked node {node:?} is already in runnable pool")
                         }
@@ -1,13 +1,25 @@
+use std::collections::HashMap;
+
 use parking_lot::Mutex;
 
-use super::{SendSystemIndex, UnsendSystemIndex};
-use crate::system;
+use super::{Node, SendSystemIndex, UnsendSystemIndex};
+use crate::{system, world};
 
 pub(crate) struct SyncState {
-    pub(crate) send_systems: Vec<(String, Mutex<Box<dyn system::Sendable>>)>,
+    pub(crate) send_systems:   Vec<(String, Mutex<Box<dyn system::Sendable>>)>,
+    /// Run conditions of systems, including thread-unsafe systems.
+    pub(crate) run_conditions: HashMap<Node, Vec<system::RunCondition>>,
 }
 
 impl SyncState {
+    /// Evaluates the run conditions of a system.
+    pub(crate) fn should_run(&self, node: Node, globals: &world::SyncGlobals) -> bool {
+        match self.run_conditions.get(&node) {
+            Some(conditions) => conditions.iter().all(|condition| condition.evaluate(globals)),
+            None => true,
+        }
+    }
+
     pub(crate) fn get_send_system(
         &self,
         index: SendSystemIndex,
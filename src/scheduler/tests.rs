@@ -34,7 +34,12 @@ fn dummy_spec(name: &str) -> system::Spec {
         global_requests:         vec![],
         simple_requests:         vec![],
         isotope_requests:        vec![],
+        dynamic_requests:        vec![],
+        simple_event_requests:   vec![],
+        event_requests:          vec![],
         entity_creator_requests: vec![],
+        run_conditions:          vec![],
+        on_error:                system::OnError::Continue,
     }
 }
 
@@ -56,8 +61,9 @@ impl system::Sendable for SendSystem {
         _components: &world::Components,
         _ealloc_shard_map: &mut ealloc::ShardMap,
         _offline_buffer: &mut offline::BufferShard,
-    ) {
+    ) -> Result<(), system::BoxedError> {
         self.1();
+        Ok(())
     }
 
     fn as_descriptor_mut(&mut self) -> &mut dyn system::Descriptor { self }
@@ -72,8 +78,9 @@ impl system::Unsendable for UnsendSystem {
         _components: &world::Components,
         _ealloc_shard_map: &mut ealloc::ShardMap,
         _offline_buffer: &mut offline::BufferShard,
-    ) {
+    ) -> Result<(), system::BoxedError> {
         self.1();
+        Ok(())
     }
 
     fn as_descriptor_mut(&mut self) -> &mut dyn system::Descriptor { self }
@@ -748,6 +755,60 @@ fn test_unsend_panic() {
     bootstrap(3, || (), |_builder, [], [_]| {}, || |_| panic!("system panic"), |_| {})
 }
 
+#[test]
+fn test_deadlock_dump() {
+    let mut builder = Builder::new(0);
+    let nodes: Vec<Node> = (0..3)
+        .map(|i| {
+            let system: SendSystem = TestSystem(format!("SendSystem #{i}"), Box::new(|| {}));
+            builder.push_send_system(Box::new(system)).0
+        })
+        .collect();
+    for &node in &nodes[..2] {
+        builder.use_resource(
+            node,
+            ResourceType::Global(DbgTypeId::of::<Global1>()),
+            ResourceAccess { mutable: true, discrim: None },
+        );
+    }
+    builder.add_dependencies(
+        vec![system::spec::Dependency::Before(Box::new(TestPartition(0)))],
+        nodes[0],
+    );
+    builder.add_dependencies(
+        vec![system::spec::Dependency::After(Box::new(TestPartition(0)))],
+        nodes[2],
+    );
+    let scheduler = builder.build();
+
+    let mut planner = scheduler.topology.initial_planner().clone();
+    let stolen = planner.steal_send(&tracer::Noop, tracer::Thread::Main, &scheduler.topology);
+    assert!(
+        matches!(stolen, planner::StealResult::Ready(index) if Node::SendSystem(index) == nodes[0])
+    );
+
+    let deadlock = Deadlock::new(&planner, &scheduler.topology);
+    assert_eq!(deadlock.started, [nodes[0]]);
+
+    let blockers_of = |node: Node| {
+        &deadlock.nodes.iter().find(|other| other.node == node).expect("node in dump").blockers
+    };
+    assert!(matches!(
+        &blockers_of(nodes[1])[..],
+        [DeadlockBlocker::Exclusion { node, resources }]
+            if *node == nodes[0] && resources[..] == [ResourceType::Global(DbgTypeId::of::<Global1>())]
+    ));
+    assert!(matches!(
+        &blockers_of(nodes[2])[..],
+        [DeadlockBlocker::Dependency(Node::Partition(_))]
+    ));
+
+    let message = deadlock.to_string();
+    assert!(message.contains("thread-safe system #1 (SendSystem #1): Blocked"));
+    assert!(message.contains("excluded by thread-safe system #0 (SendSystem #0)"));
+    assert!(message.contains("after partition #0 (TestPartition(0))"));
+}
+
 /// Bootstraps a test function for the scheduler.
 ///
 /// This function performs the following:
@@ -861,6 +922,8 @@ fn bootstrap<const S: usize, const U: usize, T, C, R, V>(
             &mut world::UnsyncGlobals::empty(),
             &mut rctrack::MaybeStoreMap::default(),
             &mut ealloc::Map::default(),
+            &mut offline::Buffer::new(concurrency + 1),
+            Vec::new(),
         );
 
         let tracer::Aggregate((_, rct, tracer::Aggregate(tracers))) = tracer;
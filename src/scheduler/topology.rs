@@ -2,9 +2,10 @@ use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::num::NonZeroUsize;
 
+use super::planner::Runnable;
 use super::{
-    Node, Order, PartitionIndex, Planner, ResourceAccess, ResourceType, SendSystemIndex,
-    UnsendSystemIndex, WakeupState,
+    Node, Order, PanicPolicy, PartitionIndex, Planner, ResourceAccess, ResourceType,
+    SendSystemIndex, UnsendSystemIndex, WakeupState,
 };
 use crate::system;
 
@@ -16,6 +17,13 @@ pub(crate) struct Topology {
     /// This means `b` is a wakeup candidate when `a` completes.
     dependents: HashMap<Node, Vec<Node>>,
 
+    /// The total weight of the heaviest chain of dependents starting from each node,
+    /// including the weight of the node itself.
+    ///
+    /// Runnable systems with a higher priority are started first,
+    /// so that long chains of systems are not delayed to the end of the cycle.
+    priorities: HashMap<Node, u64>,
+
     /// The [`Planner`] reset state every tick.
     initial_planner: Planner,
 
@@ -32,6 +40,26 @@ pub(crate) struct Topology {
     /// If `exclusions[a].contains(b)`, `a` and `b` must not execute concurrently.
     /// `exclusions[a].contains(b)` if and only if `exclusions[b].contains(a)`.
     exclusions: HashMap<Node, Vec<Node>>,
+
+    /// The resources that each pair of exclusive nodes request conflicting access to.
+    /// Both orders of each pair are present.
+    ///
+    /// This field is persisted for graph export and deadlock diagnostics.
+    exclusion_resources: HashMap<(Node, Node), Vec<ResourceType>>,
+
+    /// The human-readable descriptions of all nodes.
+    ///
+    /// This field is persisted for deadlock diagnostics.
+    descriptions: HashMap<Node, String>,
+
+    /// System nodes that are completed instantly without running.
+    pub(crate) disabled: HashSet<Node>,
+
+    /// The panic policies of system nodes, defaulting to [`PanicPolicy::Abort`] if absent.
+    pub(crate) panic_policies: HashMap<Node, PanicPolicy>,
+
+    /// System nodes declared with [`system::OnError::Block`].
+    pub(crate) blocking_on_error: HashSet<Node>,
 }
 
 impl Topology {
@@ -42,6 +70,7 @@ impl Topology {
         orders: &[Order],
         resources: &HashMap<ResourceType, HashMap<Node, Vec<ResourceAccess>>>,
         describe_node: impl Fn(Node) -> String,
+        weight_of: impl Fn(Node) -> u64,
     ) -> Self {
         let nodes_iter = (0..send_systems_count)
             .map(|index| Node::SendSystem(SendSystemIndex(index)))
@@ -51,13 +80,27 @@ impl Topology {
             .chain((0..partitions.len()).map(|index| Node::Partition(PartitionIndex(index))));
 
         let dependents = build_dependents_map(nodes_iter.clone(), orders.iter().copied());
-        scan_cycles(&dependents, describe_node);
+        scan_cycles(&dependents, &describe_node);
+        let descriptions = nodes_iter.clone().map(|node| (node, describe_node(node))).collect();
+        let priorities = build_priorities(&dependents, weight_of);
         let (initial_planner, depless_pars) =
-            build_initials(nodes_iter.clone(), orders.iter().copied(), &dependents);
-
-        let exclusions = build_exclusions(nodes_iter, resources);
-
-        Self { dependents, initial_planner, depless_pars, partitions: Vec::new(), exclusions }
+            build_initials(nodes_iter.clone(), orders.iter().copied(), &dependents, &priorities);
+
+        let (exclusions, exclusion_resources) = build_exclusions(nodes_iter, resources);
+
+        Self {
+            dependents,
+            priorities,
+            initial_planner,
+            depless_pars,
+            partitions: Vec::new(),
+            exclusions,
+            exclusion_resources,
+            descriptions,
+            disabled: HashSet::new(),
+            panic_policies: HashMap::new(),
+            blocking_on_error: HashSet::new(),
+        }
     }
 
     pub(crate) fn dependents_of(&self, node: Node) -> &[Node] {
@@ -69,6 +112,60 @@ impl Topology {
     }
 
     pub(crate) fn initial_planner(&self) -> &Planner { &self.initial_planner }
+
+    /// Returns all nodes in the topology in an unspecified order.
+    pub(crate) fn nodes(&self) -> impl Iterator<Item = Node> + '_ {
+        self.dependents.keys().copied()
+    }
+
+    pub(crate) fn describe(&self, node: Node) -> &str {
+        self.descriptions.get(&node).expect("invalid node index")
+    }
+
+    /// Returns the resources that make `node` and `excl` exclusive.
+    pub(crate) fn exclusion_resources_of(&self, node: Node, excl: Node) -> &[ResourceType] {
+        self.exclusion_resources.get(&(node, excl)).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns each pair of exclusive nodes once, as `(node1, node2)` where `node1 < node2`,
+    /// together with the resources they request conflicting access to.
+    pub(crate) fn exclusion_pairs(
+        &self,
+    ) -> impl Iterator<Item = ((Node, Node), &[ResourceType])> + '_ {
+        self.exclusion_resources
+            .iter()
+            .filter(|&(&(node1, node2), _)| node1 < node2)
+            .map(|(&nodes, resources)| (nodes, resources.as_slice()))
+    }
+
+    pub(crate) fn priority_of(&self, node: Node) -> u64 {
+        *self.priorities.get(&node).expect("invalid node index")
+    }
+
+    pub(crate) fn panic_policy_of(&self, node: Node) -> PanicPolicy {
+        self.panic_policies.get(&node).copied().unwrap_or_default()
+    }
+
+    /// Recomputes the priorities of all nodes with new weights.
+    pub(crate) fn set_weights(&mut self, weight_of: impl Fn(Node) -> u64) {
+        self.priorities = build_priorities(&self.dependents, weight_of);
+
+        // the runnable pools are ordered by priority, so they must be rebuilt
+        let send_runnable = self
+            .initial_planner
+            .send_runnable
+            .iter()
+            .map(|runnable| Runnable::new(self, runnable.index, Node::SendSystem))
+            .collect();
+        let unsend_runnable = self
+            .initial_planner
+            .unsend_runnable
+            .iter()
+            .map(|runnable| Runnable::new(self, runnable.index, Node::UnsendSystem))
+            .collect();
+        self.initial_planner.send_runnable = send_runnable;
+        self.initial_planner.unsend_runnable = unsend_runnable;
+    }
 }
 
 fn build_dependents_map(
@@ -132,10 +229,48 @@ fn scan_cycles_from(
     assert!(new_exit, "exited is inserted recursively but no cycles were detected");
 }
 
+/// Computes the heaviest chain of dependents from each node.
+///
+/// `dependents` must not contain cycles.
+fn build_priorities(
+    dependents: &HashMap<Node, Vec<Node>>,
+    weight_of: impl Fn(Node) -> u64,
+) -> HashMap<Node, u64> {
+    let mut priorities: HashMap<Node, u64> = HashMap::with_capacity(dependents.len());
+
+    for &root in dependents.keys() {
+        // post-order traversal without recursion, since chains may be long
+        let mut stack = vec![(root, false)];
+        while let Some((node, visited)) = stack.pop() {
+            if priorities.contains_key(&node) {
+                continue;
+            }
+
+            let node_dependents = dependents.get(&node).expect("invalid node index");
+            if visited {
+                let downstream = node_dependents
+                    .iter()
+                    .map(|dependent| {
+                        *priorities.get(dependent).expect("dependents are visited first")
+                    })
+                    .max()
+                    .unwrap_or(0);
+                priorities.insert(node, downstream.saturating_add(weight_of(node)));
+            } else {
+                stack.push((node, true));
+                stack.extend(node_dependents.iter().map(|&dependent| (dependent, false)));
+            }
+        }
+    }
+
+    priorities
+}
+
 fn build_initials(
     nodes: impl Iterator<Item = Node> + Clone,
     orders: impl Iterator<Item = Order>,
     dependents: &HashMap<Node, Vec<Node>>,
+    priorities: &HashMap<Node, u64>,
 ) -> (Planner, Vec<PartitionIndex>) {
     let mut dependency_counts: HashMap<Node, usize> = nodes.clone().map(|node| (node, 0)).collect();
     for order in orders {
@@ -165,17 +300,22 @@ fn build_initials(
     }
 
     // nominate dependencyless systems into the runnable pool
-    let send_runnable: BTreeSet<SendSystemIndex> = dependency_counts
+    let priority_of = |node| *priorities.get(&node).expect("invalid node index");
+    let send_runnable: BTreeSet<Runnable<SendSystemIndex>> = dependency_counts
         .iter()
         .filter_map(|entry| match entry {
-            (&Node::SendSystem(index), 0) => Some(index),
+            (&node @ Node::SendSystem(index), 0) => {
+                Some(Runnable::with_priority(priority_of(node), index))
+            }
             _ => None,
         })
         .collect();
-    let unsend_runnable: BTreeSet<UnsendSystemIndex> = dependency_counts
+    let unsend_runnable: BTreeSet<Runnable<UnsendSystemIndex>> = dependency_counts
         .iter()
         .filter_map(|entry| match entry {
-            (&Node::UnsendSystem(index), 0) => Some(index),
+            (&node @ Node::UnsendSystem(index), 0) => {
+                Some(Runnable::with_priority(priority_of(node), index))
+            }
             _ => None,
         })
         .collect();
@@ -197,17 +337,28 @@ fn build_initials(
     let remaining_systems =
         nodes.filter(|node| matches!(node, Node::SendSystem(_) | Node::UnsendSystem(_))).count();
 
-    (Planner { wakeup_state, send_runnable, unsend_runnable, remaining_systems }, depless_pars)
+    (
+        Planner {
+            wakeup_state,
+            send_runnable,
+            unsend_runnable,
+            remaining_systems,
+            durations: Vec::new(),
+        },
+        depless_pars,
+    )
 }
 
+#[allow(clippy::type_complexity)]
 fn build_exclusions(
     nodes: impl Iterator<Item = Node>,
     resources: &HashMap<ResourceType, HashMap<Node, Vec<ResourceAccess>>>,
-) -> HashMap<Node, Vec<Node>> {
+) -> (HashMap<Node, Vec<Node>>, HashMap<(Node, Node), Vec<ResourceType>>) {
     let mut exclusions: HashMap<Node, HashSet<Node>> =
         nodes.map(|node| (node, HashSet::new())).collect();
+    let mut exclusion_resources: HashMap<(Node, Node), Vec<ResourceType>> = HashMap::new();
 
-    for nodes in resources.values() {
+    for (&ty, nodes) in resources {
         for (&node1, accesses1) in nodes {
             for (&node2, accesses2) in nodes {
                 if node1 == node2 {
@@ -218,10 +369,13 @@ fn build_exclusions(
                     accesses2.iter().any(|access2| access1.check_conflicts_with(access2).is_err())
                 }) {
                     exclusions.get_mut(&node1).expect("invalid node index").insert(node2);
+                    exclusion_resources.entry((node1, node2)).or_default().push(ty);
                 }
             }
         }
     }
 
-    exclusions.into_iter().map(|(node, set)| (node, set.into_iter().collect())).collect()
+    let exclusions =
+        exclusions.into_iter().map(|(node, set)| (node, set.into_iter().collect())).collect();
+    (exclusions, exclusion_resources)
 }
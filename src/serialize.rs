@@ -0,0 +1,242 @@
+//! Saves and loads world snapshots.
+//!
+//! A world snapshot contains the allocated entities of each archetype,
+//! the components opted in with `#[comp(serialize)]`
+//! and the global states opted in with `#[global(serialize)]`.
+//! See [`World::save`](crate::World::save) and [`World::load`](crate::World::load).
+//!
+//! Archetypes, components and global states are identified by snapshot keys,
+//! which default to the identifier of the type without its module path.
+//! Unlike [`std::any::type_name`], keys do not change across compiler versions,
+//! but they must be unique among the archetypes, the components of each archetype
+//! and the global states of a world.
+//! Override a key with `serialize = "key"` or the `key` option of [`archetype!`](macro@crate::archetype)
+//! to keep loading old snapshots after renaming a type or to resolve a conflict.
+//!
+//! Entity references are written as raw entity IDs,
+//! which remain valid after loading because the allocator state is restored as well.
+//!
+//! The snapshot format is a compact little-endian binary encoding.
+//! It is only intended to be loaded into a world
+//! with the same archetypes, components and global states registered.
+
+use std::any::Any;
+use std::io::{self, Read as _};
+
+use crate::entity::{self, ealloc, rctrack, Raw};
+use crate::{storage, Global, Storage};
+
+mod std_impl;
+
+#[cfg(test)]
+mod tests;
+
+/// Values that can be written to and read from a world snapshot.
+///
+/// This trait is implemented for primitive types, standard containers,
+/// [`Entity`](crate::Entity) and [`Weak`](crate::entity::Weak).
+/// Use `#[derive(dynec::Serialize)]` to implement it for other types,
+/// or `#[comp(serialize)]`/`#[global(serialize)]` for components and global states.
+pub trait Serialize: Sized {
+    /// Writes the value to the snapshot.
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()>;
+
+    /// Reads a value previously written by [`serialize`](Self::serialize).
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self>;
+}
+
+/// Function pointers to the [`Serialize`] implementation of a component or global state.
+///
+/// This is the value of [`SimpleOrIsotope::SERIALIZER`](crate::comp::SimpleOrIsotope::SERIALIZER)
+/// and [`Global::SERIALIZER`](crate::Global::SERIALIZER) for serializable types.
+pub struct Vtable<T> {
+    /// The key that identifies the type in the snapshot.
+    pub(crate) key:         &'static str,
+    pub(crate) serialize:   fn(&T, &mut Writer<'_>) -> io::Result<()>,
+    pub(crate) deserialize: fn(&mut Reader<'_>) -> io::Result<T>,
+}
+
+impl<T: Serialize> Vtable<T> {
+    /// Creates the vtable for a serializable type identified by `key` in snapshots.
+    pub const fn of(key: &'static str) -> Self {
+        Self { key, serialize: T::serialize, deserialize: T::deserialize }
+    }
+}
+
+/// Type-erased [`Vtable`] of a global state.
+pub(crate) struct GlobalVtable {
+    /// The key that identifies the global state in the snapshot.
+    pub(crate) key:         &'static str,
+    pub(crate) serialize:   fn(&dyn Any, &mut Writer<'_>) -> io::Result<()>,
+    /// Replaces the global state with the value read from the snapshot.
+    pub(crate) deserialize: fn(&mut dyn Any, &mut Reader<'_>) -> io::Result<()>,
+}
+
+impl GlobalVtable {
+    /// Returns the vtable of a global state, or `None` if it is not serializable.
+    pub(crate) fn of<G: Global>() -> Option<Self> {
+        let key = G::SERIALIZER.as_ref()?.key;
+
+        Some(Self {
+            key,
+            serialize: |value, writer| {
+                let vtable = G::SERIALIZER.expect("checked in GlobalVtable::of");
+                (vtable.serialize)(value.downcast_ref::<G>().expect("TypeId mismatch"), writer)
+            },
+            deserialize: |value, reader| {
+                let vtable = G::SERIALIZER.expect("checked in GlobalVtable::of");
+                *value.downcast_mut::<G>().expect("TypeId mismatch") =
+                    (vtable.deserialize)(reader)?;
+                Ok(())
+            },
+        })
+    }
+}
+
+/// The output of a world snapshot.
+pub struct Writer<'t> {
+    inner: &'t mut dyn io::Write,
+}
+
+impl<'t> Writer<'t> {
+    pub(crate) fn new(inner: &'t mut dyn io::Write) -> Self { Self { inner } }
+
+    /// Writes raw bytes to the snapshot.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()> { self.inner.write_all(bytes) }
+}
+
+/// The input of a world snapshot.
+pub struct Reader<'t> {
+    inner:   &'t mut dyn io::Read,
+    /// Resolves entity references.
+    /// This is `None` when entities have not been loaded yet.
+    rctrack: Option<&'t rctrack::MaybeStoreMap>,
+}
+
+impl<'t> Reader<'t> {
+    pub(crate) fn new(
+        inner: &'t mut dyn io::Read,
+        rctrack: Option<&'t rctrack::MaybeStoreMap>,
+    ) -> Self {
+        Self { inner, rctrack }
+    }
+
+    /// Reads raw bytes from the snapshot, filling the entire buffer.
+    pub fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> { self.inner.read_exact(buf) }
+
+    /// Reads `len` raw bytes from the snapshot into a new buffer.
+    ///
+    /// Unlike allocating a buffer of `len` bytes for [`read_bytes`](Self::read_bytes),
+    /// the buffer only grows as bytes are actually read,
+    /// so a corrupt length prefix cannot allocate more memory than the input contains.
+    pub fn read_bytes_to_vec(&mut self, len: usize) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        (&mut *self.inner).take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("expected {len} bytes, got {}", bytes.len()),
+            ));
+        }
+        Ok(bytes)
+    }
+
+    pub(crate) fn rctrack(&self) -> io::Result<&'t rctrack::MaybeStoreMap> {
+        self.rctrack.ok_or_else(|| invalid_data("entity references are not allowed here"))
+    }
+}
+
+/// Creates an error for malformed snapshots.
+pub(crate) fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Writes the length of a collection.
+pub(crate) fn write_len(writer: &mut Writer<'_>, len: usize) -> io::Result<()> {
+    (len as u64).serialize(writer)
+}
+
+/// Reads the length of a collection.
+pub(crate) fn read_len(reader: &mut Reader<'_>) -> io::Result<usize> {
+    let len = u64::deserialize(reader)?;
+    usize::try_from(len).map_err(|_| invalid_data(format!("length {len} is too large")))
+}
+
+/// Writes a raw entity ID.
+pub(crate) fn write_raw<E: Raw>(writer: &mut Writer<'_>, raw: E) -> io::Result<()> {
+    write_len(writer, raw.to_primitive())
+}
+
+/// Reads a raw entity ID.
+pub(crate) fn read_raw<E: Raw>(reader: &mut Reader<'_>) -> io::Result<E> {
+    use entity::raw::Atomic as _;
+
+    let primitive = read_len(reader)?;
+    if primitive < E::new().load_mut().to_primitive() {
+        return Err(invalid_data(format!("invalid entity ID {primitive}")));
+    }
+    Ok(E::from_primitive(primitive))
+}
+
+/// Writes a type key that identifies a component or global state in the snapshot.
+pub(crate) fn write_key(writer: &mut Writer<'_>, key: &str) -> io::Result<()> {
+    write_len(writer, key.len())?;
+    writer.write_bytes(key.as_bytes())
+}
+
+/// Reads a type key written by [`write_key`].
+pub(crate) fn read_key(reader: &mut Reader<'_>) -> io::Result<String> {
+    String::deserialize(reader)
+}
+
+/// Writes all components in a storage.
+pub(crate) fn write_storage<S: Storage>(
+    writer: &mut Writer<'_>,
+    storage: &S,
+    vtable: &Vtable<S::Comp>,
+) -> io::Result<()> {
+    write_len(writer, storage.cardinality())?;
+    for (entity, comp) in storage.iter() {
+        write_raw(writer, entity)?;
+        <S::Layout as storage::Layout>::serialize(comp, vtable, writer)?;
+    }
+    Ok(())
+}
+
+/// Reads components written by [`write_storage`] into a storage.
+///
+/// Returns an error if a component belongs to an entity not in `allocated`.
+pub(crate) fn read_storage<S: Storage>(
+    reader: &mut Reader<'_>,
+    storage: &mut S,
+    vtable: &Vtable<S::Comp>,
+    allocated: &ealloc::Snapshot<S::RawEntity>,
+) -> io::Result<()> {
+    let len = read_len(reader)?;
+    for _ in 0..len {
+        let entity = read_raw(reader)?;
+        if !allocated.contains(entity) {
+            return Err(invalid_data(format!("component for unallocated entity {entity:?}")));
+        }
+        let comp = (vtable.deserialize)(reader)?;
+        storage.set(entity, Some(comp));
+    }
+    Ok(())
+}
+
+/// Returns an error if `keys`, which must be sorted, contains duplicates.
+pub(crate) fn check_unique_keys<'t>(
+    kind: &str,
+    keys: impl IntoIterator<Item = &'t str>,
+) -> io::Result<()> {
+    let mut keys = keys.into_iter().peekable();
+    while let Some(key) = keys.next() {
+        if keys.peek() == Some(&key) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("multiple {kind} use the snapshot key {key:?}"),
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,223 @@
+//! Implement [`Serialize`] for standard types.
+//!
+//! Length-prefixed values never preallocate their length prefix,
+//! so that a corrupt length in the input fails with an error at the end of the input
+//! instead of exhausting memory.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::{array, hash, io};
+
+use super::{invalid_data, read_len, write_len, Reader, Serialize, Writer};
+
+macro_rules! impl_num {
+    ($($ty:ty),*) => {
+        $(
+            impl Serialize for $ty {
+                fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+                    writer.write_bytes(&self.to_le_bytes())
+                }
+
+                fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+                    let mut bytes = [0; std::mem::size_of::<$ty>()];
+                    reader.read_bytes(&mut bytes)?;
+                    Ok(<$ty>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    }
+}
+
+impl_num!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl Serialize for usize {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> { write_len(writer, *self) }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> { read_len(reader) }
+}
+
+impl Serialize for isize {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+        (*self as i64).serialize(writer)
+    }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        let value = i64::deserialize(reader)?;
+        isize::try_from(value).map_err(|_| invalid_data(format!("{value} is out of isize range")))
+    }
+}
+
+impl Serialize for bool {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+        u8::from(*self).serialize(writer)
+    }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        match u8::deserialize(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(invalid_data(format!("invalid bool value {value}"))),
+        }
+    }
+}
+
+impl Serialize for char {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+        u32::from(*self).serialize(writer)
+    }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        let value = u32::deserialize(reader)?;
+        char::from_u32(value).ok_or_else(|| invalid_data(format!("invalid char value {value}")))
+    }
+}
+
+impl Serialize for () {
+    fn serialize(&self, _: &mut Writer<'_>) -> io::Result<()> { Ok(()) }
+
+    fn deserialize(_: &mut Reader<'_>) -> io::Result<Self> { Ok(()) }
+}
+
+impl Serialize for String {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+        write_len(writer, self.len())?;
+        writer.write_bytes(self.as_bytes())
+    }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        let len = read_len(reader)?;
+        let bytes = reader.read_bytes_to_vec(len)?;
+        String::from_utf8(bytes).map_err(|err| invalid_data(err.to_string()))
+    }
+}
+
+impl<T: Serialize> Serialize for Option<T> {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+        self.is_some().serialize(writer)?;
+        match self {
+            Some(value) => value.serialize(writer),
+            None => Ok(()),
+        }
+    }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        Ok(if bool::deserialize(reader)? { Some(T::deserialize(reader)?) } else { None })
+    }
+}
+
+impl<T: Serialize> Serialize for Box<T> {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> { T::serialize(self, writer) }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        T::deserialize(reader).map(Box::new)
+    }
+}
+
+impl<T: Serialize, const N: usize> Serialize for [T; N] {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+        self.iter().try_for_each(|item| item.serialize(writer))
+    }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        array::try_from_fn(|_| T::deserialize(reader))
+    }
+}
+
+impl<T: Serialize> Serialize for Vec<T> {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+        write_len(writer, self.len())?;
+        self.iter().try_for_each(|item| item.serialize(writer))
+    }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        let len = read_len(reader)?;
+        (0..len).map(|_| T::deserialize(reader)).collect()
+    }
+}
+
+impl<T: Serialize> Serialize for VecDeque<T> {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+        write_len(writer, self.len())?;
+        self.iter().try_for_each(|item| item.serialize(writer))
+    }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        let len = read_len(reader)?;
+        (0..len).map(|_| T::deserialize(reader)).collect()
+    }
+}
+
+impl<T: Serialize + Ord> Serialize for BTreeSet<T> {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+        write_len(writer, self.len())?;
+        self.iter().try_for_each(|item| item.serialize(writer))
+    }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        let len = read_len(reader)?;
+        (0..len).map(|_| T::deserialize(reader)).collect()
+    }
+}
+
+impl<T: Serialize + Eq + hash::Hash> Serialize for HashSet<T> {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+        write_len(writer, self.len())?;
+        self.iter().try_for_each(|item| item.serialize(writer))
+    }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        let len = read_len(reader)?;
+        (0..len).map(|_| T::deserialize(reader)).collect()
+    }
+}
+
+impl<K: Serialize + Ord, V: Serialize> Serialize for BTreeMap<K, V> {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+        write_len(writer, self.len())?;
+        self.iter().try_for_each(|(key, value)| {
+            key.serialize(writer)?;
+            value.serialize(writer)
+        })
+    }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        let len = read_len(reader)?;
+        (0..len).map(|_| <(K, V)>::deserialize(reader)).collect()
+    }
+}
+
+impl<K: Serialize + Eq + hash::Hash, V: Serialize> Serialize for HashMap<K, V> {
+    fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+        write_len(writer, self.len())?;
+        self.iter().try_for_each(|(key, value)| {
+            key.serialize(writer)?;
+            value.serialize(writer)
+        })
+    }
+
+    fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+        let len = read_len(reader)?;
+        (0..len).map(|_| <(K, V)>::deserialize(reader)).collect()
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: Serialize),*> Serialize for ($($name,)*) {
+            #[allow(non_snake_case)]
+            fn serialize(&self, writer: &mut Writer<'_>) -> io::Result<()> {
+                let ($($name,)*) = self;
+                $($name.serialize(writer)?;)*
+                Ok(())
+            }
+
+            fn deserialize(reader: &mut Reader<'_>) -> io::Result<Self> {
+                Ok(($($name::deserialize(reader)?,)*))
+            }
+        }
+    }
+}
+
+impl_tuple!(T1);
+impl_tuple!(T1, T2);
+impl_tuple!(T1, T2, T3);
+impl_tuple!(T1, T2, T3, T4);
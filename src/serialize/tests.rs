@@ -0,0 +1,76 @@
+use std::collections::{BTreeMap, HashMap};
+use std::{fmt, io};
+
+use super::{Reader, Serialize, Writer};
+
+fn roundtrip<T: Serialize + PartialEq + fmt::Debug>(value: T) {
+    let mut buf = Vec::new();
+    value.serialize(&mut Writer::new(&mut buf)).expect("write to Vec should succeed");
+
+    let mut input = &buf[..];
+    let output =
+        T::deserialize(&mut Reader::new(&mut input, None)).expect("serialized value is valid");
+    assert_eq!(output, value);
+    assert!(input.is_empty(), "all serialized bytes should be consumed");
+}
+
+#[test]
+fn test_std_roundtrip() {
+    roundtrip(0x1234_5678_u32);
+    roundtrip(-3_i64);
+    roundtrip(usize::MAX);
+    roundtrip(1.5_f64);
+    roundtrip(true);
+    roundtrip('字');
+    roundtrip(String::from("hello"));
+    roundtrip(Some(vec![1_u8, 2, 3]));
+    roundtrip(None::<String>);
+    roundtrip(Box::new([1_i16, -1]));
+    roundtrip((1_u8, String::from("a"), ()));
+    roundtrip(BTreeMap::from([(1_u32, String::from("one")), (2, String::from("two"))]));
+    roundtrip(HashMap::from([(String::from("key"), vec![0.5_f32])]));
+}
+
+#[derive(Debug, PartialEq, crate::Serialize)]
+#[dynec(dynec_as(crate))]
+enum Derived {
+    Unit,
+    Tuple(u32, String),
+    Named { flag: bool, nested: Option<Box<Derived>> },
+}
+
+#[test]
+fn test_derive_roundtrip() {
+    roundtrip(Derived::Unit);
+    roundtrip(Derived::Tuple(7, String::from("seven")));
+    roundtrip(Derived::Named { flag: true, nested: Some(Box::new(Derived::Unit)) });
+}
+
+#[test]
+fn test_invalid_variant() {
+    let mut buf = Vec::new();
+    3_u32.serialize(&mut Writer::new(&mut buf)).expect("write to Vec should succeed");
+
+    let err = Derived::deserialize(&mut Reader::new(&mut &buf[..], None))
+        .expect_err("variant index 3 is invalid");
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_corrupt_length_prefix() {
+    fn assert_eof<T: Serialize + fmt::Debug>() {
+        let mut buf = Vec::new();
+        u64::MAX.serialize(&mut Writer::new(&mut buf)).expect("write to Vec should succeed");
+        buf.extend_from_slice(b"abc");
+
+        let err = T::deserialize(&mut Reader::new(&mut &buf[..], None))
+            .expect_err("length prefix exceeds the input");
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    assert_eof::<String>();
+    assert_eof::<Vec<u8>>();
+    assert_eof::<Vec<u64>>();
+    assert_eof::<HashMap<u32, String>>();
+    assert_eof::<BTreeMap<u32, u32>>();
+}
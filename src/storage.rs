@@ -1,6 +1,9 @@
 //! A storage is the data structure where components of the same type for all entities are stored.
 
-use crate::entity;
+use std::io;
+use std::marker::PhantomData;
+
+use crate::{entity, serialize};
 
 mod vec;
 pub use vec::VecStorage as Vec;
@@ -8,6 +11,27 @@ pub use vec::VecStorage as Vec;
 mod tree;
 pub use tree::Tree;
 
+mod sparse_set;
+pub use sparse_set::SparseSet;
+
+mod paged;
+pub use paged::Paged;
+
+mod tag;
+pub use tag::{Tag, TagBits};
+
+mod dynamic;
+pub use dynamic::Dynamic;
+
+mod soa;
+pub use soa::Soa;
+
+mod tracked;
+pub use tracked::{Tick, Tracked, TrackedPartition};
+
+mod evented;
+pub use evented::{Event, EventSource, Evented};
+
 pub(crate) mod simple;
 pub(crate) use simple::Simple;
 mod isotope;
@@ -19,7 +43,7 @@ mod tests;
 /// A storage for storing component data.
 pub trait Storage: Access + Default + Send + Sync + 'static {
     /// Gets a shared reference to the component for a specific entity if it is present.
-    fn get(&self, id: Self::RawEntity) -> Option<&Self::Comp>;
+    fn get(&self, id: Self::RawEntity) -> Option<Ref<'_, Self>>;
 
     /// Sets or removes the component for a specific entity,
     /// returning the original value if it was present.
@@ -28,8 +52,56 @@ pub trait Storage: Access + Default + Send + Sync + 'static {
     /// Returns the number of components that exist in this storage.
     fn cardinality(&self) -> usize;
 
+    /// Returns the greatest entity with a component in this storage.
+    ///
+    /// The default implementation scans the whole storage with [`iter`](Self::iter),
+    /// so storages should override this if the entity can be found more efficiently.
+    fn last_entity(&self) -> Option<Self::RawEntity> { self.iter().last().map(|(id, _)| id) }
+
+    /// Sets the [`Tick`] at which subsequent mutable accesses are recorded.
+    ///
+    /// This is called before each exclusive access to the storage.
+    /// Storages that do not [track changes](Tracked) can ignore this.
+    fn set_change_tick(&mut self, _tick: Tick) {}
+
+    /// Moves the [add/remove events](Event) recorded since the previous call into `events`.
+    ///
+    /// Storages that do not [record events](Evented) can ignore this.
+    fn take_events(&mut self, _events: &mut std::vec::Vec<(Self::RawEntity, Event)>) {}
+
+    /// Moves the component of each `(old, new)` entity pair in `moves` from `old` to `new`.
+    /// This is called offline from [`World::rearrange`](crate::World::rearrange).
+    ///
+    /// Components of entities not in `moves` are dropped.
+    /// Moved components are marked as changed at `tick`.
+    ///
+    /// The default implementation moves each component into a new storage with [`set`](Self::set).
+    /// Wrapper storages override this to move their metadata along with the components
+    /// instead of recording the moves as insertions.
+    fn rearrange(&mut self, moves: &[(Self::RawEntity, Self::RawEntity)], tick: Tick) {
+        let mut rearranged = Self::default();
+        rearranged.set_change_tick(tick);
+        for &(old, new) in moves {
+            if let Some(value) = self.set(old, None) {
+                rearranged.set(new, Some(value));
+            }
+        }
+        *self = rearranged;
+    }
+
+    /// Returns the memory allocated by this storage.
+    ///
+    /// Storages that do not report their memory usage can ignore this,
+    /// in which case no memory is reported.
+    fn memory_usage(&self) -> MemoryUsage { MemoryUsage::default() }
+
+    /// Releases memory reserved beyond the greatest entity with a component.
+    ///
+    /// This is called offline from [`World::compact_storages`](crate::World::compact_storages).
+    fn shrink_to_fit(&mut self) {}
+
     /// Return value of [`iter`](Self::iter).
-    type Iter<'t>: Iterator<Item = (Self::RawEntity, &'t Self::Comp)> + 't;
+    type Iter<'t>: Iterator<Item = (Self::RawEntity, Ref<'t, Self>)> + 't;
     /// Returns an immutable iterator over the storage, ordered by entity index order.
     fn iter(&self) -> Self::Iter<'_>;
 
@@ -58,7 +130,12 @@ pub trait Storage: Access + Default + Send + Sync + 'static {
     fn iter_chunks_mut(&mut self) -> Self::IterChunksMut<'_>;
 
     /// Return value of [`as_partition`](Self::as_partition).
-    type Partition<'u>: Partition<'u, RawEntity = Self::RawEntity, Comp = Self::Comp>
+    type Partition<'u>: Partition<
+        'u,
+        RawEntity = Self::RawEntity,
+        Comp = Self::Comp,
+        Layout = Self::Layout,
+    >
     where
         Self: 'u;
     /// Converts the storage to a [`Partition`] that covers the whole storage (similar to `slice[..]`).
@@ -72,7 +149,12 @@ pub trait Storage: Access + Default + Send + Sync + 'static {
 /// and result in dangling references in other partitions that are not `&mut`-locked.
 pub trait Partition<'t>: Access + Send + Sync + Sized + 't {
     /// Return value of [`by_ref`](Self::by_ref).
-    type ByRef<'u>: Partition<'u, RawEntity = Self::RawEntity, Comp = Self::Comp>
+    type ByRef<'u>: Partition<
+        'u,
+        RawEntity = Self::RawEntity,
+        Comp = Self::Comp,
+        Layout = Self::Layout,
+    >
     where
         Self: 'u;
     /// Re-borrows the partition with reduced lifetime.
@@ -100,18 +182,18 @@ pub trait Partition<'t>: Access + Send + Sync + Sized + 't {
     fn split_out(&mut self, entity: Self::RawEntity) -> Self;
 
     /// Return value of [`into_iter_mut`](Self::into_iter_mut).
-    type IntoIterMut: Iterator<Item = (Self::RawEntity, &'t mut Self::Comp)>;
+    type IntoIterMut: Iterator<Item = (Self::RawEntity, Mut<'t, Self>)>;
     /// Same as [`iter_mut`](Access::iter_mut), but moves the partition object into the iterator.
     fn into_iter_mut(self) -> Self::IntoIterMut;
 
     /// Same as [`get_mut`](Access::get_mut), but returns a reference with lifetime `'t`.
-    fn into_mut(self, entity: Self::RawEntity) -> Option<&'t mut Self::Comp>;
+    fn into_mut(self, entity: Self::RawEntity) -> Option<Mut<'t, Self>>;
 
     /// Same as [`get_many_mut`](Access::get_many_mut), but returns a reference with lifetime `'t`.
     fn into_many_mut<const N: usize>(
         self,
         entities: [Self::RawEntity; N],
-    ) -> Option<[&'t mut Self::Comp; N]>;
+    ) -> Option<[Mut<'t, Self>; N]>;
 }
 
 /// Mutable access functions for a storage, generalizing [`Storage`] and [`Partition`].
@@ -120,9 +202,11 @@ pub trait Access {
     type RawEntity: entity::Raw;
     /// The component type stored.
     type Comp: Send + Sync + 'static;
+    /// The references through which the components are accessed.
+    type Layout: Layout<Comp = Self::Comp>;
 
     /// Gets a mutable reference to the component for a specific entity if it is present.
-    fn get_mut(&mut self, entity: Self::RawEntity) -> Option<&mut Self::Comp>;
+    fn get_mut(&mut self, entity: Self::RawEntity) -> Option<Mut<'_, Self>>;
 
     /// Gets mutable references to the components for specific entities if they are present.
     ///
@@ -131,10 +215,10 @@ pub trait Access {
     fn get_many_mut<const N: usize>(
         &mut self,
         entities: [Self::RawEntity; N],
-    ) -> Option<[&mut Self::Comp; N]>;
+    ) -> Option<[Mut<'_, Self>; N]>;
 
     /// Return value of [`iter_mut`](Self::iter_mut).
-    type IterMut<'u>: Iterator<Item = (Self::RawEntity, &'u mut Self::Comp)> + 'u
+    type IterMut<'u>: Iterator<Item = (Self::RawEntity, Mut<'u, Self>)> + 'u
     where
         Self: 'u;
     /// Returns a mutable iterator over the storage, ordered by entity index order.
@@ -149,10 +233,15 @@ pub trait Chunked: Storage + AccessChunked {
     /// Returns `None` if any of the components in the range is missing.
     ///
     /// Panics if `start > end`.
-    fn get_chunk(&self, start: Self::RawEntity, end: Self::RawEntity) -> Option<&[Self::Comp]>;
+    fn get_chunk(&self, start: Self::RawEntity, end: Self::RawEntity) -> Option<Slice<'_, Self>>;
 
     /// Return value of [`as_partition_chunk`](Self::as_partition_chunk).
-    type PartitionChunked<'u>: PartitionChunked<'u, RawEntity = Self::RawEntity, Comp = Self::Comp>;
+    type PartitionChunked<'u>: PartitionChunked<
+        'u,
+        RawEntity = Self::RawEntity,
+        Comp = Self::Comp,
+        Layout = Self::Layout,
+    >;
     /// Converts the storage to a [`PartitionChunked`] that covers the whole storage (similar to `slice[..]`).
     fn as_partition_chunk(&mut self) -> Self::PartitionChunked<'_>;
 }
@@ -168,7 +257,7 @@ pub trait AccessChunked: Access {
         &mut self,
         start: Self::RawEntity,
         end: Self::RawEntity,
-    ) -> Option<&mut [Self::Comp]>;
+    ) -> Option<SliceMut<'_, Self>>;
 }
 
 /// Borrows a slice of a chunked storage, analogously `&'t mut Chunked[..]`.
@@ -183,18 +272,116 @@ pub trait PartitionChunked<'t>: Partition<'t> + AccessChunked {
         self,
         start: Self::RawEntity,
         end: Self::RawEntity,
-    ) -> Option<&'t mut [Self::Comp]>;
+    ) -> Option<SliceMut<'t, Self>>;
 
     /// Return value of [`into_iter_chunks_mut`](Self::into_iter_chunks_mut).
-    type IntoIterChunksMut: Iterator<Item = (Self::RawEntity, &'t mut [Self::Comp])>;
+    type IntoIterChunksMut: Iterator<Item = (Self::RawEntity, SliceMut<'t, Self>)>;
     /// Returns a mutable iterator over the storage, ordered by entity index order.
     fn into_iter_chunks_mut(self) -> Self::IntoIterChunksMut;
 }
 
+/// The references through which a storage exposes its components.
+///
+/// Most storages store each component as a whole and use the [`Whole`] layout,
+/// which exposes components as `&C`, `&mut C`, `&[C]` and `&mut [C]`.
+/// [Struct-of-arrays storages](Soa) store each field in a separate array,
+/// so they expose proxy structs of field references and field slices instead.
+pub trait Layout: 'static {
+    /// The component type.
+    type Comp: Send + Sync + 'static;
+
+    /// A shared reference to a component.
+    type Ref<'t>: Copy + Send + Sync;
+    /// A mutable reference to a component.
+    type Mut<'t>: Send;
+    /// Shared references to the components of consecutive entities.
+    type Slice<'t>: Chunk + IntoIterator<Item = Self::Ref<'t>> + Copy + Send + Sync;
+    /// Mutable references to the components of consecutive entities.
+    type SliceMut<'t>: Chunk + IntoIterator<Item = Self::Mut<'t>> + Send;
+
+    /// Returns the component behind a shared reference if it is stored as a whole.
+    fn as_whole<'t>(comp: Self::Ref<'t>) -> Option<&'t Self::Comp>
+    where
+        Self::Comp: 't;
+
+    /// Returns the component behind a mutable reference if it is stored as a whole.
+    fn as_whole_mut<'t>(comp: Self::Mut<'t>) -> Option<&'t mut Self::Comp>
+    where
+        Self::Comp: 't;
+
+    /// Writes a component to a [world snapshot](crate::serialize)
+    /// in the same format as `vtable`.
+    fn serialize(
+        comp: Self::Ref<'_>,
+        vtable: &serialize::Vtable<Self::Comp>,
+        writer: &mut serialize::Writer<'_>,
+    ) -> io::Result<()>;
+}
+
+/// The [`Layout`] of storages that store each component as a whole.
+pub struct Whole<C>(PhantomData<C>);
+
+impl<C: Send + Sync + 'static> Layout for Whole<C> {
+    type Comp = C;
+
+    type Ref<'t> = &'t C;
+    type Mut<'t> = &'t mut C;
+    type Slice<'t> = &'t [C];
+    type SliceMut<'t> = &'t mut [C];
+
+    fn as_whole<'t>(comp: &'t C) -> Option<&'t C>
+    where
+        C: 't,
+    {
+        Some(comp)
+    }
+
+    fn as_whole_mut<'t>(comp: &'t mut C) -> Option<&'t mut C>
+    where
+        C: 't,
+    {
+        Some(comp)
+    }
+
+    fn serialize(
+        comp: &C,
+        vtable: &serialize::Vtable<C>,
+        writer: &mut serialize::Writer<'_>,
+    ) -> io::Result<()> {
+        (vtable.serialize)(comp, writer)
+    }
+}
+
+/// The components of consecutive entities, such as `&[C]`.
+pub trait Chunk {
+    /// The number of components in the chunk.
+    fn len(&self) -> usize;
+
+    /// Returns true if the chunk has no components.
+    fn is_empty(&self) -> bool { self.len() == 0 }
+}
+
+impl<C> Chunk for &[C] {
+    fn len(&self) -> usize { <[C]>::len(self) }
+}
+
+impl<C> Chunk for &mut [C] {
+    fn len(&self) -> usize { <[C]>::len(self) }
+}
+
+/// A shared reference to a component in the storage `S`.
+pub type Ref<'t, S> = <<S as Access>::Layout as Layout>::Ref<'t>;
+/// A mutable reference to a component in the storage `S`.
+pub type Mut<'t, S> = <<S as Access>::Layout as Layout>::Mut<'t>;
+/// Shared references to the components of consecutive entities in the storage `S`.
+pub type Slice<'t, S> = <<S as Access>::Layout as Layout>::Slice<'t>;
+/// Mutable references to the components of consecutive entities in the storage `S`.
+pub type SliceMut<'t, S> = <<S as Access>::Layout as Layout>::SliceMut<'t>;
+
 /// The iterator item of [`Storage::iter_chunks`].
 pub struct ChunkRef<'t, S: Storage> {
     /// The slice of components in the chunk.
-    pub slice: &'t [S::Comp],
+    pub slice: Slice<'t, S>,
     /// The entity index of `slice[0]`.
     pub start: S::RawEntity,
 }
@@ -202,7 +389,30 @@ pub struct ChunkRef<'t, S: Storage> {
 /// The iterator item of [`Storage::iter_chunks_mut`].
 pub struct ChunkMut<'t, S: Storage> {
     /// The slice of components in the chunk.
-    pub slice: &'t mut [S::Comp],
+    pub slice: SliceMut<'t, S>,
     /// The entity index of `slice[0]`.
     pub start: S::RawEntity,
 }
+
+/// The memory allocated by a storage, returned by [`Storage::memory_usage`].
+///
+/// Byte counts only include the allocations owned by the storage itself,
+/// excluding heap memory owned by the components.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// The number of components that can be stored without reallocation.
+    pub capacity:       usize,
+    /// The number of bytes allocated for component data.
+    pub data_bytes:     usize,
+    /// The number of bytes allocated for recording which entities have the component.
+    pub presence_bytes: usize,
+    /// The number of bytes allocated for other metadata, such as change ticks and event logs.
+    pub metadata_bytes: usize,
+}
+
+impl MemoryUsage {
+    /// The total number of bytes allocated by the storage.
+    pub fn total_bytes(&self) -> usize {
+        self.data_bytes + self.presence_bytes + self.metadata_bytes
+    }
+}
@@ -0,0 +1,149 @@
+use std::marker::PhantomData;
+use std::ops;
+
+use bitvec::prelude::BitVec;
+
+use crate::{comp, entity, storage};
+
+/// The storage for a [dynamic component](comp::dynamic).
+///
+/// Each component is a byte blob of [`size`](Self::size) bytes, indexed by entity IDs directly.
+pub struct Dynamic<RawT: entity::Raw> {
+    descriptor:  comp::dynamic::Descriptor,
+    cardinality: usize,
+    bits:        BitVec,
+    data:        Vec<u8>,
+    _ph:         PhantomData<RawT>,
+}
+
+impl<RawT: entity::Raw> Dynamic<RawT> {
+    pub(crate) fn new(descriptor: comp::dynamic::Descriptor) -> Self {
+        Self { descriptor, cardinality: 0, bits: BitVec::new(), data: Vec::new(), _ph: PhantomData }
+    }
+
+    /// The descriptor of the component stored.
+    pub fn descriptor(&self) -> comp::dynamic::Descriptor { self.descriptor }
+
+    /// The number of bytes in each component.
+    pub fn size(&self) -> usize { self.descriptor.size }
+
+    /// Returns the number of components that exist in this storage.
+    pub fn cardinality(&self) -> usize { self.cardinality }
+
+    /// Returns the memory allocated by this storage.
+    pub fn memory_usage(&self) -> storage::MemoryUsage {
+        storage::MemoryUsage {
+            // zero-sized components take no data, so their capacity is bounded by the bitset
+            capacity:       self
+                .data
+                .capacity()
+                .checked_div(self.size())
+                .unwrap_or(self.bits.capacity()),
+            data_bytes:     self.data.capacity(),
+            presence_bytes: self.bits.capacity() / 8,
+            metadata_bytes: 0,
+        }
+    }
+
+    /// Releases memory reserved beyond the greatest entity with a component.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        let len = self.bits.last_one().map_or(0, |index| index + 1);
+        self.bits.truncate(len);
+        self.bits.shrink_to_fit();
+        self.data.truncate(len * self.size());
+        self.data.shrink_to_fit();
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        match self.bits.get(index) {
+            Some(bit) => *bit,
+            None => false,
+        }
+    }
+
+    fn range(&self, index: usize) -> ops::Range<usize> {
+        let size = self.descriptor.size;
+        index * size..(index + 1) * size
+    }
+
+    /// Gets the bytes of the component for a specific entity if it is present.
+    pub(crate) fn get(&self, id: RawT) -> Option<&[u8]> {
+        let index = id.to_primitive();
+        if !self.bit(index) {
+            return None;
+        }
+        Some(&self.data[self.range(index)])
+    }
+
+    /// Gets the mutable bytes of the component for a specific entity if it is present.
+    pub(crate) fn get_mut(&mut self, id: RawT) -> Option<&mut [u8]> {
+        let index = id.to_primitive();
+        if !self.bit(index) {
+            return None;
+        }
+        let range = self.range(index);
+        Some(&mut self.data[range])
+    }
+
+    /// Sets or removes the component for a specific entity,
+    /// returning whether the component was previously present.
+    ///
+    /// # Panics
+    /// Panics if the length of `value` is not equal to [`size`](Self::size).
+    pub(crate) fn set(&mut self, id: RawT, value: Option<&[u8]>) -> bool {
+        let index = id.to_primitive();
+        let was_present = self.bit(index);
+
+        match value {
+            Some(value) => {
+                assert_eq!(
+                    value.len(),
+                    self.descriptor.size,
+                    "Dynamic component {} expects {} bytes, got {}",
+                    self.descriptor.id,
+                    self.descriptor.size,
+                    value.len(),
+                );
+
+                if self.bits.len() <= index {
+                    self.bits.resize(index + 1, false);
+                }
+                let range = self.range(index);
+                if self.data.len() < range.end {
+                    self.data.resize(range.end, 0);
+                }
+                self.data[range].copy_from_slice(value);
+                self.bits.set(index, true);
+                if !was_present {
+                    self.cardinality += 1;
+                }
+            }
+            None => {
+                if was_present {
+                    self.bits.set(index, false);
+                    self.cardinality -= 1;
+                }
+            }
+        }
+
+        was_present
+    }
+
+    /// Returns an immutable iterator over the storage, ordered by entity index order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (RawT, &[u8])> + '_ {
+        self.bits
+            .iter_ones()
+            .map(move |index| (RawT::from_primitive(index), &self.data[self.range(index)]))
+    }
+
+    /// Moves the component of each `(old, new)` entity pair in `moves` from `old` to `new`.
+    ///
+    /// Components of entities not in `moves` are dropped.
+    pub(crate) fn rearrange(&mut self, moves: &[(RawT, RawT)]) {
+        let mut rearranged = Self::new(self.descriptor);
+        for &(old, new) in moves {
+            rearranged.set(new, self.get(old));
+        }
+        *self = rearranged;
+    }
+}
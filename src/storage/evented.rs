@@ -0,0 +1,150 @@
+use std::mem;
+
+use super::{
+    Access, AccessChunked, ChunkMut, ChunkRef, Chunked, MemoryUsage, Mut, Ref, Slice, SliceMut,
+    Storage, Tick,
+};
+
+/// A change in the presence of a component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// The component was inserted into an entity that did not have it.
+    Added,
+    /// The component was removed from an entity that had it.
+    Removed,
+}
+
+/// Storages that record [add/remove events](Event) for
+/// [`SimpleEvents`](crate::system::SimpleEvents) subscribers.
+pub trait EventSource: Storage {}
+
+/// Wraps a storage to record an [`Event`] whenever a component is inserted or removed.
+///
+/// This is the storage used by components declared with `#[comp(events)]`.
+/// Overwriting an existing component does not generate any events.
+pub struct Evented<S: Storage> {
+    inner: S,
+    log:   Vec<(S::RawEntity, Event)>,
+}
+
+impl<S: Storage> Default for Evented<S> {
+    fn default() -> Self { Self { inner: S::default(), log: Vec::new() } }
+}
+
+impl<S: Storage> Access for Evented<S> {
+    type RawEntity = S::RawEntity;
+    type Comp = S::Comp;
+    type Layout = S::Layout;
+
+    fn get_mut(&mut self, id: Self::RawEntity) -> Option<Mut<'_, Self>> { self.inner.get_mut(id) }
+
+    fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [Self::RawEntity; N],
+    ) -> Option<[Mut<'_, Self>; N]> {
+        self.inner.get_many_mut(entities)
+    }
+
+    type IterMut<'u> = S::IterMut<'u> where Self: 'u;
+    fn iter_mut(&mut self) -> Self::IterMut<'_> { self.inner.iter_mut() }
+}
+
+impl<S: Storage> Storage for Evented<S> {
+    fn get(&self, id: Self::RawEntity) -> Option<Ref<'_, Self>> { self.inner.get(id) }
+
+    fn set(&mut self, id: Self::RawEntity, value: Option<Self::Comp>) -> Option<Self::Comp> {
+        let added = value.is_some();
+        let old = self.inner.set(id, value);
+        match (old.is_some(), added) {
+            (false, true) => self.log.push((id, Event::Added)),
+            (true, false) => self.log.push((id, Event::Removed)),
+            _ => {}
+        }
+        old
+    }
+
+    fn cardinality(&self) -> usize { self.inner.cardinality() }
+
+    fn last_entity(&self) -> Option<Self::RawEntity> { self.inner.last_entity() }
+
+    fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = self.inner.memory_usage();
+        usage.metadata_bytes += self.log.capacity() * mem::size_of::<(S::RawEntity, Event)>();
+        usage
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit();
+        self.log.shrink_to_fit();
+    }
+
+    fn set_change_tick(&mut self, tick: Tick) { self.inner.set_change_tick(tick) }
+
+    fn take_events(&mut self, events: &mut Vec<(Self::RawEntity, Event)>) {
+        events.append(&mut self.log);
+    }
+
+    /// Moves the components without recording any events,
+    /// and moves the undelivered events to the new entity IDs.
+    /// Undelivered events of entities not in `moves` are discarded,
+    /// since their IDs may be reused by the moved entities.
+    fn rearrange(&mut self, moves: &[(Self::RawEntity, Self::RawEntity)], tick: Tick) {
+        self.inner.rearrange(moves, tick);
+
+        let mut sorted_moves = moves.to_vec();
+        sorted_moves.sort_unstable_by_key(|&(old, _)| old);
+        self.log.retain_mut(|(id, _)| {
+            match sorted_moves.binary_search_by_key(id, |&(old, _)| old) {
+                Ok(index) => {
+                    *id = sorted_moves[index].1;
+                    true
+                }
+                Err(_) => false,
+            }
+        });
+    }
+
+    type Iter<'t> = S::Iter<'t>;
+    fn iter(&self) -> Self::Iter<'_> { self.inner.iter() }
+
+    type IterChunks<'t> = impl Iterator<Item = ChunkRef<'t, Self>> + 't;
+    fn iter_chunks(&self) -> Self::IterChunks<'_> {
+        self.inner.iter_chunks().map(|chunk| ChunkRef { slice: chunk.slice, start: chunk.start })
+    }
+
+    type IterChunksMut<'t> = impl Iterator<Item = ChunkMut<'t, Self>> + 't;
+    fn iter_chunks_mut(&mut self) -> Self::IterChunksMut<'_> {
+        self.inner
+            .iter_chunks_mut()
+            .map(|chunk| ChunkMut { slice: chunk.slice, start: chunk.start })
+    }
+
+    type Partition<'u> = S::Partition<'u>;
+    fn as_partition(&mut self) -> Self::Partition<'_> { self.inner.as_partition() }
+}
+
+impl<S: Storage> EventSource for Evented<S> {}
+
+impl<S: Chunked> AccessChunked for Evented<S> {
+    fn get_chunk_mut(
+        &mut self,
+        start: Self::RawEntity,
+        end: Self::RawEntity,
+    ) -> Option<SliceMut<'_, Self>> {
+        self.inner.get_chunk_mut(start, end)
+    }
+}
+
+impl<S: Chunked> Chunked for Evented<S> {
+    fn get_chunk(&self, start: Self::RawEntity, end: Self::RawEntity) -> Option<Slice<'_, Self>> {
+        self.inner.get_chunk(start, end)
+    }
+
+    type PartitionChunked<'u> = S::PartitionChunked<'u>;
+    fn as_partition_chunk(&mut self) -> Self::PartitionChunked<'_> {
+        self.inner.as_partition_chunk()
+    }
+}
+
+#[cfg(test)]
+super::tests::test_storage!(CHUNKED Evented<super::Vec<std::num::NonZeroU32, i64>>);
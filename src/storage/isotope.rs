@@ -1,14 +1,15 @@
 use std::any::{self, Any};
 use std::collections::HashMap;
-use std::ops;
 use std::sync::Arc;
+use std::{io, iter, ops};
 
 use parking_lot::lock_api::ArcRwLockWriteGuard;
 use parking_lot::{Mutex, RwLock};
 
 use super::Storage;
-use crate::entity::{self, referrer, Ealloc};
-use crate::{comp, storage, Archetype};
+use crate::comp::Discrim as _;
+use crate::entity::{self, ealloc, referrer, Ealloc};
+use crate::{comp, serialize, storage, Archetype};
 
 pub(crate) struct MapInner<A: Archetype, C: comp::Isotope<A>> {
     map: HashMap<C::Discrim, Arc<RwLock<C::Storage>>>,
@@ -121,7 +122,38 @@ pub(crate) trait AnyMap<A: Archetype>: Send + Sync {
         ealloc: &mut A::Ealloc,
     );
 
+    /// Moves the component data of each `(old, new)` entity pair from `old` to `new`
+    /// for all discriminants.
+    fn rearrange(&mut self, moves: &[(A::RawEntity, A::RawEntity)], tick: storage::Tick);
+
+    /// Returns the discriminant, cardinality and memory usage of each storage,
+    /// sorted by discriminant.
+    fn memory_usage(&self) -> Vec<(usize, usize, storage::MemoryUsage)>;
+
+    /// Releases memory reserved beyond the greatest entity with a component
+    /// for all discriminants.
+    fn shrink_to_fit(&mut self);
+
     fn referrer_dyn<'t>(&'t mut self) -> Box<dyn referrer::Object + 't>;
+
+    /// Returns the snapshot key of the component type
+    /// if it is [serializable](comp::SimpleOrIsotope::SERIALIZER).
+    fn serialize_key(&self) -> Option<&'static str>;
+
+    /// Writes the components of all discriminants to a snapshot.
+    ///
+    /// This method should only be called if [`serialize_key`](Self::serialize_key) is `Some`.
+    fn serialize(&mut self, writer: &mut serialize::Writer<'_>) -> io::Result<()>;
+
+    /// Reads the components of all discriminants from a snapshot.
+    /// Components of entities not in `allocated` are rejected as invalid data.
+    ///
+    /// This method should only be called if [`serialize_key`](Self::serialize_key) is `Some`.
+    fn deserialize(
+        &mut self,
+        reader: &mut serialize::Reader<'_>,
+        allocated: &ealloc::Snapshot<A::RawEntity>,
+    ) -> io::Result<()>;
 }
 
 impl<A: Archetype, C: comp::Isotope<A>> AnyMap<A> for Map<A, C> {
@@ -165,6 +197,36 @@ impl<A: Archetype, C: comp::Isotope<A>> AnyMap<A> for Map<A, C> {
         }
     }
 
+    fn rearrange(&mut self, moves: &[(A::RawEntity, A::RawEntity)], tick: storage::Tick) {
+        for (_discrim, storage) in self.map.get_mut().iter_mut() {
+            let storage: &mut C::Storage =
+                Arc::get_mut(storage).expect("storage arc was leaked").get_mut();
+            storage.rearrange(moves, tick);
+        }
+    }
+
+    fn memory_usage(&self) -> Vec<(usize, usize, storage::MemoryUsage)> {
+        let map = self.map.lock();
+        let mut usages: Vec<_> = map
+            .map()
+            .iter()
+            .map(|(discrim, storage)| {
+                let storage = storage.read();
+                (discrim.into_usize(), storage.cardinality(), storage.memory_usage())
+            })
+            .collect();
+        usages.sort_by_key(|&(discrim, _, _)| discrim);
+        usages
+    }
+
+    fn shrink_to_fit(&mut self) {
+        for (_discrim, storage) in self.map.get_mut().iter_mut() {
+            let storage: &mut C::Storage =
+                Arc::get_mut(storage).expect("storage arc was leaked").get_mut();
+            storage.shrink_to_fit();
+        }
+    }
+
     fn referrer_dyn<'t>(&'t mut self) -> Box<dyn referrer::Object + 't> {
         Box::new(referrer::NamedIter(self.map.get_mut().iter_mut().map(|(discrim, value)| {
             let storage: &mut C::Storage =
@@ -175,10 +237,54 @@ impl<A: Archetype, C: comp::Isotope<A>> AnyMap<A> for Map<A, C> {
                     any::type_name::<A>(),
                     any::type_name::<C>()
                 )),
-                referrer::UnnamedIter(storage.iter_chunks_mut().flat_map(|chunk| chunk.slice)),
+                referrer::UnnamedIter(
+                    storage
+                        .iter_chunks_mut()
+                        .flat_map(|chunk| chunk.slice)
+                        .filter_map(<comp::Layout<A, C> as storage::Layout>::as_whole_mut),
+                ),
             )
         })))
     }
+
+    fn serialize_key(&self) -> Option<&'static str> {
+        C::SERIALIZER.as_ref().map(|vtable| vtable.key)
+    }
+
+    fn serialize(&mut self, writer: &mut serialize::Writer<'_>) -> io::Result<()> {
+        let vtable = C::SERIALIZER.expect("serialize() called on unserializable component");
+
+        let mut storages: Vec<_> = self.map.get_mut().iter_mut().collect();
+        storages.sort_by_key(|&(discrim, _)| discrim.into_usize());
+
+        serialize::write_len(writer, storages.len())?;
+        for (discrim, storage) in storages {
+            serialize::write_len(writer, discrim.into_usize())?;
+            let storage: &C::Storage =
+                Arc::get_mut(storage).expect("storage arc was leaked").get_mut();
+            serialize::write_storage(writer, storage, &vtable)?;
+        }
+        Ok(())
+    }
+
+    fn deserialize(
+        &mut self,
+        reader: &mut serialize::Reader<'_>,
+        allocated: &ealloc::Snapshot<A::RawEntity>,
+    ) -> io::Result<()> {
+        let vtable = C::SERIALIZER.expect("deserialize() called on unserializable component");
+
+        let map = self.map.get_mut();
+        let count = serialize::read_len(reader)?;
+        for _ in 0..count {
+            let discrim = C::Discrim::from_usize(serialize::read_len(reader)?);
+            // Do not auto-initialize the new storage, since all components are loaded from the snapshot.
+            let storage = map.get_or_create(discrim, iter::empty());
+            let storage = Arc::get_mut(storage).expect("storage arc was leaked").get_mut();
+            serialize::read_storage(reader, storage, &vtable, allocated)?;
+        }
+        Ok(())
+    }
 }
 
 impl<A: Archetype> dyn AnyMap<A> {
@@ -0,0 +1,471 @@
+use std::cell::SyncUnsafeCell;
+use std::marker::PhantomData;
+use std::mem::{self, MaybeUninit};
+use std::ops::Range;
+use std::slice;
+
+use bitvec::prelude::BitVec;
+
+use super::vec::{
+    new_iter_chunks_mut, new_iter_chunks_ref, slice_assume_init_mut, slice_assume_init_ref,
+};
+use super::{
+    Access, AccessChunked, ChunkMut, ChunkRef, Chunked, MemoryUsage, Partition, PartitionChunked,
+    Storage, Whole,
+};
+use crate::entity;
+
+/// A storage indexed by entity IDs directly, allocated in pages of `PAGE_SIZE` entities.
+///
+/// Unlike [`Vec`](super::Vec), growing the storage never moves existing components.
+/// Each page is allocated when its first component is inserted
+/// and freed when its last component is removed.
+///
+/// Chunks never cross page boundaries,
+/// so [`get_chunk`](Chunked::get_chunk) and related methods return `None`
+/// if the requested range spans multiple pages.
+pub struct Paged<RawT: entity::Raw, C, const PAGE_SIZE: usize = 1024> {
+    cardinality: usize,
+    pages:       Vec<Option<Page<C>>>,
+    _ph:         PhantomData<RawT>,
+}
+
+struct Page<C> {
+    cardinality: usize,
+    bits:        BitVec,
+    // `SyncUnsafeCell<C>` here must be treated as a normal `C`
+    // unless the whole storage is mutably locked,
+    // which means the current function exclusively manages this page.
+    // `&Paged` must not be used to access the cells mutably.
+    data:        Box<[SyncUnsafeCell<MaybeUninit<C>>]>,
+}
+
+impl<C> Page<C> {
+    fn new(size: usize) -> Self {
+        Self {
+            cardinality: 0,
+            bits:        BitVec::repeat(false, size),
+            data:        (0..size).map(|_| SyncUnsafeCell::new(MaybeUninit::uninit())).collect(),
+        }
+    }
+
+    fn get(&self, index: usize) -> Option<&C> {
+        if !self.bits[index] {
+            return None;
+        }
+
+        // Safety: the bit is set, and `&self` implies no partitions are accessing the cell.
+        Some(unsafe { (*self.data[index].get()).assume_init_ref() })
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut C> {
+        if !self.bits[index] {
+            return None;
+        }
+
+        Some(unsafe { self.data[index].get_mut().assume_init_mut() })
+    }
+
+    fn replace(&mut self, index: usize, new: Option<C>) -> Option<C> {
+        let cell = self.data[index].get_mut();
+
+        let old = if self.bits[index] {
+            self.cardinality -= 1;
+            Some(unsafe { cell.assume_init_read() })
+        } else {
+            None
+        };
+
+        // the original value was already moved out, now we can overwrite the data or unmark it
+
+        self.bits.set(index, new.is_some());
+        if let Some(new) = new {
+            self.cardinality += 1;
+            *cell = MaybeUninit::new(new);
+        }
+
+        old
+    }
+}
+
+impl<C> Drop for Page<C> {
+    fn drop(&mut self) {
+        for index in self.bits.iter_ones() {
+            unsafe { self.data[index].get_mut().assume_init_drop() }
+        }
+    }
+}
+
+/// Reinterprets a slice of cells as a mutable slice.
+///
+/// # Safety
+/// The caller must ensure that the cells are not accessed elsewhere while the returned slice is alive.
+#[allow(clippy::mut_from_ref)] // interior mutability through `SyncUnsafeCell`
+unsafe fn cells_as_mut<T>(cells: &[SyncUnsafeCell<T>]) -> &mut [T] {
+    slice::from_raw_parts_mut(cells.as_ptr().cast::<T>().cast_mut(), cells.len())
+}
+
+/// Reinterprets a slice of cells as a shared slice.
+///
+/// # Safety
+/// The caller must ensure that the cells are not mutated while the returned slice is alive.
+unsafe fn cells_as_ref<T>(cells: &[SyncUnsafeCell<T>]) -> &[T] {
+    slice::from_raw_parts(cells.as_ptr().cast::<T>(), cells.len())
+}
+
+/// Returns the page index and the index within the page for an entity.
+fn locate<RawT: entity::Raw, const PAGE_SIZE: usize>(entity: RawT) -> (usize, usize) {
+    let index = entity.to_primitive();
+    (index / PAGE_SIZE, index % PAGE_SIZE)
+}
+
+/// Returns the cells for `start..end` if they are in the same page and all initialized.
+fn get_chunk_cells<RawT: entity::Raw, C, const PAGE_SIZE: usize>(
+    pages: &[Option<Page<C>>],
+    start: RawT,
+    end: RawT,
+) -> Option<&[SyncUnsafeCell<MaybeUninit<C>>]> {
+    let (page_index, page_start) = locate::<RawT, PAGE_SIZE>(start);
+    let page_end = end.to_primitive().checked_sub(page_index * PAGE_SIZE)?;
+    if page_end > PAGE_SIZE {
+        return None;
+    }
+
+    let page = pages.get(page_index)?.as_ref()?;
+    let range = page_start..page_end;
+    if !page.bits.get(range.clone())?.all() {
+        return None;
+    }
+    page.data.get(range)
+}
+
+impl<RawT: entity::Raw, C, const PAGE_SIZE: usize> Default for Paged<RawT, C, PAGE_SIZE> {
+    fn default() -> Self { Self { cardinality: 0, pages: Vec::new(), _ph: PhantomData } }
+}
+
+impl<RawT: entity::Raw, C: Send + Sync + 'static, const PAGE_SIZE: usize> Access
+    for Paged<RawT, C, PAGE_SIZE>
+{
+    type RawEntity = RawT;
+    type Comp = C;
+    type Layout = Whole<C>;
+
+    fn get_mut(&mut self, id: RawT) -> Option<&mut C> {
+        let (page_index, index) = locate::<RawT, PAGE_SIZE>(id);
+        self.pages.get_mut(page_index)?.as_mut()?.get_mut(index)
+    }
+
+    fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [RawT; N],
+    ) -> Option<[&mut Self::Comp; N]> {
+        self.as_partition().into_many_mut(entities)
+    }
+
+    type IterMut<'t> = impl Iterator<Item = (RawT, &'t mut C)> + 't;
+    fn iter_mut(&mut self) -> Self::IterMut<'_> { self.as_partition().into_iter_mut() }
+}
+
+impl<RawT: entity::Raw, C: Send + Sync + 'static, const PAGE_SIZE: usize> Storage
+    for Paged<RawT, C, PAGE_SIZE>
+{
+    fn get(&self, id: RawT) -> Option<&C> {
+        let (page_index, index) = locate::<RawT, PAGE_SIZE>(id);
+        self.pages.get(page_index)?.as_ref()?.get(index)
+    }
+
+    fn set(&mut self, id: RawT, new: Option<C>) -> Option<C> {
+        let (page_index, index) = locate::<RawT, PAGE_SIZE>(id);
+
+        match new {
+            Some(new) => {
+                if self.pages.len() <= page_index {
+                    self.pages.resize_with(page_index + 1, || None);
+                }
+                let page = self.pages[page_index].get_or_insert_with(|| Page::new(PAGE_SIZE));
+                let old = page.replace(index, Some(new));
+                if old.is_none() {
+                    self.cardinality += 1;
+                }
+                old
+            }
+            None => {
+                let page = self.pages.get_mut(page_index)?.as_mut()?;
+                let old = page.replace(index, None)?;
+                self.cardinality -= 1;
+
+                if page.cardinality == 0 {
+                    self.pages[page_index] = None;
+                    while let Some(None) = self.pages.last() {
+                        self.pages.pop();
+                    }
+                }
+                Some(old)
+            }
+        }
+    }
+
+    fn cardinality(&self) -> usize { self.cardinality }
+
+    fn last_entity(&self) -> Option<Self::RawEntity> {
+        self.pages.iter().enumerate().rev().find_map(|(page_index, page)| {
+            let index = page.as_ref()?.bits.last_one()?;
+            Some(RawT::from_primitive(page_index * PAGE_SIZE + index))
+        })
+    }
+
+    fn memory_usage(&self) -> MemoryUsage {
+        let pages = self.pages.iter().flatten().count();
+        MemoryUsage {
+            capacity:       pages * PAGE_SIZE,
+            data_bytes:     pages * PAGE_SIZE * mem::size_of::<C>(),
+            presence_bytes: self.pages.iter().flatten().map(|page| page.bits.capacity() / 8).sum(),
+            metadata_bytes: self.pages.capacity() * mem::size_of::<Option<Page<C>>>(),
+        }
+    }
+
+    // empty pages are already freed in `set`, so only the page table needs to be shrunk
+    fn shrink_to_fit(&mut self) { self.pages.shrink_to_fit(); }
+
+    type Iter<'t> = impl Iterator<Item = (RawT, &'t C)> + 't;
+    fn iter(&self) -> Self::Iter<'_> {
+        self.pages
+            .iter()
+            .enumerate()
+            .filter_map(|(page_index, page)| Some((page_index, page.as_ref()?)))
+            .flat_map(|(page_index, page)| {
+                page.bits.iter_ones().map(move |index| {
+                    let entity = RawT::from_primitive(page_index * PAGE_SIZE + index);
+                    (entity, page.get(index).expect("bits mismatch"))
+                })
+            })
+    }
+
+    type IterChunks<'t> = impl Iterator<Item = ChunkRef<'t, Self>> + 't;
+    fn iter_chunks(&self) -> Self::IterChunks<'_> {
+        self.pages
+            .iter()
+            .enumerate()
+            .filter_map(|(page_index, page)| Some((page_index, page.as_ref()?)))
+            .flat_map(|(page_index, page)| {
+                // Safety: `&self` implies no partitions are accessing the cells.
+                let data = unsafe { cells_as_ref(&page.data) };
+                new_iter_chunks_ref(&page.bits, data).map(move |(start, chunk)| ChunkRef {
+                    slice: unsafe { slice_assume_init_ref(chunk) },
+                    start: RawT::from_primitive(page_index * PAGE_SIZE + start),
+                })
+            })
+    }
+
+    type IterChunksMut<'t> = impl Iterator<Item = ChunkMut<'t, Self>> + 't;
+    fn iter_chunks_mut(&mut self) -> Self::IterChunksMut<'_> {
+        self.as_partition_chunk()
+            .into_iter_chunks_mut()
+            .map(|(start, slice)| ChunkMut { slice, start })
+    }
+
+    type Partition<'t> = StoragePartition<'t, RawT, C, PAGE_SIZE>;
+    fn as_partition(&mut self) -> Self::Partition<'_> { self.as_partition_chunk() }
+}
+
+/// Return value of [`Paged::as_partition`].
+pub struct StoragePartition<'t, RawT: entity::Raw, C, const PAGE_SIZE: usize> {
+    pages:       &'t [Option<Page<C>>],
+    lower_bound: Option<RawT>,
+    upper_bound: Option<RawT>,
+}
+
+impl<'t, RawT: entity::Raw, C, const PAGE_SIZE: usize> StoragePartition<'t, RawT, C, PAGE_SIZE> {
+    fn assert_bounds(&self, entity: RawT) {
+        if let Some(bound) = self.lower_bound {
+            assert!(entity >= bound, "Entity {entity:?} is not in the partition {bound:?}..");
+        }
+        if let Some(bound) = self.upper_bound {
+            assert!(entity < bound, "Entity {entity:?} is not in the partition ..{bound:?}");
+        }
+    }
+
+    /// Yields each allocated page overlapping with the partition,
+    /// along with the range of indices within the page that belong to the partition.
+    fn iter_pages(self) -> impl Iterator<Item = (usize, &'t Page<C>, Range<usize>)> {
+        let start = self.lower_bound.map_or(0, |bound| bound.to_primitive());
+        let end = self.upper_bound.map_or(usize::MAX, |bound| bound.to_primitive());
+
+        self.pages.iter().enumerate().filter_map(move |(page_index, page)| {
+            let page = page.as_ref()?;
+            let page_start = page_index * PAGE_SIZE;
+            let range = start.saturating_sub(page_start).min(PAGE_SIZE)
+                ..end.saturating_sub(page_start).min(PAGE_SIZE);
+            (!range.is_empty()).then_some((page_index, page, range))
+        })
+    }
+}
+
+impl<'t, RawT: entity::Raw, C: Send + Sync + 'static, const PAGE_SIZE: usize> Access
+    for StoragePartition<'t, RawT, C, PAGE_SIZE>
+{
+    type RawEntity = RawT;
+    type Comp = C;
+    type Layout = Whole<C>;
+
+    fn get_mut(&mut self, entity: RawT) -> Option<&mut C> { self.by_ref().into_mut(entity) }
+
+    fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [RawT; N],
+    ) -> Option<[&mut Self::Comp; N]> {
+        self.by_ref().into_many_mut(entities)
+    }
+
+    type IterMut<'u> = impl Iterator<Item = (RawT, &'u mut C)> + 'u where Self: 'u;
+    fn iter_mut(&mut self) -> Self::IterMut<'_> { self.by_ref().into_iter_mut() }
+}
+
+impl<'t, RawT: entity::Raw, C: Send + Sync + 'static, const PAGE_SIZE: usize> Partition<'t>
+    for StoragePartition<'t, RawT, C, PAGE_SIZE>
+{
+    type ByRef<'u> = StoragePartition<'u, RawT, C, PAGE_SIZE> where Self: 'u;
+    fn by_ref(&mut self) -> Self::ByRef<'_> {
+        StoragePartition {
+            pages:       self.pages,
+            lower_bound: self.lower_bound,
+            upper_bound: self.upper_bound,
+        }
+    }
+
+    type IntoIterMut = impl Iterator<Item = (RawT, &'t mut C)>;
+    fn into_iter_mut(self) -> Self::IntoIterMut {
+        self.iter_pages().flat_map(|(page_index, page, range)| {
+            page.bits[range.clone()].iter_ones().map(move |index| {
+                let index = range.start + index;
+                let entity = RawT::from_primitive(page_index * PAGE_SIZE + index);
+                // Safety: StoragePartition locks all entities within its bounds exclusively,
+                // and `index` is within the bounds due to `iter_pages`.
+                // We already have `self` by value, so no other threads are accessing this range.
+                let value = unsafe { (*page.data[index].get()).assume_init_mut() };
+                (entity, value)
+            })
+        })
+    }
+
+    fn into_mut(self, entity: RawT) -> Option<&'t mut C> {
+        self.assert_bounds(entity);
+
+        let (page_index, index) = locate::<RawT, PAGE_SIZE>(entity);
+        let page = self.pages.get(page_index)?.as_ref()?;
+        if !page.bits[index] {
+            return None;
+        }
+
+        // Safety: StoragePartition locks all entities within its bounds exclusively,
+        // and `entity` is within the bounds.
+        Some(unsafe { (*page.data[index].get()).assume_init_mut() })
+    }
+
+    fn into_many_mut<const N: usize>(
+        self,
+        entities: [Self::RawEntity; N],
+    ) -> Option<[&'t mut Self::Comp; N]> {
+        for entity in entities {
+            self.assert_bounds(entity);
+        }
+
+        if !entities.iter().enumerate().all(|(i, entity)| !entities[..i].contains(entity)) {
+            return None;
+        }
+
+        let ptrs = entities.try_map(|entity| {
+            let (page_index, index) = locate::<RawT, PAGE_SIZE>(entity);
+            let page = self.pages.get(page_index)?.as_ref()?;
+            page.bits[index].then(|| page.data[index].get())
+        })?;
+
+        Some(ptrs.map(|ptr| {
+            // Safety: all entities are distinct and within the bounds locked by this partition,
+            // and all bits have been checked to be initialized.
+            unsafe { (*ptr).assume_init_mut() }
+        }))
+    }
+
+    fn split_out(&mut self, entity: RawT) -> Self {
+        self.assert_bounds(entity);
+
+        let right = Self {
+            pages:       self.pages,
+            lower_bound: Some(entity),
+            upper_bound: self.upper_bound,
+        };
+        self.upper_bound = Some(entity);
+
+        // Safety: `entity` is between lower_bound and upper_bound,
+        // so the resultant bound will be non-overlapping.
+        right
+    }
+}
+
+impl<RawT: entity::Raw, C: Send + Sync + 'static, const PAGE_SIZE: usize> AccessChunked
+    for Paged<RawT, C, PAGE_SIZE>
+{
+    fn get_chunk_mut(&mut self, start: RawT, end: RawT) -> Option<&mut [C]> {
+        self.as_partition_chunk().into_chunk_mut(start, end)
+    }
+}
+
+impl<RawT: entity::Raw, C: Send + Sync + 'static, const PAGE_SIZE: usize> Chunked
+    for Paged<RawT, C, PAGE_SIZE>
+{
+    fn get_chunk(&self, start: RawT, end: RawT) -> Option<&[C]> {
+        let cells = get_chunk_cells::<RawT, C, PAGE_SIZE>(&self.pages, start, end)?;
+        // Safety: `&self` implies no partitions are accessing the cells,
+        // and all cells have been checked to be initialized.
+        Some(unsafe { slice_assume_init_ref(cells_as_ref(cells)) })
+    }
+
+    type PartitionChunked<'u> = Self::Partition<'u>;
+    fn as_partition_chunk(&mut self) -> Self::PartitionChunked<'_> {
+        StoragePartition { pages: &self.pages, lower_bound: None, upper_bound: None }
+    }
+}
+
+impl<'t, RawT: entity::Raw, C: Send + Sync + 'static, const PAGE_SIZE: usize> AccessChunked
+    for StoragePartition<'t, RawT, C, PAGE_SIZE>
+{
+    fn get_chunk_mut(&mut self, start: RawT, end: RawT) -> Option<&mut [C]> {
+        self.by_ref().into_chunk_mut(start, end)
+    }
+}
+
+impl<'t, RawT: entity::Raw, C: Send + Sync + 'static, const PAGE_SIZE: usize> PartitionChunked<'t>
+    for StoragePartition<'t, RawT, C, PAGE_SIZE>
+{
+    fn into_chunk_mut(self, start: RawT, end: RawT) -> Option<&'t mut [C]> {
+        self.assert_bounds(start);
+        if let Some(bound) = self.upper_bound {
+            assert!(end <= bound, "Entity {end:?} is beyond the partition ..{bound:?}");
+        }
+
+        let cells = get_chunk_cells::<RawT, C, PAGE_SIZE>(self.pages, start, end)?;
+        // Safety: StoragePartition locks all entities within its bounds exclusively,
+        // and all cells have been checked to be initialized.
+        Some(unsafe { slice_assume_init_mut(cells_as_mut(cells)) })
+    }
+
+    type IntoIterChunksMut = impl Iterator<Item = (RawT, &'t mut [C])>;
+    fn into_iter_chunks_mut(self) -> Self::IntoIterChunksMut {
+        self.iter_pages().flat_map(|(page_index, page, range)| {
+            // Safety: StoragePartition locks all entities within its bounds exclusively,
+            // and `range` is within the bounds due to `iter_pages`.
+            let data = unsafe { cells_as_mut(&page.data[range.clone()]) };
+            new_iter_chunks_mut(&page.bits[range.clone()], data).map(move |(start, chunk)| {
+                let entity = RawT::from_primitive(page_index * PAGE_SIZE + range.start + start);
+                (entity, unsafe { slice_assume_init_mut(chunk) })
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+super::tests::test_storage!(CHUNKED Paged<std::num::NonZeroU32, i64>);
+
+#[cfg(test)]
+mod tests;
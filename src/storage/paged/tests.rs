@@ -0,0 +1,91 @@
+//! Tests page-specific behavior of the Paged storage.
+
+use std::num::NonZeroU32;
+use std::sync::Arc;
+
+use super::Paged;
+use crate::storage::{Chunked, Partition, PartitionChunked, Storage};
+
+type TestStorage = Paged<NonZeroU32, i64, 4>;
+
+fn entity(id: u32) -> NonZeroU32 { NonZeroU32::new(id).unwrap() }
+
+fn setup_storage() -> TestStorage {
+    let mut storage = TestStorage::default();
+    for i in 1..=10 {
+        storage.set(entity(i), Some(i64::from(i)));
+    }
+    storage
+}
+
+#[test]
+fn test_chunks_clipped_at_pages() {
+    let mut storage = setup_storage();
+
+    let chunks: Vec<_> =
+        storage.iter_chunks().map(|chunk| (chunk.start.get(), chunk.slice.to_vec())).collect();
+    assert_eq!(chunks, vec![(1, vec![1, 2, 3]), (4, vec![4, 5, 6, 7]), (8, vec![8, 9, 10])]);
+
+    let chunks: Vec<_> =
+        storage.iter_chunks_mut().map(|chunk| (chunk.start.get(), chunk.slice.to_vec())).collect();
+    assert_eq!(chunks, vec![(1, vec![1, 2, 3]), (4, vec![4, 5, 6, 7]), (8, vec![8, 9, 10])]);
+
+    assert_eq!(storage.get_chunk(entity(4), entity(8)), Some(&[4, 5, 6, 7][..]));
+    assert_eq!(storage.get_chunk(entity(3), entity(5)), None);
+}
+
+#[test]
+fn test_partition_chunks_clipped_at_bounds() {
+    let mut storage = setup_storage();
+    let (left, right) = storage.as_partition_chunk().split_at(entity(6));
+
+    let chunks: Vec<_> =
+        left.into_iter_chunks_mut().map(|(start, slice)| (start.get(), slice.to_vec())).collect();
+    assert_eq!(chunks, vec![(1, vec![1, 2, 3]), (4, vec![4, 5])]);
+
+    let chunks: Vec<_> =
+        right.into_iter_chunks_mut().map(|(start, slice)| (start.get(), slice.to_vec())).collect();
+    assert_eq!(chunks, vec![(6, vec![6, 7]), (8, vec![8, 9, 10])]);
+}
+
+#[test]
+#[should_panic = "Entity 7 is beyond the partition ..6"]
+fn test_partition_chunk_beyond_bounds() {
+    let mut storage = setup_storage();
+    let (left, _) = storage.as_partition_chunk().split_at(entity(6));
+    left.into_chunk_mut(entity(4), entity(7));
+}
+
+#[test]
+fn test_free_empty_pages() {
+    let mut storage = setup_storage();
+
+    for i in 4..8 {
+        storage.set(entity(i), None);
+    }
+    assert_eq!(storage.pages.len(), 3);
+    assert!(storage.pages[1].is_none());
+
+    for i in 8..=10 {
+        storage.set(entity(i), None);
+    }
+    assert_eq!(storage.pages.len(), 1);
+    assert_eq!(storage.cardinality(), 3);
+}
+
+#[test]
+fn test_drop_components() {
+    let value = Arc::new(());
+
+    let mut storage = Paged::<NonZeroU32, Arc<()>, 4>::default();
+    for i in 1..=10 {
+        storage.set(entity(i), Some(Arc::clone(&value)));
+    }
+    assert_eq!(Arc::strong_count(&value), 11);
+
+    storage.set(entity(5), None);
+    assert_eq!(Arc::strong_count(&value), 10);
+
+    drop(storage);
+    assert_eq!(Arc::strong_count(&value), 1);
+}
@@ -1,4 +1,5 @@
 use std::any::{self, Any};
+use std::io;
 #[cfg(test)]
 use std::ops;
 use std::sync::Arc;
@@ -7,8 +8,8 @@ use parking_lot::RwLock;
 
 use super::Storage;
 use crate::comp::any::DepGetter;
-use crate::entity::referrer;
-use crate::{comp, Archetype};
+use crate::entity::{self, ealloc, referrer};
+use crate::{comp, serialize, storage, Archetype};
 
 /// Constructor for [`Simple`].
 pub(crate) fn builder<A: Archetype, C: comp::Simple<A>>() -> Box<dyn Any> {
@@ -21,6 +22,9 @@ pub(crate) struct Simple<A: Archetype> {
     pub(crate) dep_list: comp::DepList,
     /// The actual storage object. Downcasts to `C::Storage`.
     pub(crate) storage:  Arc<RwLock<dyn AnySimpleStorage<A>>>,
+    /// The add/remove events delivered to the current cycle,
+    /// or `None` if there are no subscribers.
+    pub(crate) events:   Option<Vec<(A::RawEntity, storage::Event)>>,
 }
 
 impl<A: Archetype> Simple<A> {
@@ -29,6 +33,20 @@ impl<A: Archetype> Simple<A> {
             dep_list: C::INIT_STRATEGY.checked_deps(),
             storage:  Arc::new(RwLock::new(SimpleStorage::<A, C>(C::Storage::default())))
                 as Arc<RwLock<dyn AnySimpleStorage<A>>>,
+            events:   None,
+        }
+    }
+
+    /// Replaces the delivered events with those recorded since the previous delivery.
+    /// This function should only be called offline.
+    pub(crate) fn deliver_events(&mut self) {
+        let storage = Arc::get_mut(&mut self.storage).expect("storage arc was leaked").get_mut();
+        match &mut self.events {
+            Some(events) => {
+                events.clear();
+                storage.take_events(events);
+            }
+            None => storage.take_events(&mut Vec::new()),
         }
     }
 
@@ -94,6 +112,21 @@ pub(crate) trait AnySimpleStorage<A: Archetype>: Send + Sync {
     /// Clears the component data for an entity if any.
     fn clear_entry(&mut self, entity: A::RawEntity);
 
+    /// Sets the tick at which subsequent mutable accesses are recorded.
+    fn set_change_tick(&mut self, tick: storage::Tick);
+
+    /// Moves the add/remove events recorded since the previous call into `events`.
+    fn take_events(&mut self, events: &mut Vec<(A::RawEntity, storage::Event)>);
+
+    /// Returns the cardinality and the memory usage of the storage.
+    fn memory_usage(&self) -> (usize, storage::MemoryUsage);
+
+    /// Releases memory reserved beyond the greatest entity with a component.
+    fn shrink_to_fit(&mut self);
+
+    /// Moves the component data of each `(old, new)` entity pair from `old` to `new`.
+    fn rearrange(&mut self, moves: &[(A::RawEntity, A::RawEntity)], tick: storage::Tick);
+
     /// Returns a [`referrer::Object`] implementation that visits all components in this storage.
     fn referrer_dyn<'t>(&'t mut self) -> Box<dyn referrer::Object + 't>;
 
@@ -102,6 +135,40 @@ pub(crate) trait AnySimpleStorage<A: Archetype>: Send + Sync {
     /// Due to the poor performance of [`Any`],
     /// this method should not be used unless component type elision is necessary.
     fn get_any(&self, entity: A::RawEntity) -> Option<&dyn Any>;
+
+    /// Returns the snapshot key of the component type
+    /// if it is [serializable](comp::SimpleOrIsotope::SERIALIZER).
+    fn serialize_key(&self) -> Option<&'static str>;
+
+    /// Returns true if the component is [required](comp::Presence::Required)
+    /// but not [serializable](comp::SimpleOrIsotope::SERIALIZER).
+    fn is_required_unserializable(&self) -> bool;
+
+    /// Returns true if the component is [required](comp::Presence::Required)
+    /// but has no [auto-initializer](comp::InitStrategy::Auto).
+    fn is_required_without_init(&self) -> bool;
+
+    /// Returns an entity in `allocated` without this component
+    /// if the component is [required](comp::Presence::Required).
+    fn find_missing_required(
+        &self,
+        allocated: &ealloc::Snapshot<A::RawEntity>,
+    ) -> Option<A::RawEntity>;
+
+    /// Writes all components in this storage to a snapshot.
+    ///
+    /// This method should only be called if [`serialize_key`](Self::serialize_key) is `Some`.
+    fn serialize(&self, writer: &mut serialize::Writer<'_>) -> io::Result<()>;
+
+    /// Reads components from a snapshot into this storage.
+    /// Components of entities not in `allocated` are rejected as invalid data.
+    ///
+    /// This method should only be called if [`serialize_key`](Self::serialize_key) is `Some`.
+    fn deserialize(
+        &mut self,
+        reader: &mut serialize::Reader<'_>,
+        allocated: &ealloc::Snapshot<A::RawEntity>,
+    ) -> io::Result<()>;
 }
 
 impl<A: Archetype> dyn AnySimpleStorage<A> {
@@ -151,11 +218,76 @@ impl<A: Archetype, C: comp::Simple<A>> AnySimpleStorage<A> for SimpleStorage<A,
 
     fn clear_entry(&mut self, entity: A::RawEntity) { self.0.set(entity, None); }
 
+    fn set_change_tick(&mut self, tick: storage::Tick) { self.0.set_change_tick(tick); }
+
+    fn take_events(&mut self, events: &mut Vec<(A::RawEntity, storage::Event)>) {
+        self.0.take_events(events);
+    }
+
+    fn memory_usage(&self) -> (usize, storage::MemoryUsage) {
+        (self.0.cardinality(), self.0.memory_usage())
+    }
+
+    fn shrink_to_fit(&mut self) { self.0.shrink_to_fit(); }
+
+    fn rearrange(&mut self, moves: &[(A::RawEntity, A::RawEntity)], tick: storage::Tick) {
+        self.0.rearrange(moves, tick);
+    }
+
     fn referrer_dyn<'t>(&'t mut self) -> Box<dyn referrer::Object + 't> {
-        Box::new(referrer::UnnamedIter(self.0.iter_chunks_mut().flat_map(|chunk| chunk.slice)))
+        Box::new(referrer::UnnamedIter(
+            self.0
+                .iter_chunks_mut()
+                .flat_map(|chunk| chunk.slice)
+                .filter_map(<comp::Layout<A, C> as storage::Layout>::as_whole_mut),
+        ))
     }
 
     fn get_any(&self, entity: A::RawEntity) -> Option<&dyn Any> {
-        self.0.get(entity).map(|v| v as &dyn Any)
+        self.0
+            .get(entity)
+            .and_then(<comp::Layout<A, C> as storage::Layout>::as_whole)
+            .map(|v| v as &dyn Any)
+    }
+
+    fn serialize_key(&self) -> Option<&'static str> {
+        C::SERIALIZER.as_ref().map(|vtable| vtable.key)
+    }
+
+    fn is_required_unserializable(&self) -> bool {
+        matches!(C::PRESENCE, comp::Presence::Required) && C::SERIALIZER.is_none()
+    }
+
+    fn is_required_without_init(&self) -> bool {
+        matches!(C::PRESENCE, comp::Presence::Required)
+            && !matches!(C::INIT_STRATEGY, comp::InitStrategy::Auto(_))
+    }
+
+    fn find_missing_required(
+        &self,
+        allocated: &ealloc::Snapshot<A::RawEntity>,
+    ) -> Option<A::RawEntity> {
+        if !matches!(C::PRESENCE, comp::Presence::Required) {
+            return None;
+        }
+
+        allocated
+            .iter_allocated_chunks()
+            .flat_map(<A::RawEntity as entity::Raw>::range)
+            .find(|&entity| self.0.get(entity).is_none())
+    }
+
+    fn serialize(&self, writer: &mut serialize::Writer<'_>) -> io::Result<()> {
+        let vtable = C::SERIALIZER.expect("serialize() called on unserializable component");
+        serialize::write_storage(writer, &self.0, &vtable)
+    }
+
+    fn deserialize(
+        &mut self,
+        reader: &mut serialize::Reader<'_>,
+        allocated: &ealloc::Snapshot<A::RawEntity>,
+    ) -> io::Result<()> {
+        let vtable = C::SERIALIZER.expect("deserialize() called on unserializable component");
+        serialize::read_storage(reader, &mut self.0, &vtable, allocated)
     }
 }
@@ -0,0 +1,320 @@
+use std::marker::PhantomData;
+use std::{iter, ops};
+
+use bitvec::prelude::BitVec;
+use bitvec::slice::BitSlice;
+
+use super::{
+    Access, AccessChunked, ChunkMut, ChunkRef, Chunked, MemoryUsage, Mut, Partition,
+    PartitionChunked, Ref, Slice, SliceMut, Storage,
+};
+use crate::comp::soa::Columns;
+use crate::entity;
+
+/// The storage for [struct-of-arrays components](crate::comp::soa).
+///
+/// Each field is stored in a separate array indexed by entity IDs directly.
+/// Components are accessed through the proxy types of the [`Columns`] [layout](super::Layout).
+pub struct Soa<RawT: entity::Raw, Cols: Columns> {
+    cardinality: usize,
+    bits:        BitVec,
+    columns:     Cols,
+    _ph:         PhantomData<RawT>,
+}
+
+impl<RawT: entity::Raw, Cols: Columns> Default for Soa<RawT, Cols> {
+    fn default() -> Self {
+        Self {
+            cardinality: 0,
+            bits:        BitVec::new(),
+            columns:     Cols::default(),
+            _ph:         PhantomData,
+        }
+    }
+}
+
+impl<RawT: entity::Raw, Cols: Columns> Drop for Soa<RawT, Cols> {
+    fn drop(&mut self) {
+        for index in self.bits.iter_ones() {
+            drop(unsafe { self.columns.read(index) });
+        }
+    }
+}
+
+impl<RawT: entity::Raw, Cols: Columns> Soa<RawT, Cols> {
+    fn bit(&self, index: usize) -> bool {
+        match self.bits.get(index) {
+            Some(bit) => *bit,
+            None => false,
+        }
+    }
+}
+
+impl<RawT: entity::Raw, Cols: Columns> Access for Soa<RawT, Cols> {
+    type RawEntity = RawT;
+    type Comp = Cols::Comp;
+    type Layout = Cols;
+
+    fn get_mut(&mut self, id: RawT) -> Option<Mut<'_, Self>> { self.as_partition().into_mut(id) }
+
+    fn get_many_mut<const N: usize>(&mut self, entities: [RawT; N]) -> Option<[Mut<'_, Self>; N]> {
+        self.as_partition().into_many_mut(entities)
+    }
+
+    type IterMut<'t> = impl Iterator<Item = (RawT, Mut<'t, Self>)> + 't;
+    fn iter_mut(&mut self) -> Self::IterMut<'_> { self.as_partition().into_iter_mut() }
+}
+
+impl<RawT: entity::Raw, Cols: Columns> Storage for Soa<RawT, Cols> {
+    fn get(&self, id: RawT) -> Option<Ref<'_, Self>> {
+        let index = id.to_primitive();
+        if !self.bit(index) {
+            return None;
+        }
+        Some(unsafe { self.columns.get(index) })
+    }
+
+    fn set(&mut self, id: RawT, value: Option<Cols::Comp>) -> Option<Cols::Comp> {
+        let index = id.to_primitive();
+        let old = match self.bit(index) {
+            true => Some(unsafe { self.columns.read(index) }),
+            false => None,
+        };
+
+        match value {
+            Some(value) => {
+                if self.bits.len() <= index {
+                    self.bits.resize(index + 1, false);
+                }
+                self.columns.grow_to(index + 1);
+                unsafe { self.columns.write(index, value) };
+                self.bits.set(index, true);
+            }
+            None => {
+                if old.is_some() {
+                    self.bits.set(index, false);
+                }
+            }
+        }
+
+        match (old.is_some(), self.bit(index)) {
+            (false, true) => self.cardinality += 1,
+            (true, false) => self.cardinality -= 1,
+            _ => {}
+        }
+
+        old
+    }
+
+    fn cardinality(&self) -> usize { self.cardinality }
+
+    fn last_entity(&self) -> Option<RawT> { self.bits.last_one().map(RawT::from_primitive) }
+
+    fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            capacity:       self.columns.capacity(),
+            data_bytes:     self.columns.data_bytes(),
+            presence_bytes: self.bits.capacity() / 8,
+            metadata_bytes: 0,
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        let len = self.bits.last_one().map_or(0, |index| index + 1);
+        self.bits.truncate(len);
+        self.bits.shrink_to_fit();
+        // slots beyond `len` are uninitialized, so truncation does not leak anything
+        self.columns.shrink_to(len);
+    }
+
+    type Iter<'t> = impl Iterator<Item = (RawT, Ref<'t, Self>)> + 't;
+    fn iter(&self) -> Self::Iter<'_> {
+        self.bits
+            .iter_ones()
+            .map(|index| (RawT::from_primitive(index), unsafe { self.columns.get(index) }))
+    }
+
+    type IterChunks<'t> = impl Iterator<Item = ChunkRef<'t, Self>> + 't;
+    fn iter_chunks(&self) -> Self::IterChunks<'_> {
+        iter_runs(&self.bits).map(|range| ChunkRef {
+            start: RawT::from_primitive(range.start),
+            slice: unsafe { self.columns.slice(range) },
+        })
+    }
+
+    type IterChunksMut<'t> = impl Iterator<Item = ChunkMut<'t, Self>> + 't;
+    fn iter_chunks_mut(&mut self) -> Self::IterChunksMut<'_> {
+        self.as_partition_chunk()
+            .into_iter_chunks_mut()
+            .map(|(start, slice)| ChunkMut { slice, start })
+    }
+
+    type Partition<'t> = StoragePartition<'t, RawT, Cols>;
+    fn as_partition(&mut self) -> Self::Partition<'_> { self.as_partition_chunk() }
+}
+
+impl<RawT: entity::Raw, Cols: Columns> AccessChunked for Soa<RawT, Cols> {
+    fn get_chunk_mut(&mut self, start: RawT, end: RawT) -> Option<SliceMut<'_, Self>> {
+        self.as_partition_chunk().into_chunk_mut(start, end)
+    }
+}
+
+impl<RawT: entity::Raw, Cols: Columns> Chunked for Soa<RawT, Cols> {
+    fn get_chunk(&self, start: RawT, end: RawT) -> Option<Slice<'_, Self>> {
+        let range = start.to_primitive()..end.to_primitive();
+        let bits = self.bits.get(range.clone())?;
+        if !bits.all() {
+            return None;
+        }
+        Some(unsafe { self.columns.slice(range) })
+    }
+
+    type PartitionChunked<'u> = Self::Partition<'u>;
+    fn as_partition_chunk(&mut self) -> Self::PartitionChunked<'_> {
+        StoragePartition {
+            bits:   &self.bits,
+            ptrs:   self.columns.as_ptrs(),
+            offset: 0,
+            _ph:    PhantomData,
+        }
+    }
+}
+
+/// Return value of [`Soa::as_partition`].
+///
+/// All mutable references are derived from `ptrs`,
+/// which are obtained once when the storage is borrowed,
+/// so references to disjoint slots in different partitions do not invalidate each other.
+pub struct StoragePartition<'t, RawT: entity::Raw, Cols: Columns> {
+    /// `bits[i]` corresponds to the slot `offset + i`.
+    bits:   &'t BitSlice,
+    /// Pointers to the whole arrays, which are only dereferenced within `bits`.
+    ptrs:   Cols::Ptrs,
+    offset: usize,
+    _ph:    PhantomData<(RawT, &'t mut Cols)>,
+}
+
+impl<'t, RawT: entity::Raw, Cols: Columns> StoragePartition<'t, RawT, Cols> {
+    /// Returns the index of `entity` in `bits` if its component is present.
+    fn index_of(&self, entity: RawT) -> Option<usize> {
+        let index = match entity.to_primitive().checked_sub(self.offset) {
+            Some(index) => index,
+            None => panic!("Entity {entity:?} is not in the partition {:?}..", self.offset),
+        };
+        match self.bits.get(index) {
+            Some(bit) if *bit => Some(index),
+            _ => None,
+        }
+    }
+}
+
+impl<'t, RawT: entity::Raw, Cols: Columns> Access for StoragePartition<'t, RawT, Cols> {
+    type RawEntity = RawT;
+    type Comp = Cols::Comp;
+    type Layout = Cols;
+
+    fn get_mut(&mut self, entity: RawT) -> Option<Mut<'_, Self>> { self.by_ref().into_mut(entity) }
+
+    fn get_many_mut<const N: usize>(&mut self, entities: [RawT; N]) -> Option<[Mut<'_, Self>; N]> {
+        self.by_ref().into_many_mut(entities)
+    }
+
+    type IterMut<'u> = impl Iterator<Item = (RawT, Mut<'u, Self>)> + 'u where Self: 'u;
+    fn iter_mut(&mut self) -> Self::IterMut<'_> { self.by_ref().into_iter_mut() }
+}
+
+impl<'t, RawT: entity::Raw, Cols: Columns> Partition<'t> for StoragePartition<'t, RawT, Cols> {
+    type ByRef<'u> = StoragePartition<'u, RawT, Cols> where Self: 'u;
+    fn by_ref(&mut self) -> Self::ByRef<'_> {
+        StoragePartition {
+            bits:   self.bits,
+            ptrs:   self.ptrs,
+            offset: self.offset,
+            _ph:    PhantomData,
+        }
+    }
+
+    type IntoIterMut = impl Iterator<Item = (RawT, Mut<'t, Self>)>;
+    fn into_iter_mut(self) -> Self::IntoIterMut {
+        let Self { bits, ptrs, offset, .. } = self;
+        bits.iter_ones().map(move |index| {
+            let index = offset + index;
+            // Safety: each index is only yielded once.
+            (RawT::from_primitive(index), unsafe { Cols::get_mut(ptrs, index) })
+        })
+    }
+
+    fn into_mut(self, entity: RawT) -> Option<Mut<'t, Self>> {
+        let index = self.index_of(entity)?;
+        Some(unsafe { Cols::get_mut(self.ptrs, self.offset + index) })
+    }
+
+    fn into_many_mut<const N: usize>(self, entities: [RawT; N]) -> Option<[Mut<'t, Self>; N]> {
+        let indices: [usize; N] = entities.try_map(|entity| self.index_of(entity))?;
+        for (i, index) in indices.iter().enumerate() {
+            if indices[..i].contains(index) {
+                return None;
+            }
+        }
+        // Safety: all indices are initialized and distinct.
+        Some(indices.map(|index| unsafe { Cols::get_mut(self.ptrs, self.offset + index) }))
+    }
+
+    fn split_out(&mut self, entity: RawT) -> Self {
+        let index =
+            entity.to_primitive().checked_sub(self.offset).expect("parameter out of bounds");
+        let (bits_left, bits_right) = self.bits.split_at(index.min(self.bits.len()));
+        self.bits = bits_left;
+
+        Self {
+            bits:   bits_right,
+            ptrs:   self.ptrs,
+            offset: self.offset + index,
+            _ph:    PhantomData,
+        }
+    }
+}
+
+impl<'t, RawT: entity::Raw, Cols: Columns> AccessChunked for StoragePartition<'t, RawT, Cols> {
+    fn get_chunk_mut(&mut self, start: RawT, end: RawT) -> Option<SliceMut<'_, Self>> {
+        self.by_ref().into_chunk_mut(start, end)
+    }
+}
+
+impl<'t, RawT: entity::Raw, Cols: Columns> PartitionChunked<'t>
+    for StoragePartition<'t, RawT, Cols>
+{
+    fn into_chunk_mut(self, start: RawT, end: RawT) -> Option<SliceMut<'t, Self>> {
+        let range = (start.to_primitive() - self.offset)..(end.to_primitive() - self.offset);
+        let bits = self.bits.get(range.clone())?;
+        if !bits.all() {
+            return None;
+        }
+        let range = (self.offset + range.start)..(self.offset + range.end);
+        Some(unsafe { Cols::slice_mut(self.ptrs, range) })
+    }
+
+    type IntoIterChunksMut = impl Iterator<Item = (RawT, SliceMut<'t, Self>)>;
+    fn into_iter_chunks_mut(self) -> Self::IntoIterChunksMut {
+        let Self { bits, ptrs, offset, .. } = self;
+        iter_runs(bits).map(move |range| {
+            let range = (offset + range.start)..(offset + range.end);
+            // Safety: the runs are disjoint.
+            (RawT::from_primitive(range.start), unsafe { Cols::slice_mut(ptrs, range) })
+        })
+    }
+}
+
+/// Yields the ranges of consecutive ones in `bits`, in ascending order.
+fn iter_runs(bits: &BitSlice) -> impl Iterator<Item = ops::Range<usize>> + '_ {
+    let mut pos = 0;
+    iter::from_fn(move || {
+        let start = pos + bits[pos..].first_one()?;
+        let end = bits[start..].first_zero().map_or(bits.len(), |len| start + len);
+        pos = end;
+        Some(start..end)
+    })
+}
+
+#[cfg(test)]
+mod tests;
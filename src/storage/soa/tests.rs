@@ -0,0 +1,170 @@
+//! Tests the Soa storage.
+
+use std::num::NonZeroU32;
+use std::sync::Arc;
+
+use super::Soa;
+use crate::comp;
+use crate::storage::{Access, AccessChunked, Chunked, Partition, PartitionChunked, Storage};
+use crate::test_util::TestArch;
+
+#[comp(dynec_as(crate), of = TestArch, storage = soa)]
+#[derive(Debug, PartialEq)]
+struct Item {
+    id:    u32,
+    label: String,
+    drops: Arc<()>,
+}
+
+type ItemStorage = Soa<NonZeroU32, ItemColumns>;
+
+fn entity(id: u32) -> NonZeroU32 { NonZeroU32::new(id).unwrap() }
+
+fn item(id: u32, drops: &Arc<()>) -> Item {
+    Item { id, label: id.to_string(), drops: Arc::clone(drops) }
+}
+
+/// Creates a storage with the components of the entities `ids`.
+fn filled(ids: impl IntoIterator<Item = u32>, drops: &Arc<()>) -> ItemStorage {
+    let mut storage = ItemStorage::default();
+    for id in ids {
+        assert!(storage.set(entity(id), Some(item(id, drops))).is_none());
+    }
+    storage
+}
+
+fn ids(storage: &ItemStorage) -> Vec<(u32, u32)> {
+    storage.iter().map(|(entity, item)| (entity.get(), *item.id)).collect()
+}
+
+#[test]
+fn test_set_get() {
+    let drops = Arc::new(());
+    let mut storage = filled([1, 2, 3, 5], &drops);
+    assert_eq!(storage.cardinality(), 4);
+    assert_eq!(storage.last_entity(), Some(entity(5)));
+
+    let old = storage.set(entity(2), Some(item(20, &drops))).expect("entity 2 was set");
+    assert_eq!(old.id, 2);
+    assert_eq!(storage.set(entity(3), None).map(|item| item.label), Some(String::from("3")));
+    assert!(storage.set(entity(100), None).is_none());
+    assert_eq!(storage.cardinality(), 3);
+
+    let item = storage.get(entity(2)).expect("entity 2 was set");
+    assert_eq!((*item.id, item.label.as_str()), (20, "20"));
+    assert!(storage.get(entity(3)).is_none());
+    assert!(storage.get(entity(100)).is_none());
+
+    assert_eq!(ids(&storage), [(1, 1), (2, 20), (5, 5)]);
+}
+
+#[test]
+fn test_iter_mut() {
+    let drops = Arc::new(());
+    let mut storage = filled([1, 2, 4, 5], &drops);
+
+    // all references are alive at the same time
+    let items: Vec<_> = storage.iter_mut().map(|(_, item)| item).collect();
+    for item in items {
+        *item.id *= 10;
+        item.label.push('!');
+    }
+
+    *storage.get_mut(entity(4)).expect("entity 4 was set").id += 1;
+    assert!(storage.get_mut(entity(3)).is_none());
+
+    assert_eq!(ids(&storage), [(1, 10), (2, 20), (4, 41), (5, 50)]);
+    assert_eq!(storage.get(entity(5)).expect("entity 5 was set").label, "5!");
+}
+
+#[test]
+fn test_chunks() {
+    let drops = Arc::new(());
+    let mut storage = filled([1, 2, 3, 5, 6, 9], &drops);
+
+    let chunks: Vec<_> =
+        storage.iter_chunks().map(|chunk| (chunk.start.get(), chunk.slice.id.to_vec())).collect();
+    assert_eq!(chunks, [(1, vec![1, 2, 3]), (5, vec![5, 6]), (9, vec![9])]);
+
+    let chunks: Vec<_> = storage.iter_chunks_mut().collect();
+    for chunk in chunks {
+        for id in chunk.slice.id {
+            *id += 100;
+        }
+    }
+    assert_eq!(ids(&storage), [(1, 101), (2, 102), (3, 103), (5, 105), (6, 106), (9, 109)]);
+
+    let slice = storage.get_chunk(entity(2), entity(4)).expect("entities 2..4 are set");
+    assert_eq!(slice.label, ["2", "3"]);
+    assert_eq!(slice.into_iter().map(|item| *item.id).collect::<Vec<_>>(), [102, 103]);
+    assert!(storage.get_chunk(entity(3), entity(6)).is_none());
+    assert!(storage.get_chunk(entity(9), entity(12)).is_none());
+
+    let slice = storage.get_chunk_mut(entity(5), entity(7)).expect("entities 5..7 are set");
+    for item in slice {
+        *item.id -= 100;
+    }
+    assert!(storage.get_chunk_mut(entity(4), entity(6)).is_none());
+    assert_eq!(ids(&storage), [(1, 101), (2, 102), (3, 103), (5, 5), (6, 6), (9, 109)]);
+}
+
+#[test]
+fn test_get_many_mut() {
+    let drops = Arc::new(());
+    let mut storage = filled([1, 2, 3], &drops);
+
+    let [first, third] = storage.get_many_mut([entity(1), entity(3)]).expect("entities are set");
+    std::mem::swap(first.label, third.label);
+    *first.id += *third.id;
+
+    assert!(storage.get_many_mut([entity(2), entity(2)]).is_none(), "duplicate entities");
+    assert!(storage.get_many_mut([entity(2), entity(4)]).is_none(), "missing entity");
+
+    assert_eq!(ids(&storage), [(1, 4), (2, 2), (3, 3)]);
+    assert_eq!(storage.get(entity(1)).expect("entity 1 was set").label, "3");
+}
+
+#[test]
+fn test_partition() {
+    let drops = Arc::new(());
+    let mut storage = filled([1, 2, 3, 4, 6, 7], &drops);
+
+    let mut left = storage.as_partition();
+    let right = left.split_out(entity(4));
+    let left_items: Vec<_> = left.by_ref().into_iter_mut().collect();
+    let right_chunks: Vec<_> = right.into_iter_chunks_mut().collect();
+
+    for (_, item) in left_items {
+        *item.id += 10;
+    }
+    for (start, slice) in right_chunks {
+        for id in slice.id {
+            *id += start.get() * 100;
+        }
+    }
+    let [second, third] = left.into_many_mut([entity(2), entity(3)]).expect("entities are set");
+    *second.id += 1;
+    *third.id += 1;
+
+    assert_eq!(ids(&storage), [(1, 11), (2, 13), (3, 14), (4, 404), (6, 606), (7, 607)]);
+}
+
+#[test]
+fn test_drop() {
+    let drops = Arc::new(());
+    let mut storage = filled(1..=8, &drops);
+    assert_eq!(Arc::strong_count(&drops), 9);
+
+    drop(storage.set(entity(8), None));
+    drop(storage.set(entity(7), None));
+    drop(storage.set(entity(2), Some(item(2, &drops))));
+    assert_eq!(Arc::strong_count(&drops), 7);
+
+    storage.shrink_to_fit();
+    assert_eq!(storage.last_entity(), Some(entity(6)));
+    assert_eq!(ids(&storage), [(1, 1), (2, 2), (3, 3), (4, 4), (5, 5), (6, 6)]);
+    assert_eq!(Arc::strong_count(&drops), 7, "shrinking does not drop components");
+
+    drop(storage);
+    assert_eq!(Arc::strong_count(&drops), 1);
+}
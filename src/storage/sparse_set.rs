@@ -0,0 +1,372 @@
+use std::{iter, mem};
+
+use super::{Access, ChunkMut, ChunkRef, MemoryUsage, Partition, Storage, Whole};
+use crate::entity;
+
+/// The value in [`SparseSet::sparse`] for entities without a component.
+const VACANT: u32 = u32::MAX;
+
+/// A storage that packs components densely, indexed by a sparse array of entity IDs.
+///
+/// Compared to [`Vec`](super::Vec), this storage only spends 4 bytes per entity
+/// on entities without the component,
+/// and iteration only visits entities that have the component.
+/// This is suitable for components that are only present on a small fraction of entities.
+///
+/// Adding a component appends it to the dense array,
+/// and removing a component moves the last component of the dense array into its place,
+/// so both operations take constant time.
+/// Since [`Storage`] iterates in entity order, the dense array is sorted lazily
+/// before it is iterated or partitioned mutably.
+/// Immutable iteration over an unsorted storage scans the sparse array instead,
+/// which takes time proportional to the greatest entity with the component.
+///
+/// This storage does not implement [`Chunked`](super::Chunked),
+/// because the components of consecutive entities are not contiguous
+/// unless the dense array is sorted.
+pub struct SparseSet<RawT: entity::Raw, C> {
+    /// `sparse[entity]` is the index of the entity in `entities` and `data`,
+    /// or [`VACANT`] if the entity does not have the component.
+    sparse:   Vec<u32>,
+    /// The entities with the component.
+    entities: Vec<RawT>,
+    /// `data[i]` is the component for `entities[i]`.
+    data:     Vec<C>,
+    /// Whether `entities` is sorted in ascending order.
+    sorted:   bool,
+}
+
+impl<RawT: entity::Raw, C> SparseSet<RawT, C> {
+    fn dense_index(&self, id: RawT) -> Option<usize> {
+        match self.sparse.get(id.to_primitive()) {
+            Some(&index) if index != VACANT => Some(index as usize),
+            _ => None,
+        }
+    }
+
+    /// Sorts the dense array by entity if it is not sorted yet.
+    fn sort(&mut self) {
+        if self.sorted {
+            return;
+        }
+
+        // `sparse` still points to the old dense indices after sorting `entities`,
+        // so `data` is permuted in place by following the cycles of the permutation,
+        // updating `sparse` to the new dense index of each visited entity.
+        self.entities.sort_unstable();
+        for start in 0..self.entities.len() {
+            let mut index = start;
+            loop {
+                let sparse = &mut self.sparse[self.entities[index].to_primitive()];
+                let source = mem::replace(sparse, index as u32) as usize;
+                if source == start {
+                    break;
+                }
+                self.data.swap(index, source);
+                index = source;
+            }
+        }
+        self.sorted = true;
+    }
+}
+
+impl<RawT: entity::Raw, C> Default for SparseSet<RawT, C> {
+    fn default() -> Self {
+        Self { sparse: Vec::new(), entities: Vec::new(), data: Vec::new(), sorted: true }
+    }
+}
+
+impl<RawT: entity::Raw, C: Send + Sync + 'static> Access for SparseSet<RawT, C> {
+    type RawEntity = RawT;
+    type Comp = C;
+    type Layout = Whole<C>;
+
+    fn get_mut(&mut self, id: RawT) -> Option<&mut C> {
+        let index = self.dense_index(id)?;
+        Some(self.data.get_mut(index).expect("sparse mismatch"))
+    }
+
+    fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [RawT; N],
+    ) -> Option<[&mut Self::Comp; N]> {
+        let indices: [usize; N] = entities.try_map(|entity| self.dense_index(entity))?;
+        self.data.get_many_mut(indices).ok()
+    }
+
+    type IterMut<'t> = impl Iterator<Item = (RawT, &'t mut C)> + 't;
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.sort();
+        self.entities.iter().copied().zip(self.data.iter_mut())
+    }
+}
+
+impl<RawT: entity::Raw, C: Send + Sync + 'static> Storage for SparseSet<RawT, C> {
+    fn get(&self, id: RawT) -> Option<&C> {
+        let index = self.dense_index(id)?;
+        Some(self.data.get(index).expect("sparse mismatch"))
+    }
+
+    fn set(&mut self, id: RawT, new: Option<C>) -> Option<C> {
+        match (self.dense_index(id), new) {
+            (Some(index), Some(new)) => Some(mem::replace(&mut self.data[index], new)),
+            (Some(index), None) => {
+                self.sparse[id.to_primitive()] = VACANT;
+                self.entities.swap_remove(index);
+                let old = self.data.swap_remove(index);
+                if let Some(&moved) = self.entities.get(index) {
+                    self.sparse[moved.to_primitive()] = index as u32;
+                    self.sorted = false;
+                }
+                Some(old)
+            }
+            (None, Some(new)) => {
+                let sparse_index = id.to_primitive();
+                if self.sparse.len() <= sparse_index {
+                    self.sparse.resize(sparse_index + 1, VACANT);
+                }
+
+                let index = self.entities.len();
+                assert!(index < VACANT as usize, "too many components in sparse set");
+                if self.entities.last().is_some_and(|&last| last > id) {
+                    self.sorted = false;
+                }
+                self.entities.push(id);
+                self.data.push(new);
+                self.sparse[sparse_index] = index as u32;
+                None
+            }
+            (None, None) => None,
+        }
+    }
+
+    fn cardinality(&self) -> usize { self.entities.len() }
+
+    fn last_entity(&self) -> Option<Self::RawEntity> {
+        if self.sorted {
+            self.entities.last().copied()
+        } else {
+            self.entities.iter().copied().max()
+        }
+    }
+
+    fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            capacity:       self.data.capacity(),
+            data_bytes:     self.data.capacity() * mem::size_of::<C>(),
+            presence_bytes: self.sparse.capacity() * mem::size_of::<u32>()
+                + self.entities.capacity() * mem::size_of::<RawT>(),
+            metadata_bytes: 0,
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        let len = self.last_entity().map_or(0, |id| id.to_primitive() + 1);
+        self.sparse.truncate(len);
+        self.sparse.shrink_to_fit();
+        self.entities.shrink_to_fit();
+        self.data.shrink_to_fit();
+    }
+
+    type Iter<'t> = impl Iterator<Item = (RawT, &'t C)> + 't;
+    #[auto_enums::auto_enum(Iterator)]
+    fn iter(&self) -> Self::Iter<'_> {
+        if self.sorted {
+            self.entities.iter().copied().zip(self.data.iter())
+        } else {
+            self.sparse
+                .iter()
+                .enumerate()
+                .filter(|&(_, &index)| index != VACANT)
+                .map(|(entity, &index)| (RawT::from_primitive(entity), &self.data[index as usize]))
+        }
+    }
+
+    type IterChunks<'t> = impl Iterator<Item = ChunkRef<'t, Self>> + 't;
+    #[auto_enums::auto_enum(Iterator)]
+    fn iter_chunks(&self) -> Self::IterChunks<'_> {
+        if self.sorted {
+            let mut data = &self.data[..];
+            iter_runs(&self.entities).map(move |(start, len)| {
+                let (slice, rest) = data.split_at(len);
+                data = rest;
+                ChunkRef { slice, start }
+            })
+        } else {
+            iter_sparse_runs(&self.sparse).map(|(start, first, len)| ChunkRef {
+                slice: &self.data[first..first + len],
+                start: RawT::from_primitive(start),
+            })
+        }
+    }
+
+    type IterChunksMut<'t> = impl Iterator<Item = ChunkMut<'t, Self>> + 't;
+    fn iter_chunks_mut(&mut self) -> Self::IterChunksMut<'_> {
+        self.sort();
+
+        let mut data = &mut self.data[..];
+        iter_runs(&self.entities).map(move |(start, len)| {
+            let (slice, rest) = mem::take(&mut data).split_at_mut(len);
+            data = rest;
+            ChunkMut { slice, start }
+        })
+    }
+
+    type Partition<'t> = StoragePartition<'t, RawT, C>;
+    fn as_partition(&mut self) -> Self::Partition<'_> {
+        self.sort();
+
+        StoragePartition {
+            sparse:      &self.sparse,
+            entities:    &self.entities,
+            data:        &mut self.data,
+            offset:      0,
+            lower_bound: None,
+            upper_bound: None,
+        }
+    }
+}
+
+/// Yields `(start, len)` for each run of consecutive entities in the sorted slice `entities`.
+fn iter_runs<RawT: entity::Raw>(entities: &[RawT]) -> impl Iterator<Item = (RawT, usize)> + '_ {
+    let mut rest = entities;
+    iter::from_fn(move || {
+        let start = *rest.first()?;
+        let len = rest
+            .iter()
+            .enumerate()
+            .take_while(|&(i, entity)| entity.to_primitive() == start.to_primitive() + i)
+            .count();
+        rest = &rest[len..];
+        Some((start, len))
+    })
+}
+
+/// Yields `(start, first, len)` for each run of `len` consecutive entities starting from `start`
+/// whose components are also consecutive in the dense array starting from `first`,
+/// in ascending order of entities.
+fn iter_sparse_runs(sparse: &[u32]) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+    let mut pos = 0;
+    iter::from_fn(move || {
+        let start = pos + sparse[pos..].iter().position(|&index| index != VACANT)?;
+        let first = sparse[start] as usize;
+        let len = sparse[start..]
+            .iter()
+            .zip(first..)
+            .take_while(|&(&index, expected)| index != VACANT && index as usize == expected)
+            .count();
+        pos = start + len;
+        Some((start, first, len))
+    })
+}
+
+/// Return value of [`SparseSet::as_partition`].
+pub struct StoragePartition<'t, RawT: entity::Raw, C> {
+    sparse:      &'t [u32],
+    entities:    &'t [RawT],
+    data:        &'t mut [C],
+    /// The dense index of `entities[0]` in the storage.
+    offset:      usize,
+    lower_bound: Option<RawT>,
+    upper_bound: Option<RawT>,
+}
+
+impl<'t, RawT: entity::Raw, C> StoragePartition<'t, RawT, C> {
+    fn assert_bounds(&self, entity: RawT) {
+        if let Some(bound) = self.lower_bound {
+            assert!(entity >= bound, "Entity {entity:?} is not in the partition {bound:?}..");
+        }
+        if let Some(bound) = self.upper_bound {
+            assert!(entity < bound, "Entity {entity:?} is not in the partition ..{bound:?}");
+        }
+    }
+
+    fn dense_index(&self, entity: RawT) -> Option<usize> {
+        self.assert_bounds(entity);
+
+        match self.sparse.get(entity.to_primitive()) {
+            Some(&index) if index != VACANT => {
+                Some((index as usize).checked_sub(self.offset).expect("sparse mismatch"))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'t, RawT: entity::Raw, C: Send + Sync + 'static> Access for StoragePartition<'t, RawT, C> {
+    type RawEntity = RawT;
+    type Comp = C;
+    type Layout = Whole<C>;
+
+    fn get_mut(&mut self, entity: RawT) -> Option<&mut C> { self.by_ref().into_mut(entity) }
+
+    fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [RawT; N],
+    ) -> Option<[&mut Self::Comp; N]> {
+        self.by_ref().into_many_mut(entities)
+    }
+
+    type IterMut<'u> = impl Iterator<Item = (RawT, &'u mut C)> + 'u where Self: 'u;
+    fn iter_mut(&mut self) -> Self::IterMut<'_> { self.by_ref().into_iter_mut() }
+}
+
+impl<'t, RawT: entity::Raw, C: Send + Sync + 'static> Partition<'t>
+    for StoragePartition<'t, RawT, C>
+{
+    type ByRef<'u> = StoragePartition<'u, RawT, C> where Self: 'u;
+    fn by_ref(&mut self) -> Self::ByRef<'_> {
+        StoragePartition {
+            sparse:      self.sparse,
+            entities:    self.entities,
+            data:        &mut *self.data,
+            offset:      self.offset,
+            lower_bound: self.lower_bound,
+            upper_bound: self.upper_bound,
+        }
+    }
+
+    type IntoIterMut = impl Iterator<Item = (RawT, &'t mut C)>;
+    fn into_iter_mut(self) -> Self::IntoIterMut {
+        self.entities.iter().copied().zip(self.data.iter_mut())
+    }
+
+    fn into_mut(self, entity: RawT) -> Option<&'t mut C> {
+        let index = self.dense_index(entity)?;
+        Some(self.data.get_mut(index).expect("sparse mismatch"))
+    }
+
+    fn into_many_mut<const N: usize>(
+        self,
+        entities: [Self::RawEntity; N],
+    ) -> Option<[&'t mut Self::Comp; N]> {
+        let indices: [usize; N] = entities.try_map(|entity| self.dense_index(entity))?;
+        self.data.get_many_mut(indices).ok()
+    }
+
+    fn split_out(&mut self, entity: RawT) -> Self {
+        self.assert_bounds(entity);
+
+        let index = self.entities.partition_point(|&other| other < entity);
+        let (entities_left, entities_right) = self.entities.split_at(index);
+        self.entities = entities_left;
+        let data_right = self.data.take_mut(index..).expect("index <= self.data.len()");
+
+        let right = Self {
+            sparse:      self.sparse,
+            entities:    entities_right,
+            data:        data_right,
+            offset:      self.offset + index,
+            lower_bound: Some(entity),
+            upper_bound: self.upper_bound,
+        };
+        self.upper_bound = Some(entity);
+        right
+    }
+}
+
+#[cfg(test)]
+super::tests::test_storage!(NON_CHUNKED SparseSet<std::num::NonZeroU32, i64>);
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,159 @@
+//! Tests the dense array bookkeeping of the SparseSet storage.
+
+use std::num::NonZeroU32;
+
+use super::{SparseSet, VACANT};
+use crate::entity::Raw;
+use crate::storage::{Access, Partition, Storage};
+
+type TestStorage = SparseSet<NonZeroU32, i64>;
+
+fn entity(id: u32) -> NonZeroU32 { NonZeroU32::new(id).unwrap() }
+
+fn setup_storage() -> TestStorage {
+    let mut storage = TestStorage::default();
+    for i in 1..=8 {
+        storage.set(entity(i), Some(i64::from(i) * 10));
+    }
+    storage
+}
+
+/// Asserts that the sparse array points to the dense index of every entity and nothing else.
+fn assert_consistent(storage: &TestStorage) {
+    assert_eq!(storage.entities.len(), storage.data.len());
+    for (index, entity) in storage.entities.iter().enumerate() {
+        assert_eq!(storage.sparse[entity.to_primitive()], index as u32, "sparse of {entity}");
+    }
+    let present = storage.sparse.iter().filter(|&&index| index != VACANT).count();
+    assert_eq!(present, storage.entities.len());
+}
+
+fn collect(storage: &TestStorage) -> Vec<(u32, i64)> {
+    storage.iter().map(|(entity, &value)| (entity.get(), value)).collect()
+}
+
+#[test]
+fn test_remove_middle() {
+    let mut storage = setup_storage();
+
+    assert_eq!(storage.set(entity(3), None), Some(30));
+    assert_consistent(&storage);
+
+    // the last component is moved into the hole instead of shifting the tail
+    assert_eq!(storage.entities[2], entity(8));
+    assert!(!storage.sorted);
+
+    assert_eq!(storage.get(entity(3)), None);
+    assert_eq!(storage.get(entity(8)), Some(&80));
+    assert_eq!(storage.cardinality(), 7);
+    assert_eq!(collect(&storage), [(1, 10), (2, 20), (4, 40), (5, 50), (6, 60), (7, 70), (8, 80)]);
+}
+
+#[test]
+fn test_remove_last() {
+    let mut storage = setup_storage();
+
+    assert_eq!(storage.set(entity(8), None), Some(80));
+    assert_consistent(&storage);
+    assert!(storage.sorted);
+    assert_eq!(collect(&storage), (1..=7).map(|i| (i, i64::from(i) * 10)).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_reinsert() {
+    let mut storage = setup_storage();
+
+    storage.set(entity(3), None);
+    storage.set(entity(5), None);
+    assert_eq!(storage.set(entity(3), Some(31)), None);
+    assert_consistent(&storage);
+
+    assert_eq!(storage.get(entity(3)), Some(&31));
+    assert_eq!(storage.get(entity(5)), None);
+    assert_eq!(collect(&storage), [(1, 10), (2, 20), (3, 31), (4, 40), (6, 60), (7, 70), (8, 80)]);
+
+    assert_eq!(storage.set(entity(3), Some(32)), Some(31));
+    assert_eq!(storage.cardinality(), 7);
+    assert_consistent(&storage);
+}
+
+#[test]
+fn test_unsorted_chunks() {
+    let mut storage = setup_storage();
+    storage.set(entity(2), None);
+
+    let chunks: Vec<_> =
+        storage.iter_chunks().map(|chunk| (chunk.start.get(), chunk.slice.to_vec())).collect();
+    // entity 8 is moved to the dense index of entity 2, splitting it from entity 7
+    assert_eq!(chunks, [(1, vec![10]), (3, vec![30, 40, 50, 60, 70]), (8, vec![80])]);
+
+    let chunks: Vec<_> =
+        storage.iter_chunks_mut().map(|chunk| (chunk.start.get(), chunk.slice.to_vec())).collect();
+    assert_eq!(chunks, [(1, vec![10]), (3, vec![30, 40, 50, 60, 70, 80])]);
+    assert!(storage.sorted);
+    assert_consistent(&storage);
+
+    let chunks: Vec<_> =
+        storage.iter_chunks().map(|chunk| (chunk.start.get(), chunk.slice.to_vec())).collect();
+    assert_eq!(chunks, [(1, vec![10]), (3, vec![30, 40, 50, 60, 70, 80])]);
+}
+
+#[test]
+fn test_unsorted_iter_mut() {
+    let mut storage = TestStorage::default();
+    for i in [5, 2, 7, 1] {
+        storage.set(entity(i), Some(i64::from(i)));
+    }
+    assert!(!storage.sorted);
+
+    let entities: Vec<_> = storage.iter_mut().map(|(entity, _)| entity.get()).collect();
+    assert_eq!(entities, [1, 2, 5, 7]);
+    assert_consistent(&storage);
+}
+
+#[test]
+fn test_sort_permutation_cycles() {
+    let mut storage = TestStorage::default();
+    // sorting the dense order [6, 3, 1, 2, 5, 4] follows the cycles (0 2 1 3 5) and (4)
+    for i in [6, 3, 1, 2, 5, 4] {
+        storage.set(entity(i), Some(i64::from(i) * 10));
+    }
+    assert!(!storage.sorted);
+    assert_eq!(storage.last_entity(), Some(entity(6)));
+
+    storage.sort();
+    assert!(storage.sorted);
+    assert_consistent(&storage);
+    assert_eq!(storage.data, [10, 20, 30, 40, 50, 60]);
+    assert_eq!(storage.last_entity(), Some(entity(6)));
+}
+
+#[test]
+fn test_unsorted_partition() {
+    let mut storage = setup_storage();
+    storage.set(entity(2), None);
+    storage.set(entity(6), None);
+
+    let mut partition = storage.as_partition();
+    let mut right = partition.split_out(entity(5));
+    assert_eq!(partition.get_mut(entity(4)), Some(&mut 40));
+    assert_eq!(right.get_mut(entity(8)), Some(&mut 80));
+
+    let left: Vec<_> =
+        partition.into_iter_mut().map(|(entity, &mut v)| (entity.get(), v)).collect();
+    let right: Vec<_> = right.into_iter_mut().map(|(entity, &mut v)| (entity.get(), v)).collect();
+    assert_eq!(left, [(1, 10), (3, 30), (4, 40)]);
+    assert_eq!(right, [(5, 50), (7, 70), (8, 80)]);
+}
+
+#[test]
+fn test_shrink_after_remove() {
+    let mut storage = setup_storage();
+    storage.set(entity(8), None);
+    storage.set(entity(7), None);
+    storage.set(entity(3), None);
+
+    storage.shrink_to_fit();
+    assert_eq!(storage.sparse.len(), 7);
+    assert_consistent(&storage);
+}
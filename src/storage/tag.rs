@@ -0,0 +1,370 @@
+use std::marker::PhantomData;
+use std::ptr::{self, NonNull};
+use std::{mem, slice};
+
+use bitvec::prelude::BitVec;
+use bitvec::slice::BitSlice;
+
+use super::vec::{new_iter_chunks_mut, new_iter_chunks_ref};
+use super::{
+    Access, AccessChunked, ChunkMut, ChunkRef, Chunked, MemoryUsage, Partition, PartitionChunked,
+    Storage, Whole,
+};
+use crate::entity;
+
+/// A storage for zero-sized marker components that only stores a bitset.
+///
+/// Since `C` has no data, this storage does not allocate anything other than
+/// one bit for each entity.
+/// The bitset is also exposed to [`system::TagFilter`](crate::system::TagFilter),
+/// which filters entities by the presence of tags with word-level bit operations.
+///
+/// # Panics
+/// Constructing this storage panics if `C` is not zero-sized.
+pub struct Tag<RawT: entity::Raw, C> {
+    cardinality: usize,
+    bits:        BitVec,
+    // The storage logically owns a `C` for each set bit,
+    // which are moved in and out of a dangling pointer since `C` is zero-sized.
+    _ph:         PhantomData<(RawT, C)>,
+}
+
+impl<RawT: entity::Raw, C> Tag<RawT, C> {
+    fn bit(&self, index: usize) -> bool {
+        match self.bits.get(index) {
+            Some(bit) => *bit,
+            None => false,
+        }
+    }
+
+    /// Returns the bitset of entities with the component.
+    pub(crate) fn bits(&self) -> TagBits<'_> { TagBits(&self.bits) }
+}
+
+impl<RawT: entity::Raw, C> Default for Tag<RawT, C> {
+    fn default() -> Self {
+        assert_eq!(
+            mem::size_of::<C>(),
+            0,
+            "storage::Tag only supports zero-sized components, but {} is not zero-sized",
+            std::any::type_name::<C>(),
+        );
+
+        Self { cardinality: 0, bits: BitVec::new(), _ph: PhantomData }
+    }
+}
+
+impl<RawT: entity::Raw, C> Drop for Tag<RawT, C> {
+    fn drop(&mut self) {
+        if mem::needs_drop::<C>() {
+            for _ in self.bits.iter_ones() {
+                // Safety: each set bit owns a value of the zero-sized `C`.
+                unsafe { ptr::drop_in_place(NonNull::<C>::dangling().as_ptr()) }
+            }
+        }
+    }
+}
+
+/// Returns a reference to a zero-sized value.
+///
+/// # Safety
+/// `C` must be zero-sized, and the caller must logically own a value of `C`.
+unsafe fn zst_mut<'t, C>() -> &'t mut C { NonNull::<C>::dangling().as_mut() }
+
+/// Returns a slice of `len` zero-sized values.
+///
+/// # Safety
+/// `C` must be zero-sized, and the caller must logically own `len` values of `C`.
+unsafe fn zst_slice_mut<'t, C>(len: usize) -> &'t mut [C] {
+    slice::from_raw_parts_mut(NonNull::<C>::dangling().as_ptr(), len)
+}
+
+impl<RawT: entity::Raw, C: Send + Sync + 'static> Access for Tag<RawT, C> {
+    type RawEntity = RawT;
+    type Comp = C;
+    type Layout = Whole<C>;
+
+    fn get_mut(&mut self, id: RawT) -> Option<&mut C> { self.as_partition().into_mut(id) }
+
+    fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [RawT; N],
+    ) -> Option<[&mut Self::Comp; N]> {
+        self.as_partition().into_many_mut(entities)
+    }
+
+    type IterMut<'t> = impl Iterator<Item = (RawT, &'t mut C)> + 't;
+    fn iter_mut(&mut self) -> Self::IterMut<'_> { self.as_partition().into_iter_mut() }
+}
+
+impl<RawT: entity::Raw, C: Send + Sync + 'static> Storage for Tag<RawT, C> {
+    fn get(&self, id: RawT) -> Option<&C> {
+        // Safety: the set bit owns a value of the zero-sized `C`.
+        self.bit(id.to_primitive()).then(|| unsafe { &*zst_mut::<C>() })
+    }
+
+    fn set(&mut self, id: RawT, new: Option<C>) -> Option<C> {
+        let index = id.to_primitive();
+
+        let old = if self.bit(index) {
+            self.cardinality -= 1;
+            // Safety: the set bit owns a value of the zero-sized `C`, which is moved out here.
+            Some(unsafe { ptr::read(NonNull::<C>::dangling().as_ptr()) })
+        } else {
+            None
+        };
+
+        if let Some(new) = new {
+            if self.bits.len() <= index {
+                self.bits.resize(index + 1, false);
+            }
+            self.bits.set(index, true);
+            self.cardinality += 1;
+            // the value is owned by the set bit
+            mem::forget(new);
+        } else if index < self.bits.len() {
+            self.bits.set(index, false);
+        }
+
+        old
+    }
+
+    fn cardinality(&self) -> usize { self.cardinality }
+
+    fn last_entity(&self) -> Option<Self::RawEntity> {
+        self.bits.last_one().map(RawT::from_primitive)
+    }
+
+    fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            capacity: self.bits.capacity(),
+            presence_bytes: self.bits.capacity() / 8,
+            ..MemoryUsage::default()
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        let len = self.bits.last_one().map_or(0, |index| index + 1);
+        self.bits.truncate(len);
+        self.bits.shrink_to_fit();
+    }
+
+    type Iter<'t> = impl Iterator<Item = (RawT, &'t C)> + 't;
+    fn iter(&self) -> Self::Iter<'_> {
+        self.bits.iter_ones().map(|index| {
+            // Safety: the set bit owns a value of the zero-sized `C`.
+            (RawT::from_primitive(index), unsafe { &*zst_mut::<C>() })
+        })
+    }
+
+    type IterChunks<'t> = impl Iterator<Item = ChunkRef<'t, Self>> + 't;
+    fn iter_chunks(&self) -> Self::IterChunks<'_> {
+        // Safety: the slice is only used for the indices of set bits.
+        let data: &[C] = unsafe { zst_slice_mut(self.bits.len()) };
+        new_iter_chunks_ref(&self.bits, data)
+            .map(|(start, slice)| ChunkRef { slice, start: RawT::from_primitive(start) })
+    }
+
+    type IterChunksMut<'t> = impl Iterator<Item = ChunkMut<'t, Self>> + 't;
+    fn iter_chunks_mut(&mut self) -> Self::IterChunksMut<'_> {
+        self.as_partition_chunk()
+            .into_iter_chunks_mut()
+            .map(|(start, slice)| ChunkMut { slice, start })
+    }
+
+    type Partition<'t> = StoragePartition<'t, RawT, C>;
+    fn as_partition(&mut self) -> Self::Partition<'_> { self.as_partition_chunk() }
+}
+
+/// The bitset of a [`Tag`] storage, used for filtering entities by tags.
+#[derive(Clone, Copy)]
+pub struct TagBits<'t>(&'t BitVec);
+
+impl<'t> TagBits<'t> {
+    /// The number of bits in a word.
+    pub(crate) const WORD_BITS: usize = usize::BITS as usize;
+
+    /// Returns the `index`-th word of the bitset,
+    /// where bit `i` of the word indicates the presence of entity `index * WORD_BITS + i`.
+    pub(crate) fn word(self, index: usize) -> usize {
+        let offset = index * Self::WORD_BITS;
+        if offset >= self.0.len() {
+            return 0;
+        }
+
+        let word = self.0.as_raw_slice()[index];
+
+        let len = self.0.len() - offset;
+        if len < Self::WORD_BITS {
+            // bits beyond the length of the bitset are unspecified
+            word & ((1 << len) - 1)
+        } else {
+            word
+        }
+    }
+}
+
+/// Return value of [`Tag::as_partition`].
+pub struct StoragePartition<'t, RawT: entity::Raw, C> {
+    bits:        &'t BitSlice,
+    offset:      usize,
+    upper_bound: Option<RawT>,
+    _ph:         PhantomData<(RawT, &'t mut C)>,
+}
+
+impl<'t, RawT: entity::Raw, C> StoragePartition<'t, RawT, C> {
+    fn assert_bounds(&self, entity: RawT) {
+        assert!(
+            entity.to_primitive() >= self.offset,
+            "Entity {entity:?} is not in the partition {:?}..",
+            self.offset
+        );
+        if let Some(bound) = self.upper_bound {
+            assert!(entity < bound, "Entity {entity:?} is not in the partition ..{bound:?}");
+        }
+    }
+
+    fn local_index(&self, entity: RawT) -> usize {
+        self.assert_bounds(entity);
+        entity.to_primitive() - self.offset
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        match self.bits.get(index) {
+            Some(bit) => *bit,
+            None => false,
+        }
+    }
+}
+
+impl<'t, RawT: entity::Raw, C: Send + Sync + 'static> Access for StoragePartition<'t, RawT, C> {
+    type RawEntity = RawT;
+    type Comp = C;
+    type Layout = Whole<C>;
+
+    fn get_mut(&mut self, entity: RawT) -> Option<&mut C> { self.by_ref().into_mut(entity) }
+
+    fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [RawT; N],
+    ) -> Option<[&mut Self::Comp; N]> {
+        self.by_ref().into_many_mut(entities)
+    }
+
+    type IterMut<'u> = impl Iterator<Item = (RawT, &'u mut C)> + 'u where Self: 'u;
+    fn iter_mut(&mut self) -> Self::IterMut<'_> { self.by_ref().into_iter_mut() }
+}
+
+impl<'t, RawT: entity::Raw, C: Send + Sync + 'static> Partition<'t>
+    for StoragePartition<'t, RawT, C>
+{
+    type ByRef<'u> = StoragePartition<'u, RawT, C> where Self: 'u;
+    fn by_ref(&mut self) -> Self::ByRef<'_> {
+        StoragePartition {
+            bits:        self.bits,
+            offset:      self.offset,
+            upper_bound: self.upper_bound,
+            _ph:         PhantomData,
+        }
+    }
+
+    type IntoIterMut = impl Iterator<Item = (RawT, &'t mut C)>;
+    fn into_iter_mut(self) -> Self::IntoIterMut {
+        let offset = self.offset;
+        self.bits.iter_ones().map(move |index| {
+            // Safety: the set bit owns a value of the zero-sized `C`.
+            (RawT::from_primitive(offset + index), unsafe { zst_mut::<C>() })
+        })
+    }
+
+    fn into_mut(self, entity: RawT) -> Option<&'t mut C> {
+        let index = self.local_index(entity);
+        // Safety: the set bit owns a value of the zero-sized `C`.
+        self.bit(index).then(|| unsafe { zst_mut::<C>() })
+    }
+
+    fn into_many_mut<const N: usize>(
+        self,
+        entities: [Self::RawEntity; N],
+    ) -> Option<[&'t mut Self::Comp; N]> {
+        let indices = entities.map(|entity| self.local_index(entity));
+
+        if !indices.iter().all(|&index| self.bit(index)) {
+            return None;
+        }
+        if !indices.iter().enumerate().all(|(i, index)| !indices[..i].contains(index)) {
+            return None;
+        }
+
+        // Safety: each set bit owns a value of the zero-sized `C`.
+        Some(indices.map(|_| unsafe { zst_mut::<C>() }))
+    }
+
+    fn split_out(&mut self, entity: RawT) -> Self {
+        let index = self.local_index(entity);
+
+        let (bits_left, bits_right) = self.bits.split_at(index.min(self.bits.len()));
+        self.bits = bits_left;
+        let upper_bound = self.upper_bound.replace(entity);
+
+        Self { bits: bits_right, offset: self.offset + index, upper_bound, _ph: PhantomData }
+    }
+}
+
+impl<RawT: entity::Raw, C: Send + Sync + 'static> AccessChunked for Tag<RawT, C> {
+    fn get_chunk_mut(&mut self, start: RawT, end: RawT) -> Option<&mut [C]> {
+        self.as_partition_chunk().into_chunk_mut(start, end)
+    }
+}
+
+impl<RawT: entity::Raw, C: Send + Sync + 'static> Chunked for Tag<RawT, C> {
+    fn get_chunk(&self, start: RawT, end: RawT) -> Option<&[C]> {
+        let bits = self.bits.get(start.to_primitive()..end.to_primitive())?;
+        // Safety: all bits in the chunk are set.
+        bits.all().then(|| unsafe { &*zst_slice_mut(bits.len()) })
+    }
+
+    type PartitionChunked<'u> = Self::Partition<'u>;
+    fn as_partition_chunk(&mut self) -> Self::PartitionChunked<'_> {
+        StoragePartition {
+            bits:        &self.bits,
+            offset:      0,
+            upper_bound: None,
+            _ph:         PhantomData,
+        }
+    }
+}
+
+impl<'t, RawT: entity::Raw, C: Send + Sync + 'static> AccessChunked
+    for StoragePartition<'t, RawT, C>
+{
+    fn get_chunk_mut(&mut self, start: RawT, end: RawT) -> Option<&mut [C]> {
+        self.by_ref().into_chunk_mut(start, end)
+    }
+}
+
+impl<'t, RawT: entity::Raw, C: Send + Sync + 'static> PartitionChunked<'t>
+    for StoragePartition<'t, RawT, C>
+{
+    fn into_chunk_mut(self, start: RawT, end: RawT) -> Option<&'t mut [C]> {
+        let start = self.local_index(start);
+        if let Some(bound) = self.upper_bound {
+            assert!(end <= bound, "Entity {end:?} is beyond the partition ..{bound:?}");
+        }
+        let bits = self.bits.get(start..end.to_primitive().saturating_sub(self.offset))?;
+        // Safety: all bits in the chunk are set.
+        bits.all().then(|| unsafe { zst_slice_mut(bits.len()) })
+    }
+
+    type IntoIterChunksMut = impl Iterator<Item = (RawT, &'t mut [C])>;
+    fn into_iter_chunks_mut(self) -> Self::IntoIterChunksMut {
+        // Safety: the slice is only used for the indices of set bits.
+        let data: &mut [C] = unsafe { zst_slice_mut(self.bits.len()) };
+        let offset = self.offset;
+        new_iter_chunks_mut(self.bits, data)
+            .map(move |(start, slice)| (RawT::from_primitive(offset + start), slice))
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,100 @@
+//! Tests the Tag storage.
+
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::Tag;
+use crate::storage::tests::TestComp;
+use crate::storage::{Access, Chunked, Partition, Storage};
+
+#[derive(Debug, PartialEq)]
+struct Marker;
+
+impl TestComp for Marker {
+    fn new(_: u32) -> Self { Marker }
+}
+
+crate::storage::tests::test_storage!(CHUNKED Tag<NonZeroU32, Marker>);
+
+crate::storage::tests::test_storage! { @CHUNKED Tag<NonZeroU32, Marker> =>
+    #[should_panic = "Entity 5 is not in the partition ..4"] test_partition_panic_left_some
+    #[should_panic = "Entity 4 is not in the partition ..4"] test_partition_panic_left_none
+    #[should_panic = "Entity 4 is not in the partition ..3"] test_repartition_panic_ll_lr
+    #[should_panic = "Entity 8 is not in the partition ..5"] test_repartition_panic_lr_r
+    #[should_panic = "Entity 8 is not in the partition ..7"] test_repartition_panic_rl_rr
+}
+
+fn entity(id: u32) -> NonZeroU32 { NonZeroU32::new(id).unwrap() }
+
+#[test]
+fn test_set_get() {
+    let mut storage = Tag::<NonZeroU32, Marker>::default();
+    for i in [1, 2, 3, 5, 6] {
+        assert_eq!(storage.set(entity(i), Some(Marker)), None);
+    }
+    assert_eq!(storage.set(entity(2), Some(Marker)), Some(Marker));
+    assert_eq!(storage.set(entity(3), None), Some(Marker));
+    assert_eq!(storage.set(entity(100), None), None);
+
+    assert_eq!(storage.cardinality(), 4);
+    assert_eq!(storage.get(entity(1)), Some(&Marker));
+    assert_eq!(storage.get(entity(3)), None);
+    assert_eq!(storage.get_many_mut([entity(1), entity(2)]), Some([&mut Marker, &mut Marker]));
+    assert_eq!(storage.get_many_mut([entity(1), entity(1)]), None);
+
+    let entities: Vec<_> = storage.iter().map(|(entity, _)| entity.get()).collect();
+    assert_eq!(entities, [1, 2, 5, 6]);
+
+    let chunks: Vec<_> =
+        storage.iter_chunks().map(|chunk| (chunk.start.get(), chunk.slice.len())).collect();
+    assert_eq!(chunks, [(1, 2), (5, 2)]);
+    assert_eq!(storage.get_chunk(entity(5), entity(7)).map(<[_]>::len), Some(2));
+    assert_eq!(storage.get_chunk(entity(2), entity(4)), None);
+
+    let (mut left, mut right) = storage.as_partition().split_at(entity(5));
+    assert_eq!(left.get_mut(entity(2)), Some(&mut Marker));
+    assert_eq!(right.get_mut(entity(5)), Some(&mut Marker));
+    let entities: Vec<_> = right.iter_mut().map(|(entity, _)| entity.get()).collect();
+    assert_eq!(entities, [5, 6]);
+}
+
+#[test]
+fn test_word_bits() {
+    let mut storage = Tag::<NonZeroU32, Marker>::default();
+    for i in [1, 63, 64, 70] {
+        storage.set(entity(i), Some(Marker));
+    }
+    storage.set(entity(70), None);
+
+    let bits = storage.bits();
+    assert_eq!(bits.word(0), 1 << 1 | 1 << 63);
+    assert_eq!(bits.word(1), 1);
+    assert_eq!(bits.word(2), 0);
+}
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+struct DropCounter;
+
+impl Drop for DropCounter {
+    fn drop(&mut self) { DROPPED.fetch_add(1, Ordering::SeqCst); }
+}
+
+#[test]
+fn test_drop() {
+    let mut storage = Tag::<NonZeroU32, DropCounter>::default();
+    for i in 1..=5 {
+        storage.set(entity(i), Some(DropCounter));
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 0);
+
+    drop(storage.set(entity(3), None));
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+
+    drop(storage);
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 5);
+}
+
+#[test]
+#[should_panic = "storage::Tag only supports zero-sized components"]
+fn test_non_zero_sized() { Tag::<NonZeroU32, u32>::default(); }
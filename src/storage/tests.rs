@@ -1,9 +1,10 @@
 #![allow(clippy::extra_unused_type_parameters)] // due to test_storage macro consistency
 
+use std::fmt;
 use std::marker::PhantomData;
 use std::num::NonZeroU32;
 
-use crate::storage::{Access, Partition};
+use crate::storage::{Access, Partition, Whole};
 use crate::Storage;
 
 macro_rules! test_storage {
@@ -11,6 +12,7 @@ macro_rules! test_storage {
         crate::storage::tests::test_storage! { @$ident $storage =>
             test_single_small_hole
             test_single_big_hole_with_reinsertion
+            test_last_entity
             test_partition_no_panic
             #[should_panic = "Entity 3 is not in the partition 5.."] test_partition_panic_right_some
             #[should_panic = "Entity 4 is not in the partition 5.."] test_partition_panic_right_none
@@ -42,6 +44,34 @@ macro_rules! test_storage {
 
 pub(crate) use test_storage;
 
+/// A component type for the generic storage tests.
+///
+/// The tests always store the value `new(id)` for the entity `id`,
+/// which allows zero-sized components to run the same tests.
+pub(super) trait TestComp: fmt::Debug + PartialEq + Send + Sync + 'static {
+    fn new(id: u32) -> Self;
+}
+
+impl TestComp for i64 {
+    fn new(id: u32) -> Self { i64::from(id) }
+}
+
+/// Checks that `value` is the test value of `entity`,
+/// and returns the entity ID along with the numeric value used in the test expectations.
+fn entry<C: TestComp>(entity: NonZeroU32, value: &C) -> (u32, i64) {
+    assert_eq!(value, &C::new(entity.get()), "value of entity {entity}");
+    (entity.get(), i64::from(entity.get()))
+}
+
+/// Converts a chunk to the numeric values used in the test expectations.
+fn chunk_entries<C: TestComp>(start: NonZeroU32, slice: &[C]) -> (u32, Vec<i64>) {
+    let values = (start.get()..)
+        .zip(slice)
+        .map(|(id, value)| entry(NonZeroU32::new(id).unwrap(), value).1)
+        .collect();
+    (start.get(), values)
+}
+
 /// Wraps the chunk capabilities of storage types if available.
 pub(super) trait Chunker<S> {
     fn to_chunks(s: &S) -> Option<Vec<(u32, Vec<i64>)>>;
@@ -49,12 +79,16 @@ pub(super) trait Chunker<S> {
 }
 
 pub(super) struct RealChunker<S>(PhantomData<S>);
-impl<S: Storage<RawEntity = NonZeroU32, Comp = i64>> Chunker<S> for RealChunker<S> {
+impl<S> Chunker<S> for RealChunker<S>
+where
+    S: Storage<RawEntity = NonZeroU32, Layout = Whole<<S as Access>::Comp>>,
+    S::Comp: TestComp,
+{
     fn to_chunks(s: &S) -> Option<Vec<(u32, Vec<i64>)>> {
-        Some(s.iter_chunks().map(|chunk| (chunk.start.get(), chunk.slice.to_vec())).collect())
+        Some(s.iter_chunks().map(|chunk| chunk_entries(chunk.start, chunk.slice)).collect())
     }
     fn to_chunks_mut(s: &mut S) -> Option<Vec<(u32, Vec<i64>)>> {
-        Some(s.iter_chunks_mut().map(|chunk| (chunk.start.get(), chunk.slice.to_vec())).collect())
+        Some(s.iter_chunks_mut().map(|chunk| chunk_entries(chunk.start, chunk.slice)).collect())
     }
 }
 
@@ -66,30 +100,31 @@ impl<S> Chunker<S> for FakeChunker {
 
 pub(super) fn test_single_small_hole<S, P>()
 where
-    S: Storage<RawEntity = NonZeroU32, Comp = i64>,
+    S: Storage<RawEntity = NonZeroU32, Layout = Whole<<S as Access>::Comp>>,
+    S::Comp: TestComp,
     P: Chunker<S>,
 {
     let mut storage = S::default();
     for i in 1..=10 {
-        storage.set(NonZeroU32::new(i).unwrap(), Some(i64::from(i)));
+        storage.set(NonZeroU32::new(i).unwrap(), Some(TestComp::new(i)));
     }
 
     for i in 1..=10 {
-        assert_eq!(storage.get(NonZeroU32::new(i).unwrap()), Some(&i64::from(i)));
+        assert_eq!(storage.get(NonZeroU32::new(i).unwrap()), Some(&TestComp::new(i)));
     }
 
     storage.set(NonZeroU32::new(3).unwrap(), None);
     for i in (1..3).chain(4..=10) {
-        assert_eq!(storage.get(NonZeroU32::new(i).unwrap()), Some(&i64::from(i)));
+        assert_eq!(storage.get(NonZeroU32::new(i).unwrap()), Some(&TestComp::new(i)));
     }
 
-    let items: Vec<_> = storage.iter().map(|(entity, value)| (entity.get(), *value)).collect();
+    let items: Vec<_> = storage.iter().map(|(entity, value)| entry(entity, value)).collect();
     assert_eq!(
         items,
         vec![(1, 1), (2, 2), (4, 4), (5, 5), (6, 6), (7, 7), (8, 8), (9, 9), (10, 10)]
     );
 
-    let items: Vec<_> = storage.iter_mut().map(|(entity, value)| (entity.get(), *value)).collect();
+    let items: Vec<_> = storage.iter_mut().map(|(entity, value)| entry(entity, value)).collect();
     assert_eq!(
         items,
         vec![(1, 1), (2, 2), (4, 4), (5, 5), (6, 6), (7, 7), (8, 8), (9, 9), (10, 10)]
@@ -104,33 +139,56 @@ where
     }
 }
 
+pub(super) fn test_last_entity<S, P>()
+where
+    S: Storage<RawEntity = NonZeroU32, Layout = Whole<<S as Access>::Comp>>,
+    S::Comp: TestComp,
+    P: Chunker<S>,
+{
+    let mut storage = S::default();
+    assert_eq!(storage.last_entity(), None);
+
+    for i in [3, 7, 5] {
+        storage.set(NonZeroU32::new(i).unwrap(), Some(TestComp::new(i)));
+    }
+    assert_eq!(storage.last_entity(), NonZeroU32::new(7));
+
+    storage.set(NonZeroU32::new(7).unwrap(), None);
+    assert_eq!(storage.last_entity(), NonZeroU32::new(5));
+
+    storage.set(NonZeroU32::new(3).unwrap(), None);
+    storage.set(NonZeroU32::new(5).unwrap(), None);
+    assert_eq!(storage.last_entity(), None);
+}
+
 pub(super) fn test_single_big_hole_with_reinsertion<S, P>()
 where
-    S: Storage<RawEntity = NonZeroU32, Comp = i64>,
+    S: Storage<RawEntity = NonZeroU32, Layout = Whole<<S as Access>::Comp>>,
+    S::Comp: TestComp,
     P: Chunker<S>,
 {
     let mut storage = S::default();
     for i in 1..=10 {
-        storage.set(NonZeroU32::new(i).unwrap(), Some(i64::from(i)));
+        storage.set(NonZeroU32::new(i).unwrap(), Some(TestComp::new(i)));
     }
 
     for i in 1..=10 {
-        assert_eq!(storage.get(NonZeroU32::new(i).unwrap()), Some(&i64::from(i)));
+        assert_eq!(storage.get(NonZeroU32::new(i).unwrap()), Some(&TestComp::new(i)));
     }
 
     for i in 3..6 {
         storage.set(NonZeroU32::new(i).unwrap(), None);
     }
-    storage.set(NonZeroU32::new(4).unwrap(), Some(i64::from(4)));
+    storage.set(NonZeroU32::new(4).unwrap(), Some(TestComp::new(4)));
 
     for i in (1..3).chain(6..=10) {
-        assert_eq!(storage.get(NonZeroU32::new(i).unwrap()), Some(&i64::from(i)));
+        assert_eq!(storage.get(NonZeroU32::new(i).unwrap()), Some(&TestComp::new(i)));
     }
 
-    let items: Vec<_> = storage.iter().map(|(entity, value)| (entity.get(), *value)).collect();
+    let items: Vec<_> = storage.iter().map(|(entity, value)| entry(entity, value)).collect();
     assert_eq!(items, vec![(1, 1), (2, 2), (4, 4), (6, 6), (7, 7), (8, 8), (9, 9), (10, 10)]);
 
-    let items: Vec<_> = storage.iter_mut().map(|(entity, value)| (entity.get(), *value)).collect();
+    let items: Vec<_> = storage.iter_mut().map(|(entity, value)| entry(entity, value)).collect();
     assert_eq!(items, vec![(1, 1), (2, 2), (4, 4), (6, 6), (7, 7), (8, 8), (9, 9), (10, 10)]);
 
     if let Some(chunks) = P::to_chunks(&storage) {
@@ -143,40 +201,45 @@ where
 }
 
 /// Returns a storage containing entities 1,2,3,5,6,8,9 (without 4 and 7).
-fn setup_partition_storage<S: Storage<RawEntity = NonZeroU32, Comp = i64>>() -> S {
+fn setup_partition_storage<S: Storage<RawEntity = NonZeroU32>>() -> S
+where
+    S::Comp: TestComp,
+{
     let mut storage = S::default();
     for i in [1, 2, 3, 5, 6, 8, 9] {
-        storage.set(NonZeroU32::new(i).unwrap(), Some(i64::from(i)));
+        storage.set(NonZeroU32::new(i).unwrap(), Some(TestComp::new(i)));
     }
     storage
 }
 
 pub(super) fn test_partition_no_panic<S, P>()
 where
-    S: Storage<RawEntity = NonZeroU32, Comp = i64>,
+    S: Storage<RawEntity = NonZeroU32, Layout = Whole<<S as Access>::Comp>>,
+    S::Comp: TestComp,
 {
     let mut storage: S = setup_partition_storage();
     {
         let (mut left, mut right) = storage.as_partition().split_at(NonZeroU32::new(4).unwrap());
-        assert_eq!(left.get_mut(NonZeroU32::new(1).unwrap()), Some(&mut 1));
-        assert_eq!(left.get_mut(NonZeroU32::new(3).unwrap()), Some(&mut 3));
+        assert_eq!(left.get_mut(NonZeroU32::new(1).unwrap()), Some(&mut TestComp::new(1)));
+        assert_eq!(left.get_mut(NonZeroU32::new(3).unwrap()), Some(&mut TestComp::new(3)));
         assert_eq!(right.get_mut(NonZeroU32::new(4).unwrap()), None);
-        assert_eq!(right.get_mut(NonZeroU32::new(5).unwrap()), Some(&mut 5));
-        assert_eq!(right.get_mut(NonZeroU32::new(9).unwrap()), Some(&mut 9));
+        assert_eq!(right.get_mut(NonZeroU32::new(5).unwrap()), Some(&mut TestComp::new(5)));
+        assert_eq!(right.get_mut(NonZeroU32::new(9).unwrap()), Some(&mut TestComp::new(9)));
     }
     {
         let (mut left, mut right) = storage.as_partition().split_at(NonZeroU32::new(5).unwrap());
-        assert_eq!(left.get_mut(NonZeroU32::new(1).unwrap()), Some(&mut 1));
-        assert_eq!(left.get_mut(NonZeroU32::new(3).unwrap()), Some(&mut 3));
+        assert_eq!(left.get_mut(NonZeroU32::new(1).unwrap()), Some(&mut TestComp::new(1)));
+        assert_eq!(left.get_mut(NonZeroU32::new(3).unwrap()), Some(&mut TestComp::new(3)));
         assert_eq!(left.get_mut(NonZeroU32::new(4).unwrap()), None);
-        assert_eq!(right.get_mut(NonZeroU32::new(5).unwrap()), Some(&mut 5));
-        assert_eq!(right.get_mut(NonZeroU32::new(9).unwrap()), Some(&mut 9));
+        assert_eq!(right.get_mut(NonZeroU32::new(5).unwrap()), Some(&mut TestComp::new(5)));
+        assert_eq!(right.get_mut(NonZeroU32::new(9).unwrap()), Some(&mut TestComp::new(9)));
     }
 }
 
 pub(super) fn test_partition_panic_left_some<S, P>()
 where
-    S: Storage<RawEntity = NonZeroU32, Comp = i64>,
+    S: Storage<RawEntity = NonZeroU32, Layout = Whole<<S as Access>::Comp>>,
+    S::Comp: TestComp,
 {
     let mut storage: S = setup_partition_storage();
     let (mut left, _) = storage.as_partition().split_at(NonZeroU32::new(4).unwrap());
@@ -185,7 +248,8 @@ where
 
 pub(super) fn test_partition_panic_left_none<S, P>()
 where
-    S: Storage<RawEntity = NonZeroU32, Comp = i64>,
+    S: Storage<RawEntity = NonZeroU32, Layout = Whole<<S as Access>::Comp>>,
+    S::Comp: TestComp,
 {
     let mut storage: S = setup_partition_storage();
     let (mut left, _) = storage.as_partition().split_at(NonZeroU32::new(4).unwrap());
@@ -194,7 +258,8 @@ where
 
 pub(super) fn test_partition_panic_right_some<S, P>()
 where
-    S: Storage<RawEntity = NonZeroU32, Comp = i64>,
+    S: Storage<RawEntity = NonZeroU32, Layout = Whole<<S as Access>::Comp>>,
+    S::Comp: TestComp,
 {
     let mut storage: S = setup_partition_storage();
     let (_, mut right) = storage.as_partition().split_at(NonZeroU32::new(5).unwrap());
@@ -203,7 +268,8 @@ where
 
 pub(super) fn test_partition_panic_right_none<S, P>()
 where
-    S: Storage<RawEntity = NonZeroU32, Comp = i64>,
+    S: Storage<RawEntity = NonZeroU32, Layout = Whole<<S as Access>::Comp>>,
+    S::Comp: TestComp,
 {
     let mut storage: S = setup_partition_storage();
     let (_, mut right) = storage.as_partition().split_at(NonZeroU32::new(5).unwrap());
@@ -222,7 +288,8 @@ macro_rules! repartition_panic_test {
     ) => { $(
         pub(super) fn $ident<S, P>()
         where
-            S: Storage<RawEntity = NonZeroU32, Comp = i64>,
+            S: Storage<RawEntity = NonZeroU32, Layout = Whole<<S as Access>::Comp>>,
+    S::Comp: TestComp,
         {
             let mut storage: S = setup_partition_storage();
 
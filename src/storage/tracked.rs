@@ -0,0 +1,326 @@
+use std::mem;
+
+use super::{
+    Access, AccessChunked, Chunk as _, ChunkMut, ChunkRef, Chunked, Event, EventSource,
+    MemoryUsage, Mut, Partition, PartitionChunked, Ref, Slice, SliceMut, Storage,
+};
+use crate::entity::Raw as _;
+
+/// A logical timestamp for [change tracking](Tracked).
+///
+/// Ticks are allocated from a world-wide clock,
+/// so ticks from different storages are comparable.
+/// [`Tick::default()`] is earlier than all ticks assigned to components,
+/// so `iter_changed_since(Tick::default())` yields all components.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tick(pub(crate) u64);
+
+/// Wraps a storage to record the [`Tick`] at which each component was last mutably accessed.
+///
+/// This is the storage used by components declared with `#[comp(track_changes)]`.
+/// A component is considered changed when it is inserted
+/// or when a mutable reference to it is obtained,
+/// regardless of whether the value is actually modified.
+pub struct Tracked<S: Storage> {
+    inner:   S,
+    /// `ticks[i]` is the tick at which the component of entity `i` was last changed.
+    /// The vector is always long enough to cover all present components.
+    ticks:   Vec<Tick>,
+    current: Tick,
+}
+
+impl<S: Storage> Default for Tracked<S> {
+    fn default() -> Self {
+        Self { inner: S::default(), ticks: Vec::new(), current: Tick::default() }
+    }
+}
+
+impl<S: Storage> Tracked<S> {
+    /// The tick assigned to changes made through the current access to this storage.
+    pub fn current_tick(&self) -> Tick { self.current }
+
+    /// Returns the tick at which the component for the specified entity was last changed,
+    /// or `None` if the component is not present.
+    pub fn changed_tick(&self, id: S::RawEntity) -> Option<Tick> {
+        self.inner.get(id)?;
+        Some(self.ticks[id.to_primitive()])
+    }
+
+    /// Returns an immutable iterator over the components changed after `since`,
+    /// ordered by entity index order.
+    pub fn iter_changed_since(
+        &self,
+        since: Tick,
+    ) -> impl Iterator<Item = (S::RawEntity, Ref<'_, S>)> + '_ {
+        self.inner.iter().filter(move |&(id, _)| self.ticks[id.to_primitive()] > since)
+    }
+}
+
+impl<S: Storage> Access for Tracked<S> {
+    type RawEntity = S::RawEntity;
+    type Comp = S::Comp;
+    type Layout = S::Layout;
+
+    fn get_mut(&mut self, id: Self::RawEntity) -> Option<Mut<'_, Self>> {
+        let value = self.inner.get_mut(id)?;
+        self.ticks[id.to_primitive()] = self.current;
+        Some(value)
+    }
+
+    fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [Self::RawEntity; N],
+    ) -> Option<[Mut<'_, Self>; N]> {
+        let values = self.inner.get_many_mut(entities)?;
+        for id in entities {
+            self.ticks[id.to_primitive()] = self.current;
+        }
+        Some(values)
+    }
+
+    type IterMut<'u> = impl Iterator<Item = (Self::RawEntity, Mut<'u, Self>)> + 'u
+    where
+        Self: 'u;
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        let (ticks, current) = (&mut self.ticks[..], self.current);
+        self.inner.iter_mut().map(move |(id, value)| {
+            ticks[id.to_primitive()] = current;
+            (id, value)
+        })
+    }
+}
+
+impl<S: Storage> Storage for Tracked<S> {
+    fn get(&self, id: Self::RawEntity) -> Option<Ref<'_, Self>> { self.inner.get(id) }
+
+    fn set(&mut self, id: Self::RawEntity, value: Option<Self::Comp>) -> Option<Self::Comp> {
+        if value.is_some() {
+            let index = id.to_primitive();
+            if self.ticks.len() <= index {
+                self.ticks.resize(index + 1, Tick::default());
+            }
+            self.ticks[index] = self.current;
+        }
+        self.inner.set(id, value)
+    }
+
+    fn cardinality(&self) -> usize { self.inner.cardinality() }
+
+    fn last_entity(&self) -> Option<Self::RawEntity> { self.inner.last_entity() }
+
+    fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = self.inner.memory_usage();
+        usage.metadata_bytes += self.ticks.capacity() * mem::size_of::<Tick>();
+        usage
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit();
+        let len = self.inner.last_entity().map_or(0, |id| id.to_primitive() + 1);
+        self.ticks.truncate(len);
+        self.ticks.shrink_to_fit();
+    }
+
+    fn set_change_tick(&mut self, tick: Tick) { self.current = tick; }
+
+    fn take_events(&mut self, events: &mut Vec<(Self::RawEntity, Event)>) {
+        self.inner.take_events(events)
+    }
+
+    fn rearrange(&mut self, moves: &[(Self::RawEntity, Self::RawEntity)], tick: Tick) {
+        self.inner.rearrange(moves, tick);
+
+        // Entity references in the moved components are rewritten through mutable accesses later,
+        // which must be recorded at the same tick.
+        self.current = tick;
+        self.ticks.clear();
+        for &(_, new) in moves {
+            if self.inner.get(new).is_some() {
+                let index = new.to_primitive();
+                if self.ticks.len() <= index {
+                    self.ticks.resize(index + 1, Tick::default());
+                }
+                self.ticks[index] = tick;
+            }
+        }
+    }
+
+    type Iter<'t> = S::Iter<'t>;
+    fn iter(&self) -> Self::Iter<'_> { self.inner.iter() }
+
+    type IterChunks<'t> = impl Iterator<Item = ChunkRef<'t, Self>> + 't;
+    fn iter_chunks(&self) -> Self::IterChunks<'_> {
+        self.inner.iter_chunks().map(|chunk| ChunkRef { slice: chunk.slice, start: chunk.start })
+    }
+
+    type IterChunksMut<'t> = impl Iterator<Item = ChunkMut<'t, Self>> + 't;
+    fn iter_chunks_mut(&mut self) -> Self::IterChunksMut<'_> {
+        let (ticks, current) = (&mut self.ticks[..], self.current);
+        self.inner.iter_chunks_mut().map(move |chunk| {
+            mark_chunk(ticks, chunk.start.to_primitive(), chunk.slice.len(), current);
+            ChunkMut { slice: chunk.slice, start: chunk.start }
+        })
+    }
+
+    type Partition<'u> = TrackedPartition<'u, S::Partition<'u>>;
+    fn as_partition(&mut self) -> Self::Partition<'_> {
+        TrackedPartition {
+            inner:   self.inner.as_partition(),
+            ticks:   &mut self.ticks,
+            offset:  0,
+            current: self.current,
+        }
+    }
+}
+
+impl<S: EventSource> EventSource for Tracked<S> {}
+
+impl<S: Chunked> AccessChunked for Tracked<S> {
+    fn get_chunk_mut(
+        &mut self,
+        start: Self::RawEntity,
+        end: Self::RawEntity,
+    ) -> Option<SliceMut<'_, Self>> {
+        let chunk = self.inner.get_chunk_mut(start, end)?;
+        mark_chunk(&mut self.ticks, start.to_primitive(), chunk.len(), self.current);
+        Some(chunk)
+    }
+}
+
+impl<S: Chunked> Chunked for Tracked<S> {
+    fn get_chunk(&self, start: Self::RawEntity, end: Self::RawEntity) -> Option<Slice<'_, Self>> {
+        self.inner.get_chunk(start, end)
+    }
+
+    type PartitionChunked<'u> = TrackedPartition<'u, S::PartitionChunked<'u>>;
+    fn as_partition_chunk(&mut self) -> Self::PartitionChunked<'_> {
+        TrackedPartition {
+            inner:   self.inner.as_partition_chunk(),
+            ticks:   &mut self.ticks,
+            offset:  0,
+            current: self.current,
+        }
+    }
+}
+
+fn mark_chunk(ticks: &mut [Tick], start: usize, len: usize, current: Tick) {
+    ticks[start..start + len].fill(current);
+}
+
+/// Return value of [`Tracked::as_partition`].
+pub struct TrackedPartition<'t, P> {
+    inner:   P,
+    /// `ticks[i]` corresponds to entity `offset + i`.
+    ticks:   &'t mut [Tick],
+    offset:  usize,
+    current: Tick,
+}
+
+impl<'t, P: Partition<'t>> Access for TrackedPartition<'t, P> {
+    type RawEntity = P::RawEntity;
+    type Comp = P::Comp;
+    type Layout = P::Layout;
+
+    fn get_mut(&mut self, entity: Self::RawEntity) -> Option<Mut<'_, Self>> {
+        self.by_ref().into_mut(entity)
+    }
+
+    fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [Self::RawEntity; N],
+    ) -> Option<[Mut<'_, Self>; N]> {
+        self.by_ref().into_many_mut(entities)
+    }
+
+    type IterMut<'u> = impl Iterator<Item = (Self::RawEntity, Mut<'u, Self>)> + 'u
+    where
+        Self: 'u;
+    fn iter_mut(&mut self) -> Self::IterMut<'_> { self.by_ref().into_iter_mut() }
+}
+
+impl<'t, P: Partition<'t>> Partition<'t> for TrackedPartition<'t, P> {
+    type ByRef<'u> = TrackedPartition<'u, P::ByRef<'u>> where Self: 'u;
+    fn by_ref(&mut self) -> Self::ByRef<'_> {
+        TrackedPartition {
+            inner:   self.inner.by_ref(),
+            ticks:   &mut *self.ticks,
+            offset:  self.offset,
+            current: self.current,
+        }
+    }
+
+    fn split_out(&mut self, entity: Self::RawEntity) -> Self {
+        let inner = self.inner.split_out(entity);
+        let index =
+            entity.to_primitive().checked_sub(self.offset).expect("parameter out of bounds");
+
+        let ticks_right = self
+            .ticks
+            .take_mut(index.min(self.ticks.len())..)
+            .expect("index is clamped to self.ticks.len()");
+
+        Self { inner, ticks: ticks_right, offset: self.offset + index, current: self.current }
+    }
+
+    type IntoIterMut = impl Iterator<Item = (Self::RawEntity, Mut<'t, Self>)>;
+    fn into_iter_mut(self) -> Self::IntoIterMut {
+        let Self { inner, ticks, offset, current } = self;
+        inner.into_iter_mut().map(move |(entity, value)| {
+            ticks[entity.to_primitive() - offset] = current;
+            (entity, value)
+        })
+    }
+
+    fn into_mut(self, entity: Self::RawEntity) -> Option<Mut<'t, Self>> {
+        let value = self.inner.into_mut(entity)?;
+        self.ticks[entity.to_primitive() - self.offset] = self.current;
+        Some(value)
+    }
+
+    fn into_many_mut<const N: usize>(
+        self,
+        entities: [Self::RawEntity; N],
+    ) -> Option<[Mut<'t, Self>; N]> {
+        let values = self.inner.into_many_mut(entities)?;
+        for entity in entities {
+            self.ticks[entity.to_primitive() - self.offset] = self.current;
+        }
+        Some(values)
+    }
+}
+
+impl<'t, P: PartitionChunked<'t>> AccessChunked for TrackedPartition<'t, P> {
+    fn get_chunk_mut(
+        &mut self,
+        start: Self::RawEntity,
+        end: Self::RawEntity,
+    ) -> Option<SliceMut<'_, Self>> {
+        let chunk = self.inner.get_chunk_mut(start, end)?;
+        mark_chunk(self.ticks, start.to_primitive() - self.offset, chunk.len(), self.current);
+        Some(chunk)
+    }
+}
+
+impl<'t, P: PartitionChunked<'t>> PartitionChunked<'t> for TrackedPartition<'t, P> {
+    fn into_chunk_mut(
+        self,
+        start: Self::RawEntity,
+        end: Self::RawEntity,
+    ) -> Option<SliceMut<'t, Self>> {
+        let chunk = self.inner.into_chunk_mut(start, end)?;
+        mark_chunk(self.ticks, start.to_primitive() - self.offset, chunk.len(), self.current);
+        Some(chunk)
+    }
+
+    type IntoIterChunksMut = impl Iterator<Item = (Self::RawEntity, SliceMut<'t, Self>)>;
+    fn into_iter_chunks_mut(self) -> Self::IntoIterChunksMut {
+        let Self { inner, ticks, offset, current } = self;
+        inner.into_iter_chunks_mut().map(move |(start, chunk)| {
+            mark_chunk(ticks, start.to_primitive() - offset, chunk.len(), current);
+            (start, chunk)
+        })
+    }
+}
+
+#[cfg(test)]
+super::tests::test_storage!(CHUNKED Tracked<super::Vec<std::num::NonZeroU32, i64>>);
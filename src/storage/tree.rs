@@ -1,9 +1,9 @@
 use std::cell::SyncUnsafeCell;
 use std::collections::BTreeMap;
 use std::ptr::NonNull;
-use std::{array, slice};
+use std::{array, mem, slice};
 
-use super::{Access, ChunkMut, ChunkRef, Partition, Storage};
+use super::{Access, ChunkMut, ChunkRef, MemoryUsage, Partition, Storage, Whole};
 use crate::{entity, util};
 
 /// A storage based on [`BTreeMap`].
@@ -22,6 +22,7 @@ impl<RawT: entity::Raw, C> Default for Tree<RawT, C> {
 impl<RawT: entity::Raw, C: Send + Sync + 'static> Access for Tree<RawT, C> {
     type RawEntity = RawT;
     type Comp = C;
+    type Layout = Whole<C>;
 
     fn get_mut(&mut self, id: Self::RawEntity) -> Option<&mut C> {
         self.data.get_mut(&id).map(|cell| cell.get_mut())
@@ -81,6 +82,19 @@ impl<RawT: entity::Raw, C: Send + Sync + 'static> Storage for Tree<RawT, C> {
 
     fn cardinality(&self) -> usize { self.data.len() }
 
+    fn last_entity(&self) -> Option<Self::RawEntity> {
+        self.data.last_key_value().map(|(&id, _)| id)
+    }
+
+    fn memory_usage(&self) -> MemoryUsage {
+        // `BTreeMap` does not expose the size of its nodes, so only the entries are counted.
+        MemoryUsage {
+            capacity: self.data.len(),
+            data_bytes: self.data.len() * mem::size_of::<(RawT, C)>(),
+            ..MemoryUsage::default()
+        }
+    }
+
     type Iter<'t> = impl Iterator<Item = (Self::RawEntity, &'t Self::Comp)> + 't;
     fn iter(&self) -> Self::Iter<'_> {
         self.data.iter().map(|(&entity, cell)| {
@@ -129,6 +143,7 @@ impl<'t, RawT: entity::Raw, C> StoragePartition<'t, RawT, C> {
 impl<'t, RawT: entity::Raw, C: Send + Sync + 'static> Access for StoragePartition<'t, RawT, C> {
     type RawEntity = RawT;
     type Comp = C;
+    type Layout = Whole<C>;
 
     fn get_mut(&mut self, entity: RawT) -> Option<&mut C> {
         self.assert_bounds(entity);
@@ -6,7 +6,8 @@ use bitvec::prelude::BitVec;
 use bitvec::slice::BitSlice;
 
 use super::{
-    Access, AccessChunked, ChunkMut, ChunkRef, Chunked, Partition, PartitionChunked, Storage,
+    Access, AccessChunked, ChunkMut, ChunkRef, Chunked, MemoryUsage, Partition, PartitionChunked,
+    Storage, Whole,
 };
 use crate::{entity, util};
 
@@ -61,6 +62,7 @@ impl<RawT: entity::Raw, T> Default for VecStorage<RawT, T> {
 impl<RawT: entity::Raw, C: Send + Sync + 'static> Access for VecStorage<RawT, C> {
     type RawEntity = RawT;
     type Comp = C;
+    type Layout = Whole<C>;
 
     fn get_mut(&mut self, id: RawT) -> Option<&mut C> {
         let index = id.to_primitive();
@@ -141,6 +143,28 @@ impl<RawT: entity::Raw, C: Send + Sync + 'static> Storage for VecStorage<RawT, C
 
     fn cardinality(&self) -> usize { self.cardinality }
 
+    fn last_entity(&self) -> Option<Self::RawEntity> {
+        self.bits.last_one().map(RawT::from_primitive)
+    }
+
+    fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            capacity:       self.data.capacity(),
+            data_bytes:     self.data.capacity() * mem::size_of::<C>(),
+            presence_bytes: self.bits.capacity() / 8,
+            metadata_bytes: 0,
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        let len = self.bits.last_one().map_or(0, |index| index + 1);
+        self.bits.truncate(len);
+        self.bits.shrink_to_fit();
+        // values beyond `len` are uninitialized, so truncation does not leak anything
+        self.data.truncate(len);
+        self.data.shrink_to_fit();
+    }
+
     type Iter<'t> = impl Iterator<Item = (RawT, &'t C)> + 't;
     fn iter(&self) -> Self::Iter<'_> {
         let indices = self.bits.iter_ones();
@@ -201,6 +225,7 @@ pub struct StoragePartition<'t, RawT: entity::Raw, C> {
 impl<'t, RawT: entity::Raw, C: Send + Sync + 'static> Access for StoragePartition<'t, RawT, C> {
     type RawEntity = RawT;
     type Comp = C;
+    type Layout = Whole<C>;
 
     fn get_mut(&mut self, entity: RawT) -> Option<&mut C> { self.by_ref().into_mut(entity) }
 
@@ -417,13 +442,13 @@ where
         trisplit,
     }
 }
-fn new_iter_chunks_ref<'iter, 'data: 'iter, C: 'static>(
+pub(super) fn new_iter_chunks_ref<'iter, 'data: 'iter, C: 'static>(
     bits: &'iter BitSlice,
     data: &'data [C],
 ) -> impl Iterator<Item = (usize, &'data [C])> + 'iter {
     new_iter_chunks(bits, data, trisplit_fn_ref)
 }
-fn new_iter_chunks_mut<'iter, 'data: 'iter, C: 'static>(
+pub(super) fn new_iter_chunks_mut<'iter, 'data: 'iter, C: 'static>(
     bits: &'iter BitSlice,
     data: &'data mut [C],
 ) -> impl Iterator<Item = (usize, &'data mut [C])> + 'iter {
@@ -481,10 +506,10 @@ fn trisplit_fn_mut<T>(data: &mut [T], index: usize) -> (&mut [T], &mut [T], &mut
     (left, mid, right)
 }
 
-unsafe fn slice_assume_init_ref<T>(slice: &[MaybeUninit<T>]) -> &[T] {
+pub(super) unsafe fn slice_assume_init_ref<T>(slice: &[MaybeUninit<T>]) -> &[T] {
     &*(slice as *const [MaybeUninit<T>] as *const [T])
 }
-unsafe fn slice_assume_init_mut<T>(slice: &mut [MaybeUninit<T>]) -> &mut [T] {
+pub(super) unsafe fn slice_assume_init_mut<T>(slice: &mut [MaybeUninit<T>]) -> &mut [T] {
     &mut *(slice as *mut [MaybeUninit<T>] as *mut [T])
 }
 
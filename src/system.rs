@@ -9,10 +9,12 @@
 //! are always executed on the main thread.
 
 use std::any::TypeId;
+use std::error::Error;
 
 use crate::entity::{ealloc, referrer};
 use crate::world;
 use crate::world::offline;
+pub use crate::world::rw::dynamic::{ReadDynamic, WriteDynamic};
 pub use crate::world::rw::isotope::read::full::ReadIsotopeFull;
 pub use crate::world::rw::isotope::read::partial::ReadIsotopePartial;
 pub use crate::world::rw::isotope::write::full::WriteIsotopeFull;
@@ -20,17 +22,26 @@ pub use crate::world::rw::isotope::write::partial::WriteIsotopePartial;
 pub use crate::world::rw::simple::{ReadSimple, WriteSimple};
 
 pub mod access;
-pub use access::{Isotope as AccessIsotope, Single as AccessSingle};
+pub use access::{Dynamic as AccessDynamic, Isotope as AccessIsotope, Single as AccessSingle};
 
 pub mod iter;
-pub use iter::{EntityIterator, IntoZip, Try, Zip, ZipChunked};
+pub use iter::{Changed, EntityIterator, IntoZip, TagFilter, Try, Zip, ZipChunked};
+
+pub mod condition;
+pub use condition::RunCondition;
 
 pub mod partition;
-pub use partition::{EntityCreationPartition, Partition};
+pub use partition::{EntityCreationPartition, EventPartition, Partition};
 
 mod offline_buffer;
 pub use offline_buffer::{EntityCreator, EntityDeleter};
 
+mod events;
+pub use events::{EventCursor, EventReader, EventWriter};
+
+mod simple_events;
+pub use simple_events::SimpleEvents;
+
 pub mod spec;
 #[doc(inline)]
 pub use spec::Spec;
@@ -64,13 +75,15 @@ pub trait Descriptor {
 /// This is meaningful as they may have different states.
 pub trait Sendable: Send + Descriptor + 'static {
     /// Runs the system.
+    ///
+    /// Returns the error returned by the system function if it is fallible.
     fn run(
         &mut self,
         globals: &world::SyncGlobals,
         components: &world::Components,
         ealloc_shard_map: &mut ealloc::ShardMap,
         offline_shard: &mut offline::BufferShard,
-    );
+    ) -> Result<(), BoxedError>;
 
     /// Returns self upcast to [`Descriptor`] as a trait object.
     fn as_descriptor_mut(&mut self) -> &mut dyn Descriptor;
@@ -81,6 +94,8 @@ pub trait Sendable: Send + Descriptor + 'static {
 /// and accessing non-<code>[Send] + [Sync]</code> global states.
 pub trait Unsendable: Descriptor + 'static {
     /// Runs the system.
+    ///
+    /// Returns the error returned by the system function if it is fallible.
     fn run(
         &mut self,
         sync_globals: &world::SyncGlobals,
@@ -88,8 +103,22 @@ pub trait Unsendable: Descriptor + 'static {
         components: &world::Components,
         ealloc_shard_map: &mut ealloc::ShardMap,
         offline_shard: &mut offline::BufferShard,
-    );
+    ) -> Result<(), BoxedError>;
 
     /// Returns self upcast to [`Descriptor`] as a trait object.
     fn as_descriptor_mut(&mut self) -> &mut dyn Descriptor;
 }
+
+/// An error returned by a fallible system.
+pub type BoxedError = Box<dyn Error + Send + Sync>;
+
+/// Determines how the executor handles an error returned by a system.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnError {
+    /// The error is reported, and systems that depend on the failed system run as usual.
+    #[default]
+    Continue,
+    /// The error is reported, and all systems that (transitively) depend on the failed system
+    /// through partitions are skipped for the rest of the cycle.
+    Block,
+}
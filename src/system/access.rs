@@ -3,6 +3,9 @@
 pub mod single;
 pub use single::Single;
 
+pub mod dynamic;
+pub use dynamic::Dynamic;
+
 pub mod isotope;
 pub use isotope::Isotope;
 pub(crate) use isotope::{PartialStorageMap, StorageMap, StorageMapMut};
@@ -0,0 +1,64 @@
+//! Access a dynamic component storage.
+//!
+//! See [`AccessDynamic`](Dynamic) for documentation.
+
+use std::marker::PhantomData;
+use std::ops;
+
+use crate::{comp, entity, storage, Archetype};
+
+/// Access a [dynamic component](comp::dynamic) storage of an archetype.
+///
+/// Components are accessed as byte slices of [`size`](Self::size) bytes.
+pub struct Dynamic<A, StorageRef> {
+    storage: StorageRef,
+    _ph:     PhantomData<A>,
+}
+
+impl<A, StorageRef> Dynamic<A, StorageRef> {
+    pub(crate) fn new(storage: StorageRef) -> Self { Self { storage, _ph: PhantomData } }
+}
+
+impl<A, StorageRef> Dynamic<A, StorageRef>
+where
+    A: Archetype,
+    StorageRef: ops::Deref<Target = storage::Dynamic<A::RawEntity>>,
+{
+    /// The descriptor of the accessed component.
+    pub fn descriptor(&self) -> comp::dynamic::Descriptor { self.storage.descriptor() }
+
+    /// The number of bytes in each component.
+    pub fn size(&self) -> usize { self.storage.size() }
+
+    /// Returns the bytes of the component for the specified entity,
+    /// or `None` if the component is not present in the entity.
+    pub fn try_get(&self, entity: impl entity::Ref<Archetype = A>) -> Option<&[u8]> {
+        self.storage.get(entity.id())
+    }
+
+    /// Iterates over all present components in this storage.
+    pub fn iter(&self) -> impl Iterator<Item = (entity::TempRef<'_, A>, &[u8])> + '_ {
+        self.storage.iter().map(|(entity, bytes)| (entity::TempRef::new(entity), bytes))
+    }
+}
+
+impl<A, StorageRef> Dynamic<A, StorageRef>
+where
+    A: Archetype,
+    StorageRef: ops::DerefMut<Target = storage::Dynamic<A::RawEntity>>,
+{
+    /// Returns the mutable bytes of the component for the specified entity,
+    /// or `None` if the component is not present in the entity.
+    pub fn try_get_mut(&mut self, entity: impl entity::Ref<Archetype = A>) -> Option<&mut [u8]> {
+        self.storage.get_mut(entity.id())
+    }
+
+    /// Overwrites the component for the specified entity, or removes it if `value` is `None`.
+    /// Returns whether the component was previously present.
+    ///
+    /// # Panics
+    /// Panics if the length of `value` is not equal to [`size`](Self::size).
+    pub fn set(&mut self, entity: impl entity::Ref<Archetype = A>, value: Option<&[u8]>) -> bool {
+        self.storage.set(entity.id(), value)
+    }
+}
@@ -130,7 +130,11 @@ where
     ///
     /// This method is infallible for correctly implemented `comp::Must`,
     /// which returns the auto-initialized value for missing components.
-    pub fn get(&mut self, entity: impl entity::Ref<Archetype = A>, discrim: KeyT) -> &C
+    pub fn get(
+        &mut self,
+        entity: impl entity::Ref<Archetype = A>,
+        discrim: KeyT,
+    ) -> comp::Ref<'_, A, C>
     where
         C: comp::Must<A>,
     {
@@ -147,7 +151,11 @@ where
     /// Returns an immutable reference to the component for the specified entity and discriminant,
     /// or the default value for isotopes with a default initializer or `None`
     /// if the component is not present in the entity.
-    pub fn try_get(&mut self, entity: impl entity::Ref<Archetype = A>, key: KeyT) -> Option<&C> {
+    pub fn try_get(
+        &mut self,
+        entity: impl entity::Ref<Archetype = A>,
+        key: KeyT,
+    ) -> Option<comp::Ref<'_, A, C>> {
         let storage = self.storages.get_storage(key);
         storage.get(entity.id())
     }
@@ -165,12 +173,12 @@ where
     pub fn get_all<'t, E: entity::Ref<Archetype = A>>(
         &'t self,
         entity: E,
-    ) -> impl Iterator<Item = (<C as comp::Isotope<A>>::Discrim, &'t C)> + 't {
+    ) -> impl Iterator<Item = (<C as comp::Isotope<A>>::Discrim, comp::Ref<'t, A, C>)> + 't {
         // workaround for https://github.com/rust-lang/rust/issues/65442
         fn without_e<A, C>(
             getter: &impl StorageMap<A, C>,
             id: <A as Archetype>::RawEntity,
-        ) -> impl Iterator<Item = (C::Discrim, &'_ C)> + '_
+        ) -> impl Iterator<Item = (C::Discrim, comp::Ref<'_, A, C>)> + '_
         where
             A: Archetype,
             C: comp::Isotope<A>,
@@ -190,7 +198,7 @@ where
     pub fn iter<'t>(
         &'t mut self,
         key: KeyT,
-    ) -> impl Iterator<Item = (entity::TempRef<'t, A>, &'t C)> {
+    ) -> impl Iterator<Item = (entity::TempRef<'t, A>, comp::Ref<'t, A, C>)> {
         let storage = self.storages.get_storage(key);
         storage.iter().map(|(entity, comp)| (entity::TempRef::new(entity), comp))
     }
@@ -225,7 +233,7 @@ where
     /// Retrieves the component for the given entity and discriminant.
     ///
     /// Identical to [`get`](Isotope::get) but does not require a mutable receiver.
-    pub fn get_ref(&self, entity: impl entity::Ref<Archetype = A>, key: KeyT) -> &C
+    pub fn get_ref(&self, entity: impl entity::Ref<Archetype = A>, key: KeyT) -> comp::Ref<'_, A, C>
     where
         C: comp::Must<A>,
     {
@@ -244,7 +252,11 @@ where
     /// if the component is not present in the entity.
     ///
     /// Identical to [`try_get`](Isotope::try_get) but does not require a mutable receiver.
-    pub fn try_get_ref<E: entity::Ref<Archetype = A>>(&self, entity: E, key: KeyT) -> Option<&C> {
+    pub fn try_get_ref<E: entity::Ref<Archetype = A>>(
+        &self,
+        entity: E,
+        key: KeyT,
+    ) -> Option<comp::Ref<'_, A, C>> {
         let storage = self.storages.get_storage_ref(key);
         storage.get(entity.id())
     }
@@ -255,7 +267,7 @@ where
     pub fn iter_ref<'t>(
         &'t self,
         key: KeyT,
-    ) -> impl Iterator<Item = (entity::TempRef<'t, A>, &'t C)> {
+    ) -> impl Iterator<Item = (entity::TempRef<'t, A>, comp::Ref<'t, A, C>)> {
         let storage = self.storages.get_storage_ref(key);
         storage.iter().map(|(entity, comp)| (entity::TempRef::new(entity), comp))
     }
@@ -280,7 +292,11 @@ where
     ///
     /// This method is infallible for correctly implemented `comp::Must`,
     /// which returns the auto-initialized value for missing components.
-    pub fn get_mut(&mut self, entity: impl entity::Ref<Archetype = A>, discrim: KeyT) -> &mut C
+    pub fn get_mut(
+        &mut self,
+        entity: impl entity::Ref<Archetype = A>,
+        discrim: KeyT,
+    ) -> comp::Mut<'_, A, C>
     where
         C: comp::Must<A>,
     {
@@ -298,14 +314,14 @@ where
     /// automatically initialized with the default initializer if present,
     /// or `None` if the component is unset and has no default initializer.
     ///
-    /// Note that this method returns `Option<&mut C>`, not `&mut Option<C>`.
+    /// Note that this method returns `Option<comp::Mut<'_, A, C>>`, not `&mut Option<C>`.
     /// This means setting the Option itself to `Some`/`None` will not modify any stored value.
     /// Use [`set`](Isotope::set) to add/remove a component.
     pub fn try_get_mut(
         &mut self,
         entity: impl entity::Ref<Archetype = A>,
         key: KeyT,
-    ) -> Option<&mut C> {
+    ) -> Option<comp::Mut<'_, A, C>> {
         let storage = self.storages.get_storage_mut(key);
         storage.get_mut(entity.id())
     }
@@ -327,7 +343,7 @@ where
     pub fn iter_mut<'t>(
         &'t mut self,
         key: KeyT,
-    ) -> impl Iterator<Item = (entity::TempRef<'t, A>, &'t mut C)> {
+    ) -> impl Iterator<Item = (entity::TempRef<'t, A>, comp::Mut<'t, A, C>)> {
         let storage = self.storages.get_storage_mut(key);
         storage.iter_mut().map(|(entity, comp)| (entity::TempRef::new(entity), comp))
     }
@@ -9,7 +9,7 @@ use derive_trait::derive_trait;
 use rayon::prelude::ParallelIterator;
 
 use crate::entity::{self, ealloc, Raw as _};
-use crate::storage::{self, Access as _, Chunked as _};
+use crate::storage::{self, Access as _, Chunk as _, Chunked as _};
 use crate::{comp, util, Archetype, Storage};
 
 /// Access a single component storage, i.e. a simple archetyped component
@@ -34,20 +34,87 @@ where
     A: Archetype,
     C: comp::SimpleOrIsotope<A>,
     StorageRef: ops::Deref + Sync,
-    StorageRef::Target: Storage<RawEntity = <A as Archetype>::RawEntity, Comp = C>,
+    StorageRef::Target:
+        Storage<RawEntity = <A as Archetype>::RawEntity, Comp = C, Layout = comp::Layout<A, C>>,
 {
     /// Returns an immutable reference to the component for the specified entity,
     /// or `None` if the component is not present in the entity.
-    pub fn try_get(&self, entity: impl entity::Ref<Archetype = A>) -> Option<&C> {
+    pub fn try_get(&self, entity: impl entity::Ref<Archetype = A>) -> Option<comp::Ref<'_, A, C>> {
         self.storage.get(entity.id())
     }
 
     /// Iterates over all initialized components in this storage.
-    pub fn iter<'t>(&'t self) -> impl Iterator<Item = (entity::TempRef<'t, A>, &'t C)> + 't {
+    pub fn iter<'t>(
+        &'t self,
+    ) -> impl Iterator<Item = (entity::TempRef<'t, A>, comp::Ref<'t, A, C>)> + 't {
         self.storage.iter().map(|(entity, comp)| (entity::TempRef::new(entity), comp))
     }
 }
 
+#[derive_trait(pub GetChanged{
+    /// The archetype that this accessor retrieves for.
+    type Arch: Archetype = A;
+    /// The component that this accessor retrieves.
+    type Comp: comp::SimpleOrIsotope<Self::Arch> = C;
+})]
+impl<A, C, StorageRef, S> Single<A, C, StorageRef>
+where
+    A: Archetype,
+    C: comp::SimpleOrIsotope<A>,
+    StorageRef: ops::Deref<Target = storage::Tracked<S>> + Sync,
+    S: Storage<RawEntity = <A as Archetype>::RawEntity, Comp = C, Layout = comp::Layout<A, C>>,
+{
+    /// Returns the tick of the latest exclusive access to this storage.
+    ///
+    /// A system can store this value at the end of each run
+    /// and pass it as `since` in the next run
+    /// to only process components changed in between.
+    /// For writable accessors, this is the tick of changes made through this accessor,
+    /// so changes made by the system itself are not observed in the next run.
+    pub fn change_tick(&self) -> storage::Tick { self.storage.current_tick() }
+
+    /// Returns an immutable reference to the component for the specified entity
+    /// if it is present and was changed after `since`.
+    pub fn try_get_changed(
+        &self,
+        entity: impl entity::Ref<Archetype = A>,
+        since: storage::Tick,
+    ) -> Option<comp::Ref<'_, A, C>> {
+        let id = entity.id();
+        match self.storage.changed_tick(id) {
+            Some(tick) if tick > since => self.storage.get(id),
+            _ => None,
+        }
+    }
+
+    /// Iterates over all components changed after `since`.
+    pub fn iter_changed_since<'t>(
+        &'t self,
+        since: storage::Tick,
+    ) -> impl Iterator<Item = (entity::TempRef<'t, A>, comp::Ref<'t, A, C>)> + 't {
+        self.storage
+            .iter_changed_since(since)
+            .map(|(entity, comp)| (entity::TempRef::new(entity), comp))
+    }
+}
+
+#[derive_trait(pub GetTag{
+    /// The archetype that this accessor retrieves for.
+    type Arch: Archetype = A;
+    /// The component that this accessor retrieves.
+    type Comp: comp::SimpleOrIsotope<Self::Arch> = C;
+})]
+impl<A, C, StorageRef> Single<A, C, StorageRef>
+where
+    A: Archetype,
+    C: comp::SimpleOrIsotope<A>,
+    StorageRef: ops::Deref<Target = storage::Tag<<A as Archetype>::RawEntity, C>> + Sync,
+{
+    /// Returns the bitset of entities with this [tag](storage::Tag) component,
+    /// to be used with [`TagFilter`](crate::system::TagFilter).
+    pub fn tag_bits(&self) -> storage::TagBits<'_> { self.storage.bits() }
+}
+
 #[derive_trait(pub MustGet{
     /// The archetype that this accessor retrieves for.
     type Arch: Archetype = A;
@@ -59,7 +126,8 @@ where
     A: Archetype,
     C: comp::SimpleOrIsotope<A> + comp::Must<A>,
     StorageRef: ops::Deref + Sync,
-    StorageRef::Target: Storage<RawEntity = <A as Archetype>::RawEntity, Comp = C>,
+    StorageRef::Target:
+        Storage<RawEntity = <A as Archetype>::RawEntity, Comp = C, Layout = comp::Layout<A, C>>,
 {
     /// Returns an immutable reference to the component for the specified entity.
     ///
@@ -69,7 +137,7 @@ where
     /// # Panics
     /// This function panics if the entity is not fully initialized yet.
     /// This happens when an entity is newly created and the cycle hasn't joined yet.
-    pub fn get(&self, entity: impl entity::Ref<Archetype = A>) -> &C {
+    pub fn get(&self, entity: impl entity::Ref<Archetype = A>) -> comp::Ref<'_, A, C> {
         match self.try_get(entity) {
             Some(comp) => comp,
             None => panic!(
@@ -90,7 +158,7 @@ where
     pub fn par_iter<'t>(
         &'t self,
         snapshot: &'t ealloc::Snapshot<<A as Archetype>::RawEntity>,
-    ) -> impl ParallelIterator<Item = (entity::TempRef<'t, A>, &'t C)> {
+    ) -> impl ParallelIterator<Item = (entity::TempRef<'t, A>, comp::Ref<'t, A, C>)> {
         rayon::iter::split(snapshot.as_slice(), |slice| slice.split()).flat_map_iter(|slice| {
             slice.iter_chunks().flat_map(<<A as Archetype>::RawEntity as entity::Raw>::range).map(
                 |id| {
@@ -114,7 +182,11 @@ where
     A: Archetype,
     C: comp::SimpleOrIsotope<A>,
     StorageRef: ops::Deref + Sync,
-    StorageRef::Target: storage::Chunked<RawEntity = <A as Archetype>::RawEntity, Comp = C>,
+    StorageRef::Target: storage::Chunked<
+        RawEntity = <A as Archetype>::RawEntity,
+        Comp = C,
+        Layout = comp::Layout<A, C>,
+    >,
 {
     /// Returns the chunk of components as a slice.
     ///
@@ -123,7 +195,7 @@ where
     /// In general, users should not get an [`entity::TempRefChunk`]
     /// that includes an uninitialized entity,
     /// so panic is basically impossible if [`comp::Must`] was implemented correctly.
-    pub fn get_chunk(&self, chunk: entity::TempRefChunk<A>) -> &[C] {
+    pub fn get_chunk(&self, chunk: entity::TempRefChunk<A>) -> comp::Slice<'_, A, C> {
         self.storage.get_chunk(chunk.start, chunk.end).expect("chunk is not completely filled")
     }
 }
@@ -139,7 +211,11 @@ where
     A: Archetype,
     C: comp::SimpleOrIsotope<A> + comp::Must<A>,
     StorageRef: ops::Deref + Sync,
-    StorageRef::Target: storage::Chunked<RawEntity = <A as Archetype>::RawEntity, Comp = C>,
+    StorageRef::Target: storage::Chunked<
+        RawEntity = <A as Archetype>::RawEntity,
+        Comp = C,
+        Layout = comp::Layout<A, C>,
+    >,
 {
     /// Iterates over chunks of entities in parallel.
     ///
@@ -148,7 +224,7 @@ where
     pub fn par_iter_chunks<'t>(
         &'t self,
         snapshot: &'t ealloc::Snapshot<<A as Archetype>::RawEntity>,
-    ) -> impl ParallelIterator<Item = (entity::TempRefChunk<'t, A>, &'t [C])> {
+    ) -> impl ParallelIterator<Item = (entity::TempRefChunk<'t, A>, comp::Slice<'t, A, C>)> {
         rayon::iter::split(snapshot.as_slice(), |slice| slice.split()).flat_map_iter(|slice| {
             // we don't need to split over the holes in parallel,
             // because splitting the total space is more important than splitting the holes
@@ -172,15 +248,22 @@ where
     A: Archetype,
     C: comp::SimpleOrIsotope<A>,
     StorageRef: ops::DerefMut + Sync,
-    StorageRef::Target: storage::Access<RawEntity = <A as Archetype>::RawEntity, Comp = C>,
+    StorageRef::Target: storage::Access<
+        RawEntity = <A as Archetype>::RawEntity,
+        Comp = C,
+        Layout = comp::Layout<A, C>,
+    >,
 {
     /// Returns a mutable reference to the component for the specified entity,
     /// or `None` if the component is not present in the entity.
     ///
-    /// Note that this function returns `Option<&mut C>`, not `&mut Option<C>`.
+    /// Note that this function returns `Option<comp::Mut<'_, A, C>>`, not `&mut Option<C>`.
     /// This means setting the Option itself to `Some`/`None` will not modify any stored value.
     /// Use [`set`](Single::set) to add/remove a component.
-    pub fn try_get_mut(&mut self, entity: impl entity::Ref<Archetype = A>) -> Option<&mut C> {
+    pub fn try_get_mut(
+        &mut self,
+        entity: impl entity::Ref<Archetype = A>,
+    ) -> Option<comp::Mut<'_, A, C>> {
         self.storage.get_mut(entity.id())
     }
 
@@ -191,14 +274,14 @@ where
     pub fn try_get_many_mut<const N: usize>(
         &mut self,
         entities: [impl entity::Ref<Archetype = A>; N],
-    ) -> Option<[&mut C; N]> {
+    ) -> Option<[comp::Mut<'_, A, C>; N]> {
         self.storage.get_many_mut(entities.map(|entity| entity.id()))
     }
 
     /// Iterates over mutable references to all initialized components in this storage.
     pub fn iter_mut<'t>(
         &'t mut self,
-    ) -> impl Iterator<Item = (entity::TempRef<'t, A>, &'t mut C)> + 't {
+    ) -> impl Iterator<Item = (entity::TempRef<'t, A>, comp::Mut<'t, A, C>)> + 't {
         self.storage.iter_mut().map(|(entity, comp)| (entity::TempRef::new(entity), comp))
     }
 }
@@ -214,7 +297,11 @@ where
     A: Archetype,
     C: comp::SimpleOrIsotope<A> + comp::Must<A>,
     StorageRef: ops::DerefMut + Sync,
-    StorageRef::Target: storage::Access<RawEntity = <A as Archetype>::RawEntity, Comp = C>,
+    StorageRef::Target: storage::Access<
+        RawEntity = <A as Archetype>::RawEntity,
+        Comp = C,
+        Layout = comp::Layout<A, C>,
+    >,
 {
     /// Returns a mutable reference to the component for the specified entity.
     ///
@@ -224,7 +311,7 @@ where
     /// # Panics
     /// This function panics if the entity is not fully initialized yet.
     /// This happens when an entity is newly created and the cycle hasn't joined yet.
-    pub fn get_mut(&mut self, entity: impl entity::Ref<Archetype = A>) -> &mut C {
+    pub fn get_mut(&mut self, entity: impl entity::Ref<Archetype = A>) -> comp::Mut<'_, A, C> {
         match self.try_get_mut(entity) {
             Some(comp) => comp,
             None => panic!(
@@ -242,7 +329,7 @@ where
     pub fn get_many_mut<const N: usize>(
         &mut self,
         entities: [impl entity::Ref<Archetype = A>; N],
-    ) -> [&mut C; N] {
+    ) -> [comp::Mut<'_, A, C>; N] {
         match self.try_get_many_mut(entities) {
             Some(comps) => comps,
             None => panic!(
@@ -266,7 +353,8 @@ where
     A: Archetype,
     C: comp::SimpleOrIsotope<A>,
     StorageRef: ops::DerefMut + Sync,
-    StorageRef::Target: Storage<RawEntity = <A as Archetype>::RawEntity, Comp = C>,
+    StorageRef::Target:
+        Storage<RawEntity = <A as Archetype>::RawEntity, Comp = C, Layout = comp::Layout<A, C>>,
 {
     /// Overwrites the component for the specified entity.
     ///
@@ -282,7 +370,7 @@ where
     A: Archetype,
     C: comp::SimpleOrIsotope<A>,
     StorageRef: ops::DerefMut + Sync,
-    StorageRef::Target: Storage<RawEntity = A::RawEntity, Comp = C>,
+    StorageRef::Target: Storage<RawEntity = A::RawEntity, Comp = C, Layout = comp::Layout<A, C>>,
 {
     /// Converts the accessor to a mutably borrowed partition that covers all entities.
     ///
@@ -306,7 +394,8 @@ where
     A: Archetype,
     C: comp::SimpleOrIsotope<A> + comp::Must<A>,
     StorageRef: ops::DerefMut + Sync,
-    StorageRef::Target: Storage<RawEntity = <A as Archetype>::RawEntity, Comp = C>,
+    StorageRef::Target:
+        Storage<RawEntity = <A as Archetype>::RawEntity, Comp = C, Layout = comp::Layout<A, C>>,
 {
     /// Iterates over all entities in parallel.
     ///
@@ -314,7 +403,7 @@ where
     pub fn par_iter_mut<'t>(
         &'t mut self,
         snapshot: &'t ealloc::Snapshot<<A as Archetype>::RawEntity>,
-    ) -> impl ParallelIterator<Item = (entity::TempRef<'t, A>, &'t mut C)> {
+    ) -> impl ParallelIterator<Item = (entity::TempRef<'t, A>, comp::Mut<'t, A, C>)> {
         rayon::iter::split((self.as_partition(), snapshot.as_slice()), |(partition, slice)| {
             let Some(midpt) = slice.midpoint_for_split() else { return ((partition, slice), None) };
             let (slice_left, slice_right) = slice.split_at(midpt);
@@ -329,7 +418,8 @@ impl<'t, A, C, StorageT> Single<A, C, util::OwnedDeref<StorageT>>
 where
     A: Archetype,
     C: comp::SimpleOrIsotope<A>,
-    StorageT: storage::Partition<'t, RawEntity = A::RawEntity, Comp = C>,
+    StorageT:
+        storage::Partition<'t, RawEntity = A::RawEntity, Comp = C, Layout = comp::Layout<A, C>>,
 {
     /// Splits the accessor into two partitions.
     ///
@@ -352,7 +442,10 @@ where
 
     /// Gets the component value of an entity accessible by this partition,
     /// preserving the lifetime `'t` of this partition object.
-    pub fn try_into_mut(self, entity: impl entity::Ref<Archetype = A>) -> Option<&'t mut C> {
+    pub fn try_into_mut(
+        self,
+        entity: impl entity::Ref<Archetype = A>,
+    ) -> Option<comp::Mut<'t, A, C>> {
         self.storage.0.into_mut(entity.id())
     }
 }
@@ -361,7 +454,8 @@ impl<'t, A, C, StorageT> Single<A, C, util::OwnedDeref<StorageT>>
 where
     A: Archetype,
     C: comp::SimpleOrIsotope<A> + comp::Must<A>,
-    StorageT: storage::Partition<'t, RawEntity = A::RawEntity, Comp = C>,
+    StorageT:
+        storage::Partition<'t, RawEntity = A::RawEntity, Comp = C, Layout = comp::Layout<A, C>>,
 {
     /// Gets the component value of an entity accessible by this partition,
     /// preserving the lifetime `'t` of this partition object.
@@ -372,7 +466,7 @@ where
     /// # Panics
     /// This function panics if the entity is not fully initialized yet.
     /// This happens when an entity is newly created and the cycle hasn't joined yet.
-    pub fn into_mut(self, entity: impl entity::Ref<Archetype = A>) -> &'t mut C {
+    pub fn into_mut(self, entity: impl entity::Ref<Archetype = A>) -> comp::Mut<'t, A, C> {
         match self.try_into_mut(entity) {
             Some(comp) => comp,
             None => panic!(
@@ -384,7 +478,9 @@ where
     }
 
     /// Iterates over mutable references to all initialized components in this partition.
-    pub fn into_iter_mut(self) -> impl Iterator<Item = (entity::TempRef<'t, A>, &'t mut C)> {
+    pub fn into_iter_mut(
+        self,
+    ) -> impl Iterator<Item = (entity::TempRef<'t, A>, comp::Mut<'t, A, C>)> {
         self.storage.0.into_iter_mut().map(|(entity, data)| (entity::TempRef::new(entity), data))
     }
 }
@@ -400,7 +496,11 @@ where
     A: Archetype,
     C: comp::SimpleOrIsotope<A> + comp::Must<A>,
     StorageRef: ops::DerefMut + Sync,
-    StorageRef::Target: storage::Chunked<RawEntity = <A as Archetype>::RawEntity, Comp = C>,
+    StorageRef::Target: storage::Chunked<
+        RawEntity = <A as Archetype>::RawEntity,
+        Comp = C,
+        Layout = comp::Layout<A, C>,
+    >,
     for<'u> <StorageRef::Target as Storage>::Partition<'u>: storage::PartitionChunked<'u>,
 {
     /// Returns the chunk of components as a mutable slice.
@@ -410,7 +510,7 @@ where
     /// In general, if [`comp::Must`] is implemented correctly,
     /// users should not obtain an [`entity::TempRefChunk`] that includes an uninitialized entity,
     /// so panic is practically impossible.
-    pub fn get_chunk_mut(&mut self, chunk: entity::TempRefChunk<A>) -> &[C] {
+    pub fn get_chunk_mut(&mut self, chunk: entity::TempRefChunk<A>) -> comp::Slice<'_, A, C> {
         self.storage.get_chunk(chunk.start, chunk.end).expect("chunk is not completely filled")
     }
 
@@ -420,7 +520,7 @@ where
     pub fn par_iter_chunks_mut<'t>(
         &'t mut self,
         snapshot: &'t ealloc::Snapshot<<A as Archetype>::RawEntity>,
-    ) -> impl ParallelIterator<Item = (entity::TempRefChunk<'t, A>, &'t mut [C])> {
+    ) -> impl ParallelIterator<Item = (entity::TempRefChunk<'t, A>, comp::SliceMut<'t, A, C>)> {
         rayon::iter::split((self.as_partition(), snapshot.as_slice()), |(partition, slice)| {
             let Some(midpt) = slice.midpoint_for_split() else { return ((partition, slice), None) };
             let (slice_left, slice_right) = slice.split_at(midpt);
@@ -435,7 +535,12 @@ impl<'t, A, C, StorageT> Single<A, C, util::OwnedDeref<StorageT>>
 where
     A: Archetype,
     C: comp::SimpleOrIsotope<A> + comp::Must<A>,
-    StorageT: storage::PartitionChunked<'t, RawEntity = A::RawEntity, Comp = C>,
+    StorageT: storage::PartitionChunked<
+        't,
+        RawEntity = A::RawEntity,
+        Comp = C,
+        Layout = comp::Layout<A, C>,
+    >,
 {
     /// Returns the chunk of components as a mutable slice,
     /// preserving the lifetime `'t` of this partition object.
@@ -445,7 +550,7 @@ where
     /// In general, if [`comp::Must`] is implemented correctly,
     /// users should not obtain an [`entity::TempRefChunk`] that includes an uninitialized entity,
     /// so panic is practically impossible.
-    pub fn into_chunk_mut(self, chunk: entity::TempRefChunk<A>) -> &'t mut [C] {
+    pub fn into_chunk_mut(self, chunk: entity::TempRefChunk<A>) -> comp::SliceMut<'t, A, C> {
         match self.storage.0.into_chunk_mut(chunk.start, chunk.end) {
             Some(comp) => comp,
             None => panic!(
@@ -459,7 +564,7 @@ where
     /// Iterates over mutable references to all initialized components in this storage.
     pub fn into_iter_chunks_mut(
         self,
-    ) -> impl Iterator<Item = (entity::TempRefChunk<'t, A>, &'t mut [C])> {
+    ) -> impl Iterator<Item = (entity::TempRefChunk<'t, A>, comp::SliceMut<'t, A, C>)> {
         self.storage
             .0
             .into_iter_chunks_mut()
@@ -0,0 +1,95 @@
+//! Run criteria for conditional system execution.
+
+use std::num::NonZeroU64;
+use std::ops;
+use std::sync::atomic::{self, AtomicU64};
+
+use super::spec;
+use crate::{world, Global};
+
+/// A predicate that decides whether a system runs in a cycle.
+///
+/// The condition is evaluated when the system is scheduled to start,
+/// i.e. after all systems it depends on have completed.
+/// If the condition does not hold, the system is skipped for the cycle,
+/// but it still counts as completed for partition ordering,
+/// so systems that depend on it are woken as usual.
+///
+/// Global states read by the condition are requested with shared access
+/// on behalf of the system, so the usual conflict checking applies.
+///
+/// Conditions are attached through `#[system(run_if = expr)]`
+/// or [`Builder::schedule_with_condition`](crate::world::Builder::schedule_with_condition).
+/// If multiple conditions are attached to the same system,
+/// the system only runs when all of them hold.
+pub struct RunCondition {
+    pub(crate) global_requests: Vec<spec::GlobalRequest>,
+    predicate:                  Box<dyn Fn(&world::SyncGlobals) -> bool + Send + Sync>,
+}
+
+impl RunCondition {
+    /// A condition that holds when `predicate` returns true for the global state `G`.
+    pub fn global<G: Global + Send + Sync>(
+        predicate: impl Fn(&G) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            global_requests: vec![spec::GlobalRequest::new_sync::<G>(false)],
+            predicate:       Box::new(move |globals| predicate(&globals.read::<G>())),
+        }
+    }
+
+    /// A condition that holds once every `period` evaluations,
+    /// starting from the first evaluation.
+    ///
+    /// Since the condition is evaluated once per cycle,
+    /// this runs the system every `period` cycles.
+    pub fn every(period: NonZeroU64) -> Self {
+        let counter = AtomicU64::new(0);
+        Self {
+            global_requests: Vec::new(),
+            predicate:       Box::new(move |_| {
+                counter.fetch_add(1, atomic::Ordering::Relaxed) % period.get() == 0
+            }),
+        }
+    }
+
+    /// A condition that holds when both `self` and `other` hold.
+    ///
+    /// `other` is not evaluated if `self` does not hold.
+    pub fn and(self, other: Self) -> Self { self.combine(other, |a, b| a() && b()) }
+
+    /// A condition that holds when either `self` or `other` holds.
+    ///
+    /// `other` is not evaluated if `self` holds.
+    pub fn or(self, other: Self) -> Self { self.combine(other, |a, b| a() || b()) }
+
+    fn combine(
+        mut self,
+        mut other: Self,
+        op: fn(&dyn Fn() -> bool, &dyn Fn() -> bool) -> bool,
+    ) -> Self {
+        self.global_requests.append(&mut other.global_requests);
+        let (left, right) = (self.predicate, other.predicate);
+        Self {
+            global_requests: self.global_requests,
+            predicate:       Box::new(move |globals| op(&|| left(globals), &|| right(globals))),
+        }
+    }
+
+    /// Evaluates the condition.
+    pub(crate) fn evaluate(&self, globals: &world::SyncGlobals) -> bool {
+        (self.predicate)(globals)
+    }
+}
+
+impl ops::Not for RunCondition {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        let predicate = self.predicate;
+        Self {
+            global_requests: self.global_requests,
+            predicate:       Box::new(move |globals| !predicate(globals)),
+        }
+    }
+}
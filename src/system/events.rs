@@ -0,0 +1,97 @@
+use std::cell::RefCell;
+
+use parking_lot::{RwLock, RwLockReadGuard};
+
+use crate::world::{self, offline};
+
+/// Sends events of type `T` to [`EventReader`]s.
+///
+/// Systems requesting an `EventWriter<T>` run before
+/// [`EventPartition::new::<T>()`](super::EventPartition).
+/// Events sent by writers on different worker threads are appended to separate buffers,
+/// so writers of the same event type can run concurrently.
+/// The order of events sent from different threads is unspecified.
+pub struct EventWriter<'t, T> {
+    buffer: &'t RwLock<Vec<T>>,
+}
+
+impl<'t, T> EventWriter<'t, T> {
+    /// Constructs an event writer from a macro.
+    ///
+    /// The offline buffer shard identifies the worker thread running the system.
+    pub fn new(
+        events: &'t world::Events<T>,
+        offline_buffer: &RefCell<&mut offline::BufferShard>,
+    ) -> Self {
+        Self { buffer: events.shard(offline_buffer.borrow().index()) }
+    }
+
+    /// Sends an event.
+    pub fn send(&mut self, event: T) { self.buffer.write().push(event); }
+
+    /// Sends all events from an iterator.
+    pub fn send_all(&mut self, events: impl IntoIterator<Item = T>) {
+        self.buffer.write().extend(events);
+    }
+}
+
+/// The position of an [`EventReader`] in an event channel.
+///
+/// The cursor is stored in the local state of the reading system,
+/// so each reader receives each event at most once.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EventCursor(u64);
+
+/// Receives events of type `T` sent by [`EventWriter`]s.
+///
+/// Systems requesting an `EventReader<T>` run after
+/// [`EventPartition::new::<T>()`](super::EventPartition),
+/// so events sent in the current cycle are received in the same cycle.
+/// Events that were not received by the end of the next cycle
+/// (e.g. because the reader was scheduled later) are lost.
+pub struct EventReader<'t, T> {
+    previous:       &'t [T],
+    previous_start: u64,
+    current:        Vec<RwLockReadGuard<'t, Vec<T>>>,
+    cursor:         &'t mut EventCursor,
+}
+
+impl<'t, T> EventReader<'t, T> {
+    /// Constructs an event reader from a macro.
+    pub fn new(events: &'t world::Events<T>, cursor: &'t mut EventCursor) -> Self {
+        let (previous, previous_start, current) = events.lock_read();
+        Self { previous, previous_start, current, cursor }
+    }
+
+    fn end(&self) -> u64 {
+        let current_len: usize = self.current.iter().map(|shard| shard.len()).sum();
+        self.previous_start + (self.previous.len() + current_len) as u64
+    }
+
+    /// Returns the index in the retained events of the first unread event.
+    fn skip(&self) -> usize {
+        usize::try_from(self.cursor.0.saturating_sub(self.previous_start))
+            .expect("cursor cannot exceed the number of retained events")
+    }
+
+    /// Iterates over the events not yet received by this reader,
+    /// marking all of them as received.
+    pub fn read(&mut self) -> impl Iterator<Item = &T> + '_ {
+        let skip = self.skip();
+        self.cursor.0 = self.end();
+
+        self.previous.iter().chain(self.current.iter().flat_map(|shard| shard.iter())).skip(skip)
+    }
+
+    /// Returns the number of events not yet received by this reader.
+    pub fn len(&self) -> usize {
+        let retained = self.end() - self.previous_start;
+        usize::try_from(retained).expect("retained events fit in memory") - self.skip()
+    }
+
+    /// Returns whether all events have been received by this reader.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Marks all events as received without reading them.
+    pub fn clear(&mut self) { self.cursor.0 = self.end(); }
+}
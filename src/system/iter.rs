@@ -101,6 +101,45 @@ impl<A: Archetype> EntityIterator<A> {
         })
     }
 
+    fn iter_tagged<'f>(
+        &'f self,
+        filter: &'f TagFilter<'_, A>,
+    ) -> impl Iterator<Item = A::RawEntity> + 'f {
+        self.ealloc
+            .iter_allocated_chunks()
+            .flat_map(move |chunk| {
+                filter.matches(chunk.start.to_primitive()..chunk.end.to_primitive())
+            })
+            .map(<A::RawEntity as entity::Raw>::from_primitive)
+    }
+
+    /// Iterates over all entity IDs in this archetype that match the [tag filter](TagFilter).
+    pub fn entities_tagged<'f>(
+        &'f self,
+        filter: &'f TagFilter<'_, A>,
+    ) -> impl Iterator<Item = entity::TempRef<'f, A>> + 'f {
+        self.iter_tagged(filter).map(entity::TempRef::new)
+    }
+
+    /// Iterates over all entities that match the [tag filter](TagFilter),
+    /// yielding the components requested.
+    ///
+    /// This is equivalent to filtering the output of [`entities_with`](Self::entities_with),
+    /// but unmatched entities are skipped with word-level bit operations
+    /// without accessing the zipped storages.
+    pub fn entities_with_tags<'f, IntoZ: IntoZip<A>>(
+        &'f self,
+        filter: &'f TagFilter<'_, A>,
+        zip: IntoZ,
+    ) -> impl Iterator<Item = (entity::TempRef<'f, A>, <IntoZ::IntoZip as Zip<A>>::Item)> + 'f
+    where
+        IntoZ::IntoZip: 'f,
+    {
+        let mut zip = ZipIter(zip.into_zip(), PhantomData);
+        self.iter_tagged(filter)
+            .map(move |entity| (entity::TempRef::new(entity), zip.take_serial(entity)))
+    }
+
     fn par_raw_chunks<IntoZ: IntoZip<A>>(
         &self,
         zip: IntoZ,
@@ -227,6 +266,7 @@ pub trait ZipChunked<A: Archetype>: Zip<A> {
 /// - [`&ReadSimple`](crate::system::ReadSimple) and [`&mut WriteSimple`](crate::system::WriteSimple)
 /// - Shared/mutable references to [split](access::Isotope::split) isotope accessors
 /// - Any of the above wrapped with [`Try`] for [optional](comp::Presence::Optional) components.
+/// - Shared references to change-tracked accessors wrapped with [`Changed`].
 /// - Non-empty tuples of `Zip` implementors, including other tuples.
 /// - Structs of `Zip` fields that use the [`Zip`](crate::zip) derive macro.
 ///
@@ -295,6 +335,117 @@ where
     fn into_zip(self) -> Self::IntoZip { Read { accessor: self, _ph: PhantomData } }
 }
 
+/// Wrap a reference to a [change-tracked](storage::Tracked) read accessor with `Changed`
+/// to only yield components changed after the given tick.
+///
+/// The item type is `Option<&C>`,
+/// which is `None` if the component is missing or unchanged since the tick.
+pub struct Changed<T>(pub T, pub storage::Tick);
+
+impl<'t, A, C, AccessorT> IntoZip<A> for Changed<&'t AccessorT>
+where
+    A: Archetype,
+    C: comp::SimpleOrIsotope<A>,
+    AccessorT: single::GetChanged<Arch = A, Comp = C>,
+{
+    type IntoZip = ReadChanged<'t, A, C, AccessorT>;
+    fn into_zip(self) -> Self::IntoZip {
+        ReadChanged { accessor: self.0, since: self.1, _ph: PhantomData }
+    }
+}
+
+/// [`IntoZip::IntoZip`] for [`Changed`] accessors.
+pub struct ReadChanged<'t, A, C, AccessorT> {
+    accessor: &'t AccessorT,
+    since:    storage::Tick,
+    _ph:      PhantomData<(A, C)>,
+}
+
+impl<'t, A, C, AccessorT> Copy for ReadChanged<'t, A, C, AccessorT> {}
+impl<'t, A, C, AccessorT> Clone for ReadChanged<'t, A, C, AccessorT> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'t, A, C, AccessorT> Zip<A> for ReadChanged<'t, A, C, AccessorT>
+where
+    A: Archetype,
+    C: comp::SimpleOrIsotope<A>,
+    AccessorT: single::GetChanged<Arch = A, Comp = C>,
+{
+    fn split(&mut self, _offset: A::RawEntity) -> Self { *self }
+
+    type Item = Option<comp::Ref<'t, A, C>>;
+    fn get<E: entity::Ref<Archetype = A>>(self, entity: E) -> Self::Item {
+        self.accessor.try_get_changed(entity, self.since)
+    }
+}
+
+/// Filters entities by the presence of [tag](storage::Tag) components,
+/// to be used with [`EntityIterator::entities_tagged`]
+/// and [`EntityIterator::entities_with_tags`].
+///
+/// The tag bitsets are combined one word at a time,
+/// so filtering over a large number of entities only costs a few instructions per 64 entities.
+pub struct TagFilter<'t, A: Archetype> {
+    with:    Vec<storage::TagBits<'t>>,
+    without: Vec<storage::TagBits<'t>>,
+    _ph:     PhantomData<A>,
+}
+
+impl<'t, A: Archetype> Default for TagFilter<'t, A> {
+    fn default() -> Self { Self { with: Vec::new(), without: Vec::new(), _ph: PhantomData } }
+}
+
+impl<'t, A: Archetype> TagFilter<'t, A> {
+    /// Creates a filter that matches all entities.
+    pub fn new() -> Self { Self::default() }
+
+    /// Only matches entities with the tag of `accessor`.
+    pub fn with(mut self, accessor: &'t impl single::GetTag<Arch = A>) -> Self {
+        self.with.push(accessor.tag_bits());
+        self
+    }
+
+    /// Only matches entities without the tag of `accessor`.
+    pub fn without(mut self, accessor: &'t impl single::GetTag<Arch = A>) -> Self {
+        self.without.push(accessor.tag_bits());
+        self
+    }
+
+    /// Yields the indices in `range` that match the filter.
+    fn matches(&self, range: ops::Range<usize>) -> impl Iterator<Item = usize> + '_ {
+        const WORD_BITS: usize = storage::TagBits::WORD_BITS;
+
+        let words = (range.start / WORD_BITS)..range.end.div_ceil(WORD_BITS);
+        words.flat_map(move |word_index| {
+            let base = word_index * WORD_BITS;
+
+            let mut word = usize::MAX;
+            for bits in &self.with {
+                word &= bits.word(word_index);
+            }
+            for bits in &self.without {
+                word &= !bits.word(word_index);
+            }
+
+            if range.start > base {
+                word &= usize::MAX << (range.start - base);
+            }
+            if range.end - base < WORD_BITS {
+                word &= (1 << (range.end - base)) - 1;
+            }
+
+            iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    base + bit
+                })
+            })
+        })
+    }
+}
+
 /// [`IntoZip::IntoZip`] for read-only accessors.
 pub struct Read<'t, A, C, AccessorT, Resln> {
     accessor: &'t AccessorT,
@@ -315,8 +466,8 @@ where
 {
     fn split(&mut self, _offset: A::RawEntity) -> Self { *self }
 
-    type Item = Resln::Result<&'t C>;
-    fn get<E: entity::Ref<Archetype = A>>(self, entity: E) -> Resln::Result<&'t C> {
+    type Item = Resln::Result<comp::Ref<'t, A, C>>;
+    fn get<E: entity::Ref<Archetype = A>>(self, entity: E) -> Self::Item {
         Resln::must_or_try(self.accessor.try_get(entity))
     }
 }
@@ -327,12 +478,14 @@ where
     C: comp::SimpleOrIsotope<A> + comp::Must<A>,
     AccessorT: single::Get<Arch = A, Comp = C> + single::GetChunked<Arch = A, Comp = C>,
 {
-    type Chunk = &'t [C];
+    type Chunk = comp::Slice<'t, A, C>;
     fn get_chunk(self, chunk: entity::TempRefChunk<A>) -> Self::Chunk {
         self.accessor.get_chunk(chunk)
     }
 
-    fn chunk_to_entities(chunk: Self::Chunk) -> impl Iterator<Item = &'t C> { chunk.iter() }
+    fn chunk_to_entities(chunk: Self::Chunk) -> impl Iterator<Item = comp::Ref<'t, A, C>> {
+        chunk.into_iter()
+    }
 }
 
 impl<'t, A, C, StorageRef> IntoZip<A> for Try<&'t mut access::Single<A, C, StorageRef>>
@@ -340,7 +493,7 @@ where
     A: Archetype,
     C: comp::SimpleOrIsotope<A>,
     StorageRef: ops::DerefMut + Sync,
-    StorageRef::Target: Storage<RawEntity = A::RawEntity, Comp = C>,
+    StorageRef::Target: Storage<RawEntity = A::RawEntity, Comp = C, Layout = comp::Layout<A, C>>,
 {
     type IntoZip = Write<
         't,
@@ -359,7 +512,7 @@ where
     A: Archetype,
     C: comp::SimpleOrIsotope<A> + comp::Must<A>,
     StorageRef: ops::DerefMut + Sync,
-    StorageRef::Target: Storage<RawEntity = A::RawEntity, Comp = C>,
+    StorageRef::Target: Storage<RawEntity = A::RawEntity, Comp = C, Layout = comp::Layout<A, C>>,
 {
     type IntoZip = Write<
         't,
@@ -381,7 +534,8 @@ impl<'t, A, C, PartitionT, Resln> Zip<A> for Write<'t, A, C, util::OwnedDeref<Pa
 where
     A: Archetype,
     C: comp::SimpleOrIsotope<A>,
-    PartitionT: storage::Partition<'t, RawEntity = A::RawEntity, Comp = C>,
+    PartitionT:
+        storage::Partition<'t, RawEntity = A::RawEntity, Comp = C, Layout = comp::Layout<A, C>>,
     Resln: MissingResln,
 {
     fn split(&mut self, offset: A::RawEntity) -> Self {
@@ -389,8 +543,8 @@ where
         Self { accessor: right, _ph: PhantomData }
     }
 
-    type Item = Resln::Result<&'t mut C>;
-    fn get<E: entity::Ref<Archetype = A>>(self, entity: E) -> Resln::Result<&'t mut C> {
+    type Item = Resln::Result<comp::Mut<'t, A, C>>;
+    fn get<E: entity::Ref<Archetype = A>>(self, entity: E) -> Self::Item {
         Resln::must_or_try(self.accessor.try_into_mut(entity))
     }
 }
@@ -400,14 +554,21 @@ impl<'t, A, C, PartitionT> ZipChunked<A>
 where
     A: Archetype,
     C: comp::SimpleOrIsotope<A> + comp::Must<A>,
-    PartitionT: storage::PartitionChunked<'t, RawEntity = A::RawEntity, Comp = C>,
+    PartitionT: storage::PartitionChunked<
+        't,
+        RawEntity = A::RawEntity,
+        Comp = C,
+        Layout = comp::Layout<A, C>,
+    >,
 {
-    type Chunk = &'t mut [C];
+    type Chunk = comp::SliceMut<'t, A, C>;
     fn get_chunk(self, chunk: entity::TempRefChunk<A>) -> Self::Chunk {
         self.accessor.into_chunk_mut(chunk)
     }
 
-    fn chunk_to_entities(chunk: Self::Chunk) -> impl Iterator<Item = &'t mut C> { chunk.iter_mut() }
+    fn chunk_to_entities(chunk: Self::Chunk) -> impl Iterator<Item = comp::Mut<'t, A, C>> {
+        chunk.into_iter()
+    }
 }
 
 mod tuple_impls;
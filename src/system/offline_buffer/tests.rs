@@ -1,8 +1,13 @@
 //! Tests EntityCreator and EntityDeleter.
 
-use crate::entity::{deletion, generation};
+use std::collections::BTreeSet;
+use std::num::NonZeroU32;
+use std::{mem, panic};
+
+use crate::entity::{deletion, ealloc, generation};
 use crate::test_util::*;
-use crate::{global, system, system_test, tracer, world, Entity};
+use crate::util::DbgTypeId;
+use crate::{comp, global, system, system_test, tracer, world, Archetype, Entity};
 
 #[test]
 fn test_entity_create() {
@@ -84,6 +89,74 @@ fn test_entity_create() {
     assert_eq!(comp1, Some(&Simple1OptionalNoDepNoInit(5)));
 }
 
+#[test]
+fn test_entity_create_multiple_archetypes() {
+    enum OtherArch {}
+    impl Archetype for OtherArch {
+        type RawEntity = NonZeroU32;
+        type Ealloc =
+            ealloc::Recycling<NonZeroU32, BTreeSet<NonZeroU32>, ealloc::ThreadRngShardAssigner>;
+    }
+
+    #[comp(dynec_as(crate), of = OtherArch)]
+    #[derive(Debug, PartialEq)]
+    struct OtherComp(i32);
+
+    #[global(dynec_as(crate), initial)]
+    #[derive(Default)]
+    struct CreatedTest(#[entity] Vec<Entity<TestArch>>);
+
+    #[global(dynec_as(crate), initial)]
+    #[derive(Default)]
+    struct CreatedOther(#[entity] Vec<Entity<OtherArch>>);
+
+    #[system(dynec_as(crate))]
+    fn create_test_system(
+        mut entity_creator: system::EntityCreator<TestArch>,
+        #[dynec(global(maybe_uninit(TestArch)))] created: &mut CreatedTest,
+        _comp: system::ReadSimple<TestArch, Simple1OptionalNoDepNoInit>,
+    ) {
+        for i in 0..64 {
+            created.0.push(
+                entity_creator
+                    .create(crate::comps![@(crate) TestArch => Simple1OptionalNoDepNoInit(i)]),
+            );
+        }
+    }
+
+    #[system(dynec_as(crate))]
+    fn create_other_system(
+        mut entity_creator: system::EntityCreator<OtherArch>,
+        #[dynec(global(maybe_uninit(OtherArch)))] created: &mut CreatedOther,
+        _comp: system::ReadSimple<OtherArch, OtherComp>,
+    ) {
+        for i in 0..64 {
+            created
+                .0
+                .push(entity_creator.create(crate::comps![@(crate) OtherArch => OtherComp(i)]));
+        }
+    }
+
+    let mut builder = world::Builder::new(2);
+    builder.schedule(create_test_system.build());
+    builder.schedule(create_other_system.build());
+    let mut world = builder.build();
+
+    world.execute(&tracer::Log(log::Level::Trace));
+
+    let created = mem::take(&mut world.get_global::<CreatedTest>().0);
+    let storage = world.components.get_simple_storage::<TestArch, Simple1OptionalNoDepNoInit>();
+    for (i, ent) in (0..).zip(&created) {
+        assert_eq!(storage.try_get(ent), Some(&Simple1OptionalNoDepNoInit(i)));
+    }
+
+    let created = mem::take(&mut world.get_global::<CreatedOther>().0);
+    let storage = world.components.get_simple_storage::<OtherArch, OtherComp>();
+    for (i, ent) in (0..).zip(&created) {
+        assert_eq!(storage.try_get(ent), Some(&OtherComp(i)));
+    }
+}
+
 #[test]
 #[should_panic = "Scheduled systems have a cyclic dependency: "]
 fn test_entity_create_conflict() {
@@ -113,6 +186,29 @@ fn test_entity_create_conflict() {
     assert_eq!(comp1, Some(&Simple1OptionalNoDepNoInit(5)));
 }
 
+#[test]
+fn test_entity_create_init_panic_restores_archetype() {
+    #[system(dynec_as(crate))]
+    fn create_system(
+        mut entity_creator: system::EntityCreator<TestArch>,
+        _comp: system::ReadSimple<TestArch, Simple5RequiredNoInit>,
+    ) {
+        // the required component is missing, so the initialization panics
+        entity_creator.create(crate::comps![@(crate) TestArch => ]);
+    }
+
+    let mut world = system_test!(create_system.build(););
+
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        world.execute(&tracer::Log(log::Level::Trace));
+    }));
+    assert!(result.is_err(), "missing required component should panic");
+
+    let ty = DbgTypeId::of::<TestArch>();
+    assert!(world.components.archetypes.contains_key(&ty));
+    assert!(world.ealloc_map.map.contains_key(&ty));
+}
+
 #[test]
 fn test_entity_delete() {
     #[system(dynec_as(crate))]
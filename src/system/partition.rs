@@ -132,3 +132,29 @@ impl EntityCreationPartition {
 
 #[cfg(test)]
 crate::assert_partition!(EntityCreationPartition);
+
+/// Builtin partition for ordering event readers after event writers.
+///
+/// All systems that request an [`EventWriter<T>`](crate::system::EventWriter)
+/// run before `EventPartition::new::<T>()`,
+/// and all systems that request an [`EventReader<T>`](crate::system::EventReader)
+/// run after it.
+/// Therefore, a system cannot both read and write the same event type.
+#[derive(PartialEq, Eq, Hash)]
+pub struct EventPartition {
+    pub(crate) ty: DbgTypeId,
+}
+
+impl fmt::Debug for EventPartition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "EventPartition<{}>", self.ty)
+    }
+}
+
+impl EventPartition {
+    /// Constructs an EventPartition with the given event type.
+    pub fn new<T: 'static>() -> Self { Self { ty: DbgTypeId::of::<T>() } }
+}
+
+#[cfg(test)]
+crate::assert_partition!(EventPartition);
@@ -0,0 +1,49 @@
+use std::marker::PhantomData;
+
+use crate::{entity, storage, Archetype};
+
+/// Reads the add/remove events of a simple component
+/// recorded since the start of the previous cycle.
+///
+/// The component must be declared with `#[comp(events)]`.
+/// Events include insertions and removals through [`AccessSingle::set`](super::access::Single::set),
+/// entity creation and entity deletion,
+/// whether they happened in systems of the previous cycle or offline before the current cycle.
+/// Events are ordered by the time they happened.
+///
+/// Events are delivered at the start of each cycle and remain unchanged during the cycle,
+/// so subscribers never conflict with systems that write the component
+/// and do not need to declare any ordering against them.
+///
+/// Entities of [`Removed`](storage::Event::Removed) events may have been deleted already.
+pub struct SimpleEvents<'t, A: Archetype, C> {
+    events: &'t [(A::RawEntity, storage::Event)],
+    _ph:    PhantomData<C>,
+}
+
+impl<'t, A: Archetype, C> SimpleEvents<'t, A, C> {
+    pub(crate) fn new(events: &'t [(A::RawEntity, storage::Event)]) -> Self {
+        Self { events, _ph: PhantomData }
+    }
+
+    /// Iterates over all delivered events.
+    pub fn iter(&self) -> impl Iterator<Item = (entity::TempRef<'t, A>, storage::Event)> + 't {
+        self.events.iter().map(|&(entity, event)| (entity::TempRef::new(entity), event))
+    }
+
+    /// Iterates over entities to which the component was added.
+    pub fn added(&self) -> impl Iterator<Item = entity::TempRef<'t, A>> + 't {
+        self.iter().filter(|&(_, event)| event == storage::Event::Added).map(|(entity, _)| entity)
+    }
+
+    /// Iterates over entities from which the component was removed.
+    pub fn removed(&self) -> impl Iterator<Item = entity::TempRef<'t, A>> + 't {
+        self.iter().filter(|&(_, event)| event == storage::Event::Removed).map(|(entity, _)| entity)
+    }
+
+    /// Returns the number of delivered events.
+    pub fn len(&self) -> usize { self.events.len() }
+
+    /// Returns whether no events were delivered.
+    pub fn is_empty(&self) -> bool { self.events.is_empty() }
+}
@@ -19,8 +19,18 @@ pub struct Spec {
     pub simple_requests:         Vec<SimpleRequest>,
     /// The isotope components requested by the system.
     pub isotope_requests:        Vec<IsotopeRequest>,
+    /// The dynamic components requested by the system.
+    pub dynamic_requests:        Vec<DynamicRequest>,
+    /// The simple components whose add/remove events are subscribed by the system.
+    pub simple_event_requests:   Vec<SimpleEventRequest>,
+    /// The event types sent or received by the system.
+    pub event_requests:          Vec<EventRequest>,
     /// The archetypes of which entities may be created.
     pub entity_creator_requests: Vec<EntityCreatorRequest>,
+    /// The conditions that must all hold for the system to run in a cycle.
+    pub run_conditions:          Vec<system::RunCondition>,
+    /// Whether an error returned by the system blocks its dependents.
+    pub on_error:                system::OnError,
 }
 
 /// Indicates the dependency of a system.
@@ -216,6 +226,76 @@ impl IsotopeRequest {
     }
 }
 
+/// Indicates that the system subscribes to add/remove events of a simple component.
+pub struct SimpleEventRequest {
+    /// The archetype requested.
+    pub(crate) arch:            ArchetypeDescriptor,
+    /// The type ID of the component.
+    pub(crate) comp:            DbgTypeId,
+    /// Builds the storage if the component is not used by other systems.
+    pub(crate) storage_builder: fn() -> Box<dyn Any>,
+}
+
+impl SimpleEventRequest {
+    /// Creates a new event subscription for the simple component `C` of archetype `A`.
+    pub fn new<A: Archetype, C: comp::Simple<A>>() -> Self
+    where
+        C::Storage: storage::EventSource,
+    {
+        Self {
+            arch:            ArchetypeDescriptor::of::<A>(),
+            comp:            DbgTypeId::of::<C>(),
+            storage_builder: storage::simple::builder::<A, C> as fn() -> Box<dyn Any>,
+        }
+    }
+}
+
+/// Indicates that the system sends or receives events of a type.
+pub struct EventRequest {
+    /// The event type.
+    pub(crate) ty:      DbgTypeId,
+    /// Builds the event channel with the given number of shards.
+    pub(crate) builder: world::events::Builder,
+    /// Whether the system sends events.
+    pub(crate) writer:  bool,
+}
+
+impl EventRequest {
+    /// Creates a request to send events of type `T`.
+    pub fn new_writer<T: Send + Sync + 'static>() -> Self { Self::new::<T>(true) }
+
+    /// Creates a request to receive events of type `T`.
+    pub fn new_reader<T: Send + Sync + 'static>() -> Self { Self::new::<T>(false) }
+
+    fn new<T: Send + Sync + 'static>(writer: bool) -> Self {
+        Self {
+            ty: DbgTypeId::of::<T>(),
+            builder: |num_shards| Box::new(world::Events::<T>::new(num_shards)),
+            writer,
+        }
+    }
+}
+
+/// Indicates that the system requires a [dynamic component](comp::dynamic) read/write.
+pub struct DynamicRequest {
+    /// The archetype requested.
+    pub(crate) arch:       ArchetypeDescriptor,
+    /// The descriptor of the dynamic component.
+    pub(crate) descriptor: comp::dynamic::Descriptor,
+    /// Whether mutable access is requested.
+    pub(crate) mutable:    bool,
+}
+
+impl DynamicRequest {
+    /// Creates a new dynamic component request for the archetype `A`.
+    ///
+    /// The component is registered if it was not registered for `A` yet.
+    /// Scheduling will panic if the same component ID is requested with different sizes.
+    pub fn new<A: Archetype>(descriptor: comp::dynamic::Descriptor, mutable: bool) -> Self {
+        Self { arch: ArchetypeDescriptor::of::<A>(), descriptor, mutable }
+    }
+}
+
 /// Indicates that the system may create entities for a particular archetype.
 pub struct EntityCreatorRequest {
     /// The archetype requested.
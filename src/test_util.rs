@@ -30,6 +30,8 @@ impl Archetype for TestArch {
     type RawEntity = NonZeroU32;
     type Ealloc =
         ealloc::Recycling<NonZeroU32, BTreeSet<NonZeroU32>, ealloc::ThreadRngShardAssigner>;
+
+    const SERIALIZE_KEY: Option<&'static str> = Some("TestArch");
 }
 
 mod simple_comps;
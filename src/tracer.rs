@@ -42,10 +42,12 @@ pub trait Tracer: Sync {
     /// to [`end_flush_ealloc`](Self::end_flush_ealloc).
     #[dynec(log_time)]
     type FlushEallocContext;
-    /// The executor starts preparing ealloc shards for each worker thread.
+    /// The executor starts flushing the entity allocator of an archetype.
+    ///
+    /// Allocators of different archetypes may be flushed concurrently on worker threads.
     #[dynec(log_return_now)]
     fn start_flush_ealloc(&self, archetype: DbgTypeId) -> Self::FlushEallocContext;
-    /// The executor has partitioned ealloc into different worker threads.
+    /// The executor has flushed the entity allocator of an archetype.
     fn end_flush_ealloc(
         &self,
         #[dynec(log_with = ElapsedFmt)] arg: Self::FlushEallocContext,
@@ -64,6 +66,15 @@ pub trait Tracer: Sync {
     /// A node is unmarked as runnable because an exclusive node has been stolen.
     fn unmark_runnable(&self, node: scheduler::Node);
 
+    /// A node is unmarked as runnable because the exclusive node `blocker` has been stolen.
+    ///
+    /// The default implementation calls [`unmark_runnable`](Self::unmark_runnable),
+    /// so tracers only need to override this method if they use `blocker`.
+    #[allow(unused_variables)]
+    fn unmark_runnable_by(&self, node: scheduler::Node, blocker: scheduler::Node) {
+        self.unmark_runnable(node);
+    }
+
     /// A system has completed. Also passes the number of remaining nodes.
     fn complete_system(&self, node: scheduler::Node, remaining: usize);
 
@@ -115,6 +126,37 @@ pub trait Tracer: Sync {
         #[dynec(log_skip)] system: &mut dyn system::Unsendable,
     );
 
+    /// A fallible system returns an error.
+    ///
+    /// This event is emitted between the start and end of the system run.
+    ///
+    /// The default implementation does nothing for compatibility.
+    #[allow(unused_variables)]
+    fn system_error(
+        &self,
+        thread: Thread,
+        node: scheduler::Node,
+        debug_name: &str,
+        #[dynec(log_with = ErrorFmt)] error: &(dyn std::error::Error + Send + Sync),
+    ) {
+    }
+
+    /// A system is skipped in this cycle because its
+    /// [run conditions](system::RunCondition) do not hold,
+    /// or because a system it depends on returned an error with [`system::OnError::Block`].
+    ///
+    /// The default implementation does nothing for compatibility.
+    #[allow(unused_variables)]
+    fn skip_system(&self, thread: Thread, node: scheduler::Node, debug_name: &str) {}
+
+    /// All threads are waiting for tasks, but the cycle has not completed.
+    ///
+    /// This event is only emitted in debug builds, right before the executor panics.
+    ///
+    /// The default implementation does nothing for compatibility.
+    #[allow(unused_variables)]
+    fn deadlock(&self, deadlock: &scheduler::Deadlock) {}
+
     /// A partition completes.
     fn partition(
         &self,
@@ -129,6 +171,12 @@ impl<'t> fmt::Display for PartitionFmt<'t> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { self.0.describe(f) }
 }
 
+struct ErrorFmt<'t>(&'t (dyn std::error::Error + Send + Sync));
+
+impl<'t> fmt::Display for ErrorFmt<'t> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { fmt::Display::fmt(self.0, f) }
+}
+
 struct ElapsedFmt(time::Instant);
 
 impl fmt::Display for ElapsedFmt {
@@ -158,3 +206,10 @@ pub enum Thread {
     /// A worker thread. The index is in the range `0..concurrency`.
     Worker(usize),
 }
+
+// Declared after `Tracer` so that the polyfill macro generated by `tracer_def` is in scope.
+mod analyzer;
+pub use analyzer::{Analyzer, Report, ReportContention, ReportSystem};
+
+mod chrome;
+pub use chrome::ChromeTrace;
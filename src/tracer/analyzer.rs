@@ -0,0 +1,379 @@
+//! Aggregates system durations and exclusion waits over multiple cycles.
+
+use std::collections::{BTreeMap, HashMap};
+use std::{fmt, time};
+
+use parking_lot::Mutex;
+
+use crate::scheduler::{Graph, GraphNode, Node, ResourceType};
+
+/// A tracer that measures where the time of a cycle is spent.
+///
+/// The analyzer records the duration of each system run,
+/// as well as how long each runnable system is blocked
+/// because a system with an exclusive resource request was started first.
+/// Measurements are accumulated until [`clear`](Self::clear) is called,
+/// so the same analyzer can be passed to multiple cycles to average over them.
+///
+/// Call [`report`](Self::report) with the [`Graph`] of the executed schedule
+/// to compute the critical path and attribute waits to conflicting resources.
+/// Since nodes are identified by their index in the schedule,
+/// the analyzer should be cleared after systems are scheduled or unscheduled.
+pub struct Analyzer {
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    cycles:      usize,
+    cycle_total: time::Duration,
+    systems:     HashMap<Node, Durations>,
+    /// Nodes that are currently unmarked as runnable, with the node that blocked them.
+    waiting:     HashMap<Node, (Node, time::Instant)>,
+    /// Waits keyed by `(blocked, blocker)`.
+    waits:       HashMap<(Node, Node), Durations>,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Durations {
+    count: usize,
+    total: time::Duration,
+    max:   time::Duration,
+}
+
+impl Durations {
+    fn add(&mut self, duration: time::Duration) {
+        self.count += 1;
+        self.total += duration;
+        self.max = self.max.max(duration);
+    }
+}
+
+impl Default for Analyzer {
+    fn default() -> Self { Self::new() }
+}
+
+impl Analyzer {
+    /// Creates an empty analyzer.
+    pub fn new() -> Self { Self { state: Mutex::default() } }
+
+    /// Discards all recorded measurements.
+    pub fn clear(&self) { *self.state.lock() = State::default(); }
+
+    fn with_state(&self, f: impl FnOnce(&mut State)) { f(&mut self.state.lock()) }
+
+    fn end_run(&self, start: time::Instant, node: Node) {
+        let duration = start.elapsed();
+        self.with_state(|state| state.systems.entry(node).or_default().add(duration));
+    }
+
+    /// Aggregates the recorded measurements against the topology of the executed schedule.
+    pub fn report(&self, graph: &Graph) -> Report {
+        let state = self.state.lock();
+
+        let names: HashMap<Node, &GraphNode> =
+            graph.nodes.iter().map(|node| (node.node, node)).collect();
+        let graph_node = |node: Node| match names.get(&node) {
+            Some(&node) => node.clone(),
+            None => GraphNode { node, name: format!("{node:?}") },
+        };
+
+        let mut systems: Vec<_> = state
+            .systems
+            .iter()
+            .map(|(&node, durations)| ReportSystem {
+                node:  graph_node(node),
+                runs:  durations.count,
+                total: durations.total,
+                max:   durations.max,
+            })
+            .collect();
+        systems.sort_by(|a, b| b.total.cmp(&a.total).then(a.node.node.cmp(&b.node.node)));
+
+        let mean_durations: HashMap<Node, time::Duration> =
+            systems.iter().map(|system| (system.node.node, system.mean())).collect();
+        let (critical_path, critical_path_duration) = critical_path(graph, &mean_durations);
+
+        let mut contentions: Vec<_> = state
+            .waits
+            .iter()
+            .map(|(&(blocked, blocker), durations)| {
+                let pair = (blocked.min(blocker), blocked.max(blocker));
+                let resources = graph
+                    .exclusions
+                    .iter()
+                    .find(|exclusion| exclusion.nodes == pair)
+                    .map(|exclusion| exclusion.resources.clone())
+                    .unwrap_or_default();
+                ReportContention {
+                    blocked: graph_node(blocked),
+                    blocker: graph_node(blocker),
+                    resources,
+                    count: durations.count,
+                    total: durations.total,
+                    max: durations.max,
+                }
+            })
+            .collect();
+        contentions.sort_by(|a, b| {
+            b.total
+                .cmp(&a.total)
+                .then(a.blocked.node.cmp(&b.blocked.node))
+                .then(a.blocker.node.cmp(&b.blocker.node))
+        });
+
+        Report {
+            cycles: state.cycles,
+            mean_cycle: mean(state.cycle_total, state.cycles),
+            systems,
+            critical_path: critical_path.into_iter().map(graph_node).collect(),
+            critical_path_duration,
+            contentions,
+        }
+    }
+}
+
+/// Computes the longest path through the order edges of `graph`,
+/// weighting each system with its mean duration.
+fn critical_path(
+    graph: &Graph,
+    durations: &HashMap<Node, time::Duration>,
+) -> (Vec<Node>, time::Duration) {
+    let mut dependents: BTreeMap<Node, Vec<Node>> =
+        graph.nodes.iter().map(|node| (node.node, Vec::new())).collect();
+    let mut dependency_counts: BTreeMap<Node, usize> =
+        graph.nodes.iter().map(|node| (node.node, 0)).collect();
+    for order in &graph.orders {
+        dependents.entry(order.before).or_default().push(order.after);
+        *dependency_counts.entry(order.after).or_default() += 1;
+        dependency_counts.entry(order.before).or_default();
+    }
+
+    // the longest path ending at each node, and the predecessor on that path
+    let mut longest: HashMap<Node, (time::Duration, Option<Node>)> = HashMap::new();
+    let mut queue: Vec<Node> =
+        dependency_counts.iter().filter(|&(_, &count)| count == 0).map(|(&node, _)| node).collect();
+    queue.reverse();
+
+    while let Some(node) = queue.pop() {
+        let weight = durations.get(&node).copied().unwrap_or_default();
+        let entry = longest.entry(node).or_insert((time::Duration::ZERO, None));
+        entry.0 += weight;
+        let end = entry.0;
+
+        for &dependent in dependents.get(&node).into_iter().flatten() {
+            let dependent_entry =
+                longest.entry(dependent).or_insert((time::Duration::ZERO, Some(node)));
+            if end > dependent_entry.0 {
+                *dependent_entry = (end, Some(node));
+            }
+
+            let count = dependency_counts.get_mut(&dependent).expect("dependent is in the map");
+            *count -= 1;
+            if *count == 0 {
+                queue.push(dependent);
+            }
+        }
+    }
+
+    let Some((&last, &(duration, _))) =
+        longest.iter().max_by(|(a_node, a), (b_node, b)| a.0.cmp(&b.0).then(b_node.cmp(a_node)))
+    else {
+        return (Vec::new(), time::Duration::ZERO);
+    };
+
+    let mut path = vec![last];
+    let mut node = last;
+    while let Some(&(_, Some(prev))) = longest.get(&node) {
+        path.push(prev);
+        node = prev;
+    }
+    path.reverse();
+
+    (path, duration)
+}
+
+fn mean(total: time::Duration, count: usize) -> time::Duration {
+    match u32::try_from(count) {
+        Ok(0) => time::Duration::ZERO,
+        Ok(count) => total / count,
+        Err(_) => total.div_f64(count as f64),
+    }
+}
+
+/// The measurements aggregated by an [`Analyzer`].
+///
+/// The [`Display`](fmt::Display) implementation renders the report as human-readable text.
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// The number of cycles measured.
+    pub cycles:                 usize,
+    /// The mean duration of a cycle, including the offline phase.
+    pub mean_cycle:             time::Duration,
+    /// The systems that have run at least once, sorted by descending total duration.
+    pub systems:                Vec<ReportSystem>,
+    /// The chain of systems and partitions with the longest total mean duration.
+    ///
+    /// A cycle cannot complete faster than this path regardless of the number of threads.
+    pub critical_path:          Vec<GraphNode>,
+    /// The sum of the mean durations of the systems in [`critical_path`](Self::critical_path).
+    pub critical_path_duration: time::Duration,
+    /// The exclusion waits between pairs of systems, sorted by descending total duration.
+    pub contentions:            Vec<ReportContention>,
+}
+
+/// The durations of a system in a [`Report`].
+#[derive(Debug, Clone)]
+pub struct ReportSystem {
+    /// The system node.
+    pub node:  GraphNode,
+    /// The number of times the system has run.
+    pub runs:  usize,
+    /// The total duration of all runs.
+    pub total: time::Duration,
+    /// The duration of the slowest run.
+    pub max:   time::Duration,
+}
+
+impl ReportSystem {
+    /// The mean duration of a run.
+    pub fn mean(&self) -> time::Duration { mean(self.total, self.runs) }
+}
+
+/// The time a system spent blocked by another system in a [`Report`].
+///
+/// A wait starts when `blocked` is unmarked as runnable because `blocker` has started,
+/// and ends when `blocked` becomes runnable again.
+#[derive(Debug, Clone)]
+pub struct ReportContention {
+    /// The system that was blocked.
+    pub blocked:   GraphNode,
+    /// The system whose start blocked `blocked`.
+    pub blocker:   GraphNode,
+    /// The resources that the two systems request conflicting access to.
+    pub resources: Vec<ResourceType>,
+    /// The number of times `blocked` was blocked by `blocker`.
+    pub count:     usize,
+    /// The total duration of all waits.
+    pub total:     time::Duration,
+    /// The duration of the longest wait.
+    pub max:       time::Duration,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} cycles, mean cycle duration {:?}", self.cycles, self.mean_cycle)?;
+
+        writeln!(f, "\nSystems:")?;
+        for system in &self.systems {
+            writeln!(
+                f,
+                "  {}: {} runs, total {:?}, mean {:?}, max {:?}",
+                system.node.name,
+                system.runs,
+                system.total,
+                system.mean(),
+                system.max,
+            )?;
+        }
+
+        writeln!(f, "\nCritical path ({:?}):", self.critical_path_duration)?;
+        for node in &self.critical_path {
+            writeln!(f, "  {}", node.name)?;
+        }
+
+        writeln!(f, "\nContention:")?;
+        for contention in &self.contentions {
+            writeln!(
+                f,
+                "  {} blocked by {}: {} times, total {:?}, max {:?}",
+                contention.blocked.name,
+                contention.blocker.name,
+                contention.count,
+                contention.total,
+                contention.max,
+            )?;
+            for resource in &contention.resources {
+                writeln!(f, "    on {resource}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[dynec_codegen::tracer(dynec_as())]
+impl Tracer for Analyzer {
+    type CycleContext = time::Instant;
+    type RunSendableContext = time::Instant;
+    type RunUnsendableContext = time::Instant;
+
+    fn start_cycle(&self) -> time::Instant { time::Instant::now() }
+
+    fn end_cycle(&self, start: time::Instant) {
+        let duration = start.elapsed();
+        self.with_state(|state| {
+            state.cycles += 1;
+            state.cycle_total += duration;
+            state.waiting.clear();
+        });
+    }
+
+    fn mark_runnable(&self, node: scheduler::Node) {
+        let now = time::Instant::now();
+        self.with_state(|state| {
+            if let Some((blocker, start)) = state.waiting.remove(&node) {
+                state.waits.entry((node, blocker)).or_default().add(now - start);
+            }
+        });
+    }
+
+    fn unmark_runnable_by(&self, node: scheduler::Node, blocker: scheduler::Node) {
+        let now = time::Instant::now();
+        self.with_state(|state| {
+            state.waiting.entry(node).or_insert((blocker, now));
+        });
+    }
+
+    fn start_run_sendable(
+        &self,
+        _thread: Thread,
+        _node: scheduler::Node,
+        _debug_name: &str,
+        _system: &mut dyn system::Sendable,
+    ) -> time::Instant {
+        time::Instant::now()
+    }
+
+    fn end_run_sendable(
+        &self,
+        start: time::Instant,
+        _thread: Thread,
+        node: scheduler::Node,
+        _debug_name: &str,
+        _system: &mut dyn system::Sendable,
+    ) {
+        self.end_run(start, node);
+    }
+
+    fn start_run_unsendable(
+        &self,
+        _thread: Thread,
+        _node: scheduler::Node,
+        _debug_name: &str,
+        _system: &mut dyn system::Unsendable,
+    ) -> time::Instant {
+        time::Instant::now()
+    }
+
+    fn end_run_unsendable(
+        &self,
+        start: time::Instant,
+        _thread: Thread,
+        node: scheduler::Node,
+        _debug_name: &str,
+        _system: &mut dyn system::Unsendable,
+    ) {
+        self.end_run(start, node);
+    }
+}
@@ -0,0 +1,235 @@
+//! Records scheduling events in the Chrome Trace Event format.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::time;
+
+use parking_lot::Mutex;
+
+use super::Thread;
+use crate::util::JsonString;
+
+/// A tracer that records the timeline of each thread
+/// in the [Chrome Trace Event format][format].
+///
+/// System runs, ealloc flushes and cycles are recorded as duration events
+/// on the thread that executed them,
+/// while skipped systems and completed partitions are recorded as instant events.
+/// The output of [`to_json`](Self::to_json) can be opened in `chrome://tracing` or
+/// [Perfetto](https://ui.perfetto.dev) to inspect thread utilization.
+///
+/// Events are accumulated until [`clear`](Self::clear) is called,
+/// so the same tracer can be passed to multiple cycles to record them in one timeline.
+///
+/// [format]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+pub struct ChromeTrace {
+    origin: time::Instant,
+    events: Mutex<Vec<Event>>,
+}
+
+struct Event {
+    name:     String,
+    category: &'static str,
+    thread:   Thread,
+    start:    time::Instant,
+    kind:     EventKind,
+}
+
+enum EventKind {
+    /// A duration event that ends at the given instant.
+    Complete(time::Instant),
+    /// An instant event with the given scope.
+    Instant(&'static str),
+}
+
+impl Default for ChromeTrace {
+    fn default() -> Self { Self::new() }
+}
+
+impl ChromeTrace {
+    /// Creates an empty tracer.
+    ///
+    /// Timestamps in the output are relative to the creation of the tracer.
+    pub fn new() -> Self { Self { origin: time::Instant::now(), events: Mutex::default() } }
+
+    /// Discards all recorded events.
+    pub fn clear(&self) { self.events.lock().clear(); }
+
+    /// Renders the recorded events as a Chrome Trace Event JSON document.
+    ///
+    /// The main thread is rendered as thread 0,
+    /// and worker thread `i` is rendered as thread `i + 1`.
+    pub fn to_json(&self) -> String {
+        let events = self.events.lock();
+
+        let mut out = String::from(r#"{"traceEvents":["#);
+        let mut first = true;
+        let mut separator = || if std::mem::take(&mut first) { "\n" } else { ",\n" };
+
+        let threads: BTreeSet<Thread> = events.iter().map(|event| event.thread).collect();
+        for thread in threads {
+            let name = match thread {
+                Thread::Main => "main".to_string(),
+                Thread::Worker(index) => format!("worker {index}"),
+            };
+            write!(
+                out,
+                r#"{}{{"name":"thread_name","ph":"M","pid":0,"tid":{},"args":{{"name":{}}}}}"#,
+                separator(),
+                thread_id(thread),
+                JsonString(&name),
+            )
+            .expect("String write is infallible");
+        }
+
+        for event in events.iter() {
+            write!(
+                out,
+                r#"{}{{"name":{},"cat":"{}","pid":0,"tid":{},"ts":{}"#,
+                separator(),
+                JsonString(&event.name),
+                event.category,
+                thread_id(event.thread),
+                self.micros(event.start),
+            )
+            .expect("String write is infallible");
+            match event.kind {
+                EventKind::Complete(end) => {
+                    write!(out, r#","ph":"X","dur":{:.3}}}"#, micros(end - event.start))
+                }
+                EventKind::Instant(scope) => write!(out, r#","ph":"i","s":"{scope}"}}"#),
+            }
+            .expect("String write is infallible");
+        }
+
+        out.push_str("\n],\"displayTimeUnit\":\"ms\"}\n");
+        out
+    }
+
+    fn micros(&self, instant: time::Instant) -> String {
+        format!("{:.3}", micros(instant.saturating_duration_since(self.origin)))
+    }
+
+    fn push(&self, event: Event) { self.events.lock().push(event); }
+
+    fn push_complete(
+        &self,
+        name: String,
+        category: &'static str,
+        thread: Thread,
+        start: time::Instant,
+    ) {
+        let kind = EventKind::Complete(time::Instant::now());
+        self.push(Event { name, category, thread, start, kind });
+    }
+
+    fn push_instant(
+        &self,
+        name: String,
+        category: &'static str,
+        thread: Thread,
+        scope: &'static str,
+    ) {
+        let start = time::Instant::now();
+        self.push(Event { name, category, thread, start, kind: EventKind::Instant(scope) });
+    }
+}
+
+fn micros(duration: time::Duration) -> f64 { duration.as_secs_f64() * 1e6 }
+
+fn thread_id(thread: Thread) -> usize {
+    match thread {
+        Thread::Main => 0,
+        Thread::Worker(index) => index + 1,
+    }
+}
+
+#[dynec_codegen::tracer(dynec_as())]
+impl Tracer for ChromeTrace {
+    type CycleContext = time::Instant;
+    type PrepareEallocShardsContext = time::Instant;
+    type FlushEallocContext = time::Instant;
+    type RunSendableContext = time::Instant;
+    type RunUnsendableContext = time::Instant;
+
+    fn start_cycle(&self) -> time::Instant { time::Instant::now() }
+
+    fn end_cycle(&self, start: time::Instant) {
+        self.push_complete("cycle".into(), "cycle", Thread::Main, start);
+    }
+
+    fn start_prepare_ealloc_shards(&self) -> time::Instant { time::Instant::now() }
+
+    fn end_prepare_ealloc_shards(&self, start: time::Instant) {
+        self.push_complete("prepare ealloc shards".into(), "ealloc", Thread::Main, start);
+    }
+
+    fn start_flush_ealloc(&self, _archetype: DbgTypeId) -> time::Instant { time::Instant::now() }
+
+    fn end_flush_ealloc(&self, start: time::Instant, archetype: DbgTypeId) {
+        // allocators are flushed on the thread pool of the executor if it has one.
+        let thread = rayon::current_thread_index().map_or(Thread::Main, Thread::Worker);
+        self.push_complete(format!("flush ealloc {archetype}"), "ealloc", thread, start);
+    }
+
+    fn start_run_sendable(
+        &self,
+        _thread: Thread,
+        _node: scheduler::Node,
+        _debug_name: &str,
+        _system: &mut dyn system::Sendable,
+    ) -> time::Instant {
+        time::Instant::now()
+    }
+
+    fn end_run_sendable(
+        &self,
+        start: time::Instant,
+        thread: Thread,
+        _node: scheduler::Node,
+        debug_name: &str,
+        _system: &mut dyn system::Sendable,
+    ) {
+        self.push_complete(debug_name.to_string(), "sendable", thread, start);
+    }
+
+    fn start_run_unsendable(
+        &self,
+        _thread: Thread,
+        _node: scheduler::Node,
+        _debug_name: &str,
+        _system: &mut dyn system::Unsendable,
+    ) -> time::Instant {
+        time::Instant::now()
+    }
+
+    fn end_run_unsendable(
+        &self,
+        start: time::Instant,
+        thread: Thread,
+        _node: scheduler::Node,
+        debug_name: &str,
+        _system: &mut dyn system::Unsendable,
+    ) {
+        self.push_complete(debug_name.to_string(), "unsendable", thread, start);
+    }
+
+    fn system_error(
+        &self,
+        thread: Thread,
+        _node: scheduler::Node,
+        debug_name: &str,
+        error: &(dyn std::error::Error + Send + Sync),
+    ) {
+        self.push_instant(format!("{debug_name} failed: {error}"), "error", thread, "t");
+    }
+
+    fn skip_system(&self, thread: Thread, _node: scheduler::Node, debug_name: &str) {
+        self.push_instant(format!("skip {debug_name}"), "skip", thread, "t");
+    }
+
+    fn partition(&self, _node: scheduler::Node, partition: &dyn system::Partition) {
+        // partitions are not completed by a specific thread, so they are rendered process-wide.
+        self.push_instant(PartitionFmt(partition).to_string(), "partition", Thread::Main, "p");
+    }
+}
@@ -4,6 +4,7 @@
 use std::any;
 use std::any::TypeId;
 use std::borrow::Borrow;
+use std::fmt::Write;
 use std::{cmp, fmt, hash, mem, num, ops};
 
 /// A generic mutable/immutable reference type.
@@ -164,3 +165,22 @@ pub(crate) fn is_all_distinct_quadtime<T: PartialEq>(slice: &[T]) -> bool {
     }
     true
 }
+
+/// Formats a quoted JSON string.
+pub(crate) struct JsonString<'t>(pub(crate) &'t str);
+
+impl<'t> fmt::Display for JsonString<'t> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char('"')?;
+        for ch in self.0.chars() {
+            match ch {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                ch if (ch as u32) < 0x20 => write!(f, "\\u{:04x}", ch as u32)?,
+                ch => f.write_char(ch)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
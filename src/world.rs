@@ -1,19 +1,23 @@
 //! The world stores the states of the game.
 
 use std::any::{self, TypeId};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::entity::{deletion, ealloc, generation, rctrack, Ealloc, Raw};
-use crate::scheduler::Scheduler;
+use crate::scheduler::{self, Scheduler};
 use crate::tracer::Tracer;
 use crate::{comp, entity, system, Archetype, Entity, Global, Storage};
 
 mod builder;
-pub use builder::Builder;
+pub use builder::{Builder, NamedSchedule};
 
 pub(crate) mod global;
 pub use global::{SyncGlobals, UnsyncGlobals};
 
+pub(crate) mod events;
+pub use events::Events;
+
 pub(crate) mod rw;
 pub use rw::Components;
 
@@ -21,6 +25,15 @@ pub(crate) mod typed;
 
 pub mod offline;
 
+mod memory;
+pub use memory::{MemoryReport, StorageMemory};
+
+mod rearrange;
+
+mod reschedule;
+
+mod serialize;
+
 /// A bundle encapsulates the systems and resources for a specific feature.
 /// This can be used by library crates to expose their features as a single API.
 pub trait Bundle {
@@ -30,6 +43,9 @@ pub trait Bundle {
     fn register(&mut self, _builder: &mut Builder) {}
 
     /// Populates the world with entities and global states.
+    ///
+    /// Initialization logic that requires system parameters
+    /// can be scheduled as a [startup system](Builder::schedule_startup) instead.
     fn populate(&mut self, _world: &mut World) {}
 }
 
@@ -83,6 +99,12 @@ pub struct World {
     pub components:     Components,
     /// Stores the system-local states and the scheduler topology.
     scheduler:          Scheduler,
+    /// Stores the system-local states and the topologies of named schedules.
+    named_schedulers:   HashMap<String, Scheduler>,
+    /// Stores the startup systems until they are executed before the first cycle.
+    startup_scheduler:  Option<Scheduler>,
+    /// Stores offline operations queued by systems of all schedules.
+    offline_buffer:     offline::Buffer,
     /// Global states that can be concurrently accessed by systems on other threads.
     pub sync_globals:   SyncGlobals,
     /// Global states that must be accessed on the main thread.
@@ -92,17 +114,117 @@ pub struct World {
 }
 
 impl World {
-    /// Executes all systems in the world.
-    pub fn execute(&mut self, tracer: &impl Tracer) {
-        self.ealloc_map.flush_if_marked();
-        self.scheduler.execute(
+    /// Executes all systems in the default schedule of the world.
+    ///
+    /// If this is the first cycle of the world,
+    /// [startup systems](Builder::schedule_startup) are executed in a separate cycle beforehand.
+    ///
+    /// Returns the errors returned by fallible systems
+    /// and the system panics recovered according to their
+    /// [panic policies](scheduler::PanicPolicy) during the cycle,
+    /// including those during the startup cycle.
+    pub fn execute(&mut self, tracer: &impl Tracer) -> scheduler::CycleReport {
+        self.execute_scheduler(None, tracer)
+    }
+
+    /// Executes all systems in the [named schedule](Builder::named_schedule) `name`.
+    ///
+    /// Each call to [`execute`](Self::execute) or `execute_schedule` is a separate cycle.
+    /// Simple component events and event channels advance once per cycle
+    /// regardless of which schedule is executed.
+    ///
+    /// Returns the system errors and recovered panics during the cycle,
+    /// similar to [`execute`](Self::execute).
+    ///
+    /// # Panics
+    /// Panics if no systems were scheduled into the schedule `name`.
+    pub fn execute_schedule(&mut self, name: &str, tracer: &impl Tracer) -> scheduler::CycleReport {
+        self.execute_scheduler(Some(name), tracer)
+    }
+
+    /// Describes the topology of the default schedule,
+    /// which can be rendered in the Graphviz DOT or JSON format.
+    pub fn schedule_graph(&self) -> scheduler::Graph { self.scheduler.graph() }
+
+    /// Describes the topology of the [named schedule](Builder::named_schedule) `name`.
+    ///
+    /// # Panics
+    /// Panics if no systems were scheduled into the schedule `name`.
+    pub fn named_schedule_graph(&self, name: &str) -> scheduler::Graph {
+        match self.named_schedulers.get(name) {
+            Some(scheduler) => scheduler.graph(),
+            None => panic!("No systems were scheduled into the schedule {name:?}"),
+        }
+    }
+
+    fn execute_scheduler(
+        &mut self,
+        name: Option<&str>,
+        tracer: &impl Tracer,
+    ) -> scheduler::CycleReport {
+        let mut startup_report = None;
+        if let Some(mut startup_scheduler) = self.startup_scheduler.take() {
+            let other_systems =
+                all_system_refs(&mut self.scheduler, &mut self.named_schedulers, None);
+            startup_report = Some(execute_cycle(
+                &mut startup_scheduler,
+                other_systems,
+                WorldMut {
+                    ealloc_map:     &mut self.ealloc_map,
+                    components:     &mut self.components,
+                    sync_globals:   &mut self.sync_globals,
+                    unsync_globals: &mut self.unsync_globals,
+                    rctrack:        &mut self.rctrack,
+                },
+                &mut self.offline_buffer,
+                tracer,
+            ));
+        }
+
+        let (scheduler, other_systems) = match name {
+            None => (
+                &mut self.scheduler,
+                self.named_schedulers.values_mut().flat_map(Scheduler::get_system_refs).collect(),
+            ),
+            Some(name) => {
+                let mut target = None;
+                let mut other_systems = self.scheduler.get_system_refs();
+                for (scheduler_name, scheduler) in &mut self.named_schedulers {
+                    if scheduler_name == name {
+                        target = Some(scheduler);
+                    } else {
+                        other_systems.extend(scheduler.get_system_refs());
+                    }
+                }
+                match target {
+                    Some(scheduler) => (scheduler, other_systems),
+                    None => panic!("No systems were scheduled into the schedule {name:?}"),
+                }
+            }
+        };
+
+        let report = execute_cycle(
+            scheduler,
+            other_systems,
+            WorldMut {
+                ealloc_map:     &mut self.ealloc_map,
+                components:     &mut self.components,
+                sync_globals:   &mut self.sync_globals,
+                unsync_globals: &mut self.unsync_globals,
+                rctrack:        &mut self.rctrack,
+            },
+            &mut self.offline_buffer,
             tracer,
-            &mut self.components,
-            &mut self.sync_globals,
-            &mut self.unsync_globals,
-            &mut self.rctrack,
-            &mut self.ealloc_map,
         );
+
+        match startup_report {
+            Some(mut startup_report) => {
+                startup_report.panics.extend(report.panics);
+                startup_report.errors.extend(report.errors);
+                startup_report
+            }
+            None => report,
+        }
     }
 
     /// Adds an entity to the world.
@@ -142,7 +264,11 @@ impl World {
     }
 
     pub(crate) fn as_mut(&mut self) -> (WorldMut<'_>, Vec<(&str, &mut dyn system::Descriptor)>) {
-        let system_refs = self.scheduler.get_system_refs();
+        let system_refs = all_system_refs(
+            &mut self.scheduler,
+            &mut self.named_schedulers,
+            self.startup_scheduler.as_mut(),
+        );
 
         (
             WorldMut {
@@ -175,12 +301,10 @@ impl World {
         match result {
             DeleteResult::Deleted => {}
             DeleteResult::Terminating => {
-                self.scheduler.offline_buffer().rerun_queue.push(Box::new(offline::DeleteEntity::<
-                    E::Archetype,
-                > {
-                    entity: id,
-                })
-                    as Box<dyn offline::Operation>);
+                self.offline_buffer
+                    .rerun_queue
+                    .push(Box::new(offline::DeleteEntity::<E::Archetype> { entity: id })
+                        as Box<dyn offline::Operation>);
             }
         }
 
@@ -217,6 +341,43 @@ impl World {
     }
 }
 
+/// Executes a cycle of `scheduler`.
+///
+/// `other_systems` are the systems of other schedules,
+/// which are visited when offline operations are applied.
+fn execute_cycle(
+    scheduler: &mut Scheduler,
+    other_systems: Vec<(&str, &mut dyn system::Descriptor)>,
+    world: WorldMut<'_>,
+    offline_buffer: &mut offline::Buffer,
+    tracer: &impl Tracer,
+) -> scheduler::CycleReport {
+    world.ealloc_map.flush_if_marked();
+    world.components.deliver_simple_events();
+    scheduler.execute(
+        tracer,
+        world.components,
+        world.sync_globals,
+        world.unsync_globals,
+        world.rctrack,
+        world.ealloc_map,
+        offline_buffer,
+        other_systems,
+    )
+}
+
+/// Returns the system states of all schedules.
+fn all_system_refs<'t>(
+    scheduler: &'t mut Scheduler,
+    named_schedulers: &'t mut HashMap<String, Scheduler>,
+    startup_scheduler: Option<&'t mut Scheduler>,
+) -> Vec<(&'t str, &'t mut dyn system::Descriptor)> {
+    let mut system_refs = scheduler.get_system_refs();
+    system_refs.extend(named_schedulers.values_mut().flat_map(Scheduler::get_system_refs));
+    system_refs.extend(startup_scheduler.into_iter().flat_map(Scheduler::get_system_refs));
+    system_refs
+}
+
 /// Borrows a world mutably.
 pub(crate) struct WorldMut<'t> {
     pub(crate) ealloc_map:     &'t mut ealloc::Map,
@@ -258,8 +419,9 @@ fn init_entity<A: Archetype>(
         _rctrack.0.set::<A>(id.to_primitive(), _rc);
     }
 
+    let tick = components.next_change_tick();
     let typed = components.archetype_mut::<A>();
-    typed.init_entity(id, comp_map, ealloc_map.get::<A>());
+    typed.init_entity(id, comp_map, ealloc_map.get::<A>(), tick);
 }
 
 /// Result of deleting an entity.
@@ -314,6 +476,10 @@ fn try_real_delete_entity<A: Archetype>(
             .clear_entry(entity);
     }
 
+    for storage in world.components.archetype_mut::<A>().dynamic_storages.values_mut() {
+        storage.get_mut().set(entity, None);
+    }
+
     #[cfg(any(
         all(debug_assertions, feature = "debug-entity-rc"),
         all(not(debug_assertions), feature = "release-entity-rc"),
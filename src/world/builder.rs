@@ -1,21 +1,33 @@
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
+use std::mem;
+use std::sync::atomic::AtomicU64;
 
 use parking_lot::RwLock;
 
-use super::typed;
+use super::{events, offline, typed};
 use crate::entity::{ealloc, generation, referrer};
 use crate::system::spec;
 use crate::util::DbgTypeId;
 use crate::{scheduler, system, Global};
 
 /// This type is used to build a world.
-/// No more systems can be scheduled after the builder is built.
+/// Systems can still be scheduled after the builder is built
+/// through [`World::schedule`](super::World::schedule).
 pub struct Builder {
-    scheduler:      scheduler::Builder,
+    scheduler:         scheduler::Builder,
+    named_schedulers:  HashMap<String, scheduler::Builder>,
+    startup_scheduler: Option<scheduler::Builder>,
+    registry:          Registry,
+}
+
+/// Archetypes, global states and event channels used by scheduled systems.
+#[derive(Default)]
+pub(super) struct Registry {
     archetypes:     HashMap<DbgTypeId, (ealloc::AnyBuilder, Box<dyn typed::AnyBuilder>)>,
     sync_globals:   GlobalBuilderMap<dyn Any + Send + Sync>,
     unsync_globals: GlobalBuilderMap<dyn Any>,
+    events:         HashMap<DbgTypeId, events::Builder>,
 }
 
 enum GlobalBuilder<G: ?Sized> {
@@ -23,21 +35,289 @@ enum GlobalBuilder<G: ?Sized> {
     Missing(fn() -> Box<G>),
 }
 
+impl<G: ?Sized> GlobalBuilder<G> {
+    fn build(self) -> Box<G> {
+        match self {
+            Self::Provided(value) => value,
+            Self::Missing(default) => default(),
+        }
+    }
+}
+
 impl Builder {
     /// Creates a new builder with the specified concurrency.
     pub fn new(concurrency: usize) -> Self {
         Self {
-            scheduler:      scheduler::Builder::new(concurrency),
-            archetypes:     HashMap::new(),
-            sync_globals:   {
-                let mut map = HashMap::new();
-                populate_default_globals(&mut map);
-                map
+            scheduler:         scheduler::Builder::new(concurrency),
+            named_schedulers:  HashMap::new(),
+            startup_scheduler: None,
+            registry:          Registry {
+                sync_globals: {
+                    let mut map = HashMap::new();
+                    populate_default_globals(&mut map);
+                    map
+                },
+                ..Registry::default()
             },
-            unsync_globals: HashMap::new(),
         }
     }
 
+    /// Splits the registry and the scheduler of `target` for scheduling systems.
+    fn split(&mut self, target: Target<'_>) -> (&mut Registry, &mut scheduler::Builder) {
+        let scheduler = match target {
+            Target::Default => &mut self.scheduler,
+            Target::Named(name) => self
+                .named_schedulers
+                .get_mut(name)
+                .expect("named schedulers are created in Builder::named_schedule"),
+            Target::Startup => {
+                let concurrency = self.scheduler.concurrency;
+                self.startup_scheduler.get_or_insert_with(|| scheduler::Builder::new(concurrency))
+            }
+        };
+        (&mut self.registry, scheduler)
+    }
+
+    fn schedule_send(
+        &mut self,
+        target: Target<'_>,
+        system: Box<dyn system::Sendable>,
+        condition: Option<system::RunCondition>,
+    ) -> scheduler::SystemHandle {
+        let (registry, scheduler) = self.split(target);
+        registry.schedule_send(scheduler, system, condition)
+    }
+
+    fn schedule_unsend(
+        &mut self,
+        target: Target<'_>,
+        system: Box<dyn system::Unsendable>,
+        condition: Option<system::RunCondition>,
+    ) -> scheduler::SystemHandle {
+        let (registry, scheduler) = self.split(target);
+        registry.schedule_unsend(scheduler, system, condition)
+    }
+
+    /// Schedules a thread-safe system.
+    ///
+    /// The returned handle can be used to [disable](super::World::set_enabled)
+    /// or [unschedule](super::World::unschedule) the system after the world is built.
+    pub fn schedule(&mut self, system: impl system::Sendable) -> scheduler::SystemHandle {
+        self.schedule_boxed(Box::new(system))
+    }
+
+    /// Schedules a thread-safe system.
+    pub fn schedule_boxed(&mut self, system: Box<dyn system::Sendable>) -> scheduler::SystemHandle {
+        self.schedule_send(Target::Default, system, None)
+    }
+
+    /// Schedules a thread-safe system that only runs in cycles where `condition` holds.
+    ///
+    /// See [`system::RunCondition`] for the semantics of skipped systems.
+    pub fn schedule_with_condition(
+        &mut self,
+        system: impl system::Sendable,
+        condition: system::RunCondition,
+    ) -> scheduler::SystemHandle {
+        self.schedule_send(Target::Default, Box::new(system), Some(condition))
+    }
+
+    /// Schedules a system that must be run on the main thread.
+    pub fn schedule_thread_unsafe(
+        &mut self,
+        system: impl system::Unsendable,
+    ) -> scheduler::SystemHandle {
+        self.schedule_thread_unsafe_boxed(Box::new(system))
+    }
+
+    /// Schedules a system that must be run on the main thread.
+    pub fn schedule_thread_unsafe_boxed(
+        &mut self,
+        system: Box<dyn system::Unsendable>,
+    ) -> scheduler::SystemHandle {
+        self.schedule_unsend(Target::Default, system, None)
+    }
+
+    /// Schedules a system that must be run on the main thread
+    /// and only runs in cycles where `condition` holds.
+    ///
+    /// See [`system::RunCondition`] for the semantics of skipped systems.
+    pub fn schedule_thread_unsafe_with_condition(
+        &mut self,
+        system: impl system::Unsendable,
+        condition: system::RunCondition,
+    ) -> scheduler::SystemHandle {
+        self.schedule_unsend(Target::Default, Box::new(system), Some(condition))
+    }
+
+    /// Returns a handle to schedule systems into the named schedule `name`,
+    /// creating the schedule if it does not exist yet.
+    ///
+    /// Named schedules share the components and global states of the world,
+    /// but have their own systems and execution order.
+    /// Systems in a named schedule are only run by
+    /// [`World::execute_schedule`](super::World::execute_schedule).
+    pub fn named_schedule(&mut self, name: &str) -> NamedSchedule<'_> {
+        if !self.named_schedulers.contains_key(name) {
+            let scheduler = scheduler::Builder::new(self.scheduler.concurrency);
+            self.named_schedulers.insert(name.to_string(), scheduler);
+        }
+        NamedSchedule { builder: self, name: name.to_string() }
+    }
+
+    /// Schedules a thread-safe system that runs exactly once before the first cycle.
+    ///
+    /// Startup systems are scheduled in a separate topology with the same conflict checking,
+    /// executed as a cycle before the first call to
+    /// [`World::execute`](super::World::execute) or
+    /// [`World::execute_schedule`](super::World::execute_schedule),
+    /// and dropped afterwards.
+    pub fn schedule_startup(&mut self, system: impl system::Sendable) -> scheduler::SystemHandle {
+        self.schedule_send(Target::Startup, Box::new(system), None)
+    }
+
+    /// Schedules a system that must be run on the main thread
+    /// and runs exactly once before the first cycle.
+    ///
+    /// See [`schedule_startup`](Self::schedule_startup) for details.
+    pub fn schedule_startup_thread_unsafe(
+        &mut self,
+        system: impl system::Unsendable,
+    ) -> scheduler::SystemHandle {
+        self.schedule_unsend(Target::Startup, Box::new(system), None)
+    }
+
+    /// Provides a thread-safe global resource.
+    pub fn global<G: Global + Send + Sync>(&mut self, value: G) {
+        self.registry.sync_globals.insert(
+            DbgTypeId::of::<G>(),
+            (referrer::SingleVtable::of::<G>(), GlobalBuilder::Provided(Box::new(value))),
+        );
+    }
+
+    /// Provides a thread-unsafe global resource.
+    pub fn global_thread_unsafe<G: Global>(&mut self, value: G) {
+        self.registry.unsync_globals.insert(
+            DbgTypeId::of::<G>(),
+            (referrer::SingleVtable::of::<G>(), GlobalBuilder::Provided(Box::new(value))),
+        );
+    }
+
+    /// Adjust the concurrency of the scheduler.
+    /// Pass `0` to disable parallelism.
+    pub fn set_concurrency(&mut self, concurrency: usize) {
+        self.scheduler.concurrency = concurrency;
+        for scheduler in self.named_schedulers.values_mut().chain(&mut self.startup_scheduler) {
+            scheduler.concurrency = concurrency;
+        }
+    }
+
+    /// Sets how the planner weights systems when prioritizing runnable systems
+    /// in all schedules.
+    ///
+    /// See [`scheduler::Weighting`] for details.
+    pub fn set_weighting(&mut self, weighting: scheduler::Weighting) {
+        self.scheduler.weighting = weighting;
+        for scheduler in self.named_schedulers.values_mut().chain(&mut self.startup_scheduler) {
+            scheduler.weighting = weighting;
+        }
+    }
+
+    /// Sets the static weight of a scheduled system,
+    /// which estimates the run duration of the system relative to other systems.
+    ///
+    /// See [`World::set_weight`](super::World::set_weight) for details.
+    ///
+    /// # Panics
+    /// Panics if `handle` was not returned by this builder.
+    pub fn set_weight(&mut self, handle: scheduler::SystemHandle, weight: u64) {
+        let mut schedulers = [&mut self.scheduler]
+            .into_iter()
+            .chain(self.named_schedulers.values_mut())
+            .chain(&mut self.startup_scheduler);
+        if !schedulers.any(|scheduler| scheduler.set_weight(handle, weight)) {
+            panic!("{handle:?} is not scheduled in this builder");
+        }
+    }
+
+    /// Sets the panic policy of a scheduled system.
+    ///
+    /// See [`World::set_panic_policy`](super::World::set_panic_policy) for details.
+    ///
+    /// # Panics
+    /// Panics if `handle` was not returned by this builder.
+    pub fn set_panic_policy(
+        &mut self,
+        handle: scheduler::SystemHandle,
+        policy: scheduler::PanicPolicy,
+    ) {
+        let mut schedulers = [&mut self.scheduler]
+            .into_iter()
+            .chain(self.named_schedulers.values_mut())
+            .chain(&mut self.startup_scheduler);
+        if !schedulers.any(|scheduler| scheduler.set_panic_policy(handle, policy)) {
+            panic!("{handle:?} is not scheduled in this builder");
+        }
+    }
+
+    /// Constructs the world from the builder.
+    pub fn build(self) -> super::World {
+        let (ealloc_map, storages) = self
+            .registry
+            .archetypes
+            .into_iter()
+            .map(|(ty, (ealloc, storages))| {
+                ((ty, ealloc(self.scheduler.concurrency + 1)), (ty, storages.build()))
+            })
+            .unzip();
+
+        let ealloc_map = ealloc::Map::new(ealloc_map);
+        let storages =
+            super::Components { archetypes: storages, change_clock: AtomicU64::new(0) };
+
+        let sync_globals = self
+            .registry
+            .sync_globals
+            .into_iter()
+            .map(|(ty, (vtable, global_builder))| {
+                (ty, (vtable, RwLock::new(global_builder.build())))
+            })
+            .collect();
+        let events = self
+            .registry
+            .events
+            .into_iter()
+            .map(|(ty, builder)| (ty, builder(self.scheduler.concurrency + 1)))
+            .collect();
+        let sync_globals = super::SyncGlobals { sync_globals, events };
+
+        let unsync_globals = self
+            .registry
+            .unsync_globals
+            .into_iter()
+            .map(|(ty, (vtable, global_builder))| (ty, (vtable, global_builder.build())))
+            .collect();
+        let unsync_globals = super::UnsyncGlobals { unsync_globals };
+
+        super::World {
+            ealloc_map,
+            components: storages,
+            sync_globals,
+            unsync_globals,
+            offline_buffer: offline::Buffer::new(self.scheduler.concurrency + 1),
+            scheduler: self.scheduler.build(),
+            named_schedulers: self
+                .named_schedulers
+                .into_iter()
+                .map(|(name, scheduler)| (name, scheduler.build()))
+                .collect(),
+            startup_scheduler: self.startup_scheduler.map(scheduler::Builder::build),
+            rctrack: Default::default(),
+        }
+    }
+}
+
+impl Registry {
     fn archetype(
         &mut self,
         archetype: spec::ArchetypeDescriptor,
@@ -51,6 +331,7 @@ impl Builder {
         type_visitor: referrer::VisitTypeArg,
         state_maybe_uninit: &[TypeId],
         sync: bool,
+        scheduler: &mut scheduler::Builder,
         node: scheduler::Node,
     ) {
         for arch in type_visitor.found_archs {
@@ -58,7 +339,7 @@ impl Builder {
                 continue;
             }
 
-            self.scheduler.add_dependencies(
+            scheduler.add_dependencies(
                 vec![spec::Dependency::Before(Box::new(system::EntityCreationPartition {
                     ty: arch,
                 }))],
@@ -66,7 +347,20 @@ impl Builder {
             );
         }
 
-        for request in system.global_requests {
+        // Globals read by run conditions are requested on behalf of the system,
+        // unless the system already requests them itself.
+        let mut run_conditions = system.run_conditions;
+        let condition_globals: Vec<_> = run_conditions
+            .iter_mut()
+            .flat_map(|condition| mem::take(&mut condition.global_requests))
+            .filter(|request| {
+                !system.global_requests.iter().any(|system_request| system_request.ty == request.ty)
+            })
+            .collect();
+        scheduler.add_run_conditions(node, run_conditions);
+        scheduler.set_on_error(node, system.on_error);
+
+        for request in system.global_requests.into_iter().chain(condition_globals) {
             match (request.initial, sync) {
                 (spec::GlobalInitial::Sync(initial), _) => {
                     if self.unsync_globals.contains_key(&request.ty) {
@@ -80,7 +374,7 @@ impl Builder {
                         .entry(request.ty)
                         .or_insert_with(|| (request.vtable, GlobalBuilder::Missing(initial)));
 
-                    self.scheduler.use_resource(
+                    scheduler.use_resource(
                         node,
                         scheduler::ResourceType::Global(request.ty),
                         scheduler::ResourceAccess::new(request.mutable),
@@ -105,7 +399,7 @@ impl Builder {
                         .entry(request.ty)
                         .or_insert_with(|| (request.vtable, GlobalBuilder::Missing(initial)));
 
-                    self.scheduler.use_resource(
+                    scheduler.use_resource(
                         node,
                         scheduler::ResourceType::Global(request.ty),
                         scheduler::ResourceAccess::new(request.mutable),
@@ -114,7 +408,7 @@ impl Builder {
             }
 
             for &strong_ref in &request.strong_refs {
-                self.scheduler.add_dependencies(
+                scheduler.add_dependencies(
                     vec![spec::Dependency::Before(Box::new(system::EntityCreationPartition {
                         ty: strong_ref,
                     }))],
@@ -127,14 +421,14 @@ impl Builder {
             let builder = self.archetype(request.arch);
             builder.add_simple_storage_if_missing(request.comp, request.storage_builder);
 
-            self.scheduler.use_resource(
+            scheduler.use_resource(
                 node,
                 scheduler::ResourceType::Simple { arch: request.arch.id, comp: request.comp },
                 scheduler::ResourceAccess::new(request.mutable),
             );
 
             for &strong_ref in &request.strong_refs {
-                self.scheduler.add_dependencies(
+                scheduler.add_dependencies(
                     vec![spec::Dependency::Before(Box::new(system::EntityCreationPartition {
                         ty: strong_ref,
                     }))],
@@ -147,14 +441,14 @@ impl Builder {
             let builder = self.archetype(request.arch);
             builder.add_isotope_map_if_missing(request.comp, request.map_builder);
 
-            self.scheduler.use_resource(
+            scheduler.use_resource(
                 node,
                 scheduler::ResourceType::Isotope { arch: request.arch.id, comp: request.comp },
                 scheduler::ResourceAccess::with_discrim(request.mutable, request.discrim.clone()),
             );
 
             for &strong_ref in &request.strong_refs {
-                self.scheduler.add_dependencies(
+                scheduler.add_dependencies(
                     vec![spec::Dependency::Before(Box::new(system::EntityCreationPartition {
                         ty: strong_ref,
                     }))],
@@ -163,9 +457,42 @@ impl Builder {
             }
         }
 
+        for request in system.dynamic_requests {
+            let builder = self.archetype(request.arch);
+            builder.add_dynamic_storage_if_missing(request.descriptor);
+
+            scheduler.use_resource(
+                node,
+                scheduler::ResourceType::Dynamic {
+                    arch: request.arch.id,
+                    comp: request.descriptor.id,
+                },
+                scheduler::ResourceAccess::new(request.mutable),
+            );
+        }
+
+        for request in system.simple_event_requests {
+            let builder = self.archetype(request.arch);
+            builder.add_simple_storage_if_missing(request.comp, request.storage_builder);
+            builder.enable_simple_events(request.comp);
+        }
+
+        for request in system.event_requests {
+            self.events.entry(request.ty).or_insert(request.builder);
+
+            let partition = Box::new(system::EventPartition { ty: request.ty });
+            scheduler.add_dependencies(
+                vec![match request.writer {
+                    true => spec::Dependency::Before(partition),
+                    false => spec::Dependency::After(partition),
+                }],
+                node,
+            );
+        }
+
         for request in system.entity_creator_requests {
             if !request.no_partition {
-                self.scheduler.add_dependencies(
+                scheduler.add_dependencies(
                     vec![spec::Dependency::After(Box::new(system::EntityCreationPartition {
                         ty: request.arch,
                     }))],
@@ -174,123 +501,149 @@ impl Builder {
             }
         }
 
-        self.scheduler.add_dependencies(system.dependencies, node);
-    }
-
-    /// Schedules a thread-safe system.
-    pub fn schedule(&mut self, system: impl system::Sendable) {
-        self.schedule_boxed(Box::new(system))
+        scheduler.add_dependencies(system.dependencies, node);
     }
 
-    /// Schedules a thread-safe system.
-    pub fn schedule_boxed(&mut self, system: Box<dyn system::Sendable>) {
+    pub(super) fn schedule_send(
+        &mut self,
+        scheduler: &mut scheduler::Builder,
+        system: Box<dyn system::Sendable>,
+        condition: Option<system::RunCondition>,
+    ) -> scheduler::SystemHandle {
         let mut type_visitor = referrer::VisitTypeArg::new();
         system.visit_type(&mut type_visitor);
         let state_maybe_uninit = system.state_maybe_uninit();
 
-        let (node, spec) = self.scheduler.push_send_system(system);
-        self.register_resources(spec, type_visitor, &state_maybe_uninit, true, node);
+        let (node, mut spec) = scheduler.push_send_system(system);
+        spec.run_conditions.extend(condition);
+        self.register_resources(spec, type_visitor, &state_maybe_uninit, true, scheduler, node);
+        scheduler.handle_of(node)
     }
 
-    /// Schedules a system that must be run on the main thread.
-    pub fn schedule_thread_unsafe(&mut self, system: impl system::Unsendable) {
-        self.schedule_thread_unsafe_boxed(Box::new(system))
-    }
-
-    /// Schedules a system that must be run on the main thread.
-    pub fn schedule_thread_unsafe_boxed(&mut self, system: Box<dyn system::Unsendable>) {
+    pub(super) fn schedule_unsend(
+        &mut self,
+        scheduler: &mut scheduler::Builder,
+        system: Box<dyn system::Unsendable>,
+        condition: Option<system::RunCondition>,
+    ) -> scheduler::SystemHandle {
         let mut type_visitor = referrer::VisitTypeArg::new();
         system.visit_type(&mut type_visitor);
         let state_maybe_uninit = system.state_maybe_uninit();
 
-        let (node, spec) = self.scheduler.push_unsend_system(system);
-        self.register_resources(spec, type_visitor, &state_maybe_uninit, false, node);
+        let (node, mut spec) = scheduler.push_unsend_system(system);
+        spec.run_conditions.extend(condition);
+        self.register_resources(spec, type_visitor, &state_maybe_uninit, false, scheduler, node);
+        scheduler.handle_of(node)
     }
 
-    /// Provides a thread-safe global resource.
-    pub fn global<G: Global + Send + Sync>(&mut self, value: G) {
-        self.sync_globals.insert(
-            DbgTypeId::of::<G>(),
-            (referrer::SingleVtable::of::<G>(), GlobalBuilder::Provided(Box::new(value))),
-        );
-    }
+    /// Adds the archetypes, storages, global states and event channels
+    /// that are missing in an already-built world.
+    pub(super) fn extend_world(self, world: &mut super::World) {
+        let num_shards = world.offline_buffer.shards.len();
+        let tick = world.components.next_change_tick();
+
+        for (ty, (ealloc, typed)) in self.archetypes {
+            match world.components.archetypes.get_mut(&ty) {
+                Some(existing) => existing.extend(typed, &mut world.ealloc_map, tick),
+                None => {
+                    world.ealloc_map.map.insert(ty, ealloc(num_shards));
+                    world.components.archetypes.insert(ty, typed.build());
+                }
+            }
+        }
 
-    /// Provides a thread-unsafe global resource.
-    pub fn global_thread_unsafe<G: Global>(&mut self, value: G) {
-        self.unsync_globals.insert(
-            DbgTypeId::of::<G>(),
-            (referrer::SingleVtable::of::<G>(), GlobalBuilder::Provided(Box::new(value))),
-        );
+        for (ty, (vtable, global_builder)) in self.sync_globals {
+            if world.unsync_globals.unsync_globals.contains_key(&ty) {
+                panic!("Global type {} is used as both thread-safe and thread-local", ty);
+            }
+            world
+                .sync_globals
+                .sync_globals
+                .entry(ty)
+                .or_insert_with(|| (vtable, RwLock::new(global_builder.build())));
+        }
+
+        for (ty, (vtable, global_builder)) in self.unsync_globals {
+            if world.sync_globals.sync_globals.contains_key(&ty) {
+                panic!("Global type {} is used as both thread-safe and thread-local", ty);
+            }
+            world
+                .unsync_globals
+                .unsync_globals
+                .entry(ty)
+                .or_insert_with(|| (vtable, global_builder.build()));
+        }
+
+        for (ty, builder) in self.events {
+            world.sync_globals.events.entry(ty).or_insert_with(|| builder(num_shards));
+        }
     }
+}
 
-    /// Adjust the concurrency of the scheduler.
-    /// Pass `0` to disable parallelism.
-    pub fn set_concurrency(&mut self, concurrency: usize) {
-        self.scheduler.concurrency = concurrency;
+/// The schedule that a system is scheduled into.
+#[derive(Clone, Copy)]
+enum Target<'t> {
+    Default,
+    Named(&'t str),
+    Startup,
+}
+
+/// Schedules systems into a named schedule.
+/// Returned by [`Builder::named_schedule`].
+pub struct NamedSchedule<'t> {
+    builder: &'t mut Builder,
+    name:    String,
+}
+
+impl<'t> NamedSchedule<'t> {
+    /// Schedules a thread-safe system.
+    pub fn schedule(&mut self, system: impl system::Sendable) -> scheduler::SystemHandle {
+        self.schedule_boxed(Box::new(system))
     }
 
-    /// Constructs the world from the builder.
-    pub fn build(self) -> super::World {
-        let (ealloc_map, storages) = self
-            .archetypes
-            .into_iter()
-            .map(|(ty, (ealloc, storages))| {
-                ((ty, ealloc(self.scheduler.concurrency + 1)), (ty, storages.build()))
-            })
-            .unzip();
+    /// Schedules a thread-safe system.
+    pub fn schedule_boxed(&mut self, system: Box<dyn system::Sendable>) -> scheduler::SystemHandle {
+        self.builder.schedule_send(Target::Named(&self.name), system, None)
+    }
 
-        let ealloc_map = ealloc::Map::new(ealloc_map);
-        let storages = super::Components { archetypes: storages };
+    /// Schedules a thread-safe system that only runs in cycles where `condition` holds.
+    pub fn schedule_with_condition(
+        &mut self,
+        system: impl system::Sendable,
+        condition: system::RunCondition,
+    ) -> scheduler::SystemHandle {
+        self.builder.schedule_send(Target::Named(&self.name), Box::new(system), Some(condition))
+    }
 
-        let sync_globals = self
-            .sync_globals
-            .into_iter()
-            .map(|(ty, (vtable, global_builder))| {
-                (
-                    ty,
-                    (
-                        vtable,
-                        RwLock::new(match global_builder {
-                            GlobalBuilder::Provided(value) => value,
-                            GlobalBuilder::Missing(default) => default(),
-                        }),
-                    ),
-                )
-            })
-            .collect();
-        let sync_globals = super::SyncGlobals { sync_globals };
+    /// Schedules a system that must be run on the main thread.
+    pub fn schedule_thread_unsafe(
+        &mut self,
+        system: impl system::Unsendable,
+    ) -> scheduler::SystemHandle {
+        self.schedule_thread_unsafe_boxed(Box::new(system))
+    }
 
-        let unsync_globals = self
-            .unsync_globals
-            .into_iter()
-            .map(|(ty, (vtable, global_builder))| {
-                (
-                    ty,
-                    (
-                        vtable,
-                        match global_builder {
-                            GlobalBuilder::Provided(value) => value,
-                            GlobalBuilder::Missing(default) => default(),
-                        },
-                    ),
-                )
-            })
-            .collect();
-        let unsync_globals = super::UnsyncGlobals { unsync_globals };
+    /// Schedules a system that must be run on the main thread.
+    pub fn schedule_thread_unsafe_boxed(
+        &mut self,
+        system: Box<dyn system::Unsendable>,
+    ) -> scheduler::SystemHandle {
+        self.builder.schedule_unsend(Target::Named(&self.name), system, None)
+    }
 
-        super::World {
-            ealloc_map,
-            components: storages,
-            sync_globals,
-            unsync_globals,
-            scheduler: self.scheduler.build(),
-            rctrack: Default::default(),
-        }
+    /// Schedules a system that must be run on the main thread
+    /// and only runs in cycles where `condition` holds.
+    pub fn schedule_thread_unsafe_with_condition(
+        &mut self,
+        system: impl system::Unsendable,
+        condition: system::RunCondition,
+    ) -> scheduler::SystemHandle {
+        self.builder.schedule_unsend(Target::Named(&self.name), Box::new(system), Some(condition))
     }
 }
 
 fn populate_default_globals(map: &mut GlobalBuilderMap<dyn Any + Send + Sync>) {
-    fn put_global<T: Any + Send + Sync + referrer::Referrer>(
+    fn put_global<T: Global + Send + Sync>(
         map: &mut GlobalBuilderMap<dyn Any + Send + Sync>,
         value: T,
     ) {
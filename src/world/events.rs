@@ -0,0 +1,108 @@
+//! Typed event channels between systems.
+
+use std::any::Any;
+
+use parking_lot::{RwLock, RwLockReadGuard};
+
+/// A channel of events of type `T` sent between systems.
+///
+/// Systems send events through [`EventWriter`](crate::system::EventWriter)
+/// and receive them through [`EventReader`](crate::system::EventReader).
+/// All writers of `T` run before all readers of `T` in the same cycle,
+/// enforced by [`EventPartition`](crate::system::EventPartition).
+///
+/// Events are double-buffered:
+/// events sent in a cycle remain readable until the end of the next cycle,
+/// after which they are dropped.
+/// Each reader has its own cursor, so every event is received at most once by each reader.
+///
+/// Events are not visited by entity reference tracking,
+/// so they should not contain strong entity references.
+pub struct Events<T> {
+    /// Events sent in the previous cycle, ordered by shard.
+    previous:       Vec<T>,
+    /// Sequence number of the first event in `previous`.
+    previous_start: u64,
+    /// Events sent in the current cycle.
+    /// Each worker thread appends to its own shard to avoid contention.
+    shards:         Vec<RwLock<Vec<T>>>,
+}
+
+impl<T> Events<T> {
+    pub(crate) fn new(num_shards: usize) -> Self {
+        Self {
+            previous:       Vec::new(),
+            previous_start: 0,
+            shards:         (0..num_shards).map(|_| RwLock::new(Vec::new())).collect(),
+        }
+    }
+
+    /// Returns the buffer of the current cycle for the worker with the given shard index.
+    pub(crate) fn shard(&self, index: usize) -> &RwLock<Vec<T>> {
+        self.shards.get(index).expect("shard index out of bounds")
+    }
+
+    /// Locks the buffers for reading.
+    ///
+    /// Returns the events of the previous cycle, the sequence number of its first event,
+    /// and the buffers of the current cycle.
+    ///
+    /// # Panics
+    /// Panics if a writer is still sending events, which indicates a scheduler bug.
+    pub(crate) fn lock_read(&self) -> (&[T], u64, Vec<RwLockReadGuard<'_, Vec<T>>>) {
+        let current = self
+            .shards
+            .iter()
+            .map(|shard| {
+                shard.try_read().expect(
+                    "Event buffer is locked by a writer while it is being read. Maybe scheduler \
+                     bug?",
+                )
+            })
+            .collect();
+        (&self.previous, self.previous_start, current)
+    }
+
+    /// Sends an event in offline mode.
+    ///
+    /// The event is received by readers in the next cycle.
+    pub fn send(&mut self, event: T) {
+        self.shards.last_mut().expect("at least one shard is allocated").get_mut().push(event);
+    }
+
+    /// Iterates over all retained events in offline mode,
+    /// including those that have already been received by all readers.
+    pub fn iter(&mut self) -> impl Iterator<Item = &T> + '_ {
+        self.previous.iter().chain(self.shards.iter_mut().flat_map(|shard| shard.get_mut().iter()))
+    }
+
+    /// Drops the events of the previous cycle
+    /// and moves the events of the current cycle to the previous buffer.
+    fn swap_buffers(&mut self) {
+        self.previous_start += self.previous.len() as u64;
+        self.previous.clear();
+        for shard in &mut self.shards {
+            self.previous.append(shard.get_mut());
+        }
+    }
+}
+
+/// Builds an [`Events`] channel with the given number of shards.
+pub(crate) type Builder = fn(usize) -> Box<dyn AnyEvents>;
+
+/// Type-erased [`Events`] stored in [`SyncGlobals`](super::SyncGlobals).
+pub(crate) trait AnyEvents: Send + Sync {
+    fn swap_buffers(&mut self);
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Send + Sync + 'static> AnyEvents for Events<T> {
+    fn swap_buffers(&mut self) { Events::swap_buffers(self) }
+
+    fn as_any(&self) -> &dyn Any { self }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any { self }
+}
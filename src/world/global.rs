@@ -4,6 +4,7 @@ use std::ops;
 
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
+use super::events::{AnyEvents, Events};
 use crate::entity::referrer;
 use crate::util::DbgTypeId;
 use crate::Global;
@@ -13,11 +14,13 @@ pub struct SyncGlobals {
     /// Global states that can be concurrently accessed by systems on other threads.
     pub(crate) sync_globals:
         HashMap<DbgTypeId, (referrer::SingleVtable, RwLock<Box<dyn Any + Send + Sync>>)>,
+    /// Event channels used by systems, keyed by the event type.
+    pub(crate) events:       HashMap<DbgTypeId, Box<dyn AnyEvents>>,
 }
 
 impl SyncGlobals {
     /// Creates a dummy, empty global store.
-    pub fn empty() -> Self { Self { sync_globals: HashMap::new() } }
+    pub fn empty() -> Self { Self { sync_globals: HashMap::new(), events: HashMap::new() } }
 
     /// Retrieves a read-only, shared reference to the given global state.
     ///
@@ -81,6 +84,44 @@ impl SyncGlobals {
         };
         lock.get_mut().downcast_mut::<G>().expect("TypeId mismatch")
     }
+
+    /// Retrieves a shared reference to the event channel of type `T`.
+    ///
+    /// # Panics
+    /// Panics if the event type is not used in any systems.
+    pub fn events<T: Send + Sync + 'static>(&self) -> &Events<T> {
+        match self.events.get(&TypeId::of::<T>()) {
+            Some(events) => events.as_any().downcast_ref::<Events<T>>().expect("TypeId mismatch"),
+            None => panic!(
+                "The event type {} cannot be used because it is not used in any systems",
+                any::type_name::<T>()
+            ),
+        }
+    }
+
+    /// Returns a reference to the event channel of type `T` in offline mode.
+    ///
+    /// # Panics
+    /// Panics if the event type is not used in any systems.
+    pub fn events_mut<T: Send + Sync + 'static>(&mut self) -> &mut Events<T> {
+        match self.events.get_mut(&TypeId::of::<T>()) {
+            Some(events) => {
+                events.as_any_mut().downcast_mut::<Events<T>>().expect("TypeId mismatch")
+            }
+            None => panic!(
+                "The event type {} cannot be used because it is not used in any systems",
+                any::type_name::<T>()
+            ),
+        }
+    }
+
+    /// Drops the events sent in the previous cycle
+    /// and retains the events sent in the current cycle for one more cycle.
+    pub(crate) fn swap_event_buffers(&mut self) {
+        for events in self.events.values_mut() {
+            events.swap_buffers();
+        }
+    }
 }
 
 /// Stores the thread-unsafe global states in a world.
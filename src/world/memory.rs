@@ -0,0 +1,67 @@
+//! Reports and releases the memory allocated by component storages.
+
+use super::World;
+use crate::storage;
+
+/// The memory allocated by a component storage, as listed in a [`MemoryReport`].
+#[derive(Debug, Clone)]
+pub struct StorageMemory {
+    /// The type name of the archetype.
+    pub archetype:   &'static str,
+    /// The type name of the component, or the ID of a [dynamic component](crate::comp::dynamic).
+    ///
+    /// Type names are only available in debug builds.
+    pub component:   String,
+    /// The discriminant of an isotope component, or `None` for other components.
+    pub discrim:     Option<usize>,
+    /// The number of entities with the component.
+    pub cardinality: usize,
+    /// The memory allocated by the storage.
+    pub usage:       storage::MemoryUsage,
+}
+
+/// The memory allocated by all component storages in a world,
+/// returned by [`World::memory_report`].
+#[derive(Debug, Clone, Default)]
+pub struct MemoryReport {
+    /// The memory allocated by each storage,
+    /// sorted by archetype, component and discriminant.
+    pub storages: Vec<StorageMemory>,
+}
+
+impl MemoryReport {
+    /// The total number of bytes allocated by all storages.
+    pub fn total_bytes(&self) -> usize {
+        self.storages.iter().map(|storage| storage.usage.total_bytes()).sum()
+    }
+}
+
+impl World {
+    /// Reports the memory allocated by each component storage,
+    /// including each discriminant of isotope components.
+    ///
+    /// Storage types that do not override [`Storage::memory_usage`](storage::Storage::memory_usage)
+    /// are reported with zero usage.
+    pub fn memory_report(&self) -> MemoryReport {
+        let mut storages = Vec::new();
+        for typed in self.components.archetypes.values() {
+            typed.memory_report(&mut storages);
+        }
+        storages.sort_by(|a, b| {
+            (a.archetype, &a.component, a.discrim).cmp(&(b.archetype, &b.component, b.discrim))
+        });
+        MemoryReport { storages }
+    }
+
+    /// Releases the memory reserved by component storages beyond the greatest entity
+    /// with the component.
+    ///
+    /// This is useful after deleting a large number of entities,
+    /// since storages do not release memory when components are removed.
+    /// Subsequent component insertions may need to reallocate the released memory.
+    pub fn compact_storages(&mut self) {
+        for typed in self.components.archetypes.values_mut() {
+            typed.compact_storages();
+        }
+    }
+}
@@ -1,7 +1,16 @@
 //! Operations queued to be executed after the cycle joins.
 
+use std::any::Any;
+use std::collections::HashMap;
+use std::mem;
+use std::sync::atomic::AtomicU64;
+
+use rayon::prelude::*;
+
 use super::WorldMut;
-use crate::entity::{self, ealloc};
+use crate::entity::referrer::rearrange::Rearrange;
+use crate::entity::{self, ealloc, generation, rctrack, Raw};
+use crate::util::DbgTypeId;
 use crate::{comp, system, world, Archetype};
 
 /// An operation to be executed after join.
@@ -12,6 +21,9 @@ pub(crate) trait Operation: Send {
         world: WorldMut<'_>,
         systems: &mut [(&str, &mut dyn system::Descriptor)],
     ) -> OperationResult;
+
+    /// Updates the entity IDs referenced by this operation after [rearrangement](world::World::rearrange).
+    fn rearrange(&mut self, _state: &Rearrange) {}
 }
 
 /// Result of an operation.
@@ -23,8 +35,8 @@ pub(crate) enum OperationResult {
     QueueForRerun(Box<dyn Operation>),
 }
 
-/// Create an entity.
-pub(crate) struct CreateEntity<A: Archetype> {
+/// An entity that was allocated online and is initialized offline.
+struct CreateEntity<A: Archetype> {
     /// The entity ID, which was already allocated.
     entity:   A::RawEntity,
     /// The entity ref count, only useful in debug mode.
@@ -33,22 +45,112 @@ pub(crate) struct CreateEntity<A: Archetype> {
     comp_map: comp::Map<A>,
 }
 
-impl<A: Archetype> Operation for CreateEntity<A> {
-    fn run(
-        self: Box<Self>,
-        world: WorldMut<'_>,
-        _systems: &mut [(&str, &mut dyn system::Descriptor)],
-    ) -> OperationResult {
-        world::init_entity(
-            world.sync_globals,
-            self.entity,
-            self.rc,
-            world.rctrack,
-            world.components,
-            self.comp_map,
-            world.ealloc_map,
-        );
-        OperationResult::Ok
+/// Entity creations of a single archetype queued in a shard.
+trait AnyCreations: Send {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Initializes the queued entities in the order they were created.
+    fn init(self: Box<Self>, archetype: &mut ArchetypeParts, change_clock: &AtomicU64);
+}
+
+struct Creations<A: Archetype>(Vec<CreateEntity<A>>);
+
+impl<A: Archetype> AnyCreations for Creations<A> {
+    fn as_any_mut(&mut self) -> &mut dyn Any { self }
+
+    fn init(self: Box<Self>, archetype: &mut ArchetypeParts, change_clock: &AtomicU64) {
+        let typed = archetype
+            .typed
+            .as_any_mut()
+            .downcast_mut::<world::typed::Typed<A>>()
+            .expect("TypeId mismatch");
+        let ealloc =
+            archetype.ealloc.as_any_mut().downcast_mut::<A::Ealloc>().expect("TypeId mismatch");
+
+        for CreateEntity { entity, rc, comp_map } in self.0 {
+            archetype.generations.next(entity.to_primitive());
+            archetype.rctrack.set(entity.to_primitive(), rc);
+            typed.init_entity(entity, comp_map, ealloc, world::rw::next_change_tick(change_clock));
+        }
+    }
+}
+
+/// The states of an archetype moved out of the world,
+/// so that archetypes can be initialized concurrently.
+struct ArchetypeParts {
+    typed:       Box<dyn world::typed::AnyTyped>,
+    ealloc:      Box<dyn ealloc::AnyEalloc>,
+    generations: generation::Store,
+    rctrack:     rctrack::MaybeStore,
+}
+
+/// Initializes the entities created during a cycle.
+///
+/// Each archetype is initialized as a separate job on `pool` if available.
+/// Storages within an archetype are still initialized serially,
+/// since component initializers may depend on other components of the same entity.
+fn init_entities(
+    world: WorldMut<'_>,
+    creations: HashMap<DbgTypeId, Vec<Box<dyn AnyCreations>>>,
+    pool: Option<&rayon::ThreadPool>,
+) {
+    let generations = world.sync_globals.get_mut::<generation::StoreMap>();
+
+    let jobs: Vec<_> = creations
+        .into_iter()
+        .map(|(ty, creations)| {
+            let parts = ArchetypeParts {
+                typed:       world
+                    .components
+                    .archetypes
+                    .remove(&ty)
+                    .expect("archetype of created entity is registered"),
+                ealloc:      world
+                    .ealloc_map
+                    .map
+                    .remove(&ty)
+                    .expect("archetype of created entity is registered"),
+                generations: mem::take(generations.get_mut_by_id(ty)),
+                rctrack:     mem::take(world.rctrack.0.get_mut_by_id(ty)),
+            };
+            (ty, creations, parts)
+        })
+        .collect();
+
+    let mut moved = MovedArchetypes { world, jobs };
+
+    let change_clock = &moved.world.components.change_clock;
+    let run = |(_, creations, parts): &mut Job| {
+        for creations in creations.drain(..) {
+            creations.init(parts, change_clock);
+        }
+    };
+    match pool {
+        Some(pool) if moved.jobs.len() > 1 => {
+            pool.install(|| moved.jobs.par_iter_mut().for_each(run));
+        }
+        _ => moved.jobs.iter_mut().for_each(run),
+    }
+}
+
+type Job = (DbgTypeId, Vec<Box<dyn AnyCreations>>, ArchetypeParts);
+
+/// Reinserts the archetypes moved out by [`init_entities`] into the world when dropped,
+/// so that the world remains consistent even if a component initializer panics.
+struct MovedArchetypes<'t> {
+    world: WorldMut<'t>,
+    jobs:  Vec<Job>,
+}
+
+impl Drop for MovedArchetypes<'_> {
+    fn drop(&mut self) {
+        let generations = self.world.sync_globals.get_mut::<generation::StoreMap>();
+        for (ty, _, parts) in self.jobs.drain(..) {
+            self.world.components.archetypes.insert(ty, parts.typed);
+            self.world.ealloc_map.map.insert(ty, parts.ealloc);
+            *generations.get_mut_by_id(ty) = parts.generations;
+            *self.world.rctrack.0.get_mut_by_id(ty) = parts.rctrack;
+        }
     }
 }
 
@@ -67,6 +169,8 @@ impl<A: Archetype> Operation for DeleteEntity<A> {
             world::DeleteResult::Terminating => OperationResult::QueueForRerun(self),
         }
     }
+
+    fn rearrange(&mut self, state: &Rearrange) { self.entity = state.map_raw::<A>(self.entity); }
 }
 
 /// A sharded store for offline operations.
@@ -79,38 +183,75 @@ pub(crate) struct Buffer {
 
 impl Buffer {
     pub(crate) fn new(num_shards: usize) -> Self {
-        let shards = (0..num_shards).map(|_| BufferShard::default()).collect();
+        let shards =
+            (0..num_shards).map(|index| BufferShard { index, ..BufferShard::default() }).collect();
         Self { rerun_queue: Vec::new(), shards }
     }
 
+    /// Executes the operations queued during a cycle in the order they were queued.
+    ///
+    /// Consecutive entity creations are batched,
+    /// with different archetypes in a batch initialized concurrently on `pool` if available.
     pub(crate) fn drain_cycle(
         &mut self,
         mut world: WorldMut<'_>,
         mut systems: Vec<(&str, &mut dyn system::Descriptor)>,
+        pool: Option<&rayon::ThreadPool>,
     ) {
+        let mut creations: HashMap<DbgTypeId, Vec<Box<dyn AnyCreations>>> = HashMap::new();
         let mut new_queue = Vec::new();
-        for op in self
+        for item in self
             .rerun_queue
             .drain(..)
+            .map(Item::Operation)
             .chain(self.shards.iter_mut().flat_map(|shard| shard.items.drain(..)))
         {
+            let op = match item {
+                Item::Creations(ty, shard_creations) => {
+                    creations.entry(ty).or_default().push(shard_creations);
+                    continue;
+                }
+                Item::Operation(op) => op,
+            };
+
+            if !creations.is_empty() {
+                init_entities(world.as_mut(), mem::take(&mut creations), pool);
+            }
+
             let result = op.run(world.as_mut(), &mut systems[..]);
             match result {
                 OperationResult::Ok => {}
                 OperationResult::QueueForRerun(op) => new_queue.push(op),
             }
         }
+        if !creations.is_empty() {
+            init_entities(world.as_mut(), creations, pool);
+        }
         self.rerun_queue = new_queue;
     }
 }
 
+/// An item in the queue of a [`BufferShard`].
+enum Item {
+    /// Consecutive entity creations of an archetype.
+    Creations(DbgTypeId, Box<dyn AnyCreations>),
+    Operation(Box<dyn Operation>),
+}
+
 /// A shard of offline operation store.
 #[derive(Default)]
 pub struct BufferShard {
-    items: Vec<Box<dyn Operation>>,
+    items: Vec<Item>,
+    index: usize,
 }
 
 impl BufferShard {
+    /// The index of this shard.
+    ///
+    /// Each worker thread owns a distinct shard during a cycle,
+    /// so the index can be used to select other per-thread buffers.
+    pub fn index(&self) -> usize { self.index }
+
     /// Creates an entity and queues for initialization.
     pub fn create_entity<A: Archetype>(
         &mut self,
@@ -156,7 +297,30 @@ impl BufferShard {
 
         let allocated = entity::Entity::new_allocated(entity);
 
-        self.items.push(Box::new(CreateEntity { entity, comp_map, rc: allocated.rc.clone() }));
+        let ty = DbgTypeId::of::<A>();
+        // creations of different archetypes commute,
+        // so join the creations of `A` queued after the last other operation if any
+        let trailing = self
+            .items
+            .iter()
+            .rposition(|item| matches!(item, Item::Operation(_)))
+            .map_or(0, |index| index + 1);
+        let index = match self.items[trailing..]
+            .iter()
+            .position(|item| matches!(item, Item::Creations(other, _) if *other == ty))
+        {
+            Some(index) => trailing + index,
+            None => {
+                self.items.push(Item::Creations(ty, Box::new(Creations::<A>(Vec::new()))));
+                self.items.len() - 1
+            }
+        };
+        let Item::Creations(_, creations) = &mut self.items[index] else {
+            unreachable!("index points to creations")
+        };
+        let creations =
+            creations.as_any_mut().downcast_mut::<Creations<A>>().expect("TypeId mismatch");
+        creations.0.push(CreateEntity { entity, comp_map, rc: allocated.rc.clone() });
 
         allocated
     }
@@ -165,6 +329,6 @@ impl BufferShard {
     pub fn delete_entity<A: Archetype, E: entity::Ref<Archetype = A>>(&mut self, entity: E) {
         let entity = entity.id();
 
-        self.items.push(Box::new(DeleteEntity::<A> { entity }));
+        self.items.push(Item::Operation(Box::new(DeleteEntity::<A> { entity })));
     }
 }
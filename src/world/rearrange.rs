@@ -0,0 +1,144 @@
+//! Permutes the entities of an archetype.
+
+use std::any::{self, Any};
+
+use super::World;
+use crate::entity::raw::Atomic as _;
+use crate::entity::referrer::rearrange::{Moved, Rearrange};
+use crate::entity::{generation, Ealloc, Permutation, Raw};
+use crate::util::DbgTypeId;
+use crate::Archetype;
+
+impl World {
+    /// Rearranges the entities of an archetype according to a permutation.
+    ///
+    /// The `i`-th entity in `permutation` is moved to the `i`-th smallest entity ID,
+    /// and all components of the entity are moved together.
+    /// All [strong](crate::Entity) and [weak](crate::entity::Weak) references to the moved entities
+    /// in components, global states and system-local states are updated accordingly.
+    /// Weak references to deleted entities remain dangling.
+    ///
+    /// After rearrangement, the archetype has no recyclable IDs,
+    /// and newly created entities are allocated after the rearranged entities.
+    ///
+    /// # Panics
+    /// Panics if `permutation` does not contain every allocated entity of the archetype exactly once.
+    ///
+    /// If entity refcounting is enabled,
+    /// also panics if a strong reference to a moved entity is held outside the world,
+    /// since such references cannot be updated.
+    pub fn rearrange<A: Archetype>(&mut self, permutation: Permutation<A>) {
+        let ealloc = self.ealloc_map.get::<A>();
+        Ealloc::flush(ealloc);
+
+        let mut allocated = Vec::new();
+        for chunk in Ealloc::snapshot(ealloc).iter_allocated_chunks() {
+            for entity in A::RawEntity::range(chunk) {
+                let id = entity.to_primitive();
+                if allocated.len() <= id {
+                    allocated.resize(id + 1, false);
+                }
+                *allocated.get_mut(id).expect("just resized") = true;
+            }
+        }
+        let count = allocated.iter().filter(|&&b| b).count();
+
+        let base = <A::RawEntity as Raw>::new().load_mut();
+        let mut moves = Vec::with_capacity(count);
+        for (index, old) in permutation.iter().enumerate() {
+            match allocated.get_mut(old.to_primitive()) {
+                Some(slot @ true) => *slot = false,
+                _ => panic!(
+                    "Permutation contains entity {}#{old:?}, which is not allocated or is \
+                     duplicated",
+                    any::type_name::<A>(),
+                ),
+            }
+            moves.push((old, base.add(index)));
+        }
+        assert_eq!(
+            moves.len(),
+            count,
+            "Permutation does not contain all allocated entities of {}",
+            any::type_name::<A>(),
+        );
+
+        let primitive_moves: Vec<_> =
+            moves.iter().map(|&(old, new)| (old.to_primitive(), new.to_primitive())).collect();
+
+        let tick = self.components.next_change_tick();
+        self.components.archetype_mut::<A>().rearrange(&moves, tick);
+
+        let generations =
+            self.sync_globals.get_mut::<generation::StoreMap>().rearrange::<A>(&primitive_moves);
+
+        let mut state = Rearrange::new(
+            DbgTypeId::of::<A>(),
+            primitive_moves.iter().zip(generations).map(
+                |(&(old, new_raw), (old_generation, new_generation))| {
+                    (old, Moved { new_raw, old_generation, new_generation })
+                },
+            ),
+        );
+        self.visit_rearrange(&mut state);
+
+        #[cfg(any(
+            all(debug_assertions, feature = "debug-entity-rc"),
+            all(not(debug_assertions), feature = "release-entity-rc"),
+        ))]
+        {
+            let store = self.rctrack.0.get_mut::<A>();
+            store.rearrange(&primitive_moves);
+
+            for &(_, new) in &primitive_moves {
+                let rc = store.get(new).expect("just moved");
+                let visits = state.strong_visits.get(new).copied().unwrap_or(0);
+                assert!(
+                    std::sync::Arc::strong_count(rc) == visits + 1,
+                    "Detected strong reference to entity {}#{:?} outside the world during \
+                     rearrangement. All strong references held outside components, global states \
+                     and system-local states must be dropped before rearranging entities.",
+                    any::type_name::<A>(),
+                    A::RawEntity::from_primitive(new),
+                );
+            }
+        }
+
+        Ealloc::reset_compact(self.ealloc_map.get::<A>(), count);
+    }
+
+    /// Rewrites all entity references in the world with the given rearrangement state.
+    fn visit_rearrange(&mut self, state: &mut Rearrange) {
+        for op in &mut self.offline_buffer.rerun_queue {
+            op.rearrange(state);
+        }
+
+        let system_refs = super::all_system_refs(
+            &mut self.scheduler,
+            &mut self.named_schedulers,
+            self.startup_scheduler.as_mut(),
+        );
+        for (_, system) in system_refs {
+            system.visit_mut().0.rearrange(state);
+        }
+
+        let globals = self
+            .sync_globals
+            .sync_globals
+            .values_mut()
+            .map(|(vtable, value)| (vtable, &mut **value.get_mut() as &mut dyn Any))
+            .chain(
+                self.unsync_globals
+                    .unsync_globals
+                    .values_mut()
+                    .map(|(vtable, value)| (vtable, &mut **value)),
+            );
+        for (vtable, value) in globals {
+            vtable.rearrange(value, state);
+        }
+
+        for (archetype, typed) in &mut self.components.archetypes {
+            typed.referrer_dyn_iter(&archetype.to_string()).rearrange(state);
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Schedules, unschedules, enables and disables systems after the world is built.
+
+use super::{builder, Scheduler, World};
+use crate::entity::referrer::search_single::SearchStrong;
+use crate::scheduler::{PanicPolicy, SystemHandle};
+use crate::system;
+
+impl World {
+    /// Schedules a thread-safe system into the default schedule in offline mode.
+    ///
+    /// The topology of the schedule is rebuilt with the same conflict checking as
+    /// [`Builder::schedule`](super::Builder::schedule).
+    /// Archetypes, components, global states and event channels requested by the system
+    /// are registered if they are not used by any scheduled system yet.
+    /// New global states are initialized with their initial values,
+    /// and existing entities are initialized with the auto-initializer
+    /// of each newly added simple component, if any.
+    ///
+    /// # Panics
+    /// Panics if the system cannot be scheduled together with the existing systems,
+    /// e.g. due to a cyclic dependency.
+    /// The schedule is left unchanged in that case.
+    ///
+    /// Also panics if the system requests a new [required](crate::comp::Presence::Required)
+    /// simple component without an auto-initializer for an archetype with existing entities,
+    /// since the existing entities would not have the component.
+    pub fn schedule(&mut self, system: impl system::Sendable) -> SystemHandle {
+        self.schedule_boxed(Box::new(system))
+    }
+
+    /// Schedules a thread-safe system into the default schedule in offline mode.
+    ///
+    /// See [`schedule`](Self::schedule) for details.
+    pub fn schedule_boxed(&mut self, system: Box<dyn system::Sendable>) -> SystemHandle {
+        let mut registry = builder::Registry::default();
+        let handle =
+            self.scheduler.edit(|scheduler| registry.schedule_send(scheduler, system, None));
+        registry.extend_world(self);
+        handle
+    }
+
+    /// Schedules a system that must be run on the main thread
+    /// into the default schedule in offline mode.
+    ///
+    /// See [`schedule`](Self::schedule) for details.
+    pub fn schedule_thread_unsafe(&mut self, system: impl system::Unsendable) -> SystemHandle {
+        self.schedule_thread_unsafe_boxed(Box::new(system))
+    }
+
+    /// Schedules a system that must be run on the main thread
+    /// into the default schedule in offline mode.
+    ///
+    /// See [`schedule`](Self::schedule) for details.
+    pub fn schedule_thread_unsafe_boxed(
+        &mut self,
+        system: Box<dyn system::Unsendable>,
+    ) -> SystemHandle {
+        let mut registry = builder::Registry::default();
+        let handle =
+            self.scheduler.edit(|scheduler| registry.schedule_unsend(scheduler, system, None));
+        registry.extend_world(self);
+        handle
+    }
+
+    /// Schedules a thread-safe system into the [named schedule](super::Builder::named_schedule)
+    /// `name` in offline mode.
+    ///
+    /// See [`schedule`](Self::schedule) for details.
+    ///
+    /// # Panics
+    /// Panics if no systems were scheduled into the schedule `name` when the world was built,
+    /// in addition to the conditions listed in [`schedule`](Self::schedule).
+    pub fn schedule_named(&mut self, name: &str, system: impl system::Sendable) -> SystemHandle {
+        self.schedule_named_boxed(name, Box::new(system))
+    }
+
+    /// Schedules a thread-safe system into the [named schedule](super::Builder::named_schedule)
+    /// `name` in offline mode.
+    ///
+    /// See [`schedule_named`](Self::schedule_named) for details.
+    pub fn schedule_named_boxed(
+        &mut self,
+        name: &str,
+        system: Box<dyn system::Sendable>,
+    ) -> SystemHandle {
+        let mut registry = builder::Registry::default();
+        let handle = self
+            .named_scheduler(name)
+            .edit(|scheduler| registry.schedule_send(scheduler, system, None));
+        registry.extend_world(self);
+        handle
+    }
+
+    /// Schedules a system that must be run on the main thread
+    /// into the [named schedule](super::Builder::named_schedule) `name` in offline mode.
+    ///
+    /// See [`schedule_named`](Self::schedule_named) for details.
+    pub fn schedule_named_thread_unsafe(
+        &mut self,
+        name: &str,
+        system: impl system::Unsendable,
+    ) -> SystemHandle {
+        self.schedule_named_thread_unsafe_boxed(name, Box::new(system))
+    }
+
+    /// Schedules a system that must be run on the main thread
+    /// into the [named schedule](super::Builder::named_schedule) `name` in offline mode.
+    ///
+    /// See [`schedule_named`](Self::schedule_named) for details.
+    pub fn schedule_named_thread_unsafe_boxed(
+        &mut self,
+        name: &str,
+        system: Box<dyn system::Unsendable>,
+    ) -> SystemHandle {
+        let mut registry = builder::Registry::default();
+        let handle = self
+            .named_scheduler(name)
+            .edit(|scheduler| registry.schedule_unsend(scheduler, system, None));
+        registry.extend_world(self);
+        handle
+    }
+
+    /// Removes a system from the schedule it was scheduled into.
+    ///
+    /// The dependencies and resource accesses of the system are removed from the topology,
+    /// and the system-local states of the system are dropped.
+    /// Archetypes, components and global states used by the system are retained.
+    ///
+    /// # Panics
+    /// Panics if `handle` does not identify a system scheduled in this world.
+    ///
+    /// Also panics if the system-local states still hold strong references to entities,
+    /// since the references would be dropped without the entity being deleted explicitly.
+    pub fn unschedule(&mut self, handle: SystemHandle) {
+        let scheduler = self.scheduler_of(handle);
+
+        let (debug_name, system) =
+            scheduler.get_system_mut(handle).expect("checked in scheduler_of");
+        let mut state = SearchStrong::default();
+        system.visit_mut().0.search_strong(&mut state);
+        if state.found {
+            panic!(
+                "Cannot unschedule system {debug_name} because its local states still hold strong \
+                 references to entities. All strong references in a system must be dropped before \
+                 unscheduling it."
+            );
+        }
+
+        let removed = scheduler.edit(|scheduler| scheduler.remove_system(handle));
+        debug_assert!(removed, "checked in scheduler_of");
+    }
+
+    /// Enables or disables a system.
+    ///
+    /// A disabled system is not run, but it still counts as completed for partition ordering,
+    /// so systems that depend on it are woken as usual.
+    /// Unlike a system skipped by a [run condition](system::RunCondition),
+    /// a disabled system does not block systems that conflict with it.
+    /// The system-local states of a disabled system are retained.
+    ///
+    /// # Panics
+    /// Panics if `handle` does not identify a system scheduled in this world.
+    pub fn set_enabled(&mut self, handle: SystemHandle, enabled: bool) {
+        let found = self.scheduler_of(handle).set_enabled(handle, enabled);
+        debug_assert!(found, "checked in scheduler_of");
+    }
+
+    /// Sets the static weight of a system.
+    ///
+    /// When multiple systems are runnable,
+    /// the planner starts the system with the heaviest chain of dependent systems first.
+    /// The weight estimates the run duration of the system relative to other systems,
+    /// and defaults to 1.
+    /// With [`Weighting::Measured`](crate::scheduler::Weighting::Measured),
+    /// the static weight is only used until the system has been measured.
+    ///
+    /// # Panics
+    /// Panics if `handle` does not identify a system scheduled in this world.
+    pub fn set_weight(&mut self, handle: SystemHandle, weight: u64) {
+        let found = self.scheduler_of(handle).set_weight(handle, weight);
+        debug_assert!(found, "checked in scheduler_of");
+    }
+
+    /// Sets how the executor handles a panic in a system.
+    ///
+    /// Systems abort the cycle on panic by default.
+    /// With other policies, the panic is caught and reported in the
+    /// [`CycleReport`](crate::scheduler::CycleReport) returned by [`execute`](Self::execute),
+    /// and the other systems in the cycle continue to run.
+    /// Note that the panicking system may have left its system-local states,
+    /// as well as the globals and components it writes to, partially updated.
+    ///
+    /// # Panics
+    /// Panics if `handle` does not identify a system scheduled in this world.
+    pub fn set_panic_policy(&mut self, handle: SystemHandle, policy: PanicPolicy) {
+        let found = self.scheduler_of(handle).set_panic_policy(handle, policy);
+        debug_assert!(found, "checked in scheduler_of");
+    }
+
+    /// Returns the scheduler of the named schedule `name`.
+    fn named_scheduler(&mut self, name: &str) -> &mut Scheduler {
+        match self.named_schedulers.get_mut(name) {
+            Some(scheduler) => scheduler,
+            None => panic!("No systems were scheduled into the schedule {name:?}"),
+        }
+    }
+
+    /// Returns the scheduler that contains the system identified by `handle`.
+    fn scheduler_of(&mut self, handle: SystemHandle) -> &mut Scheduler {
+        let schedulers = [&mut self.scheduler]
+            .into_iter()
+            .chain(self.named_schedulers.values_mut())
+            .chain(&mut self.startup_scheduler);
+        for scheduler in schedulers {
+            if scheduler.get_system_mut(handle).is_some() {
+                return scheduler;
+            }
+        }
+        panic!("{handle:?} is not scheduled in this world")
+    }
+}
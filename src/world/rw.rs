@@ -2,22 +2,36 @@
 
 use std::any::type_name;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use super::typed;
 use crate::util::DbgTypeId;
-use crate::Archetype;
+use crate::{storage, Archetype};
 
+pub(crate) mod dynamic;
 pub(crate) mod isotope;
 pub(crate) mod simple;
 
 /// Stores the component states in a world.
 pub struct Components {
-    pub(crate) archetypes: HashMap<DbgTypeId, Box<dyn typed::AnyTyped>>,
+    pub(crate) archetypes:   HashMap<DbgTypeId, Box<dyn typed::AnyTyped>>,
+    /// The last [`Tick`](storage::Tick) allocated for change tracking.
+    pub(crate) change_clock: AtomicU64,
 }
 
 impl Components {
     /// Creates a dummy, empty component store used for testing.
-    pub fn empty() -> Self { Self { archetypes: HashMap::new() } }
+    pub fn empty() -> Self { Self { archetypes: HashMap::new(), change_clock: AtomicU64::new(0) } }
+
+    /// Allocates a new tick for change tracking, later than all previously allocated ticks.
+    pub(crate) fn next_change_tick(&self) -> storage::Tick { next_change_tick(&self.change_clock) }
+
+    /// Delivers the simple component add/remove events recorded since the previous delivery.
+    pub(crate) fn deliver_simple_events(&mut self) {
+        for typed in self.archetypes.values_mut() {
+            typed.deliver_simple_events();
+        }
+    }
 
     /// Fetches the [`Typed`](typed::Typed) for the requested archetype.
     pub(crate) fn archetype<A: Archetype>(&self) -> &typed::Typed<A> {
@@ -42,6 +56,13 @@ impl Components {
     }
 }
 
+/// Allocates a new tick from `change_clock`, later than all previously allocated ticks.
+///
+/// This allows allocating ticks while the archetypes of [`Components`] are borrowed mutably.
+pub(crate) fn next_change_tick(change_clock: &AtomicU64) -> storage::Tick {
+    storage::Tick(change_clock.fetch_add(1, Ordering::Relaxed) + 1)
+}
+
 #[cfg(test)]
 #[allow(clippy::extra_unused_type_parameters)] // macro magic
 mod _assert {
@@ -0,0 +1,86 @@
+use std::any::type_name;
+use std::ops;
+
+use crate::world::{self};
+use crate::{comp, storage, system, Archetype};
+
+/// Provides access to a dynamic component in a specific archetype.
+pub type ReadDynamic<'t, A: Archetype> = system::AccessDynamic<
+    A,
+    impl ops::Deref<Target = storage::Dynamic<<A as Archetype>::RawEntity>> + 't,
+>;
+
+/// Provides access to a dynamic component in a specific archetype.
+pub type WriteDynamic<'t, A: Archetype> = system::AccessDynamic<
+    A,
+    impl ops::DerefMut<Target = storage::Dynamic<<A as Archetype>::RawEntity>> + 't,
+>;
+
+impl world::Components {
+    /// Creates a read-only, shared accessor to the given archetyped dynamic component.
+    ///
+    /// # Panics
+    /// - if the archetyped component is not used in any systems
+    /// - if another thread is exclusively accessing the same archetyped component.
+    pub fn read_dynamic_storage<A: Archetype>(&self, comp: comp::dynamic::Id) -> ReadDynamic<A> {
+        let storage = match self.archetype::<A>().dynamic_storages.get(&comp) {
+            Some(storage) => storage,
+            None => panic!(
+                "The component {}/{comp} cannot be used because it is not used in any systems",
+                type_name::<A>(),
+            ),
+        };
+        let guard = match storage.try_read() {
+            Some(guard) => guard,
+            None => panic!(
+                "The component {}/{comp} is currently exclusively locked by another system. Maybe \
+                 scheduler bug?",
+                type_name::<A>(),
+            ),
+        };
+
+        system::AccessDynamic::new(guard)
+    }
+
+    /// Creates a writable, exclusive accessor to the given archetyped dynamic component.
+    ///
+    /// # Panics
+    /// - if the archetyped component is not used in any systems.
+    /// - if another thread is accessing the same archetyped component.
+    pub fn write_dynamic_storage<A: Archetype>(&self, comp: comp::dynamic::Id) -> WriteDynamic<A> {
+        let storage = match self.archetype::<A>().dynamic_storages.get(&comp) {
+            Some(storage) => storage,
+            None => panic!(
+                "The component {}/{comp} cannot be used because it is not used in any systems",
+                type_name::<A>(),
+            ),
+        };
+        let guard = match storage.try_write() {
+            Some(guard) => guard,
+            None => panic!(
+                "The component {}/{comp} is currently used by another system. Maybe scheduler bug?",
+                type_name::<A>(),
+            ),
+        };
+
+        system::AccessDynamic::new(guard)
+    }
+
+    /// Exclusively accesses a dynamic component in offline mode.
+    ///
+    /// Requires a mutable reference to the world to ensure that the world is offline.
+    pub fn get_dynamic_storage<A: Archetype>(
+        &mut self,
+        comp: comp::dynamic::Id,
+    ) -> system::AccessDynamic<A, &mut storage::Dynamic<A::RawEntity>> {
+        let typed = self.archetype_mut::<A>();
+        let storage = match typed.dynamic_storages.get_mut(&comp) {
+            Some(storage) => storage,
+            None => panic!(
+                "The component {}/{comp} cannot be retrieved because it is not used in any systems",
+                type_name::<A>(),
+            ),
+        };
+        system::AccessDynamic::new(storage.get_mut())
+    }
+}
@@ -26,7 +26,11 @@ impl world::Components {
     }
 
     /// Gets an isotope component for a specific entity and discriminant.
-    pub fn get_isotope<A, C, E>(&mut self, entity: E, discrim: C::Discrim) -> Option<&mut C>
+    pub fn get_isotope<A, C, E>(
+        &mut self,
+        entity: E,
+        discrim: C::Discrim,
+    ) -> Option<comp::Mut<'_, A, C>>
     where
         A: Archetype,
         C: comp::Isotope<A>,
@@ -3,6 +3,7 @@ use std::ops;
 
 use parking_lot::{RwLockReadGuard, RwLockWriteGuard};
 
+use crate::storage::Storage as _;
 use crate::world::{self};
 use crate::{comp, system, Archetype};
 
@@ -63,7 +64,7 @@ impl world::Components {
                 type_name::<C>()
             ),
         };
-        let guard = match storage.storage.try_write() {
+        let mut guard = match storage.storage.try_write() {
             Some(guard) => guard,
             None => panic!(
                 "The component {}/{} is currently used by another system. Maybe scheduler bug?",
@@ -71,6 +72,7 @@ impl world::Components {
                 type_name::<C>()
             ),
         };
+        guard.set_change_tick(self.next_change_tick());
         let guard = RwLockWriteGuard::map(guard, |storage| storage.downcast_mut::<C>());
 
         system::AccessSingle::new(guard)
@@ -82,6 +84,7 @@ impl world::Components {
     pub fn get_simple_storage<A: Archetype, C: comp::Simple<A>>(
         &mut self,
     ) -> system::AccessSingle<A, C, &mut C::Storage> {
+        let tick = self.next_change_tick();
         let typed = self.archetype_mut::<A>();
         let storage = match typed.simple_storages.get_mut(&TypeId::of::<C>()) {
             Some(storage) => storage,
@@ -91,6 +94,29 @@ impl world::Components {
             ),
         };
         let storage = storage.get_storage::<C>();
+        storage.set_change_tick(tick);
         system::AccessSingle::new(storage)
     }
+
+    /// Reads the add/remove events of a simple component delivered to the current cycle.
+    ///
+    /// # Panics
+    /// - if no systems subscribe to the events of the archetyped component.
+    pub fn read_simple_events<A: Archetype, C: comp::Simple<A>>(
+        &self,
+    ) -> system::SimpleEvents<'_, A, C> {
+        let events = self
+            .archetype::<A>()
+            .simple_storages
+            .get(&TypeId::of::<C>())
+            .and_then(|storage| storage.events.as_ref());
+        match events {
+            Some(events) => system::SimpleEvents::new(events),
+            None => panic!(
+                "The events of {}/{} cannot be read because they are not subscribed in any systems",
+                type_name::<A>(),
+                type_name::<C>()
+            ),
+        }
+    }
 }
@@ -0,0 +1,186 @@
+//! Saves and loads world snapshots.
+
+use std::any::Any;
+use std::io;
+
+use super::typed::AnyTyped;
+use super::{Components, World};
+use crate::entity::generation;
+use crate::serialize::{self, Reader, Serialize, Writer};
+
+/// Identifies a dynec world snapshot.
+const MAGIC: &[u8; 8] = b"dynecwld";
+/// The version of the snapshot format.
+const VERSION: u32 = 1;
+
+impl World {
+    /// Saves a snapshot of the world.
+    ///
+    /// The snapshot contains the allocated entities of all archetypes,
+    /// the components declared with `#[comp(serialize)]`
+    /// and the global states declared with `#[global(serialize)]`.
+    /// Other components (including [dynamic components](crate::comp::dynamic))
+    /// and global states are not saved.
+    /// See the [`serialize`](crate::serialize) module for details.
+    ///
+    /// Entities that are pending deletion due to finalizers are saved as allocated entities,
+    /// but their pending deletion is not saved.
+    ///
+    /// # Errors
+    /// Returns an error if writing to `output` fails,
+    /// if an archetype with entities has a [required](crate::comp::Presence::Required)
+    /// component that is not serializable or has no [snapshot key](crate::Archetype::SERIALIZE_KEY),
+    /// or if multiple archetypes, components of the same archetype or global states
+    /// have the same snapshot key.
+    pub fn save(&mut self, mut output: impl io::Write) -> io::Result<()> {
+        for ealloc in self.ealloc_map.map.values_mut() {
+            ealloc.flush();
+        }
+
+        let writer = &mut Writer::new(&mut output);
+        writer.write_bytes(MAGIC)?;
+        VERSION.serialize(writer)?;
+
+        let mut archetypes: Vec<(&'static str, &mut Box<dyn AnyTyped>)> = Vec::new();
+        for typed in self.components.archetypes.values_mut() {
+            match typed.serialize_key() {
+                Some(key) => archetypes.push((key, typed)),
+                None => typed.check_unkeyed(&mut self.ealloc_map)?,
+            }
+        }
+        archetypes.sort_by_key(|&(key, _)| key);
+        serialize::check_unique_keys("archetypes", archetypes.iter().map(|&(key, _)| key))?;
+
+        let generations = self.sync_globals.get_mut::<generation::StoreMap>();
+        serialize::write_len(writer, archetypes.len())?;
+        for (key, typed) in &archetypes {
+            serialize::write_key(writer, key)?;
+            typed.save_entities(&mut self.ealloc_map, generations, writer)?;
+        }
+
+        serialize::write_len(writer, archetypes.len())?;
+        for (key, typed) in archetypes {
+            serialize::write_key(writer, key)?;
+            typed.save_components(writer)?;
+        }
+
+        let mut globals: Vec<_> = self
+            .sync_globals
+            .sync_globals
+            .values_mut()
+            .map(|(vtable, value)| (vtable, &**value.get_mut() as &dyn Any))
+            .chain(
+                self.unsync_globals
+                    .unsync_globals
+                    .values_mut()
+                    .map(|(vtable, value)| (vtable, &**value)),
+            )
+            .filter_map(|(vtable, value)| Some((vtable.serializer.as_ref()?, value)))
+            .collect();
+        globals.sort_by_key(|(vtable, _)| vtable.key);
+        serialize::check_unique_keys(
+            "global states",
+            globals.iter().map(|(vtable, _)| vtable.key),
+        )?;
+
+        serialize::write_len(writer, globals.len())?;
+        for (vtable, value) in globals {
+            serialize::write_key(writer, vtable.key)?;
+            (vtable.serialize)(value, writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Loads a snapshot saved by [`save`](Self::save).
+    ///
+    /// The world must have the same archetypes, serializable components and
+    /// serializable global states registered as the world that saved the snapshot.
+    /// Global states that were not saved retain their current values.
+    ///
+    /// # Panics
+    /// Panics if the world already contains entities.
+    ///
+    /// # Errors
+    /// Returns an error if reading from `input` fails, if the snapshot is malformed,
+    /// or if a loaded entity lacks a [required](crate::comp::Presence::Required) component.
+    /// The world may be partially loaded if an error is returned.
+    pub fn load(&mut self, mut input: impl io::Read) -> io::Result<()> {
+        for ealloc in self.ealloc_map.map.values_mut() {
+            ealloc.flush();
+            assert!(
+                ealloc.is_empty(),
+                "World::load can only be called on a world without entities"
+            );
+        }
+
+        // Entity references cannot be resolved until all entities are loaded.
+        let reader = &mut Reader::new(&mut input, None);
+
+        let mut magic = [0; MAGIC.len()];
+        reader.read_bytes(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(serialize::invalid_data("input is not a dynec world snapshot"));
+        }
+        let version = u32::deserialize(reader)?;
+        if version != VERSION {
+            return Err(serialize::invalid_data(format!("unsupported snapshot version {version}")));
+        }
+
+        let generations = self.sync_globals.get_mut::<generation::StoreMap>();
+        let archetypes_len = serialize::read_len(reader)?;
+        for _ in 0..archetypes_len {
+            let key = serialize::read_key(reader)?;
+            let typed = find_archetype(&mut self.components, &key)?;
+            typed.load_entities(&mut self.ealloc_map, generations, &mut self.rctrack, reader)?;
+        }
+
+        let reader = &mut Reader::new(&mut input, Some(&self.rctrack));
+
+        let tick = self.components.next_change_tick();
+        let archetypes_len = serialize::read_len(reader)?;
+        for _ in 0..archetypes_len {
+            let key = serialize::read_key(reader)?;
+            let typed = find_archetype(&mut self.components, &key)?;
+            typed.load_components(&mut self.ealloc_map, reader, tick)?;
+        }
+
+        for typed in self.components.archetypes.values() {
+            typed.check_required_loaded(&mut self.ealloc_map)?;
+        }
+
+        let globals_len = serialize::read_len(reader)?;
+        for _ in 0..globals_len {
+            let key = serialize::read_key(reader)?;
+            let (vtable, value) = self
+                .sync_globals
+                .sync_globals
+                .values_mut()
+                .map(|(vtable, value)| (vtable, &mut **value.get_mut() as &mut dyn Any))
+                .chain(
+                    self.unsync_globals
+                        .unsync_globals
+                        .values_mut()
+                        .map(|(vtable, value)| (vtable, &mut **value)),
+                )
+                .filter_map(|(vtable, value)| Some((vtable.serializer.as_ref()?, value)))
+                .find(|(vtable, _)| vtable.key == key)
+                .ok_or_else(|| serialize::invalid_data(format!("unknown global state {key}")))?;
+            (vtable.deserialize)(value, reader)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Finds the archetype with the given snapshot key.
+fn find_archetype<'t>(
+    components: &'t mut Components,
+    key: &str,
+) -> io::Result<&'t mut Box<dyn AnyTyped>> {
+    components
+        .archetypes
+        .values_mut()
+        .find(|typed| typed.serialize_key() == Some(key))
+        .ok_or_else(|| serialize::invalid_data(format!("unknown archetype {key}")))
+}
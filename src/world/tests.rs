@@ -1,4 +1,43 @@
 #![allow(clippy::ptr_arg)]
 
+use crate::global;
+
+/// Records the systems run in the tests, in the order they were run.
+#[global(dynec_as(crate), initial)]
+#[derive(Default)]
+struct Log {
+    entries: Vec<&'static str>,
+}
+
+/// Declares a system that pushes its own name to [`Log`].
+///
+/// The arguments after the system name are passed to the `#[system]` attribute.
+macro_rules! log_system {
+    ($name:ident $(, $($args:tt)*)?) => {
+        #[crate::system(dynec_as(crate) $(, $($args)*)?)]
+        fn $name(#[dynec(global)] log: &mut super::Log) { log.entries.push(stringify!($name)); }
+    };
+}
+
+mod analyzer;
+mod change;
+mod chrome_trace;
+mod conditions;
 mod dependencies;
+mod dynamic;
+mod enabled;
+mod events;
+mod fallible;
 mod globals;
+mod graph;
+mod memory;
+mod panic_policy;
+mod priority;
+mod rearrange;
+mod reschedule;
+mod schedules;
+mod serialize;
+mod simple_events;
+mod soa;
+mod startup;
+mod tag;
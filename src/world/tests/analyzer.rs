@@ -0,0 +1,85 @@
+//! Tests analyzing the critical path and contention of a schedule.
+
+use std::{thread, time};
+
+use super::Log;
+use crate::scheduler::ResourceType;
+use crate::util::DbgTypeId;
+use crate::{system, tracer, world};
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct LogPartition;
+
+#[system(dynec_as(crate), before(LogPartition))]
+fn simulate(#[dynec(global)] log: &mut Log) {
+    log.entries.push("simulate");
+    thread::sleep(time::Duration::from_millis(5));
+}
+
+#[system(dynec_as(crate), after(LogPartition))]
+fn render(#[dynec(global)] log: &mut Log) {
+    log.entries.push("render");
+    thread::sleep(time::Duration::from_millis(5));
+}
+
+log_system!(audit);
+
+#[test]
+fn test_analyzer_report() {
+    let mut builder = world::Builder::new(1);
+    builder.schedule(simulate.build());
+    builder.schedule(render.build());
+    builder.schedule(audit.build());
+    let mut world = builder.build();
+
+    let analyzer = tracer::Analyzer::new();
+    world.execute(&analyzer);
+    world.execute(&analyzer);
+    let report = analyzer.report(&world.schedule_graph());
+
+    assert_eq!(report.cycles, 2);
+    assert_eq!(report.systems.len(), 3);
+    assert!(report.systems.iter().all(|system| system.runs == 2));
+
+    let path: Vec<_> = report.critical_path.iter().map(|node| node.name.as_str()).collect();
+    assert_eq!(
+        path,
+        [
+            "dynec::world::tests::analyzer::simulate",
+            "LogPartition",
+            "dynec::world::tests::analyzer::render",
+        ]
+    );
+
+    // audit is runnable at the start of the cycle, but the single worker steals simulate first.
+    // After simulate completes, render is stolen before audit again.
+    let mut contentions: Vec<_> = report
+        .contentions
+        .iter()
+        .map(|contention| (contention.blocked.node, contention.blocker.node, contention.count))
+        .collect();
+    contentions.sort();
+    let [simulate_node, render_node, audit_node] = ["simulate", "render", "audit"].map(|name| {
+        let name = format!("dynec::world::tests::analyzer::{name}");
+        let system = report.systems.iter().find(|system| system.node.name == name);
+        system.expect("system should be reported").node.node
+    });
+    assert_eq!(contentions, [(audit_node, simulate_node, 2), (audit_node, render_node, 2)]);
+    for contention in &report.contentions {
+        assert_eq!(contention.resources, [ResourceType::Global(DbgTypeId::of::<Log>())]);
+    }
+
+    let text = report.to_string();
+    assert!(text.starts_with("2 cycles, mean cycle duration "));
+    assert!(text.contains(
+        "  dynec::world::tests::analyzer::audit blocked by \
+         dynec::world::tests::analyzer::simulate: 2 times"
+    ));
+    assert!(text.contains("    on global state dynec::world::tests::Log\n"));
+
+    analyzer.clear();
+    let report = analyzer.report(&world.schedule_graph());
+    assert_eq!(report.cycles, 0);
+    assert!(report.systems.is_empty());
+    assert!(report.contentions.is_empty());
+}
@@ -0,0 +1,124 @@
+//! Tests change tracking of simple components.
+
+use crate::entity::Ref as _;
+use crate::storage::Tick;
+use crate::test_util::*;
+use crate::{comp, global, system, system_test, tracer, Entity};
+
+#[comp(dynec_as(crate), of = TestArch, track_changes)]
+#[derive(Debug, PartialEq)]
+struct Position(u32);
+
+#[comp(dynec_as(crate), of = TestArch)]
+struct Velocity(u32);
+
+#[global(dynec_as(crate), initial)]
+#[derive(Default)]
+struct Rendered {
+    ids: Vec<Vec<u32>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct MovePartition;
+
+#[system(dynec_as(crate), before(MovePartition))]
+fn movement(
+    entities: system::EntityIterator<TestArch>,
+    mut positions: system::WriteSimple<TestArch, Position>,
+    velocities: system::ReadSimple<TestArch, Velocity>,
+) {
+    for entity in entities.entities() {
+        if let Some(&Velocity(velocity)) = velocities.try_get(&entity) {
+            if velocity != 0 {
+                if let Some(position) = positions.try_get_mut(&entity) {
+                    position.0 += velocity;
+                }
+            }
+        }
+    }
+}
+
+#[system(dynec_as(crate), after(MovePartition))]
+fn render(
+    entities: system::EntityIterator<TestArch>,
+    positions: system::ReadSimple<TestArch, Position>,
+    #[dynec(local(initial = Tick::default()))] last_run: &mut Tick,
+    #[dynec(global)] rendered: &mut Rendered,
+) {
+    let ids: Vec<u32> = entities
+        .entities_with(system::Changed(&positions, *last_run))
+        .filter_map(|(entity, position)| position.map(|_| entity.id().get()))
+        .collect();
+
+    let iter_ids: Vec<u32> =
+        positions.iter_changed_since(*last_run).map(|(entity, _)| entity.id().get()).collect();
+    assert_eq!(ids, iter_ids);
+
+    rendered.ids.push(ids);
+    *last_run = positions.change_tick();
+}
+
+fn create(world: &mut crate::World, velocity: u32) -> Entity<TestArch> {
+    world.create::<TestArch>(crate::comps![@(crate) TestArch => Position(0), Velocity(velocity)])
+}
+
+#[test]
+fn test_changed_since_last_run() {
+    let mut world = system_test!(movement.build(), render.build(););
+
+    let moving = create(&mut world, 1);
+    let resting = create(&mut world, 0);
+
+    world.execute(&tracer::Noop);
+    world.execute(&tracer::Noop);
+
+    let later = create(&mut world, 0);
+    world.execute(&tracer::Noop);
+
+    let rendered = &world.get_global::<Rendered>().ids;
+    assert_eq!(
+        rendered,
+        &[
+            vec![moving.id().get(), resting.id().get()],
+            vec![moving.id().get()],
+            vec![moving.id().get(), later.id().get()],
+        ]
+    );
+}
+
+#[test]
+fn test_offline_changes() {
+    let mut world = system_test!(movement.build(), render.build(););
+
+    let first = create(&mut world, 0);
+    let second = create(&mut world, 0);
+    world.execute(&tracer::Noop);
+
+    let storage = world.components.get_simple_storage::<TestArch, Position>();
+    let since = storage.change_tick();
+    assert_eq!(storage.iter_changed_since(since).count(), 0);
+
+    let mut storage = world.components.get_simple_storage::<TestArch, Position>();
+    storage.try_get_mut(&second).expect("position is initialized").0 = 5;
+    assert_eq!(storage.try_get_changed(&first, since), None);
+    assert_eq!(storage.try_get_changed(&second, since), Some(&Position(5)));
+
+    world.execute(&tracer::Noop);
+    assert_eq!(world.get_global::<Rendered>().ids.last(), Some(&vec![second.id().get()]));
+}
+
+#[test]
+fn test_rearrange_marks_changed() {
+    let mut world = system_test!(movement.build(), render.build(););
+
+    let first = create(&mut world, 0);
+    let second = create(&mut world, 0);
+    world.execute(&tracer::Noop);
+
+    let permutation = crate::entity::Permutation::from_order([second.id(), first.id()]);
+    drop((first, second));
+    world.rearrange::<TestArch>(permutation);
+
+    world.execute(&tracer::Noop);
+    assert_eq!(world.get_global::<Rendered>().ids.last().map(Vec::len), Some(2));
+}
@@ -0,0 +1,49 @@
+//! Tests recording cycles with the Chrome trace tracer.
+
+use crate::{tracer, world};
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct LogPartition;
+
+log_system!(simulate, before(LogPartition));
+
+log_system!(render, thread_local, after(LogPartition));
+
+#[test]
+fn test_chrome_trace_events() {
+    let mut builder = world::Builder::new(1);
+    builder.schedule(simulate.build());
+    builder.schedule_thread_unsafe(render.build());
+    let mut world = builder.build();
+
+    let trace = tracer::ChromeTrace::new();
+    world.execute(&trace);
+    world.execute(&trace);
+    let json = trace.to_json();
+
+    assert!(json.starts_with("{\"traceEvents\":[\n"));
+    assert!(json.ends_with("\n],\"displayTimeUnit\":\"ms\"}\n"));
+    assert!(
+        json.contains(r#"{"name":"thread_name","ph":"M","pid":0,"tid":0,"args":{"name":"main"}}"#)
+    );
+
+    let count = |pattern: &str| json.matches(pattern).count();
+    assert_eq!(count(r#""name":"cycle","cat":"cycle","pid":0,"tid":0,"#), 2);
+    assert_eq!(
+        count(r#""name":"dynec::world::tests::chrome_trace::simulate","cat":"sendable","#),
+        2
+    );
+    assert_eq!(
+        count(
+            r#""name":"dynec::world::tests::chrome_trace::render","cat":"unsendable","pid":0,"tid":0,"#
+        ),
+        2
+    );
+    assert_eq!(count(r#""name":"LogPartition","cat":"partition","#), 2);
+    assert_eq!(count(r#""name":"prepare ealloc shards","cat":"ealloc","pid":0,"tid":0,"#), 2);
+    assert_eq!(count(r#""ph":"X","dur":"#), 8);
+    assert_eq!(count(r#""ph":"i","s":"p"}"#), 2);
+
+    trace.clear();
+    assert_eq!(trace.to_json(), "{\"traceEvents\":[\n],\"displayTimeUnit\":\"ms\"}\n");
+}
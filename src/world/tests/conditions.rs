@@ -0,0 +1,79 @@
+//! Tests run conditions of systems.
+
+use std::num::NonZeroU64;
+
+use super::Log;
+use crate::system::RunCondition;
+use crate::{global, system, system_test, tracer, world};
+
+#[global(dynec_as(crate), initial)]
+#[derive(Default)]
+struct Paused(bool);
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct LogPartition;
+
+fn not_paused() -> RunCondition { RunCondition::global(|&Paused(paused): &Paused| !paused) }
+
+log_system!(simulate, before(LogPartition), run_if = not_paused());
+
+log_system!(render, after(LogPartition));
+
+#[test]
+fn test_run_if_global() {
+    let mut world = system_test!(simulate.build(), render.build(););
+
+    world.execute(&tracer::Noop);
+    world.get_global::<Paused>().0 = true;
+    world.execute(&tracer::Noop);
+    world.get_global::<Paused>().0 = false;
+    world.execute(&tracer::Noop);
+
+    assert_eq!(
+        world.get_global::<Log>().entries,
+        ["simulate", "render", "render", "simulate", "render"]
+    );
+}
+
+#[system(dynec_as(crate))]
+fn toggle(#[dynec(global)] paused: &mut Paused, #[dynec(global)] log: &mut Log) {
+    paused.0 = !paused.0;
+    log.entries.push("toggle");
+}
+
+#[test]
+fn test_condition_on_written_global() {
+    let mut builder = world::Builder::new(0);
+    builder.schedule_with_condition(toggle.build(), not_paused());
+    let mut world = builder.build();
+
+    world.execute(&tracer::Noop);
+    world.execute(&tracer::Noop);
+
+    assert_eq!(world.get_global::<Log>().entries, ["toggle"]);
+    assert!(world.get_global::<Paused>().0);
+}
+
+#[test]
+fn test_every_and_combinators() {
+    let mut builder = world::Builder::new(0);
+    builder.schedule(simulate.build());
+    builder.schedule_with_condition(
+        render.build(),
+        RunCondition::every(NonZeroU64::new(2).expect("2 != 0")).or(!not_paused()),
+    );
+    let mut world = builder.build();
+
+    for _ in 0..3 {
+        world.execute(&tracer::Noop);
+    }
+    world.get_global::<Paused>().0 = true;
+    world.execute(&tracer::Noop);
+    world.get_global::<Paused>().0 = false;
+    world.execute(&tracer::Noop);
+
+    assert_eq!(
+        world.get_global::<Log>().entries,
+        ["simulate", "render", "simulate", "simulate", "render", "render", "simulate", "render"]
+    );
+}
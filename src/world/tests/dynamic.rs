@@ -0,0 +1,104 @@
+//! Tests dynamic components.
+
+use crate::comp::dynamic::{Descriptor, Id};
+use crate::test_util::*;
+use crate::{system, system_test, tracer, Entity};
+
+const HEALTH: Descriptor = Descriptor::new(Id(1), 4);
+
+#[system(dynec_as(crate))]
+fn heal(
+    entities: system::EntityIterator<TestArch>,
+    #[dynec(dynamic(comp = HEALTH))] mut health: system::WriteDynamic<TestArch>,
+) {
+    for entity in entities.entities() {
+        if let Some(bytes) = health.try_get_mut(entity) {
+            let value = u32::from_le_bytes(bytes.try_into().expect("size is 4"));
+            bytes.copy_from_slice(&(value + 1).to_le_bytes());
+        }
+    }
+}
+
+#[system(dynec_as(crate))]
+fn check_health(#[dynec(dynamic(comp = HEALTH))] health: system::ReadDynamic<TestArch>) {
+    for (_, bytes) in health.iter() {
+        assert_eq!(bytes.len(), HEALTH.size);
+    }
+}
+
+fn get_health(world: &mut crate::World, entity: &Entity<TestArch>) -> Option<u32> {
+    let storage = world.components.get_dynamic_storage::<TestArch>(HEALTH.id);
+    storage.try_get(entity).map(|bytes| u32::from_le_bytes(bytes.try_into().expect("size is 4")))
+}
+
+#[test]
+fn test_dynamic_read_write() {
+    let mut world = system_test!(heal.build(), check_health.build(););
+
+    let first = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    let second = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    assert_eq!(get_health(&mut world, &first), None, "dynamic components are not initialized");
+
+    let mut storage = world.components.get_dynamic_storage::<TestArch>(HEALTH.id);
+    assert_eq!(storage.size(), 4);
+    assert!(!storage.set(&first, Some(&10_u32.to_le_bytes())));
+
+    world.execute(&tracer::Noop);
+    assert_eq!(get_health(&mut world, &first), Some(11));
+    assert_eq!(get_health(&mut world, &second), None);
+
+    world.delete(first);
+    let third = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    world.execute(&tracer::Noop);
+    assert_eq!(get_health(&mut world, &third), None, "dynamic components are cleared on deletion");
+}
+
+#[test]
+#[should_panic = "Dynamic component dynamic#1 expects 4 bytes, got 2"]
+fn test_dynamic_set_wrong_size() {
+    let mut world = system_test!(heal.build(););
+    let entity = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+
+    let mut storage = world.components.get_dynamic_storage::<TestArch>(HEALTH.id);
+    storage.set(&entity, Some(&[0, 0]));
+}
+
+#[test]
+#[should_panic = "Dynamic component dynamic#1 of dynec::test_util::TestArch is requested with size \
+                  4 and 8"]
+fn test_dynamic_size_mismatch() {
+    #[system(dynec_as(crate))]
+    fn wide(
+        #[dynec(dynamic(comp = Descriptor::new(Id(1), 8)))] _health: system::ReadDynamic<TestArch>,
+    ) {
+    }
+
+    system_test!(heal.build(), wide.build(););
+}
+
+#[test]
+#[should_panic = "due to conflicts in dynamic component"]
+fn test_dynamic_conflict_detection() {
+    #[system(dynec_as(crate))]
+    fn alias(
+        #[dynec(dynamic(comp = HEALTH))] _read: system::ReadDynamic<TestArch>,
+        #[dynec(dynamic(comp = HEALTH))] _write: system::WriteDynamic<TestArch>,
+    ) {
+    }
+
+    system_test!(alias.build(););
+}
+
+#[test]
+fn test_dynamic_distinct_ids() {
+    #[system(dynec_as(crate))]
+    fn distinct(
+        #[dynec(dynamic(comp = HEALTH))] _health: system::WriteDynamic<TestArch>,
+        #[dynec(dynamic(arch = TestArch, comp = Descriptor::new(Id(2), 8), mut))]
+        _mana: system::WriteDynamic<TestArch>,
+    ) {
+    }
+
+    let mut world = system_test!(distinct.build(););
+    world.execute(&tracer::Noop);
+}
@@ -0,0 +1,69 @@
+//! Tests enabling and disabling systems at runtime.
+
+use super::Log;
+use crate::{tracer, world};
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct AiPartition;
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct RenderPartition;
+
+log_system!(input, before(AiPartition));
+
+log_system!(ai, after(AiPartition), before(RenderPartition));
+
+log_system!(render, after(RenderPartition));
+
+#[test]
+fn test_disable_and_enable() {
+    let mut builder = world::Builder::new(0);
+    builder.schedule(input.build());
+    let ai_handle = builder.schedule(ai.build());
+    builder.schedule(render.build());
+    let mut world = builder.build();
+
+    world.set_enabled(ai_handle, false);
+    world.execute(&tracer::Noop);
+    world.set_enabled(ai_handle, true);
+    world.execute(&tracer::Noop);
+
+    assert_eq!(world.get_global::<Log>().entries, ["input", "render", "input", "ai", "render"]);
+}
+
+#[test]
+fn test_disable_all_concurrent() {
+    let mut builder = world::Builder::new(2);
+    let handles = [
+        builder.schedule(input.build()),
+        builder.schedule(ai.build()),
+        builder.schedule(render.build()),
+    ];
+    let mut world = builder.build();
+
+    for handle in handles {
+        world.set_enabled(handle, false);
+    }
+    for _ in 0..10 {
+        world.execute(&tracer::Noop);
+    }
+    world.set_enabled(handles[2], true);
+    world.execute(&tracer::Noop);
+
+    assert_eq!(world.get_global::<Log>().entries, ["render"]);
+}
+
+#[test]
+fn test_disabled_after_unschedule() {
+    let mut builder = world::Builder::new(0);
+    let input_handle = builder.schedule(input.build());
+    let ai_handle = builder.schedule(ai.build());
+    let mut world = builder.build();
+
+    world.set_enabled(ai_handle, false);
+    world.unschedule(input_handle);
+    world.schedule(render.build());
+    world.execute(&tracer::Noop);
+
+    assert_eq!(world.get_global::<Log>().entries, ["render"]);
+}
@@ -0,0 +1,77 @@
+//! Tests event channels between systems.
+
+use crate::{global, system, system_test, tracer, world};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Damage(u32);
+
+#[global(dynec_as(crate), initial)]
+#[derive(Default)]
+struct Received {
+    cycles: Vec<Vec<u32>>,
+}
+
+#[system(dynec_as(crate))]
+fn receive(mut reader: system::EventReader<Damage>, #[dynec(global)] received: &mut Received) {
+    let unread = reader.len();
+    let damages: Vec<u32> = reader.read().map(|&Damage(damage)| damage).collect();
+    assert_eq!(damages.len(), unread);
+    assert!(reader.is_empty());
+    received.cycles.push(damages);
+}
+
+#[system(dynec_as(crate))]
+fn send(mut writer: system::EventWriter<Damage>, #[dynec(local(initial = 0))] counter: &mut u32) {
+    *counter += 1;
+    writer.send(Damage(*counter));
+}
+
+#[test]
+fn test_events_received_in_same_cycle() {
+    let mut world = system_test!(receive.build(), send.build(););
+
+    world.execute(&tracer::Noop);
+    world.sync_globals.events_mut::<Damage>().send(Damage(10));
+    world.execute(&tracer::Noop);
+
+    assert_eq!(world.get_global::<Received>().cycles, [vec![1], vec![10, 2]]);
+}
+
+#[test]
+fn test_events_double_buffered() {
+    let mut world = system_test!(send.build(););
+
+    world.execute(&tracer::Noop);
+    world.execute(&tracer::Noop);
+    world.execute(&tracer::Noop);
+
+    // events sent in the last cycle are retained for readers in the next cycle
+    let events = world.sync_globals.events_mut::<Damage>();
+    events.send(Damage(10));
+    assert_eq!(events.iter().copied().collect::<Vec<_>>(), [Damage(3), Damage(10)]);
+
+    world.execute(&tracer::Noop);
+    let events = world.sync_globals.events_mut::<Damage>();
+    assert_eq!(events.iter().copied().collect::<Vec<_>>(), [Damage(10), Damage(4)]);
+}
+
+#[system(dynec_as(crate))]
+fn send_batch(mut writer: system::EventWriter<Damage>, #[dynec(param)] base: &mut u32) {
+    writer.send_all((0..100).map(|i| Damage(*base + i)));
+}
+
+#[test]
+fn test_concurrent_writers() {
+    let mut builder = world::Builder::new(4);
+    builder.schedule(send_batch.build(0));
+    builder.schedule(send_batch.build(100));
+    builder.schedule(send_batch.build(200));
+    builder.schedule(receive.build());
+    let mut world = builder.build();
+
+    world.execute(&tracer::Noop);
+
+    let mut received = world.get_global::<Received>().cycles[0].clone();
+    received.sort_unstable();
+    assert_eq!(received, (0..300).collect::<Vec<_>>());
+}
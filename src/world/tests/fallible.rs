@@ -0,0 +1,110 @@
+//! Tests systems that return errors.
+
+use std::sync::Mutex;
+use std::{error, fmt};
+
+use super::Log;
+use crate::{system, tracer, world};
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct LoadPartition;
+
+#[derive(Debug)]
+struct LoadError;
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("asset not found") }
+}
+
+impl error::Error for LoadError {}
+
+#[system(dynec_as(crate), before(LoadPartition))]
+fn load(#[dynec(global)] log: &mut Log) -> Result<(), LoadError> {
+    log.entries.push("load");
+    Err(LoadError)
+}
+
+#[system(dynec_as(crate), before(LoadPartition), on_error = system::OnError::Block)]
+fn load_blocking(#[dynec(global)] log: &mut Log) -> Result<(), LoadError> {
+    log.entries.push("load");
+    Err(LoadError)
+}
+
+#[system(dynec_as(crate), after(LoadPartition))]
+fn render(#[dynec(global)] log: &mut Log) -> Result<(), LoadError> {
+    log.entries.push("render");
+    Ok(())
+}
+
+log_system!(present, after(LoadPartition));
+
+#[test]
+fn test_continue_on_error() {
+    let mut builder = world::Builder::new(0);
+    let load_handle = builder.schedule(load.build());
+    builder.schedule(render.build());
+    let mut world = builder.build();
+
+    let report = world.execute(&tracer::Noop);
+    assert!(!report.is_ok());
+    assert!(report.panics.is_empty());
+    assert_eq!(report.errors.len(), 1);
+    let error = &report.errors[0];
+    assert_eq!(error.handle, load_handle);
+    assert!(error.debug_name.contains("load"));
+    assert_eq!(error.error.to_string(), "asset not found");
+
+    assert_eq!(world.get_global::<Log>().entries, ["load", "render"]);
+}
+
+#[test]
+fn test_block_on_error() {
+    for concurrency in [0, 2] {
+        let mut builder = world::Builder::new(concurrency);
+        builder.schedule(load_blocking.build());
+        builder.schedule(render.build());
+        builder.schedule(present.build());
+        let mut world = builder.build();
+
+        let report = world.execute(&tracer::Noop);
+        assert_eq!(report.errors.len(), 1);
+
+        // the blocked systems run again in the next cycle
+        let report = world.execute(&tracer::Noop);
+        assert_eq!(report.errors.len(), 1);
+
+        assert_eq!(world.get_global::<Log>().entries, ["load", "load"]);
+    }
+}
+
+#[derive(Default)]
+struct ErrorTracer(Mutex<Vec<String>>);
+
+#[dynec_codegen::tracer(dynec_as())]
+impl Tracer for ErrorTracer {
+    fn system_error(
+        &self,
+        _thread: Thread,
+        _node: scheduler::Node,
+        debug_name: &str,
+        error: &(dyn std::error::Error + Send + Sync),
+    ) {
+        let mut errors = self.0.lock().expect("poisoned mutex");
+        errors.push(format!("{debug_name}: {error}"));
+    }
+}
+
+#[test]
+fn test_tracer_system_error() {
+    let mut builder = world::Builder::new(0);
+    builder.schedule(load.build());
+    builder.schedule(render.build());
+    let mut world = builder.build();
+
+    let tracer = ErrorTracer::default();
+    world.execute(&tracer);
+
+    let errors = tracer.0.into_inner().expect("poisoned mutex");
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].ends_with("load: asset not found"));
+}
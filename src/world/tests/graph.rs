@@ -0,0 +1,54 @@
+//! Tests exporting the schedule topology.
+
+use crate::system_test;
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct LogPartition;
+
+log_system!(simulate, before(LogPartition));
+
+log_system!(render, after(LogPartition));
+
+#[test]
+fn test_schedule_graph_dot() {
+    let world = system_test!(simulate.build(), render.build(););
+
+    assert_eq!(
+        world.schedule_graph().to_dot(),
+        r#"digraph schedule {
+	send_0 [label = "dynec::world::tests::graph::simulate", shape = box]
+	send_1 [label = "dynec::world::tests::graph::render", shape = box]
+	partition_0 [label = "LogPartition", shape = diamond]
+
+	send_0 -> partition_0
+	partition_0 -> send_1
+
+	send_0 -> send_1 [dir = none, style = dashed, color = red, label = "global state dynec::world::tests::Log"]
+}
+"#
+    );
+}
+
+#[test]
+fn test_schedule_graph_json() {
+    let world = system_test!(simulate.build(), render.build(););
+
+    assert_eq!(
+        world.schedule_graph().to_json(),
+        r#"{
+  "nodes": [
+    {"id": "send_0", "kind": "send_system", "name": "dynec::world::tests::graph::simulate"},
+    {"id": "send_1", "kind": "send_system", "name": "dynec::world::tests::graph::render"},
+    {"id": "partition_0", "kind": "partition", "name": "LogPartition"}
+  ],
+  "orders": [
+    {"before": "send_0", "after": "partition_0"},
+    {"before": "partition_0", "after": "send_1"}
+  ],
+  "exclusions": [
+    {"nodes": ["send_0", "send_1"], "resources": ["global state dynec::world::tests::Log"]}
+  ]
+}
+"#
+    );
+}
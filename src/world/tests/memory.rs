@@ -0,0 +1,108 @@
+//! Tests storage memory reports and compaction.
+
+use std::any;
+
+use crate::comp::dynamic::{Descriptor, Id};
+use crate::test_util::*;
+use crate::{system, system_test, world, Entity};
+
+#[system(dynec_as(crate))]
+fn use_comps(
+    _comp1: system::ReadSimple<TestArch, Simple1OptionalNoDepNoInit>,
+    _iso1: system::ReadIsotopeFull<TestArch, IsoNoInit>,
+) {
+}
+
+fn find<'t>(
+    report: &'t world::MemoryReport,
+    component: &str,
+    discrim: Option<usize>,
+) -> &'t world::StorageMemory {
+    report
+        .storages
+        .iter()
+        .find(|storage| storage.component == component && storage.discrim == discrim)
+        .expect("storage is reported")
+}
+
+#[test]
+fn test_memory_report_lists_storages() {
+    let mut world = system_test!(use_comps.build(););
+
+    let _entity = world.create::<TestArch>(crate::comps![@(crate) TestArch =>
+        Simple1OptionalNoDepNoInit(1),
+        @(TestDiscrim1(7), IsoNoInit(10)),
+    ]);
+
+    let report = world.memory_report();
+
+    let simple = find(&report, any::type_name::<Simple1OptionalNoDepNoInit>(), None);
+    assert_eq!(simple.archetype, any::type_name::<TestArch>());
+    assert_eq!(simple.cardinality, 1);
+    assert!(simple.usage.capacity >= 2);
+    assert!(simple.usage.data_bytes > 0);
+    assert!(simple.usage.presence_bytes > 0);
+
+    let iso = find(&report, any::type_name::<IsoNoInit>(), Some(7));
+    assert_eq!(iso.cardinality, 1);
+
+    assert!(report.total_bytes() >= simple.usage.total_bytes() + iso.usage.total_bytes());
+}
+
+#[test]
+fn test_compact_storages_after_mass_deletion() {
+    let mut world = system_test!(use_comps.build(););
+
+    let mut entities: Vec<Entity<TestArch>> = (0..1000)
+        .map(|i| {
+            world.create::<TestArch>(crate::comps![@(crate) TestArch =>
+                Simple1OptionalNoDepNoInit(i),
+            ])
+        })
+        .collect();
+    for entity in entities.drain(2..) {
+        world.delete(entity);
+    }
+
+    let component = any::type_name::<Simple1OptionalNoDepNoInit>();
+    let before = find(&world.memory_report(), component, None).clone();
+    assert_eq!(before.cardinality, 2);
+    assert!(before.usage.capacity > 1000);
+
+    world.compact_storages();
+
+    let after = find(&world.memory_report(), component, None).clone();
+    assert_eq!(after.cardinality, 2);
+    assert!(after.usage.capacity < 10, "capacity {} was not shrunk", after.usage.capacity);
+    assert!(after.usage.total_bytes() < before.usage.total_bytes());
+
+    let storage = world.components.get_simple_storage::<TestArch, Simple1OptionalNoDepNoInit>();
+    assert_eq!(storage.try_get(&entities[0]), Some(&Simple1OptionalNoDepNoInit(0)));
+    assert_eq!(storage.try_get(&entities[1]), Some(&Simple1OptionalNoDepNoInit(1)));
+
+    let created = world.create::<TestArch>(crate::comps![@(crate) TestArch =>
+        Simple1OptionalNoDepNoInit(5),
+    ]);
+    let storage = world.components.get_simple_storage::<TestArch, Simple1OptionalNoDepNoInit>();
+    assert_eq!(storage.try_get(&created), Some(&Simple1OptionalNoDepNoInit(5)));
+}
+
+const MARKER: Descriptor = Descriptor::new(Id(1), 0);
+
+#[system(dynec_as(crate))]
+fn use_marker(#[dynec(dynamic(comp = MARKER))] _marker: system::ReadDynamic<TestArch>) {}
+
+#[test]
+fn test_memory_report_zero_sized_dynamic() {
+    let mut world = system_test!(use_marker.build(););
+
+    let entity = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    world.components.get_dynamic_storage::<TestArch>(MARKER.id).set(&entity, Some(&[]));
+
+    let report = world.memory_report();
+    let marker = find(&report, &MARKER.id.to_string(), None);
+    assert_eq!(marker.cardinality, 1);
+    assert_eq!(marker.usage.data_bytes, 0);
+    assert!(marker.usage.capacity >= 2, "capacity {} is not the bitset", marker.usage.capacity);
+    assert!(marker.usage.capacity < usize::MAX);
+}
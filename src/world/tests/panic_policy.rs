@@ -0,0 +1,81 @@
+//! Tests recovering from system panics.
+
+use super::Log;
+use crate::{scheduler, system, tracer, world};
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct AiPartition;
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct RenderPartition;
+
+log_system!(input, before(AiPartition));
+
+#[system(dynec_as(crate), after(AiPartition), before(RenderPartition))]
+fn ai(#[dynec(global)] log: &mut Log) {
+    log.entries.push("ai");
+    panic!("ai failed");
+}
+
+log_system!(render, after(RenderPartition));
+
+fn build(
+    concurrency: usize,
+    policy: scheduler::PanicPolicy,
+) -> (world::World, scheduler::SystemHandle) {
+    let mut builder = world::Builder::new(concurrency);
+    builder.schedule(input.build());
+    let ai_handle = builder.schedule(ai.build());
+    builder.schedule(render.build());
+    builder.set_panic_policy(ai_handle, policy);
+    (builder.build(), ai_handle)
+}
+
+#[test]
+fn test_skip() {
+    for concurrency in [0, 2] {
+        let (mut world, ai_handle) = build(concurrency, scheduler::PanicPolicy::Skip);
+
+        for _ in 0..2 {
+            let report = world.execute(&tracer::Noop);
+            assert_eq!(report.panics.len(), 1);
+            let panic = &report.panics[0];
+            assert_eq!(panic.handle, ai_handle);
+            assert!(panic.debug_name.contains("ai"));
+            assert_eq!(panic.policy, scheduler::PanicPolicy::Skip);
+            assert_eq!(panic.message(), Some("ai failed"));
+        }
+
+        assert_eq!(
+            world.get_global::<Log>().entries,
+            ["input", "ai", "render", "input", "ai", "render"]
+        );
+    }
+}
+
+#[test]
+fn test_disable() {
+    for concurrency in [0, 2] {
+        let (mut world, ai_handle) = build(concurrency, scheduler::PanicPolicy::Disable);
+
+        let report = world.execute(&tracer::Noop);
+        assert_eq!(report.panics.len(), 1);
+        assert_eq!(report.panics[0].handle, ai_handle);
+
+        let report = world.execute(&tracer::Noop);
+        assert!(report.is_ok());
+
+        assert_eq!(world.get_global::<Log>().entries, ["input", "ai", "render", "input", "render"]);
+
+        world.set_enabled(ai_handle, true);
+        let report = world.execute(&tracer::Noop);
+        assert_eq!(report.panics.len(), 1);
+    }
+}
+
+#[test]
+#[should_panic = "ai failed"]
+fn test_abort() {
+    let (mut world, _) = build(0, scheduler::PanicPolicy::Abort);
+    world.execute(&tracer::Noop);
+}
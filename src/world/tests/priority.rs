@@ -0,0 +1,68 @@
+//! Tests prioritizing runnable systems by their downstream chains.
+
+use std::{thread, time};
+
+use super::Log;
+use crate::{scheduler, system, tracer, world};
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct SimulatePartition;
+
+log_system!(audit);
+
+log_system!(input, before(SimulatePartition));
+
+log_system!(simulate, after(SimulatePartition));
+
+#[system(dynec_as(crate))]
+fn slow_audit(#[dynec(global)] log: &mut Log) {
+    log.entries.push("slow_audit");
+    thread::sleep(time::Duration::from_millis(20));
+}
+
+#[test]
+fn test_longer_chain_first() {
+    let mut builder = world::Builder::new(0);
+    builder.schedule(audit.build());
+    builder.schedule(input.build());
+    builder.schedule(simulate.build());
+    let mut world = builder.build();
+
+    world.execute(&tracer::Noop);
+    assert_eq!(world.get_global::<Log>().entries, ["input", "audit", "simulate"]);
+}
+
+#[test]
+fn test_static_weight() {
+    let mut builder = world::Builder::new(0);
+    let audit_handle = builder.schedule(audit.build());
+    builder.schedule(input.build());
+    builder.schedule(simulate.build());
+    builder.set_weight(audit_handle, 3);
+    let mut world = builder.build();
+
+    world.execute(&tracer::Noop);
+    assert_eq!(world.get_global::<Log>().entries, ["audit", "input", "simulate"]);
+
+    world.get_global::<Log>().entries.clear();
+    world.set_weight(audit_handle, 1);
+    world.execute(&tracer::Noop);
+    assert_eq!(world.get_global::<Log>().entries, ["input", "audit", "simulate"]);
+}
+
+#[test]
+fn test_measured_weight() {
+    let mut builder = world::Builder::new(0);
+    builder.set_weighting(scheduler::Weighting::Measured);
+    builder.schedule(slow_audit.build());
+    builder.schedule(input.build());
+    builder.schedule(simulate.build());
+    let mut world = builder.build();
+
+    world.execute(&tracer::Noop);
+    assert_eq!(world.get_global::<Log>().entries, ["input", "slow_audit", "simulate"]);
+
+    world.get_global::<Log>().entries.clear();
+    world.execute(&tracer::Noop);
+    assert_eq!(world.get_global::<Log>().entries, ["slow_audit", "input", "simulate"]);
+}
@@ -0,0 +1,169 @@
+//! Tests entity rearrangement.
+
+use crate::entity::{generation, Permutation, Ref as _};
+use crate::test_util::*;
+use crate::{system, system_test, Entity};
+
+#[system(dynec_as(crate))]
+fn use_ref_comps(
+    _comp1: system::ReadSimple<TestArch, Simple1OptionalNoDepNoInit>,
+    _strong: system::ReadSimple<TestArch, StrongRefSimple>,
+    _iso1: system::ReadIsotopeFull<TestArch, IsoNoInit>,
+    #[dynec(global)] _initials: &InitialEntities,
+) {
+}
+
+#[test]
+fn test_rearrange_moves_components_and_refs() {
+    let mut world = system_test!(use_ref_comps.build(););
+
+    let first = world.create::<TestArch>(crate::comps![@(crate) TestArch =>
+        Simple1OptionalNoDepNoInit(1),
+        @(TestDiscrim1(11), IsoNoInit(10)),
+    ]);
+    let second = world.create::<TestArch>(crate::comps![@(crate) TestArch =>
+        Simple1OptionalNoDepNoInit(2),
+        StrongRefSimple(first.clone()),
+    ]);
+    let third = world.create::<TestArch>(crate::comps![@(crate) TestArch =>
+        Simple1OptionalNoDepNoInit(3),
+        StrongRefSimple(second.clone()),
+        @(TestDiscrim1(13), IsoNoInit(30)),
+    ]);
+
+    let permutation = Permutation::from_order([third.id(), first.id(), second.id()]);
+    let first_weak = first.weak(world.get_global::<generation::StoreMap>());
+    let initials = world.get_global::<InitialEntities>();
+    initials.weak = Some(first_weak);
+    initials.strong = Some(third);
+    drop((first, second));
+
+    world.rearrange::<TestArch>(permutation);
+
+    let third = world.get_global::<InitialEntities>().strong.clone().expect("strong ref is set");
+    assert_eq!(third.id().get(), 1);
+
+    let storage = world.components.get_simple_storage::<TestArch, Simple1OptionalNoDepNoInit>();
+    assert_eq!(storage.try_get(&third), Some(&Simple1OptionalNoDepNoInit(3)));
+
+    let storage = world.components.get_simple_storage::<TestArch, StrongRefSimple>();
+    let second = storage.try_get(&third).expect("third references second").0.clone();
+    assert_eq!(second.id().get(), 3);
+    let first = storage.try_get(&second).expect("second references first").0.clone();
+    assert_eq!(first.id().get(), 2);
+    assert!(storage.try_get(&first).is_none());
+
+    let storage = world.components.get_simple_storage::<TestArch, Simple1OptionalNoDepNoInit>();
+    assert_eq!(storage.try_get(&second), Some(&Simple1OptionalNoDepNoInit(2)));
+    assert_eq!(storage.try_get(&first), Some(&Simple1OptionalNoDepNoInit(1)));
+
+    assert_eq!(
+        world.components.get_isotope::<TestArch, IsoNoInit, _>(&first, TestDiscrim1(11)),
+        Some(&mut IsoNoInit(10)),
+    );
+    assert_eq!(
+        world.components.get_isotope::<TestArch, IsoNoInit, _>(&third, TestDiscrim1(13)),
+        Some(&mut IsoNoInit(30)),
+    );
+    assert_eq!(
+        world.components.get_isotope::<TestArch, IsoNoInit, _>(&second, TestDiscrim1(11)),
+        None
+    );
+
+    let weak = world.get_global::<InitialEntities>().weak.clone().expect("weak ref is set");
+    assert_eq!(weak.id(), first.id());
+    let store = world.get_global::<generation::StoreMap>();
+    assert_eq!(weak.generation(), store.get::<TestArch>(first.id().get() as usize));
+}
+
+#[test]
+fn test_rearrange_keeps_deleted_weak_dangling() {
+    let mut world = system_test!(use_ref_comps.build(););
+
+    let deleted = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    let retained = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    let deleted_weak = deleted.weak(world.get_global::<generation::StoreMap>());
+    let retained_id = retained.id();
+    world.get_global::<InitialEntities>().weak = Some(deleted_weak.clone());
+    drop(retained);
+    world.delete(deleted);
+
+    world.rearrange::<TestArch>(Permutation::from_order([retained_id]));
+
+    let weak = world.get_global::<InitialEntities>().weak.clone().expect("weak ref is set");
+    assert_eq!(weak.id(), deleted_weak.id());
+    let store = world.get_global::<generation::StoreMap>();
+    assert_ne!(weak.generation(), store.get::<TestArch>(weak.id().get() as usize));
+}
+
+#[test]
+fn test_rearrange_compacts_allocator() {
+    let mut world = system_test!(use_ref_comps.build(););
+
+    let entities: Vec<Entity<TestArch>> =
+        (0..4).map(|_| world.create::<TestArch>(crate::comps![@(crate) TestArch =>])).collect();
+    let mut ids: Vec<_> = entities.iter().map(|entity| entity.id()).collect();
+    let mut entities = entities.into_iter();
+    world.delete(entities.next().expect("4 entities"));
+    ids.remove(0);
+    ids.reverse();
+    drop(entities);
+
+    world.rearrange::<TestArch>(Permutation::from_order(ids));
+
+    let snapshot = world.ealloc_map.snapshot::<TestArch>();
+    let chunks: Vec<_> = snapshot
+        .iter_allocated_chunks()
+        .map(|range| (range.start.get(), range.end.get()))
+        .collect();
+    assert_eq!(chunks, vec![(1, 4)]);
+
+    let created = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    assert_eq!(created.id().get(), 4);
+}
+
+#[test]
+#[should_panic = "Permutation does not contain all allocated entities"]
+fn test_rearrange_missing_entity() {
+    let mut world = system_test!(use_ref_comps.build(););
+
+    let first = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    let second = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    let first_id = first.id();
+    drop((first, second));
+
+    world.rearrange::<TestArch>(Permutation::from_order([first_id]));
+}
+
+#[test]
+#[should_panic = "which is not allocated or is duplicated"]
+fn test_rearrange_duplicate_entity() {
+    let mut world = system_test!(use_ref_comps.build(););
+
+    let first = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    let first_id = first.id();
+    drop(first);
+
+    world.rearrange::<TestArch>(Permutation::from_order([first_id, first_id]));
+}
+
+#[test]
+#[cfg_attr(
+    any(
+        all(debug_assertions, not(feature = "debug-entity-rc")),
+        all(not(debug_assertions), not(feature = "release-entity-rc")),
+    ),
+    ignore
+)]
+#[should_panic = "Detected strong reference to entity dynec::test_util::TestArch#1 outside the \
+                  world during rearrangement"]
+fn test_rearrange_outside_strong_ref() {
+    let mut world = system_test!(use_ref_comps.build(););
+
+    let first = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    let _second = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    let first_id = first.id();
+    drop(first);
+
+    world.rearrange::<TestArch>(Permutation::from_order([_second.id(), first_id]));
+}
@@ -0,0 +1,179 @@
+//! Tests scheduling and unscheduling systems after the world is built.
+
+use std::panic;
+
+use super::Log;
+use crate::test_util::*;
+use crate::{global, system, tracer, world, Entity};
+
+#[global(dynec_as(crate), initial)]
+#[derive(Default)]
+struct Counter(u32);
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct LogPartition;
+
+log_system!(render, after(LogPartition));
+
+#[system(dynec_as(crate), before(LogPartition))]
+fn simulate(
+    _comps: system::ReadSimple<TestArch, Simple5RequiredNoInit>,
+    #[dynec(global)] counter: &mut Counter,
+    #[dynec(global)] log: &mut Log,
+) {
+    counter.0 += 1;
+    log.entries.push("simulate");
+}
+
+fn build() -> world::World {
+    let mut builder = world::Builder::new(0);
+    builder.schedule(render.build());
+    builder.build()
+}
+
+#[test]
+fn test_schedule_after_build() {
+    let mut world = build();
+
+    world.execute(&tracer::Noop);
+    world.schedule(simulate.build());
+    world.execute(&tracer::Noop);
+
+    assert_eq!(world.get_global::<Log>().entries, ["render", "simulate", "render"]);
+    assert_eq!(world.get_global::<Counter>().0, 1);
+
+    // the storage of the newly registered archetype is usable offline
+    world.create::<TestArch>(crate::comps![@(crate) TestArch => Simple5RequiredNoInit(1)]);
+    assert_eq!(
+        world.components.get_simple_storage::<TestArch, Simple5RequiredNoInit>().iter().count(),
+        1
+    );
+}
+
+log_system!(cyclic, before(LogPartition), after(LogPartition));
+
+#[test]
+fn test_schedule_cyclic_restores_schedule() {
+    let mut world = build();
+
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| world.schedule(cyclic.build())));
+    assert!(result.is_err(), "scheduling a cyclic dependency should panic");
+    world.execute(&tracer::Noop);
+
+    assert_eq!(world.get_global::<Log>().entries, ["render"]);
+}
+
+#[test]
+fn test_schedule_named_after_build() {
+    let mut builder = world::Builder::new(0);
+    builder.schedule(render.build());
+    builder.named_schedule("sim").schedule(render.build());
+    let mut world = builder.build();
+
+    world.schedule_named("sim", simulate.build());
+    world.execute_schedule("sim", &tracer::Noop);
+
+    assert_eq!(world.get_global::<Log>().entries, ["simulate", "render"]);
+    assert_eq!(world.get_global::<Counter>().0, 1);
+}
+
+#[test]
+fn test_unschedule() {
+    let mut world = build();
+
+    let handle = world.schedule(simulate.build());
+    world.execute(&tracer::Noop);
+    world.unschedule(handle);
+    world.execute(&tracer::Noop);
+
+    assert_eq!(world.get_global::<Log>().entries, ["simulate", "render", "render"]);
+}
+
+#[test]
+#[should_panic = "is not scheduled in this world"]
+fn test_unschedule_twice() {
+    let mut world = build();
+
+    let handle = world.schedule(simulate.build());
+    world.unschedule(handle);
+    world.unschedule(handle);
+}
+
+#[system(dynec_as(crate))]
+fn hold(
+    #[dynec(local(initial = None, entity))] entity: &mut Option<Entity<TestArch>>,
+    #[dynec(global)] initials: &mut InitialEntities,
+    _comps: system::ReadSimple<TestArch, Simple5RequiredNoInit>,
+) {
+    if let Some(ent) = initials.strong.take() {
+        *entity = Some(ent);
+    }
+}
+
+#[test]
+#[should_panic = "Cannot unschedule system dynec::world::tests::reschedule::hold because its local \
+                  states still hold strong references to entities"]
+fn test_unschedule_strong_reference() {
+    let mut world = build();
+
+    let handle = world.schedule(hold.build());
+    let entity =
+        world.create::<TestArch>(crate::comps![@(crate) TestArch => Simple5RequiredNoInit(1)]);
+    world.get_global::<InitialEntities>().strong = Some(entity);
+    world.execute(&tracer::Noop);
+
+    world.unschedule(handle);
+}
+
+#[global(dynec_as(crate), initial)]
+#[derive(Default)]
+struct Observed(Vec<i32>);
+
+#[system(dynec_as(crate))]
+fn prepare(
+    _comps: system::ReadSimple<TestArch, Simple5RequiredNoInit>,
+    #[dynec(global)] _initials: &InitialEntities,
+) {
+}
+
+#[system(dynec_as(crate))]
+fn observe(
+    comps: system::ReadSimple<TestArch, Simple6RequiredWithInitNoDeps>,
+    #[dynec(global)] initials: &InitialEntities,
+    #[dynec(global)] observed: &mut Observed,
+) {
+    let entity = initials.strong.as_ref().expect("initial entity is set before execution");
+    observed.0.push(comps.get(entity).0);
+}
+
+#[test]
+fn test_schedule_new_required_comp_for_existing_entities() {
+    let mut world = build();
+
+    world.schedule(prepare.build());
+    let entity =
+        world.create::<TestArch>(crate::comps![@(crate) TestArch => Simple5RequiredNoInit(1)]);
+    world.get_global::<InitialEntities>().strong = Some(entity);
+
+    world.schedule(observe.build());
+    world.execute(&tracer::Noop);
+
+    assert_eq!(world.get_global::<Observed>().0, [9]);
+}
+
+#[system(dynec_as(crate))]
+fn require_no_init(_comps: system::ReadSimple<TestArch, Simple5RequiredNoInit>) {}
+
+#[test]
+#[should_panic = "Cannot add the required component \
+                  dynec::test_util::simple_comps::Simple5RequiredNoInit to \
+                  dynec::test_util::TestArch because the archetype already has entities and the \
+                  component has no auto-initializer"]
+fn test_schedule_new_required_comp_without_init() {
+    let mut world = build();
+
+    world.schedule(observe.build());
+    world.create::<TestArch>(crate::comps![@(crate) TestArch => ]);
+
+    world.schedule(require_no_init.build());
+}
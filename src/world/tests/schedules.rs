@@ -0,0 +1,69 @@
+//! Tests named schedules.
+
+use super::Log;
+use crate::test_util::*;
+use crate::{system, tracer, world};
+
+log_system!(update);
+
+log_system!(fixed_update);
+
+#[system(dynec_as(crate))]
+fn cleanup(
+    entities: system::EntityIterator<TestArch>,
+    mut deleter: system::EntityDeleter<TestArch>,
+    _comps: system::ReadSimple<TestArch, Simple5RequiredNoInit>,
+    #[dynec(global)] log: &mut Log,
+) {
+    for entity in entities.entities() {
+        deleter.queue(entity);
+    }
+    log.entries.push("cleanup");
+}
+
+fn build() -> world::World {
+    let mut builder = world::Builder::new(0);
+    builder.schedule(update.build());
+    builder.named_schedule("fixed_update").schedule(fixed_update.build());
+    builder.named_schedule("cleanup").schedule(cleanup.build());
+    builder.build()
+}
+
+#[test]
+fn test_named_schedules_share_globals() {
+    let mut world = build();
+
+    world.execute(&tracer::Noop);
+    world.execute_schedule("fixed_update", &tracer::Noop);
+    world.execute_schedule("fixed_update", &tracer::Noop);
+    world.execute(&tracer::Noop);
+
+    assert_eq!(
+        world.get_global::<Log>().entries,
+        ["update", "fixed_update", "fixed_update", "update"]
+    );
+}
+
+#[test]
+fn test_named_schedule_offline_operations() {
+    let mut world = build();
+
+    let entity =
+        world.create::<TestArch>(crate::comps![@(crate) TestArch => Simple5RequiredNoInit(1)]);
+    drop(entity);
+    world.execute_schedule("cleanup", &tracer::Noop);
+    world.execute_schedule("cleanup", &tracer::Noop);
+
+    assert_eq!(world.get_global::<Log>().entries, ["cleanup", "cleanup"]);
+    assert_eq!(
+        world.components.get_simple_storage::<TestArch, Simple5RequiredNoInit>().iter().count(),
+        0
+    );
+}
+
+#[test]
+#[should_panic = "No systems were scheduled into the schedule \"render\""]
+fn test_unknown_schedule() {
+    let mut world = build();
+    world.execute_schedule("render", &tracer::Noop);
+}
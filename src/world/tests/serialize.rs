@@ -0,0 +1,296 @@
+//! Tests world snapshot serialization.
+
+use crate::entity::{self, generation, Ref as _};
+use crate::test_util::*;
+use crate::{comp, global, system, system_test, Entity, World};
+
+#[comp(dynec_as(crate), of = TestArch, serialize)]
+#[derive(Debug, PartialEq)]
+struct Label(i32, String);
+
+#[comp(dynec_as(crate), of = TestArch, serialize)]
+struct Link {
+    #[entity]
+    strong: Option<Entity<TestArch>>,
+    #[entity]
+    weak:   Option<entity::Weak<TestArch>>,
+}
+
+#[comp(dynec_as(crate), of = TestArch, isotope = TestDiscrim1, serialize)]
+#[derive(Debug, PartialEq)]
+struct Tag(u8);
+
+#[comp(dynec_as(crate), of = TestArch)]
+#[derive(Debug, PartialEq)]
+struct Transient(u32);
+
+#[global(dynec_as(crate), initial, serialize)]
+#[derive(Default)]
+struct Saved {
+    #[entity]
+    root:  Option<Entity<TestArch>>,
+    count: u64,
+}
+
+#[system(dynec_as(crate))]
+fn use_serialized(
+    _label: system::ReadSimple<TestArch, Label>,
+    _link: system::ReadSimple<TestArch, Link>,
+    _tag: system::ReadIsotopeFull<TestArch, Tag>,
+    _transient: system::ReadSimple<TestArch, Transient>,
+    #[dynec(global)] _saved: &Saved,
+) {
+}
+
+fn new_world() -> World { system_test!(use_serialized.build();) }
+
+fn save_sample() -> Vec<u8> {
+    let mut world = new_world();
+
+    let first = world.create::<TestArch>(crate::comps![@(crate) TestArch =>
+        Label(1, "first".into()),
+        Transient(10),
+        @(TestDiscrim1(3), Tag(30)),
+    ]);
+    let deleted = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    let deleted_weak = deleted.weak(world.get_global::<generation::StoreMap>());
+    let first_weak = first.weak(world.get_global::<generation::StoreMap>());
+    world.delete(deleted);
+    let third = world.create::<TestArch>(crate::comps![@(crate) TestArch =>
+        Label(3, "third".into()),
+        Link { strong: Some(first.clone()), weak: Some(deleted_weak) },
+        @(TestDiscrim1(5), Tag(50)),
+    ]);
+    let fourth = world.create::<TestArch>(crate::comps![@(crate) TestArch =>
+        Link { strong: None, weak: Some(first_weak) },
+    ]);
+
+    let saved = world.get_global::<Saved>();
+    saved.root = Some(third);
+    saved.count = 42;
+    drop((first, fourth));
+
+    let mut buf = Vec::new();
+    world.save(&mut buf).expect("write to Vec should succeed");
+    buf
+}
+
+#[test]
+fn test_save_load_roundtrip() {
+    let buf = save_sample();
+
+    let mut world = new_world();
+    world.load(&buf[..]).expect("snapshot should be valid");
+
+    let saved = world.get_global::<Saved>();
+    assert_eq!(saved.count, 42);
+    let third = saved.root.clone().expect("root should be loaded");
+    assert_eq!(third.id().get(), 3);
+
+    let labels = world.components.get_simple_storage::<TestArch, Label>();
+    assert_eq!(labels.try_get(&third), Some(&Label(3, "third".into())));
+
+    let links = world.components.get_simple_storage::<TestArch, Link>();
+    let link = links.try_get(&third).expect("link should be loaded");
+    let first = link.strong.clone().expect("strong ref should be loaded");
+    assert_eq!(first.id().get(), 1);
+    let deleted_weak = link.weak.clone().expect("weak ref should be loaded");
+    assert_eq!(deleted_weak.id().get(), 2);
+
+    let fourth_link =
+        links.try_get(entity::TempRef::new(4.try_into().expect("4 != 0"))).expect("fourth link");
+    let first_weak = fourth_link.weak.clone().expect("weak ref should be loaded");
+
+    let store = world.get_global::<generation::StoreMap>();
+    assert_eq!(first_weak.generation(), store.get::<TestArch>(1));
+
+    let labels = world.components.get_simple_storage::<TestArch, Label>();
+    assert_eq!(labels.try_get(&first), Some(&Label(1, "first".into())));
+
+    let transients = world.components.get_simple_storage::<TestArch, Transient>();
+    assert_eq!(transients.try_get(&first), None, "unserializable components are not saved");
+
+    assert_eq!(
+        world.components.get_isotope::<TestArch, Tag, _>(&first, TestDiscrim1(3)),
+        Some(&mut Tag(30)),
+    );
+    assert_eq!(
+        world.components.get_isotope::<TestArch, Tag, _>(&third, TestDiscrim1(5)),
+        Some(&mut Tag(50)),
+    );
+    assert_eq!(world.components.get_isotope::<TestArch, Tag, _>(&third, TestDiscrim1(3)), None);
+
+    let created = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    assert_eq!(created.id().get(), 2, "deleted ID should be recyclable after loading");
+    let store = world.get_global::<generation::StoreMap>();
+    assert_ne!(deleted_weak.generation(), store.get::<TestArch>(2));
+}
+
+#[test]
+fn test_load_rebuilds_refcounts() {
+    let buf = save_sample();
+
+    let mut world = new_world();
+    world.load(&buf[..]).expect("snapshot should be valid");
+
+    let fourth: Entity<TestArch> =
+        world.rctrack.to_strong(entity::TempRef::new(4.try_into().expect("4 != 0")));
+    world.delete(fourth);
+
+    let third = world.get_global::<Saved>().root.take().expect("root should be loaded");
+    world.delete(third);
+}
+
+#[test]
+#[should_panic = "World::load can only be called on a world without entities"]
+fn test_load_nonempty_world() {
+    let buf = save_sample();
+
+    let mut world = new_world();
+    drop(world.create::<TestArch>(crate::comps![@(crate) TestArch =>]));
+    let _ = world.load(&buf[..]);
+}
+
+#[test]
+fn test_load_component_of_unallocated_entity() {
+    let mut world = new_world();
+    let entity = world.create::<TestArch>(crate::comps![@(crate) TestArch =>
+        Label(0x5a5a, "needle".into()),
+    ]);
+    drop(entity);
+    let mut buf = Vec::new();
+    world.save(&mut buf).expect("write to Vec should succeed");
+
+    // Replace the entity ID of the label entry with an ID beyond the gauge.
+    let mut entry = 1_u64.to_le_bytes().to_vec();
+    entry.extend(0x5a5a_i32.to_le_bytes());
+    let offset = buf.windows(entry.len()).position(|window| window == entry).expect("label entry");
+    buf[offset..offset + 8].copy_from_slice(&9_u64.to_le_bytes());
+
+    let mut world = new_world();
+    let err = world.load(&buf[..]).expect_err("entity 9 is not allocated");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("unallocated entity"), "unexpected error: {err}");
+}
+
+#[test]
+fn test_load_invalid_magic() {
+    let mut world = new_world();
+    let err = world.load(&b"not a snapshot"[..]).expect_err("magic should be checked");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_save_required_unserializable() {
+    #[comp(dynec_as(crate), of = TestArch, required)]
+    struct Mandatory;
+
+    #[system(dynec_as(crate))]
+    fn use_mandatory(_mandatory: system::ReadSimple<TestArch, Mandatory>) {}
+
+    let mut world = system_test!(use_mandatory.build(););
+    drop(world.create::<TestArch>(crate::comps![@(crate) TestArch => Mandatory]));
+
+    let err = world.save(Vec::new()).expect_err("required component is not serializable");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_load_missing_required() {
+    #[comp(dynec_as(crate), of = TestArch, required, serialize)]
+    struct Mandatory(u8);
+
+    #[system(dynec_as(crate))]
+    fn use_mandatory(_mandatory: system::ReadSimple<TestArch, Mandatory>) {}
+
+    let buf = save_sample();
+
+    let mut world = system_test!(use_serialized.build(), use_mandatory.build(););
+    let err = world.load(&buf[..]).expect_err("loaded entities lack the required component");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("missing the required component"), "unexpected error: {err}");
+}
+
+#[test]
+fn test_snapshot_keys_are_identifiers() {
+    let buf = save_sample();
+
+    let contains = |key: &str| buf.windows(key.len()).any(|window| window == key.as_bytes());
+    assert!(contains("TestArch"));
+    assert!(contains("Label"));
+    assert!(contains("Saved"));
+    assert!(!contains("tests::serialize"), "keys should not contain module paths");
+
+    assert_eq!(
+        <Label as comp::SimpleOrIsotope<TestArch>>::SERIALIZER.map(|v| v.key),
+        Some("Label")
+    );
+}
+
+#[test]
+fn test_save_duplicate_keys() {
+    #[comp(dynec_as(crate), of = TestArch, serialize = "Duplicate")]
+    struct First(u8);
+    #[comp(dynec_as(crate), of = TestArch, serialize = "Duplicate")]
+    struct Second(u8);
+
+    #[system(dynec_as(crate))]
+    fn use_duplicates(
+        _first: system::ReadSimple<TestArch, First>,
+        _second: system::ReadSimple<TestArch, Second>,
+    ) {
+    }
+
+    let mut world = system_test!(use_duplicates.build(););
+    let err = world.save(Vec::new()).expect_err("snapshot keys are not unique");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_save_unkeyed_archetype() {
+    enum Keyed {}
+    impl crate::Archetype for Keyed {
+        type RawEntity = std::num::NonZeroU32;
+        type Ealloc = crate::entity::ealloc::Recycling<
+            std::num::NonZeroU32,
+            std::collections::BTreeSet<std::num::NonZeroU32>,
+            crate::entity::ealloc::ThreadRngShardAssigner,
+        >;
+
+        const SERIALIZE_KEY: Option<&'static str> = Some("Keyed");
+    }
+
+    enum Unkeyed {}
+    impl crate::Archetype for Unkeyed {
+        type RawEntity = std::num::NonZeroU32;
+        type Ealloc = crate::entity::ealloc::Recycling<
+            std::num::NonZeroU32,
+            std::collections::BTreeSet<std::num::NonZeroU32>,
+            crate::entity::ealloc::ThreadRngShardAssigner,
+        >;
+    }
+
+    #[comp(dynec_as(crate), of = Keyed, of = Unkeyed)]
+    struct Marker;
+
+    #[system(dynec_as(crate))]
+    fn use_archetypes(
+        _keyed: system::ReadSimple<Keyed, Marker>,
+        _unkeyed: system::ReadSimple<Unkeyed, Marker>,
+    ) {
+    }
+
+    let mut world = system_test!(use_archetypes.build(););
+    drop(world.create::<Keyed>(crate::comps![@(crate) Keyed => Marker]));
+    world.save(Vec::new()).expect("empty unkeyed archetypes are skipped");
+
+    drop(world.create::<Unkeyed>(crate::comps![@(crate) Unkeyed => Marker]));
+    let err = world.save(Vec::new()).expect_err("Unkeyed has entities but no key");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_serializer_only_for_opted_in_comps() {
+    assert!(<Label as comp::SimpleOrIsotope<TestArch>>::SERIALIZER.is_some());
+    assert!(<Transient as comp::SimpleOrIsotope<TestArch>>::SERIALIZER.is_none());
+}
@@ -0,0 +1,128 @@
+//! Tests add/remove events of simple components.
+
+use crate::entity::{Permutation, Ref as _};
+use crate::storage::Event;
+use crate::test_util::*;
+use crate::{comp, global, system, system_test, tracer};
+
+#[comp(dynec_as(crate), of = TestArch, events)]
+struct Marker(u32);
+
+#[global(dynec_as(crate), initial)]
+#[derive(Default)]
+struct Observed {
+    cycles: Vec<Vec<(u32, Event)>>,
+}
+
+#[system(dynec_as(crate))]
+fn strip(
+    entities: system::EntityIterator<TestArch>,
+    mut markers: system::WriteSimple<TestArch, Marker>,
+) {
+    for entity in entities.entities() {
+        if let Some(Marker(0)) = markers.try_get(&entity) {
+            markers.set(&entity, None);
+        }
+    }
+}
+
+#[system(dynec_as(crate))]
+fn observe(
+    events: system::SimpleEvents<TestArch, Marker>,
+    #[dynec(global)] observed: &mut Observed,
+) {
+    assert_eq!(events.len(), events.added().count() + events.removed().count());
+    observed.cycles.push(events.iter().map(|(entity, event)| (entity.id().get(), event)).collect());
+}
+
+#[test]
+fn test_simple_events_delivered_next_cycle() {
+    let mut world = system_test!(strip.build(), observe.build(););
+
+    let kept = world.create::<TestArch>(crate::comps![@(crate) TestArch => Marker(1)]);
+    let stripped = world.create::<TestArch>(crate::comps![@(crate) TestArch => Marker(0)]);
+    world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    let (kept_id, stripped_id) = (kept.id().get(), stripped.id().get());
+
+    world.execute(&tracer::Noop);
+    world.execute(&tracer::Noop);
+    world.delete(kept);
+    world.execute(&tracer::Noop);
+    world.execute(&tracer::Noop);
+
+    assert_eq!(
+        world.get_global::<Observed>().cycles,
+        [
+            vec![(kept_id, Event::Added), (stripped_id, Event::Added)],
+            vec![(stripped_id, Event::Removed)],
+            vec![(kept_id, Event::Removed)],
+            vec![],
+        ]
+    );
+}
+
+#[test]
+fn test_simple_events_without_storage_access() {
+    let mut world = system_test!(observe.build(););
+    world.create::<TestArch>(crate::comps![@(crate) TestArch => Marker(1)]);
+    world.execute(&tracer::Noop);
+
+    assert_eq!(world.get_global::<Observed>().cycles.len(), 1);
+    assert_eq!(world.get_global::<Observed>().cycles[0].len(), 1);
+}
+
+#[test]
+fn test_simple_events_rearranged() {
+    let mut world = system_test!(strip.build(), observe.build(););
+
+    let kept = world.create::<TestArch>(crate::comps![@(crate) TestArch => Marker(1)]);
+    let stripped = world.create::<TestArch>(crate::comps![@(crate) TestArch => Marker(0)]);
+    let empty = world.create::<TestArch>(crate::comps![@(crate) TestArch =>]);
+    let order = [stripped.id(), kept.id(), empty.id()];
+    drop((kept, stripped, empty));
+
+    // The removal of `stripped` is recorded in the first cycle and delivered in the second cycle.
+    world.execute(&tracer::Noop);
+    world.rearrange::<TestArch>(Permutation::from_order(order));
+    world.execute(&tracer::Noop);
+    world.execute(&tracer::Noop);
+
+    assert_eq!(
+        world.get_global::<Observed>().cycles,
+        [vec![(1, Event::Added), (2, Event::Added)], vec![(1, Event::Removed)], vec![]],
+        "rearrangement should move pending events without recording new events",
+    );
+}
+
+#[system(dynec_as(crate))]
+fn replace(
+    entities: system::EntityIterator<TestArch>,
+    markers: system::ReadSimple<TestArch, Marker>,
+    mut deleter: system::EntityDeleter<TestArch>,
+    #[dynec(entity_creator(no_partition))] mut creator: system::EntityCreator<TestArch>,
+) {
+    for entity in entities.entities() {
+        if let Some(Marker(0)) = markers.try_get(&entity) {
+            deleter.queue(entity);
+            creator.create(crate::comps![@(crate) TestArch => Marker(2)]);
+        }
+    }
+}
+
+#[test]
+fn test_simple_events_follow_offline_queue_order() {
+    let mut world = system_test!(replace.build(), observe.build(););
+
+    let old_id = world.create::<TestArch>(crate::comps![@(crate) TestArch => Marker(0)]).id().get();
+    world.execute(&tracer::Noop);
+    world.execute(&tracer::Noop);
+
+    let cycles = &world.get_global::<Observed>().cycles;
+    assert_eq!(cycles[0], [(old_id, Event::Added)]);
+    assert_eq!(
+        cycles[1].iter().map(|&(_, event)| event).collect::<Vec<_>>(),
+        [Event::Removed, Event::Added],
+        "the deletion queued before the creation should be applied first",
+    );
+    assert_eq!(cycles[1][0].0, old_id);
+}
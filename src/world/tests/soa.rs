@@ -0,0 +1,148 @@
+//! Tests struct-of-arrays components.
+
+use std::any;
+
+use rayon::prelude::ParallelIterator;
+
+use crate::entity::{Permutation, Ref as _};
+use crate::test_util::*;
+use crate::{comp, system, system_test, tracer, Entity, World};
+
+#[comp(dynec_as(crate), of = TestArch, storage = soa, required, serialize)]
+#[derive(Debug, PartialEq)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[comp(dynec_as(crate), of = TestArch, required, serialize)]
+struct Velocity(f32);
+
+#[system(dynec_as(crate))]
+fn advance(
+    entities: system::EntityIterator<TestArch>,
+    mut positions: system::WriteSimple<TestArch, Position>,
+    velocities: system::ReadSimple<TestArch, Velocity>,
+) {
+    for (_, (position, velocity)) in entities.chunks_with((&mut positions, &velocities)) {
+        assert_eq!(position.x.len(), velocity.len());
+        for (x, velocity) in position.x.iter_mut().zip(velocity) {
+            *x += velocity.0;
+        }
+    }
+
+    entities.par_entities_with(&mut positions).for_each(|(_, position)| *position.y += 1.0);
+}
+
+#[system(dynec_as(crate))]
+fn check(
+    entities: system::EntityIterator<TestArch>,
+    positions: system::ReadSimple<TestArch, Position>,
+) {
+    let from_entities: Vec<_> = entities
+        .entities_with(&positions)
+        .map(|(entity, position)| (entity.id().get(), *position.x))
+        .collect();
+    let from_chunks: Vec<_> = entities
+        .chunks_with(&positions)
+        .flat_map(|(chunk, position)| (chunk.start.get()..).zip(position.x.iter().copied()))
+        .collect();
+    assert_eq!(from_entities, from_chunks);
+}
+
+fn new_world() -> World { system_test!(advance.build(), check.build();) }
+
+fn create(world: &mut World, x: f32, velocity: f32) -> Entity<TestArch> {
+    world.create::<TestArch>(crate::comps![@(crate) TestArch =>
+        Position { x, y: 0.0 },
+        Velocity(velocity),
+    ])
+}
+
+fn position(world: &mut World, entity: &Entity<TestArch>) -> (f32, f32) {
+    let storage = world.components.get_simple_storage::<TestArch, Position>();
+    let position = storage.try_get(entity).expect("position is required");
+    (*position.x, *position.y)
+}
+
+#[test]
+fn test_soa_systems() {
+    let mut world = new_world();
+
+    let first = create(&mut world, 1.0, 1.0);
+    let second = create(&mut world, 2.0, 0.5);
+    let third = create(&mut world, 3.0, -1.0);
+
+    world.execute(&tracer::Noop);
+    assert_eq!(position(&mut world, &first), (2.0, 1.0));
+    assert_eq!(position(&mut world, &second), (2.5, 1.0));
+    assert_eq!(position(&mut world, &third), (2.0, 1.0));
+
+    world.execute(&tracer::Noop);
+    assert_eq!(position(&mut world, &first), (3.0, 2.0));
+    assert_eq!(position(&mut world, &second), (3.0, 2.0));
+    assert_eq!(position(&mut world, &third), (1.0, 2.0));
+
+    let mut storage = world.components.get_simple_storage::<TestArch, Position>();
+    let position = storage.try_get_mut(&third).expect("position is required");
+    *position.y = 5.0;
+    assert_eq!(
+        storage.set(&third, Some(Position { x: 0.0, y: 0.0 })),
+        Some(Position { x: 1.0, y: 5.0 }),
+    );
+    let entries: Vec<_> =
+        storage.iter().map(|(entity, pos)| (entity.id().get(), *pos.x, *pos.y)).collect();
+    assert_eq!(entries, [(1, 3.0, 2.0), (2, 3.0, 2.0), (3, 0.0, 0.0)]);
+}
+
+#[test]
+fn test_soa_rearrange() {
+    let mut world = new_world();
+
+    let first = create(&mut world, 1.0, 0.0);
+    let second = create(&mut world, 2.0, 0.0);
+
+    let permutation = Permutation::from_order([second.id(), first.id()]);
+    drop((first, second));
+    world.rearrange::<TestArch>(permutation);
+
+    let storage = world.components.get_simple_storage::<TestArch, Position>();
+    let entries: Vec<_> = storage.iter().map(|(entity, pos)| (entity.id().get(), *pos.x)).collect();
+    assert_eq!(entries, [(1, 2.0), (2, 1.0)]);
+}
+
+#[test]
+fn test_soa_memory_report() {
+    let mut world = new_world();
+    let _entity = create(&mut world, 1.0, 0.0);
+
+    let report = world.memory_report();
+    let storage = report
+        .storages
+        .iter()
+        .find(|storage| storage.component == any::type_name::<Position>())
+        .expect("storage is reported");
+    assert_eq!(storage.cardinality, 1);
+    assert!(storage.usage.data_bytes >= 2 * std::mem::size_of::<f32>());
+}
+
+#[test]
+fn test_soa_save_load() {
+    let mut world = new_world();
+    let first = create(&mut world, 1.0, 0.0);
+    let second = create(&mut world, 2.0, 0.0);
+    let mut storage = world.components.get_simple_storage::<TestArch, Position>();
+    *storage.try_get_mut(&second).expect("position is required").y = 7.0;
+    drop((first, second));
+
+    let mut buf = Vec::new();
+    world.save(&mut buf).expect("write to Vec should succeed");
+
+    let mut world = new_world();
+    world.load(&buf[..]).expect("snapshot should be valid");
+
+    let storage = world.components.get_simple_storage::<TestArch, Position>();
+    let entries: Vec<_> =
+        storage.iter().map(|(entity, pos)| (entity.id().get(), *pos.x, *pos.y)).collect();
+    assert_eq!(entries, [(1, 1.0, 0.0), (2, 2.0, 7.0)]);
+}
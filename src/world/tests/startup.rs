@@ -0,0 +1,60 @@
+//! Tests startup systems.
+
+use super::Log;
+use crate::test_util::*;
+use crate::{system, tracer, world};
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct SpawnPartition;
+
+#[system(dynec_as(crate), before(SpawnPartition))]
+fn spawn(
+    #[dynec(entity_creator(no_partition))] mut creator: system::EntityCreator<TestArch>,
+    #[dynec(global)] log: &mut Log,
+) {
+    for i in 0..3 {
+        creator.create(crate::comps![@(crate) TestArch => Simple5RequiredNoInit(i)]);
+    }
+    log.entries.push("spawn");
+}
+
+log_system!(announce, after(SpawnPartition));
+
+#[system(dynec_as(crate))]
+fn count(
+    _comps: system::ReadSimple<TestArch, Simple5RequiredNoInit>,
+    entities: system::EntityIterator<TestArch>,
+    #[dynec(global)] log: &mut Log,
+) {
+    assert_eq!(entities.entities().count(), 3, "startup systems run before the first cycle");
+    log.entries.push("count");
+}
+
+fn build() -> world::World {
+    let mut builder = world::Builder::new(0);
+    builder.schedule_startup(spawn.build());
+    builder.schedule_startup(announce.build());
+    builder.schedule(count.build());
+    builder.named_schedule("fixed_update").schedule(count.build());
+    builder.build()
+}
+
+#[test]
+fn test_startup_runs_once_before_first_cycle() {
+    let mut world = build();
+
+    world.execute(&tracer::Noop);
+    world.execute(&tracer::Noop);
+
+    assert_eq!(world.get_global::<Log>().entries, ["spawn", "announce", "count", "count"]);
+}
+
+#[test]
+fn test_startup_runs_before_named_schedule() {
+    let mut world = build();
+
+    world.execute_schedule("fixed_update", &tracer::Noop);
+    world.execute(&tracer::Noop);
+
+    assert_eq!(world.get_global::<Log>().entries, ["spawn", "announce", "count", "count"]);
+}
@@ -0,0 +1,78 @@
+//! Tests filtering entities by tag components.
+
+use crate::entity::Ref as _;
+use crate::test_util::*;
+use crate::{comp, global, system, system_test, tracer};
+
+#[comp(dynec_as(crate), of = TestArch, storage = crate::storage::Tag)]
+struct Selected;
+
+#[comp(dynec_as(crate), of = TestArch, storage = crate::storage::Tag)]
+struct Frozen;
+
+#[comp(dynec_as(crate), of = TestArch)]
+struct Position(u32);
+
+#[global(dynec_as(crate), initial)]
+#[derive(Default)]
+struct Matched {
+    ids:       Vec<u32>,
+    positions: Vec<Option<u32>>,
+}
+
+#[system(dynec_as(crate))]
+fn select(
+    entities: system::EntityIterator<TestArch>,
+    selected: system::ReadSimple<TestArch, Selected>,
+    frozen: system::ReadSimple<TestArch, Frozen>,
+    positions: system::ReadSimple<TestArch, Position>,
+    #[dynec(global)] matched: &mut Matched,
+) {
+    let filter = system::TagFilter::new().with(&selected).without(&frozen);
+
+    let ids: Vec<u32> = entities.entities_tagged(&filter).map(|entity| entity.id().get()).collect();
+    let expected: Vec<u32> = entities
+        .entities()
+        .filter(|entity| selected.try_get(entity).is_some() && frozen.try_get(entity).is_none())
+        .map(|entity| entity.id().get())
+        .collect();
+    assert_eq!(ids, expected);
+
+    matched.ids = ids;
+    matched.positions = entities
+        .entities_with_tags(&filter, system::Try(&positions))
+        .map(|(_, position)| position.map(|position| position.0))
+        .collect();
+}
+
+#[test]
+fn test_tag_filter() {
+    let mut world = system_test!(select.build(););
+
+    let mut expected_ids = Vec::new();
+    let mut expected_positions = Vec::new();
+    for i in 0..200 {
+        let mut comps = crate::comps![@(crate) TestArch =>];
+        if i % 3 == 0 {
+            comps.insert_simple(Selected);
+        }
+        if i % 5 == 0 {
+            comps.insert_simple(Frozen);
+        }
+        if i % 2 == 0 {
+            comps.insert_simple(Position(i));
+        }
+        let entity = world.create::<TestArch>(comps);
+
+        if i % 3 == 0 && i % 5 != 0 {
+            expected_ids.push(entity.id().get());
+            expected_positions.push((i % 2 == 0).then_some(i));
+        }
+    }
+
+    world.execute(&tracer::Noop);
+
+    let matched = world.get_global::<Matched>();
+    assert_eq!(matched.ids, expected_ids);
+    assert_eq!(matched.positions, expected_positions);
+}
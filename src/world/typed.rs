@@ -1,14 +1,18 @@
 use std::any::{self, Any};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::io;
 use std::sync::Arc;
 
 use indexmap::IndexMap;
 use parking_lot::lock_api::ArcRwLockWriteGuard;
+use parking_lot::RwLock;
 
-use crate::entity::{self, referrer};
+use crate::entity::raw::Atomic as _;
+use crate::entity::{self, ealloc, generation, rctrack, referrer, Ealloc, Generation, Raw};
+use crate::serialize::{self, Serialize};
 use crate::storage::simple::AnySimpleStorage;
 use crate::util::DbgTypeId;
-use crate::{comp, storage, Archetype};
+use crate::{comp, storage, world, Archetype};
 
 pub(crate) trait AnyBuilder {
     fn add_simple_storage_if_missing(
@@ -23,13 +27,21 @@ pub(crate) trait AnyBuilder {
         map_builder: fn() -> Box<dyn Any>,
     );
 
+    fn add_dynamic_storage_if_missing(&mut self, descriptor: comp::dynamic::Descriptor);
+
+    /// Starts delivering add/remove events of a simple component that was already added.
+    fn enable_simple_events(&mut self, component: DbgTypeId);
+
     fn build(self: Box<Self>) -> Box<dyn AnyTyped>;
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
 }
 
 pub(crate) fn builder<A: Archetype>() -> impl AnyBuilder {
     let mut builder = Builder::<A> {
         simple_storages:      IndexMap::new(),
         isotope_storage_maps: HashMap::new(),
+        dynamic_storages:     HashMap::new(),
     };
 
     // Native components from dynec that must be present for every archetype.
@@ -45,6 +57,7 @@ fn populate_native_component<A: Archetype, C: comp::Simple<A>>(builder: &mut Bui
 struct Builder<A: Archetype> {
     simple_storages:      IndexMap<DbgTypeId, storage::Simple<A>>,
     isotope_storage_maps: HashMap<DbgTypeId, Arc<dyn storage::AnyIsotopeMap<A>>>,
+    dynamic_storages:     HashMap<comp::dynamic::Id, RwLock<storage::Dynamic<A::RawEntity>>>,
 }
 
 impl<A: Archetype> AnyBuilder for Builder<A> {
@@ -93,12 +106,38 @@ impl<A: Archetype> AnyBuilder for Builder<A> {
         });
     }
 
+    fn add_dynamic_storage_if_missing(&mut self, descriptor: comp::dynamic::Descriptor) {
+        let storage = self
+            .dynamic_storages
+            .entry(descriptor.id)
+            .or_insert_with(|| RwLock::new(storage::Dynamic::new(descriptor)));
+        let existing = storage.get_mut().descriptor();
+        if existing != descriptor {
+            panic!(
+                "Dynamic component {} of {} is requested with size {} and {}",
+                descriptor.id,
+                any::type_name::<A>(),
+                existing.size,
+                descriptor.size,
+            );
+        }
+    }
+
+    fn enable_simple_events(&mut self, component: DbgTypeId) {
+        let storage =
+            self.simple_storages.get_mut(&component).expect("storage was added before enabling");
+        storage.events.get_or_insert_with(Vec::new);
+    }
+
     fn build(self: Box<Self>) -> Box<dyn AnyTyped> {
         Box::new(Typed::<A> {
             simple_storages:      self.simple_storages,
             isotope_storage_maps: self.isotope_storage_maps,
+            dynamic_storages:     self.dynamic_storages,
         })
     }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> { self }
 }
 
 /// Stores everything related to a specific archetype.
@@ -106,6 +145,32 @@ impl<A: Archetype> AnyBuilder for Builder<A> {
 pub(crate) struct Typed<A: Archetype> {
     pub(crate) simple_storages:      IndexMap<DbgTypeId, storage::Simple<A>>,
     pub(crate) isotope_storage_maps: HashMap<DbgTypeId, Arc<dyn storage::AnyIsotopeMap<A>>>,
+    pub(crate) dynamic_storages: HashMap<comp::dynamic::Id, RwLock<storage::Dynamic<A::RawEntity>>>,
+}
+
+/// Looks up the dependencies of a component being initialized.
+struct DepGetter<'t, A: Archetype> {
+    simple_storages: &'t IndexMap<DbgTypeId, storage::Simple<A>>,
+    /// The toposorted index of the simple component being initialized, if any.
+    index:           Option<usize>,
+    entity:          A::RawEntity,
+}
+
+impl<'t, A: Archetype> comp::any::DepGetterInner<A> for DepGetter<'t, A> {
+    fn get(
+        &self,
+        ty: DbgTypeId,
+    ) -> ArcRwLockWriteGuard<parking_lot::RawRwLock, dyn AnySimpleStorage<A>> {
+        let (dep_index, _, dep_storage) =
+            self.simple_storages.get_full(&ty).expect("dep storage does not exist, toposort bug");
+        if let Some(index) = self.index {
+            assert!(dep_index < index, "{dep_index} >= {index}, toposort bug");
+        }
+        dep_storage
+            .storage
+            .try_write_arc()
+            .expect("mut access to indexmap and dep indices checked to be unique during toposort")
+    }
 }
 
 impl<A: Archetype> Typed<A> {
@@ -115,32 +180,11 @@ impl<A: Archetype> Typed<A> {
         entity: A::RawEntity,
         mut comp_map: comp::Map<A>,
         ealloc: &mut A::Ealloc,
+        tick: storage::Tick,
     ) {
-        struct DepGetter<'t, A: Archetype> {
-            simple_storages: &'t IndexMap<DbgTypeId, storage::Simple<A>>,
-            index:           Option<usize>,
-            entity:          A::RawEntity,
-        }
-        impl<'t, A: Archetype> comp::any::DepGetterInner<A> for DepGetter<'t, A> {
-            fn get(
-                &self,
-                ty: DbgTypeId,
-            ) -> ArcRwLockWriteGuard<parking_lot::RawRwLock, dyn AnySimpleStorage<A>> {
-                let (dep_index, _, dep_storage) = self
-                    .simple_storages
-                    .get_full(&ty)
-                    .expect("dep storage does not exist, toposort bug");
-                if let Some(index) = self.index {
-                    assert!(dep_index < index, "{dep_index} >= {index}, toposort bug");
-                }
-                dep_storage.storage.try_write_arc().expect(
-                    "mut access to indexmap and dep indices checked to be unique during toposort",
-                )
-            }
-        }
-
         for (index, storage) in self.simple_storages.values().enumerate() {
             let mut any_storage = storage.storage.try_write().expect("storage arc was leaked");
+            any_storage.set_change_tick(tick);
 
             any_storage.fill_init_simple(
                 entity,
@@ -172,6 +216,29 @@ impl<A: Archetype> Typed<A> {
             );
         }
     }
+
+    /// Moves the components of each `(old, new)` entity pair from `old` to `new`.
+    /// This function should only be called offline.
+    pub(crate) fn rearrange(
+        &mut self,
+        moves: &[(A::RawEntity, A::RawEntity)],
+        tick: storage::Tick,
+    ) {
+        for storage in self.simple_storages.values_mut() {
+            Arc::get_mut(&mut storage.storage)
+                .expect("storage arc was leaked")
+                .get_mut()
+                .rearrange(moves, tick);
+        }
+
+        for map in self.isotope_storage_maps.values_mut() {
+            Arc::get_mut(map).expect("storage map arc was leaked").rearrange(moves, tick);
+        }
+
+        for storage in self.dynamic_storages.values_mut() {
+            storage.get_mut().rearrange(moves);
+        }
+    }
 }
 
 pub(crate) trait AnyTyped: Send + Sync {
@@ -179,6 +246,69 @@ pub(crate) trait AnyTyped: Send + Sync {
     fn as_any_mut(&mut self) -> &mut (dyn Any + Send + Sync);
 
     fn referrer_dyn_iter<'t>(&'t mut self, archetype: &'t str) -> Box<dyn referrer::Object + 't>;
+
+    /// Returns the snapshot key of the archetype, or `None` if it cannot be saved.
+    fn serialize_key(&self) -> Option<&'static str>;
+
+    /// Returns an error if this archetype has entities but no snapshot key.
+    fn check_unkeyed(&self, ealloc_map: &mut ealloc::Map) -> io::Result<()>;
+
+    /// Writes the allocator state and entity generations of this archetype to a snapshot.
+    fn save_entities(
+        &self,
+        ealloc_map: &mut ealloc::Map,
+        generations: &generation::StoreMap,
+        writer: &mut serialize::Writer<'_>,
+    ) -> io::Result<()>;
+
+    /// Restores the allocator state and entity generations of this archetype from a snapshot.
+    fn load_entities(
+        &self,
+        ealloc_map: &mut ealloc::Map,
+        generations: &mut generation::StoreMap,
+        rctrack: &mut rctrack::MaybeStoreMap,
+        reader: &mut serialize::Reader<'_>,
+    ) -> io::Result<()>;
+
+    /// Writes all serializable components of this archetype to a snapshot.
+    fn save_components(&mut self, writer: &mut serialize::Writer<'_>) -> io::Result<()>;
+
+    /// Reads components of this archetype from a snapshot.
+    ///
+    /// The entities of this archetype must have been loaded into `ealloc_map`.
+    fn load_components(
+        &mut self,
+        ealloc_map: &mut ealloc::Map,
+        reader: &mut serialize::Reader<'_>,
+        tick: storage::Tick,
+    ) -> io::Result<()>;
+
+    /// Returns an error if an allocated entity of this archetype
+    /// lacks a [required](comp::Presence::Required) simple component.
+    ///
+    /// This is called after all components are loaded from a snapshot.
+    fn check_required_loaded(&self, ealloc_map: &mut ealloc::Map) -> io::Result<()>;
+
+    /// Delivers the simple component add/remove events recorded since the previous delivery.
+    fn deliver_simple_events(&mut self);
+
+    /// Appends the memory usage of each storage in this archetype to `storages`.
+    fn memory_report(&self, storages: &mut Vec<world::StorageMemory>);
+
+    /// Releases memory reserved beyond the greatest entity with a component in each storage.
+    fn compact_storages(&mut self);
+
+    /// Adds the storages in `builder` that are missing in this archetype.
+    ///
+    /// Existing entities are initialized with the auto-initializer of each added simple component.
+    /// Panics if an added simple component is required but has no auto-initializer
+    /// while the archetype has existing entities.
+    fn extend(
+        &mut self,
+        builder: Box<dyn AnyBuilder>,
+        ealloc_map: &mut ealloc::Map,
+        tick: storage::Tick,
+    );
 }
 
 impl<A: Archetype> AnyTyped for Typed<A> {
@@ -202,4 +332,357 @@ impl<A: Archetype> AnyTyped for Typed<A> {
                 })),
         ))
     }
+
+    fn serialize_key(&self) -> Option<&'static str> { A::SERIALIZE_KEY }
+
+    fn check_unkeyed(&self, ealloc_map: &mut ealloc::Map) -> io::Result<()> {
+        if Ealloc::snapshot(ealloc_map.get::<A>()).iter_allocated_chunks().next().is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Cannot save entities of {} because it has no Archetype::SERIALIZE_KEY",
+                    any::type_name::<A>(),
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    fn save_entities(
+        &self,
+        ealloc_map: &mut ealloc::Map,
+        generations: &generation::StoreMap,
+        writer: &mut serialize::Writer<'_>,
+    ) -> io::Result<()> {
+        let snapshot = Ealloc::snapshot(ealloc_map.get::<A>());
+
+        if snapshot.iter_allocated_chunks().next().is_some() {
+            for (comp_ty, storage) in &self.simple_storages {
+                if storage.storage.read().is_required_unserializable() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "Cannot save entities of {} because the required component {comp_ty} \
+                             is not serializable",
+                            any::type_name::<A>(),
+                        ),
+                    ));
+                }
+            }
+        }
+
+        serialize::write_raw(writer, snapshot.gauge)?;
+        serialize::write_len(writer, snapshot.recyclable.len())?;
+        for &id in &*snapshot.recyclable {
+            serialize::write_raw(writer, id)?;
+        }
+
+        let base = <A::RawEntity as Raw>::new().load_mut();
+        for id in A::RawEntity::range(base..snapshot.gauge) {
+            generations.get::<A>(id.to_primitive()).serialize(writer)?;
+        }
+
+        Ok(())
+    }
+
+    fn load_entities(
+        &self,
+        ealloc_map: &mut ealloc::Map,
+        generations: &mut generation::StoreMap,
+        _rctrack: &mut rctrack::MaybeStoreMap,
+        reader: &mut serialize::Reader<'_>,
+    ) -> io::Result<()> {
+        let gauge: A::RawEntity = serialize::read_raw(reader)?;
+        let recyclable_len = serialize::read_len(reader)?;
+        let recyclable = (0..recyclable_len)
+            .map(|_| serialize::read_raw(reader))
+            .collect::<io::Result<BTreeSet<A::RawEntity>>>()?;
+        if let Some(&last) = recyclable.last() {
+            if last >= gauge {
+                return Err(serialize::invalid_data(format!(
+                    "recyclable entity {}#{last:?} is not below the gauge {gauge:?}",
+                    any::type_name::<A>(),
+                )));
+            }
+        }
+
+        let store = generations.get_mut::<A>();
+        let base = <A::RawEntity as Raw>::new().load_mut();
+        for id in A::RawEntity::range(base..gauge) {
+            store.set(id.to_primitive(), Generation::deserialize(reader)?);
+        }
+
+        let ealloc = ealloc_map.get::<A>();
+        ealloc.restore(gauge, recyclable.into_iter());
+
+        #[cfg(any(
+            all(debug_assertions, feature = "debug-entity-rc"),
+            all(not(debug_assertions), feature = "release-entity-rc"),
+        ))]
+        for chunk in Ealloc::snapshot(ealloc).iter_allocated_chunks() {
+            for id in A::RawEntity::range(chunk) {
+                _rctrack.0.set::<A>(id.to_primitive(), Arc::new(()));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn save_components(&mut self, writer: &mut serialize::Writer<'_>) -> io::Result<()> {
+        let mut simple: Vec<_> = self
+            .simple_storages
+            .values_mut()
+            .map(|storage| {
+                Arc::get_mut(&mut storage.storage).expect("storage arc was leaked").get_mut()
+            })
+            .filter_map(|storage| Some((storage.serialize_key()?, storage)))
+            .collect();
+        simple.sort_by_key(|&(key, _)| key);
+        serialize::check_unique_keys(
+            &format!("simple components of {}", any::type_name::<A>()),
+            simple.iter().map(|&(key, _)| key),
+        )?;
+
+        serialize::write_len(writer, simple.len())?;
+        for (key, storage) in simple {
+            serialize::write_key(writer, key)?;
+            storage.serialize(writer)?;
+        }
+
+        let mut isotope: Vec<_> = self
+            .isotope_storage_maps
+            .values_mut()
+            .map(|map| Arc::get_mut(map).expect("storage map arc was leaked"))
+            .filter_map(|map| Some((map.serialize_key()?, map)))
+            .collect();
+        isotope.sort_by_key(|&(key, _)| key);
+        serialize::check_unique_keys(
+            &format!("isotope components of {}", any::type_name::<A>()),
+            isotope.iter().map(|&(key, _)| key),
+        )?;
+
+        serialize::write_len(writer, isotope.len())?;
+        for (key, map) in isotope {
+            serialize::write_key(writer, key)?;
+            map.serialize(writer)?;
+        }
+
+        Ok(())
+    }
+
+    fn load_components(
+        &mut self,
+        ealloc_map: &mut ealloc::Map,
+        reader: &mut serialize::Reader<'_>,
+        tick: storage::Tick,
+    ) -> io::Result<()> {
+        let allocated = Ealloc::snapshot(ealloc_map.get::<A>());
+
+        let simple_len = serialize::read_len(reader)?;
+        for _ in 0..simple_len {
+            let key = serialize::read_key(reader)?;
+            let storage = self
+                .simple_storages
+                .values_mut()
+                .map(|storage| {
+                    Arc::get_mut(&mut storage.storage).expect("storage arc was leaked").get_mut()
+                })
+                .find(|storage| storage.serialize_key() == Some(&key))
+                .ok_or_else(|| {
+                    serialize::invalid_data(format!(
+                        "unknown simple component {key} for {}",
+                        any::type_name::<A>()
+                    ))
+                })?;
+            storage.set_change_tick(tick);
+            storage.deserialize(reader, &allocated)?;
+        }
+
+        let isotope_len = serialize::read_len(reader)?;
+        for _ in 0..isotope_len {
+            let key = serialize::read_key(reader)?;
+            let map = self
+                .isotope_storage_maps
+                .values_mut()
+                .map(|map| Arc::get_mut(map).expect("storage map arc was leaked"))
+                .find(|map| map.serialize_key() == Some(&key))
+                .ok_or_else(|| {
+                    serialize::invalid_data(format!(
+                        "unknown isotope component {key} for {}",
+                        any::type_name::<A>()
+                    ))
+                })?;
+            map.deserialize(reader, &allocated)?;
+        }
+
+        Ok(())
+    }
+
+    fn check_required_loaded(&self, ealloc_map: &mut ealloc::Map) -> io::Result<()> {
+        let allocated = Ealloc::snapshot(ealloc_map.get::<A>());
+
+        for (comp_ty, storage) in &self.simple_storages {
+            if let Some(entity) = storage.storage.read().find_missing_required(&allocated) {
+                return Err(serialize::invalid_data(format!(
+                    "entity {}#{entity:?} is missing the required component {comp_ty}",
+                    any::type_name::<A>(),
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn deliver_simple_events(&mut self) {
+        for storage in self.simple_storages.values_mut() {
+            storage.deliver_events();
+        }
+    }
+
+    fn memory_report(&self, storages: &mut Vec<world::StorageMemory>) {
+        let archetype = any::type_name::<A>();
+
+        for (comp_ty, storage) in &self.simple_storages {
+            let (cardinality, usage) = storage.storage.read().memory_usage();
+            storages.push(world::StorageMemory {
+                archetype,
+                component: comp_ty.to_string(),
+                discrim: None,
+                cardinality,
+                usage,
+            });
+        }
+
+        for (comp_ty, map) in &self.isotope_storage_maps {
+            for (discrim, cardinality, usage) in map.memory_usage() {
+                storages.push(world::StorageMemory {
+                    archetype,
+                    component: comp_ty.to_string(),
+                    discrim: Some(discrim),
+                    cardinality,
+                    usage,
+                });
+            }
+        }
+
+        for (id, storage) in &self.dynamic_storages {
+            let storage = storage.read();
+            storages.push(world::StorageMemory {
+                archetype,
+                component: id.to_string(),
+                discrim: None,
+                cardinality: storage.cardinality(),
+                usage: storage.memory_usage(),
+            });
+        }
+    }
+
+    fn compact_storages(&mut self) {
+        for storage in self.simple_storages.values_mut() {
+            Arc::get_mut(&mut storage.storage)
+                .expect("storage arc was leaked")
+                .get_mut()
+                .shrink_to_fit();
+        }
+
+        for map in self.isotope_storage_maps.values_mut() {
+            Arc::get_mut(map).expect("storage map arc was leaked").shrink_to_fit();
+        }
+
+        for storage in self.dynamic_storages.values_mut() {
+            storage.get_mut().shrink_to_fit();
+        }
+    }
+
+    fn extend(
+        &mut self,
+        builder: Box<dyn AnyBuilder>,
+        ealloc_map: &mut ealloc::Map,
+        tick: storage::Tick,
+    ) {
+        let builder = builder.into_any().downcast::<Builder<A>>().expect("TypeId mismatch");
+
+        let ealloc = ealloc_map.get::<A>();
+        Ealloc::flush(ealloc);
+        let allocated = Ealloc::snapshot(ealloc);
+        let has_entities = allocated.iter_allocated_chunks().next().is_some();
+
+        if has_entities {
+            for (comp_ty, storage) in &builder.simple_storages {
+                if !self.simple_storages.contains_key(comp_ty)
+                    && storage.storage.read().is_required_without_init()
+                {
+                    panic!(
+                        "Cannot add the required component {comp_ty} to {} because the archetype \
+                         already has entities and the component has no auto-initializer",
+                        any::type_name::<A>(),
+                    );
+                }
+            }
+        }
+
+        // `builder.simple_storages` is toposorted and the existing storages are never removed,
+        // so appending the missing storages in order keeps `self.simple_storages` toposorted.
+        let mut new_indices = Vec::new();
+        for (comp_ty, storage) in builder.simple_storages {
+            match self.simple_storages.entry(comp_ty) {
+                indexmap::map::Entry::Vacant(entry) => {
+                    new_indices.push(entry.index());
+                    entry.insert(storage);
+                }
+                indexmap::map::Entry::Occupied(mut entry) => {
+                    if storage.events.is_some() {
+                        entry.get_mut().events.get_or_insert_with(Vec::new);
+                    }
+                }
+            }
+        }
+
+        if has_entities {
+            // Initialize the new storages for existing entities in toposorted order,
+            // so that the dependencies of each component are initialized before it.
+            for index in new_indices {
+                let (_, storage) =
+                    self.simple_storages.get_index(index).expect("index was just inserted");
+                let mut any_storage = storage.storage.try_write().expect("storage arc was leaked");
+                any_storage.set_change_tick(tick);
+
+                for chunk in allocated.iter_allocated_chunks() {
+                    for entity in A::RawEntity::range(chunk) {
+                        any_storage.fill_init_simple(
+                            entity,
+                            &mut comp::Map::default(),
+                            comp::any::DepGetter {
+                                inner: &DepGetter {
+                                    simple_storages: &self.simple_storages,
+                                    index: Some(index),
+                                    entity,
+                                },
+                                entity,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        for (comp_ty, map) in builder.isotope_storage_maps {
+            self.isotope_storage_maps.entry(comp_ty).or_insert(map);
+        }
+
+        for (id, mut storage) in builder.dynamic_storages {
+            let descriptor = storage.get_mut().descriptor();
+            let existing =
+                self.dynamic_storages.entry(id).or_insert(storage).get_mut().descriptor();
+            if existing != descriptor {
+                panic!(
+                    "Dynamic component {} of {} is requested with size {} and {}",
+                    descriptor.id,
+                    any::type_name::<A>(),
+                    existing.size,
+                    descriptor.size,
+                );
+            }
+        }
+    }
 }